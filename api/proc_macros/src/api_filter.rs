@@ -3,6 +3,17 @@ use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenSt
 use quote::ToTokens;
 use syn::{Fields, ItemStruct};
 
+/// A field of the filter structure that bounds a field of the filtered type.
+/// Declared with `#[filter_min(field)]` or `#[filter_max(field)]` on an `Option<T>` field.
+struct BoundField {
+    /// The name of the field in the filter structure.
+    name: String,
+    /// The name of the field in the filtered type.
+    target: String,
+    /// The comparison operator (`>=` or `<=`).
+    operator: &'static str,
+}
+
 /// Create a `TokenTree::Group` with the given delimiter and contents.
 pub fn new_tokentree_group_with(delimiter: Delimiter, contents: Vec<TokenTree>) -> TokenTree {
     let mut contents_stream = TokenStream::new();
@@ -32,27 +43,32 @@ pub(crate) fn create_compile_error_at(span: Span, message: &str) -> TokenStream
 }
 
 /// Create a stream with the implementation of `compiled` for the given structure.
-fn make_compiled_fn(fields: &[String]) -> TokenStream {
+fn make_compiled_fn(fields: &[String], bounds: &[BoundField]) -> TokenStream {
     format!(
         r"
     /// Compile all filters within `self`.
     pub fn compiled(self) -> Result<Self, crate::error::Error> {{
         Ok(Self {{
-            {},
+            {}
             options: self.options,
         }})
     }}",
         fields
             .iter()
-            .map(|name| format!("{}: self.{}.compiled()?", name, name))
-            .join(",")
+            .map(|name| format!("{}: self.{}.compiled()?,", name, name))
+            .chain(
+                bounds
+                    .iter()
+                    .map(|bound| format!("{}: self.{},", bound.name, bound.name))
+            )
+            .join("")
     )
     .parse()
     .unwrap()
 }
 
 /// Create a stream with the implementation of `is_none` for the given structure.
-fn make_is_none_fn(fields: &[String]) -> TokenStream {
+fn make_is_none_fn(fields: &[String], bounds: &[BoundField]) -> TokenStream {
     format!(
         r"
     /// Check whether all filters are set to `Filter::None`.
@@ -61,6 +77,7 @@ fn make_is_none_fn(fields: &[String]) -> TokenStream {
     }}",
         fields
             .iter()
+            .chain(bounds.iter().map(|bound| &bound.name))
             .map(|name| format!("self.{}.is_none()", name))
             .join("&&")
     )
@@ -69,7 +86,11 @@ fn make_is_none_fn(fields: &[String]) -> TokenStream {
 }
 
 /// Create a stream with the implementation of `into_fn_vec` for the given structure.
-fn make_into_fn_vec_fn(fields: &[String], filtered_type: &str) -> TokenStream {
+fn make_into_fn_vec_fn(
+    fields: &[String],
+    bounds: &[BoundField],
+    filtered_type: &str,
+) -> TokenStream {
     format!(
         r"
     /// Return a `Vec` of closures for each non-`None` filter in `self`.
@@ -85,12 +106,44 @@ fn make_into_fn_vec_fn(fields: &[String], filtered_type: &str) -> TokenStream {
                 "self.{}.into_fn(|value: &{}| &value.{})",
                 name, filtered_type, name
             ))
+            .chain(bounds.iter().map(|bound| format!(
+                "self.{}.map(|bound| Box::new(move |value: &{}| value.{} {} bound) as Box<dyn Fn(&{}) -> bool + 'a>)",
+                bound.name, filtered_type, bound.target, bound.operator, filtered_type
+            )))
             .join(","),
     )
     .parse()
     .unwrap()
 }
 
+/// Create a stream with the implementation of `check_bounds` for the given structure.
+fn make_check_bounds_fn(bounds: &[BoundField]) -> TokenStream {
+    format!(
+        r#"
+    /// Check that no range given by a pair of bounds is empty.
+    pub fn check_bounds(&self) -> Result<(), crate::error::Error> {{
+        {}
+        Ok(())
+    }}"#,
+        bounds
+            .iter()
+            .filter(|min| min.operator == ">=")
+            .filter_map(|min| {
+                bounds
+                    .iter()
+                    .find(|max| max.operator == "<=" && max.target == min.target)
+                    .map(|max| (min, max))
+            })
+            .map(|(min, max)| format!(
+                r#"crate::filter::check_range("{}", self.{}.as_ref(), self.{}.as_ref())?;"#,
+                min.target, min.name, max.name
+            ))
+            .join("")
+    )
+    .parse()
+    .unwrap()
+}
+
 /// Create a stream with the implementation of `apply_sort` for the given structure.
 fn make_apply_sort_fn(fields: &Fields, field_names: &[String], filtered_type: &str) -> TokenStream {
     format!(
@@ -113,8 +166,8 @@ fn make_apply_sort_fn(fields: &Fields, field_names: &[String], filtered_type: &s
         filtered_type,
         fields
             .iter()
-            .zip(field_names.iter())
-            .filter(|(_, name)| *name != "options")
+            .filter_map(|field| field.ident.as_ref().map(|name| (field, name.to_string())))
+            .filter(|(_, name)| field_names.contains(name))
             .map(|(field, name)| {
                 let type_name = field.ty.to_token_stream().to_string();
                 // Valid types to sort by are integer / floating types, Strings, and Options of
@@ -157,7 +210,12 @@ fn make_apply_sort_fn(fields: &Fields, field_names: &[String], filtered_type: &s
 }
 
 /// Create a stream with an `impl` block for the given filter with its methods.
-fn make_impl(fields: &[String], structure: &ItemStruct, filtered_type: &str) -> TokenStream {
+fn make_impl(
+    fields: &[String],
+    bounds: &[BoundField],
+    structure: &ItemStruct,
+    filtered_type: &str,
+) -> TokenStream {
     let mut stream = TokenStream::new();
     // `impl<generics> Name<generics>
     stream.extend::<TokenStream>(TokenTree::Ident(Ident::new("impl", Span::call_site())).into());
@@ -167,9 +225,10 @@ fn make_impl(fields: &[String], structure: &ItemStruct, filtered_type: &str) ->
 
     // Add functions, one by one.
     let mut impl_stream = TokenStream::new();
-    impl_stream.extend(make_compiled_fn(fields));
-    impl_stream.extend(make_is_none_fn(fields));
-    impl_stream.extend(make_into_fn_vec_fn(fields, filtered_type));
+    impl_stream.extend(make_compiled_fn(fields, bounds));
+    impl_stream.extend(make_is_none_fn(fields, bounds));
+    impl_stream.extend(make_into_fn_vec_fn(fields, bounds, filtered_type));
+    impl_stream.extend(make_check_bounds_fn(bounds));
     impl_stream.extend(make_apply_sort_fn(&structure.fields, fields, filtered_type));
 
     // Make a group out of all the methods.
@@ -190,11 +249,18 @@ fn make_impl(fields: &[String], structure: &ItemStruct, filtered_type: &str) ->
 ///       Return a `Vec` of closures for each non-`None` filter in `self`.
 ///       Should be faster than invoking each and every filter each time.
 ///       This method must not be called if there are uncompiled filters.
+///     - `fn check_bounds(&self) -> Result<(), Error>`
+///       Check that no range given by a `filter_min` and a `filter_max` on the same field is
+///       empty.
 ///     - `fn apply_sort(options: &Options, v: &mut Vec<{}>) -> Result<(), Error>`
 ///       Sorts a `Vec` of structures given the options.
 ///
 /// The identifier of the type this filter is to be used upon must be given as an attribute
 /// parameter of the macro: `#[api_filter(FooItem)]` will create methods to filter `FooItem`s.
+///
+/// Fields of type `Option<T>` annotated with `#[filter_min(field)]` or `#[filter_max(field)]` are
+/// inclusive bounds on `field` of the filtered type rather than `Filter`s. They cannot be sorted
+/// by.
 pub fn api_filter(attr: TokenStream, item: TokenStream) -> Result<TokenStream, TokenStream> {
     // Retrieve the name of the type to filter from the attribute.
     let mut attr = attr.into_iter();
@@ -208,20 +274,52 @@ pub fn api_filter(attr: TokenStream, item: TokenStream) -> Result<TokenStream, T
             ));
         };
 
-    // Parse the item as a structure and get a list of its fields.
-    let structure = match syn::parse::<ItemStruct>(item) {
+    // Parse the item as a structure.
+    let mut structure = match syn::parse::<ItemStruct>(item) {
         Ok(x) => x,
         Err(x) => return Err(TokenStream::from(x.to_compile_error())),
     };
+
+    // Collect bound fields, removing their attributes from the structure.
+    let mut bounds = vec![];
+    for field in structure.fields.iter_mut() {
+        let name = match field.ident.as_ref() {
+            Some(ident) => ident.to_string(),
+            None => continue,
+        };
+        let mut error = None;
+        field.attrs.retain(|attr| {
+            for (attr_name, operator) in [("filter_min", ">="), ("filter_max", "<=")] {
+                if attr.path.is_ident(attr_name) {
+                    match attr.parse_args::<syn::Ident>() {
+                        Ok(target) => bounds.push(BoundField {
+                            name: name.clone(),
+                            target: target.to_string(),
+                            operator,
+                        }),
+                        Err(err) => error = Some(err),
+                    }
+                    return false;
+                }
+            }
+            true
+        });
+        if let Some(err) = error {
+            return Err(TokenStream::from(err.to_compile_error()));
+        }
+    }
+
+    // Get a list of the `Filter` fields.
     let field_names = structure
         .fields
         .iter()
         .filter_map(|field| field.ident.as_ref().map(|id| id.to_string()))
         .filter(|field| field != "options")
+        .filter(|field| !bounds.iter().any(|bound| bound.name == *field))
         .collect_vec();
 
-    // Copy the structure we decorate as-is, then add an `impl` block with the methods we need.
+    // Copy the structure we decorate, then add an `impl` block with the methods we need.
     let mut ret: TokenStream = structure.to_token_stream().into();
-    ret.extend(make_impl(&field_names, &structure, &filtered_type));
+    ret.extend(make_impl(&field_names, &bounds, &structure, &filtered_type));
     Ok(ret)
 }
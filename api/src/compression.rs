@@ -0,0 +1,237 @@
+use std::io::{Cursor, Write};
+
+use flate2::{
+    write::{GzEncoder, ZlibEncoder},
+    Compression as Level,
+};
+use rocket::{
+    fairing::{Fairing, Info, Kind},
+    http::Header,
+    Request, Response,
+};
+
+/// An encoding in which we can compress response bodies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    Gzip,
+    Deflate,
+}
+
+impl Encoding {
+    /// The name of the encoding, as in the `Content-Encoding` header.
+    fn name(self) -> &'static str {
+        match self {
+            Encoding::Gzip => "gzip",
+            Encoding::Deflate => "deflate",
+        }
+    }
+
+    /// Compress `body` with the encoding.
+    fn compress(self, body: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            Encoding::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), Level::default());
+                encoder.write_all(body)?;
+                encoder.finish()
+            }
+            Encoding::Deflate => {
+                let mut encoder = ZlibEncoder::new(Vec::new(), Level::default());
+                encoder.write_all(body)?;
+                encoder.finish()
+            }
+        }
+    }
+}
+
+/// Select the encoding to use from the values of the `Accept-Encoding` headers of a request.
+/// `gzip` is preferred over `deflate`. Encodings with a quality of 0 are not acceptable.
+fn select_encoding<'a, I>(accept_encoding: I) -> Option<Encoding>
+where
+    I: Iterator<Item = &'a str>,
+{
+    let accepted = accept_encoding
+        .flat_map(|value| value.split(','))
+        .filter_map(|coding| {
+            let mut parts = coding.split(';').map(str::trim);
+            let name = parts.next()?.to_ascii_lowercase();
+            let refused = parts
+                .filter_map(|param| param.strip_prefix("q="))
+                .any(|quality| quality.parse::<f32>().is_ok_and(|q| q <= 0.0));
+            (!refused).then_some(name)
+        })
+        .collect::<Vec<_>>();
+
+    [Encoding::Gzip, Encoding::Deflate]
+        .into_iter()
+        .find(|encoding| accepted.iter().any(|name| name == encoding.name()))
+}
+
+/// Fairing compressing response bodies with `gzip` or `deflate`, if the client accepts it.
+/// Only the responses to requests whose path starts with one of the given prefixes are
+/// compressed.
+pub struct Compression {
+    /// Prefixes of the paths whose responses are compressed.
+    prefixes: Vec<&'static str>,
+}
+
+impl Compression {
+    /// Compress the responses to requests whose path starts with any of `prefixes`.
+    pub fn new(prefixes: &[&'static str]) -> Self {
+        Self {
+            prefixes: prefixes.to_vec(),
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for Compression {
+    fn info(&self) -> Info {
+        Info {
+            name: "Compress responses",
+            kind: Kind::Response,
+        }
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        let path = request.uri().path();
+        if !self
+            .prefixes
+            .iter()
+            .any(|prefix| path.as_str().starts_with(prefix))
+            || response.headers().contains("Content-Encoding")
+        {
+            return;
+        }
+        let encoding = match select_encoding(request.headers().get("Accept-Encoding")) {
+            Some(encoding) => encoding,
+            None => return,
+        };
+
+        // Failing to compress is not fatal. The body is sent uncompressed.
+        let body = match response.body_mut().to_bytes().await {
+            Ok(body) => body,
+            Err(_) => return,
+        };
+        let (body, encoding) = match encoding.compress(&body) {
+            Ok(compressed) => (compressed, Some(encoding)),
+            Err(_) => (body, None),
+        };
+        response.set_sized_body(body.len(), Cursor::new(body));
+        response.set_header(Header::new("Vary", "Accept-Encoding"));
+        if let Some(encoding) = encoding {
+            response.set_header(Header::new("Content-Encoding", encoding.name()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Read;
+
+    use flate2::read::{GzDecoder, ZlibDecoder};
+    use rocket::{
+        http::{ContentType, Header},
+        local::blocking::Client,
+    };
+
+    use crate::{
+        compression::{select_encoding, Compression, Encoding},
+        cors::Cors,
+    };
+
+    #[post("/api/items")]
+    fn items() -> (ContentType, &'static str) {
+        (
+            ContentType::JSON,
+            r#"[{"id":1,"name":"Sword"},{"id":2,"name":"Bow"}]"#,
+        )
+    }
+
+    #[post("/other")]
+    fn other() -> &'static str {
+        "other"
+    }
+
+    fn client() -> Client {
+        let rocket = rocket::build()
+            .attach(Cors)
+            .attach(Compression::new(&["/api/items"]))
+            .mount("/", routes![items, other]);
+        Client::untracked(rocket).unwrap()
+    }
+
+    #[test]
+    fn test_select_encoding() {
+        assert_eq!(select_encoding(["gzip"].into_iter()), Some(Encoding::Gzip));
+        assert_eq!(
+            select_encoding(["deflate, gzip;q=0.5"].into_iter()),
+            Some(Encoding::Gzip)
+        );
+        assert_eq!(
+            select_encoding(["br", "deflate"].into_iter()),
+            Some(Encoding::Deflate)
+        );
+        assert_eq!(
+            select_encoding(["gzip;q=0, deflate"].into_iter()),
+            Some(Encoding::Deflate)
+        );
+        assert_eq!(select_encoding(["identity"].into_iter()), None);
+        assert_eq!(select_encoding(std::iter::empty()), None);
+    }
+
+    #[test]
+    fn test_gzip_response() {
+        let client = client();
+        let plain = client.post("/api/items").dispatch();
+        assert!(!plain.headers().contains("Content-Encoding"));
+        let plain = plain.into_bytes().unwrap();
+
+        let response = client
+            .post("/api/items")
+            .header(Header::new("Accept-Encoding", "gzip"))
+            .dispatch();
+        assert_eq!(response.headers().get_one("Content-Encoding"), Some("gzip"));
+        assert_eq!(
+            response.headers().get_one("Access-Control-Allow-Origin"),
+            Some("*")
+        );
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+        let compressed = response.into_bytes().unwrap();
+        assert_ne!(compressed, plain);
+        let mut decompressed = Vec::new();
+        GzDecoder::new(compressed.as_slice())
+            .read_to_end(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, plain);
+    }
+
+    #[test]
+    fn test_deflate_response() {
+        let client = client();
+        let plain = client.post("/api/items").dispatch().into_bytes().unwrap();
+        let response = client
+            .post("/api/items")
+            .header(Header::new("Accept-Encoding", "deflate"))
+            .dispatch();
+        assert_eq!(
+            response.headers().get_one("Content-Encoding"),
+            Some("deflate")
+        );
+        let mut decompressed = Vec::new();
+        ZlibDecoder::new(response.into_bytes().unwrap().as_slice())
+            .read_to_end(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, plain);
+    }
+
+    #[test]
+    fn test_other_routes_are_not_compressed() {
+        let client = client();
+        let response = client
+            .post("/other")
+            .header(Header::new("Accept-Encoding", "gzip"))
+            .dispatch();
+        assert!(!response.headers().contains("Content-Encoding"));
+        assert_eq!(response.into_string().unwrap(), "other");
+    }
+}
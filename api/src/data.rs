@@ -1,16 +1,41 @@
-use std::{collections::HashMap, sync::RwLock};
+use std::{collections::HashMap, sync::RwLock, time::SystemTime};
 
 use ornaguide_rs::{data::OrnaData, error::Error as OError};
 
 use lazy_static::{__Deref, lazy_static};
+use rocket::http::Status;
 
 use crate::error::{Error, ToErrorable};
 
 mod translations;
 
 lazy_static! {
-    pub static ref DATA: Result<RwLock<OrnaData>, OError> =
-        OrnaData::load_from("data/current_entries").map(RwLock::new);
+    pub static ref DATA: Result<RwLock<OrnaData>, OError> = {
+        let data = load_data().map(RwLock::new);
+        lazy_static::initialize(&DATA_LOADED_AT);
+        data
+    };
+    /// The time at which `DATA` finished loading.
+    pub static ref DATA_LOADED_AT: SystemTime = SystemTime::now();
+}
+
+/// Load the data the API serves.
+#[cfg(not(test))]
+fn load_data() -> Result<OrnaData, OError> {
+    OrnaData::load_from("data/current_entries")
+}
+
+/// Routes are tested against a small set of data, rather than that on disk.
+#[cfg(test)]
+fn load_data() -> Result<OrnaData, OError> {
+    let mut data = OrnaData::default();
+    data.guide.pets.pets.push(ornaguide_rs::with_defaults!(
+        ornaguide_rs::pets::admin::AdminPet {
+            id: 1,
+            name: "Cat".to_string(),
+        }
+    ));
+    Ok(data)
 }
 
 /// Run a callable with a reference to the `OrnaData`.
@@ -55,12 +80,39 @@ where
                 .to_internal_server_error()?
                 .deref();
 
-            if let Some(data) = locale_data.get(lang) {
-                f(data)
-            } else {
-                Err(OError::Misc(format!("Failed to find locale {}", lang)))
-                    .to_internal_server_error()
-            }
+            f(find_locale(locale_data, lang)?)
         }
     }
 }
+
+/// Find the data for the given locale.
+/// If there is no such locale, return an `Unprocessable Entity` error.
+fn find_locale<'a>(
+    locale_data: &'a HashMap<String, OrnaData>,
+    lang: &str,
+) -> Result<&'a OrnaData, Error> {
+    locale_data
+        .get(lang)
+        .ok_or_else(|| OError::Misc(format!("Failed to find locale {}", lang)))
+        .to_api_error(Status::UnprocessableEntity)
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use ornaguide_rs::data::OrnaData;
+    use rocket::http::Status;
+
+    use crate::data::find_locale;
+
+    #[test]
+    fn test_find_locale() {
+        let locale_data = HashMap::from([("de".to_string(), OrnaData::default())]);
+        assert!(find_locale(&locale_data, "de").is_ok());
+        assert_eq!(
+            find_locale(&locale_data, "xx").err().unwrap().status,
+            Status::UnprocessableEntity
+        );
+    }
+}
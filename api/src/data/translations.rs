@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use ornaguide_rs::{
-    codex::translation::{GenericMonsterTranslation, LocaleDB, TranslationFor},
+    codex::translation::{GenericMonsterTranslation, LocaleDB, LocaleStrings, TranslationFor},
     data::{CodexGenericMonster, OrnaData},
 };
 
@@ -24,11 +24,87 @@ where
     }
 }
 
+/// Return a copy of `data` with guide entities translated using `db`.
+/// Entities that have no translation in `db` are left as-is.
+fn localize(data: &OrnaData, db: &LocaleStrings) -> OrnaData {
+    let mut localized: OrnaData = data.clone();
+
+    // Translate items.
+    translate_with(&mut localized.guide.items.items, |item| {
+        data.codex
+            .items
+            .find_by_uri(&item.codex_uri)
+            .and_then(|codex_item| db.item(&codex_item.slug))
+            .cloned()
+    });
+
+    // Translate monsters.
+    translate_with(&mut localized.guide.monsters.monsters, |monster| {
+        // Monsters with a malformed `codex_uri` are left untranslated.
+        data.codex
+            .find_generic_monster_from_uri(&monster.codex_uri)
+            .ok()
+            .flatten()
+            .and_then(|codex_monster| match codex_monster {
+                CodexGenericMonster::Monster(x) => db
+                    .monster(&x.slug)
+                    .cloned()
+                    .map(GenericMonsterTranslation::Monster),
+                CodexGenericMonster::Boss(x) => db
+                    .boss(&x.slug)
+                    .cloned()
+                    .map(GenericMonsterTranslation::Boss),
+                CodexGenericMonster::Raid(x) => db
+                    .raid(&x.slug)
+                    .cloned()
+                    .map(GenericMonsterTranslation::Raid),
+            })
+    });
+
+    // Translate skills.
+    translate_with(&mut localized.guide.skills.skills, |skill| {
+        data.codex
+            .skills
+            .find_by_uri(&skill.codex_uri)
+            .and_then(|codex_skill| db.skill(&codex_skill.slug))
+            .cloned()
+    });
+
+    // Translate pets.
+    translate_with(&mut localized.guide.pets.pets, |pet| {
+        data.codex
+            .followers
+            .find_by_uri(&pet.codex_uri)
+            .and_then(|codex_follower| db.follower(&codex_follower.slug))
+            .cloned()
+    });
+
+    // Translate status effects.
+    for status in localized.guide.static_.status_effects.iter_mut() {
+        if let Some(localized_effect) = db.status(&status.name) {
+            status.name = localized_effect.to_string();
+        }
+    }
+    // Translate spawns.
+    for spawn in localized.guide.static_.spawns.iter_mut() {
+        if let Some(localized_spawn) = db.spawn(&spawn.name) {
+            spawn.name = localized_spawn.to_string();
+        }
+    }
+    // Translate monster families.
+    for family in localized.guide.static_.monster_families.iter_mut() {
+        if let Some(localized_family) = db.spawn(&family.name) {
+            family.name = localized_family.to_string();
+        }
+    }
+
+    localized
+}
+
 /// Generate multiple versions of `OrnaData`s, one for each locale we know of.
 pub(crate) fn generate_locale_data() -> Result<HashMap<String, OrnaData>, Error> {
     with_data(|data| {
-        let mut ret = HashMap::new();
-        for (lang, db) in LocaleDB::load_from("data/current_entries/i18n")
+        Ok(LocaleDB::load_from("data/current_entries/i18n")
             .and_then(|mut db| {
                 db.merge_with(LocaleDB::load_from("data/current_entries/i18n/manual")?);
                 Ok(db)
@@ -36,78 +112,64 @@ pub(crate) fn generate_locale_data() -> Result<HashMap<String, OrnaData>, Error>
             .to_internal_server_error()?
             .locales
             .into_iter()
-        {
-            let mut localized: OrnaData = data.clone();
-
-            // Translate items.
-            translate_with(&mut localized.guide.items.items, |item| {
-                data.codex
-                    .items
-                    .find_by_uri(&item.codex_uri)
-                    .and_then(|codex_item| db.item(&codex_item.slug))
-                    .cloned()
-            });
-
-            // Translate monsters.
-            translate_with(&mut localized.guide.monsters.monsters, |monster| {
-                data.codex
-                    .find_generic_monster_from_uri(&monster.codex_uri)
-                    .and_then(|codex_monster| match codex_monster {
-                        CodexGenericMonster::Monster(x) => db
-                            .monster(&x.slug)
-                            .cloned()
-                            .map(GenericMonsterTranslation::Monster),
-                        CodexGenericMonster::Boss(x) => db
-                            .boss(&x.slug)
-                            .cloned()
-                            .map(GenericMonsterTranslation::Boss),
-                        CodexGenericMonster::Raid(x) => db
-                            .raid(&x.slug)
-                            .cloned()
-                            .map(GenericMonsterTranslation::Raid),
-                    })
-            });
-
-            // Translate skills.
-            translate_with(&mut localized.guide.skills.skills, |skill| {
-                data.codex
-                    .skills
-                    .find_by_uri(&skill.codex_uri)
-                    .and_then(|codex_skill| db.skill(&codex_skill.slug))
-                    .cloned()
-            });
-
-            // Translate pets.
-            translate_with(&mut localized.guide.pets.pets, |pet| {
-                data.codex
-                    .followers
-                    .find_by_uri(&pet.codex_uri)
-                    .and_then(|codex_follower| db.follower(&codex_follower.slug))
-                    .cloned()
-            });
-
-            // Translate status effects.
-            for status in localized.guide.static_.status_effects.iter_mut() {
-                if let Some(localized_effect) = db.status(&status.name) {
-                    status.name = localized_effect.to_string();
-                }
-            }
-            // Translate spawns.
-            for spawn in localized.guide.static_.spawns.iter_mut() {
-                if let Some(localized_spawn) = db.spawn(&spawn.name) {
-                    spawn.name = localized_spawn.to_string();
-                }
-            }
-            // Translate monster families.
-            for family in localized.guide.static_.monster_families.iter_mut() {
-                if let Some(localized_family) = db.spawn(&family.name) {
-                    family.name = localized_family.to_string();
-                }
-            }
-
-            ret.insert(lang.clone(), localized);
+            .map(|(lang, db)| (lang, localize(data, &db)))
+            .collect())
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use ornaguide_rs::{
+        codex::{
+            translation::{ItemTranslation, LocaleStrings},
+            CodexItem,
+        },
+        data::OrnaData,
+        items::admin::AdminItem,
+        with_defaults,
+    };
+
+    use crate::data::translations::localize;
+
+    #[test]
+    fn test_localize_items() {
+        let mut data = OrnaData::default();
+        data.codex.items.items.push(CodexItem {
+            slug: "sword".to_string(),
+            name: "Sword".to_string(),
+            description: "A sword.".to_string(),
+            tier: 1,
+            ..CodexItem::default()
+        });
+        for (id, name, codex_uri) in [(1, "Sword", "/codex/items/sword/"), (2, "Stick", "")] {
+            data.guide.items.items.push(with_defaults!(AdminItem {
+                id,
+                name: name.to_string(),
+                codex_uri: codex_uri.to_string(),
+                description: format!("A {}.", name.to_lowercase()),
+            }));
         }
+        let db = LocaleStrings {
+            locale: "de".to_string(),
+            items: HashMap::from([(
+                "sword".to_string(),
+                ItemTranslation {
+                    name: "Schwert".to_string(),
+                    description: "Ein Schwert.".to_string(),
+                },
+            )]),
+            ..LocaleStrings::default()
+        };
 
-        Ok(ret)
-    })
+        let localized = localize(&data, &db);
+        let sword = localized.guide.items.find_by_id(1).unwrap();
+        assert_eq!(sword.name, "Schwert");
+        assert_eq!(sword.description, "Ein Schwert.");
+        // Untranslated items are left as-is.
+        assert_eq!(localized.guide.items.find_by_id(2).unwrap().name, "Stick");
+        // The original data is untouched.
+        assert_eq!(data.guide.items.find_by_id(1).unwrap().name, "Sword");
+    }
 }
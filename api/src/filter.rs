@@ -1,12 +1,19 @@
+use std::fmt::Display;
+
+use ornaguide_rs::error::Error as OError;
+use rocket::http::Status;
 use serde::{Deserialize, Serialize};
 
+use crate::error::{Error, ToErrorable};
+
 pub mod compilable;
 
 /// A field in a request which allows filtering the results.
-#[derive(Serialize, Deserialize)]
+#[derive(Default, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum Filter<'a, T> {
     /// No filter. Will always allow any item through.
+    #[default]
     None,
     /// An expression. Must start with an operator (`==`, `!=`, `>`, `<`, `>=`, `<=`) and be
     /// immediately followed by a string parseable into `T`.
@@ -18,11 +25,8 @@ pub enum Filter<'a, T> {
     Compiled(Box<dyn Fn(&T) -> bool + 'a>),
 }
 
-impl<'a, T> Default for Filter<'a, T> {
-    fn default() -> Self {
-        Self::None
-    }
-}
+/// A boxed predicate over entities, as returned by `Filter::into_fn`.
+pub type EntityFilterFn<'a, U> = Box<dyn Fn(&U) -> bool + 'a>;
 
 impl<'a, T> Filter<'a, T>
 where
@@ -48,7 +52,7 @@ where
 
     /// Return a closure capturing `self` and whose invocation runs the filter.
     /// If `self.is_none()` return `None`.
-    pub fn into_fn<U, F>(self, f: F) -> Option<Box<dyn Fn(&U) -> bool + 'a>>
+    pub fn into_fn<U, F>(self, f: F) -> Option<EntityFilterFn<'a, U>>
     where
         F: Fn(&U) -> &T + 'a,
     {
@@ -58,3 +62,41 @@ where
         }
     }
 }
+
+/// Check that the inclusive range given by the `min` and `max` bounds on `field` is not empty.
+/// Return an `Unprocessable Entity` error if `min > max`.
+pub fn check_range<T>(field: &str, min: Option<&T>, max: Option<&T>) -> Result<(), Error>
+where
+    T: PartialOrd + Display,
+{
+    match (min, max) {
+        (Some(min), Some(max)) if min > max => Err(OError::Misc(format!(
+            "Empty range for {}: minimum {} is greater than maximum {}",
+            field, min, max
+        )))
+        .to_api_error(Status::UnprocessableEntity),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rocket::http::Status;
+
+    use crate::filter::check_range;
+
+    #[test]
+    fn test_check_range() {
+        assert!(check_range("tier", Some(&5), Some(&8)).is_ok());
+        assert!(check_range("tier", Some(&8), Some(&8)).is_ok());
+        assert!(check_range("tier", None, Some(&8)).is_ok());
+        assert!(check_range::<u8>("tier", None, None).is_ok());
+        assert_eq!(
+            check_range("tier", Some(&9), Some(&8))
+                .err()
+                .unwrap()
+                .status,
+            Status::UnprocessableEntity
+        );
+    }
+}
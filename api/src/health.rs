@@ -0,0 +1,88 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ornaguide_rs::data::OrnaData;
+use rocket::{http::Status, serde::json::Json};
+use serde_json::{json, Value};
+
+use crate::data::{with_data, DATA_LOADED_AT};
+
+/// Build the health report of the given data, along with the status to answer with.
+/// The data is healthy only if none of its collections is empty.
+fn health_from(data: &OrnaData, loaded_at: SystemTime) -> (Status, Value) {
+    let item_count = data.guide.items.items.len();
+    let monster_count = data.guide.monsters.monsters.len();
+    let skill_count = data.guide.skills.skills.len();
+    let healthy = item_count > 0 && monster_count > 0 && skill_count > 0;
+
+    (
+        if healthy {
+            Status::Ok
+        } else {
+            Status::ServiceUnavailable
+        },
+        json!({
+            "status": if healthy { "ok" } else { "unavailable" },
+            "item_count": item_count,
+            "monster_count": monster_count,
+            "skill_count": skill_count,
+            "loaded_at": loaded_at
+                .duration_since(UNIX_EPOCH)
+                .map(|since_epoch| since_epoch.as_secs())
+                .unwrap_or_default(),
+        }),
+    )
+}
+
+/// Report whether the API has data to serve.
+/// Answers `200 OK` if all collections are loaded and non-empty, `503 Service Unavailable`
+/// otherwise. `loaded_at` is a UNIX timestamp, in seconds.
+#[get("/health")]
+pub fn get() -> (Status, Json<Value>) {
+    let (status, health) =
+        with_data(|data| Ok(health_from(data, *DATA_LOADED_AT))).unwrap_or_else(|err| {
+            (
+                Status::ServiceUnavailable,
+                json!({ "status": "unavailable", "error": format!("{}", err.error) }),
+            )
+        });
+    (status, Json(health))
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use ornaguide_rs::{
+        data::OrnaData, items::admin::AdminItem, monsters::admin::AdminMonster,
+        skills::admin::AdminSkill,
+    };
+    use rocket::http::Status;
+    use serde_json::json;
+
+    use crate::health::health_from;
+
+    #[test]
+    fn test_health() {
+        let loaded_at = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let mut data = OrnaData::default();
+        data.guide.items.items.push(AdminItem::default());
+        data.guide.items.items.push(AdminItem::default());
+        data.guide.monsters.monsters.push(AdminMonster::default());
+        assert_eq!(health_from(&data, loaded_at).0, Status::ServiceUnavailable);
+
+        data.guide.skills.skills.push(AdminSkill::default());
+        assert_eq!(
+            health_from(&data, loaded_at),
+            (
+                Status::Ok,
+                json!({
+                    "status": "ok",
+                    "item_count": 2,
+                    "monster_count": 1,
+                    "skill_count": 1,
+                    "loaded_at": 1_700_000_000,
+                })
+            )
+        );
+    }
+}
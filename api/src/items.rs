@@ -5,7 +5,7 @@ use rocket::serde::json::Json;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    data::with_locale_data,
+    data::{with_data, with_locale_data},
     deref::{
         deref_element, deref_equipped_bys, deref_item_category, deref_item_type, deref_items,
         deref_skill, deref_status_effects,
@@ -29,6 +29,12 @@ pub struct ItemFilters<'a> {
     pub name: Filter<'a, String>,
     /// Filter by tier.
     pub tier: Filter<'a, u8>,
+    /// Only keep items whose tier is at least this value.
+    #[filter_min(tier)]
+    pub tier_min: Option<u8>,
+    /// Only keep items whose tier is at most this value.
+    #[filter_max(tier)]
+    pub tier_max: Option<u8>,
     /// Filter by type_.
     pub type_: Filter<'a, u32>,
     /// Filter by image_name.
@@ -96,6 +102,7 @@ pub struct ItemFilters<'a> {
     /// Filter by element.
     pub element: Filter<'a, Option<u32>>,
     /// Filter by equipped_by.
+    /// Classes may be given by name or by id. See `resolve_equipped_by_names`.
     pub equipped_by: Filter<'a, Vec<u32>>,
     /// Filter by two_handed.
     pub two_handed: Filter<'a, bool>,
@@ -109,19 +116,23 @@ pub struct ItemFilters<'a> {
     pub spawn_bonus: Filter<'a, f32>,
     /// Filter by exp_bonus.
     pub exp_bonus: Filter<'a, f32>,
-    // Filter by boss.
+    /// Filter by boss.
     pub boss: Filter<'a, bool>,
     /// Filter by arena.
     pub arena: Filter<'a, bool>,
     /// Filter by category.
     pub category: Filter<'a, Option<u32>>,
     /// Filter by causes.
+    /// Status effects may be given by name or by id. See `resolve_status_effect_names`.
     pub causes: Filter<'a, Vec<u32>>,
     /// Filter by cures.
+    /// Status effects may be given by name or by id. See `resolve_status_effect_names`.
     pub cures: Filter<'a, Vec<u32>>,
     /// Filter by gives.
+    /// Status effects may be given by name or by id. See `resolve_status_effect_names`.
     pub gives: Filter<'a, Vec<u32>>,
     /// Filter by prevents.
+    /// Status effects may be given by name or by id. See `resolve_status_effect_names`.
     pub prevents: Filter<'a, Vec<u32>>,
     /// Filter by materials.
     pub materials: Filter<'a, Vec<u32>>,
@@ -139,6 +150,38 @@ impl ItemFilters<'_> {
         &data.guide.items.items
     }
 
+    /// Replace status effect names with their ids in the `causes`, `cures`, `gives` and
+    /// `prevents` filters. Names are resolved against the (English) static status effects.
+    ///
+    /// Array expressions (`[x, y]`, `&[x, y]`, `|[x, y]`, `![x, y]`) may mix names and ids.
+    /// Any other expression is a comma-separated list of status effects which must all be present
+    /// on the item (e.g.: `"Burning, Blind"` is equivalent to `"&[Burning, Blind]"`).
+    fn resolve_status_effect_names(&mut self, data: &OrnaData) -> Result<(), Error> {
+        for filter in [
+            &mut self.causes,
+            &mut self.cures,
+            &mut self.gives,
+            &mut self.prevents,
+        ] {
+            if let Filter::Expr(expr) = filter {
+                *expr = resolve_status_effect_expr(expr, data).to_bad_request()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Replace class names with their ids in the `equipped_by` filter. Names are resolved against
+    /// the (English) static `equipped_by`s.
+    ///
+    /// Expressions follow the same rules as status effects (see `resolve_status_effect_names`):
+    /// `"Mage, Thief"` matches items that can be equipped by both classes.
+    fn resolve_equipped_by_names(&mut self, data: &OrnaData) -> Result<(), Error> {
+        if let Filter::Expr(expr) = &mut self.equipped_by {
+            *expr = resolve_equipped_by_expr(expr, data).to_bad_request()?;
+        }
+        Ok(())
+    }
+
     /// Dereference IDs to the name of the entity they refer to.
     fn deref(items: &mut serde_json::Value, data: &OrnaData) -> Result<(), Error> {
         if let serde_json::Value::Array(items) = items {
@@ -190,6 +233,58 @@ impl ItemFilters<'_> {
     }
 }
 
+/// Rewrite a status effect filter expression so that it only contains ids.
+fn resolve_status_effect_expr(expr: &str, data: &OrnaData) -> Result<String, OError> {
+    resolve_names_expr(expr, "status effect", |name| {
+        data.guide
+            .static_
+            .status_effects
+            .iter()
+            .find(|effect| effect.name.eq_ignore_ascii_case(name))
+            .map(|effect| effect.id)
+    })
+}
+
+/// Rewrite an `equipped_by` filter expression so that it only contains ids.
+fn resolve_equipped_by_expr(expr: &str, data: &OrnaData) -> Result<String, OError> {
+    resolve_names_expr(expr, "class", |name| {
+        data.guide
+            .static_
+            .equipped_bys
+            .iter()
+            .find(|equipped_by| equipped_by.name.eq_ignore_ascii_case(name))
+            .map(|equipped_by| equipped_by.id)
+    })
+}
+
+/// Rewrite an array filter expression so that it only contains ids.
+/// Values that are not ids are looked up with `find_id`. `kind` names the entities in errors.
+fn resolve_names_expr<F>(expr: &str, kind: &str, find_id: F) -> Result<String, OError>
+where
+    F: Fn(&str) -> Option<u32>,
+{
+    let expr = expr.trim();
+    let (prefix, list) = match expr.find('[') {
+        Some(pos) if expr.ends_with(']') && ["", "&", "|", "!"].contains(&&expr[..pos]) => {
+            (&expr[..pos], &expr[pos + 1..expr.len() - 1])
+        }
+        _ => ("&", expr),
+    };
+
+    let ids = list
+        .split(',')
+        .map(str::trim)
+        .map(|value| {
+            if let Ok(id) = value.parse::<u32>() {
+                Ok(id)
+            } else {
+                find_id(value).ok_or_else(|| OError::Misc(format!("Unknown {}: '{}'", kind, value)))
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(format!("{}[{}]", prefix, ids.iter().join(",")))
+}
+
 make_post_impl!(ItemFilters);
 
 /// Query for items.
@@ -197,8 +292,13 @@ make_post_impl!(ItemFilters);
 /// Even when using no filter, the body should be an empty JSON object (`{}`).
 #[post("/items", format = "json", data = "<filters>")]
 pub fn post(filters: Json<ItemFilters>) -> MaybeResponse {
+    let mut filters = filters.into_inner();
     MaybeResponse {
-        contents: post_impl(filters.into_inner()),
+        contents: with_data(|data| {
+            filters.resolve_status_effect_names(data)?;
+            filters.resolve_equipped_by_names(data)
+        })
+        .and_then(|_| post_impl(filters)),
     }
 }
 
@@ -207,3 +307,205 @@ pub fn post(filters: Json<ItemFilters>) -> MaybeResponse {
 pub fn options() -> &'static str {
     ""
 }
+
+#[cfg(test)]
+mod test {
+    use itertools::Itertools;
+    use ornaguide_rs::{
+        data::OrnaData,
+        guide::{EquippedBy, StatusEffect},
+        items::admin::AdminItem,
+        with_defaults,
+    };
+
+    use crate::{
+        filter::Filter,
+        items::{resolve_equipped_by_expr, resolve_status_effect_expr, ItemFilters},
+    };
+
+    fn data() -> OrnaData {
+        let mut data = OrnaData::default();
+        data.guide.static_.status_effects = vec![
+            StatusEffect {
+                id: 1,
+                name: "Burning".to_string(),
+            },
+            StatusEffect {
+                id: 2,
+                name: "Frozen".to_string(),
+            },
+            StatusEffect {
+                id: 3,
+                name: "Blind".to_string(),
+            },
+        ];
+        data.guide.static_.equipped_bys = vec![
+            EquippedBy {
+                id: 1,
+                name: "Warrior".to_string(),
+            },
+            EquippedBy {
+                id: 2,
+                name: "Mage".to_string(),
+            },
+            EquippedBy {
+                id: 3,
+                name: "Thief".to_string(),
+            },
+        ];
+        for (id, name, causes, equipped_by) in [
+            (1, "Fire Sword", vec![1], vec![1]),
+            (2, "Ice Sword", vec![2], vec![1, 3]),
+            (3, "Fire Bow", vec![1, 3], vec![3]),
+            (4, "Wooden Sword", vec![], vec![1]),
+            (5, "Iron", vec![], vec![1, 2, 3]),
+        ] {
+            data.guide.items.items.push(with_defaults!(AdminItem {
+                id,
+                name: name.to_string(),
+                causes,
+                equipped_by,
+            }));
+        }
+        data
+    }
+
+    fn filter_names(mut filters: ItemFilters, data: &OrnaData) -> Vec<String> {
+        assert!(filters.resolve_status_effect_names(data).is_ok());
+        assert!(filters.resolve_equipped_by_names(data).is_ok());
+        let filters = filters.compiled().ok().unwrap().into_fn_vec();
+        data.guide
+            .items
+            .items
+            .iter()
+            .filter(|item| filters.iter().all(|f| f(item)))
+            .map(|item| item.name.clone())
+            .collect_vec()
+    }
+
+    #[test]
+    fn test_filter_by_status_effect_name() {
+        let data = data();
+        let causes = |expr: &str| ItemFilters {
+            causes: Filter::Expr(expr.to_string()),
+            ..ItemFilters::default()
+        };
+
+        assert_eq!(
+            filter_names(causes("Burning"), &data),
+            vec!["Fire Sword", "Fire Bow"]
+        );
+        assert_eq!(
+            filter_names(causes("burning, Blind"), &data),
+            vec!["Fire Bow"]
+        );
+        assert_eq!(
+            filter_names(causes("|[Frozen, 3]"), &data),
+            vec!["Ice Sword", "Fire Bow"]
+        );
+        assert_eq!(
+            filter_names(causes("![Burning]"), &data),
+            vec!["Ice Sword", "Wooden Sword", "Iron"]
+        );
+        assert!(resolve_status_effect_expr("Poisoned", &data).is_err());
+    }
+
+    #[test]
+    fn test_filter_by_equipped_by() {
+        let data = data();
+        let equipped_by = |expr: &str| ItemFilters {
+            equipped_by: Filter::Expr(expr.to_string()),
+            ..ItemFilters::default()
+        };
+
+        // Materials can be equipped by all classes and are always included.
+        assert_eq!(filter_names(equipped_by("Mage"), &data), vec!["Iron"]);
+        assert_eq!(
+            filter_names(equipped_by("thief"), &data),
+            vec!["Ice Sword", "Fire Bow", "Iron"]
+        );
+        assert_eq!(
+            filter_names(equipped_by("Warrior, Thief"), &data),
+            vec!["Ice Sword", "Iron"]
+        );
+        assert_eq!(filter_names(equipped_by("&[1, Mage]"), &data), vec!["Iron"]);
+        assert!(resolve_equipped_by_expr("Paladin", &data).is_err());
+    }
+
+    #[test]
+    fn test_filter_boss() {
+        let mut data = OrnaData::default();
+        for (name, tier, boss, arena) in [
+            ("Sword", 5, false, false),
+            ("Boss Sword", 5, true, false),
+            ("Boss Bow", 7, true, false),
+            ("Arena Bow", 7, false, true),
+        ] {
+            data.guide.items.items.push(with_defaults!(AdminItem {
+                name: name.to_string(),
+                tier,
+                boss,
+                arena,
+            }));
+        }
+
+        assert_eq!(
+            filter_names(
+                ItemFilters {
+                    boss: Filter::Value(true),
+                    ..ItemFilters::default()
+                },
+                &data
+            ),
+            vec!["Boss Sword", "Boss Bow"]
+        );
+        assert_eq!(
+            filter_names(
+                ItemFilters {
+                    boss: Filter::Value(true),
+                    tier: Filter::Value(7),
+                    ..ItemFilters::default()
+                },
+                &data
+            ),
+            vec!["Boss Bow"]
+        );
+        assert_eq!(
+            filter_names(
+                ItemFilters {
+                    boss: Filter::Value(false),
+                    arena: Filter::Value(false),
+                    ..ItemFilters::default()
+                },
+                &data
+            ),
+            vec!["Sword"]
+        );
+    }
+
+    #[test]
+    fn test_filter_tier_range() {
+        let mut data = OrnaData::default();
+        for tier in 1..=10 {
+            data.guide.items.items.push(with_defaults!(AdminItem {
+                name: format!("T{}", tier),
+                tier,
+            }));
+        }
+        let filters = ItemFilters {
+            tier_min: Some(5),
+            tier_max: Some(8),
+            ..ItemFilters::default()
+        };
+
+        assert!(filters.check_bounds().is_ok());
+        assert_eq!(filter_names(filters, &data), vec!["T5", "T6", "T7", "T8"]);
+        assert!(ItemFilters {
+            tier_min: Some(8),
+            tier_max: Some(5),
+            ..ItemFilters::default()
+        }
+        .check_bounds()
+        .is_err());
+    }
+}
@@ -0,0 +1,258 @@
+use std::{
+    io::Write,
+    str::FromStr,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use ornaguide_rs::error::Error;
+use rocket::{
+    fairing::{Fairing, Info, Kind},
+    http::Method,
+    Data, Request, Response,
+};
+
+/// Format of the request logs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// A human-readable line: `POST /api/v0.1/items 200 1.234ms req=12B res=3456B`.
+    Plain,
+    /// One json object per line.
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "plain" => Ok(LogFormat::Plain),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(format!("Invalid log format: {}", s)),
+        }
+    }
+}
+
+/// The time at which the request was received, stored in the request-local cache.
+struct RequestStart(Option<Instant>);
+
+/// The method and path of a request before a fairing rewrote them, stored in the request-local
+/// cache. Fairings that rewrite requests (e.g.: `RateLimiter`) set it, so that the request the
+/// client sent is logged rather than the rewritten one.
+pub struct OriginalRequest(pub Option<(Method, String)>);
+
+/// A completed request, as it is logged.
+struct LogRecord<'a> {
+    method: &'a str,
+    path: &'a str,
+    status: u16,
+    duration: Duration,
+    /// Size of the request body, if the client sent a `Content-Length`.
+    request_size: Option<u64>,
+    /// Size of the response body, if known.
+    response_size: Option<usize>,
+}
+
+impl LogRecord<'_> {
+    /// Format the record as a single line, without the trailing newline.
+    fn format(&self, format: LogFormat) -> String {
+        let duration_ms = self.duration.as_secs_f64() * 1000.0;
+        match format {
+            LogFormat::Plain => format!(
+                "{} {} {} {:.3}ms req={} res={}",
+                self.method,
+                self.path,
+                self.status,
+                duration_ms,
+                size_to_string(self.request_size),
+                size_to_string(self.response_size.map(|size| size as u64)),
+            ),
+            LogFormat::Json => serde_json::json!({
+                "method": self.method,
+                "path": self.path,
+                "status": self.status,
+                "duration_ms": duration_ms,
+                "request_size": self.request_size,
+                "response_size": self.response_size,
+            })
+            .to_string(),
+        }
+    }
+}
+
+/// Format a body size for plain logs.
+fn size_to_string(size: Option<u64>) -> String {
+    match size {
+        Some(size) => format!("{}B", size),
+        None => "-".to_string(),
+    }
+}
+
+/// Fairing logging the method, path, status, duration and body sizes of each request.
+/// Request bodies are never logged, as they may contain large filters.
+pub struct RequestLogger {
+    /// The format of the log lines.
+    format: LogFormat,
+    /// Where the log lines are written.
+    output: Mutex<Box<dyn Write + Send>>,
+}
+
+impl RequestLogger {
+    /// Create a logger writing to stdout.
+    pub fn new(format: LogFormat) -> Self {
+        Self::with_writer(format, Box::new(std::io::stdout()))
+    }
+
+    /// Create a logger writing to the given output.
+    pub fn with_writer(format: LogFormat, output: Box<dyn Write + Send>) -> Self {
+        Self {
+            format,
+            output: Mutex::new(output),
+        }
+    }
+
+    /// Create a logger writing to stdout, whose format is read from the `API_LOG_FORMAT`
+    /// environment variable (`plain` or `json`). Defaults to `plain`.
+    /// Returns an error if the variable is set to any other value.
+    pub fn from_env() -> Result<Self, Error> {
+        let format = match std::env::var("API_LOG_FORMAT") {
+            Ok(format) => format.parse().map_err(Error::Misc)?,
+            Err(_) => LogFormat::Plain,
+        };
+        Ok(Self::new(format))
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for RequestLogger {
+    fn info(&self) -> Info {
+        Info {
+            name: "Log requests",
+            kind: Kind::Request | Kind::Response,
+        }
+    }
+
+    async fn on_request(&self, request: &mut Request<'_>, _: &mut Data<'_>) {
+        request.local_cache(|| RequestStart(Some(Instant::now())));
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        let duration = request
+            .local_cache(|| RequestStart(None))
+            .0
+            .map(|start| start.elapsed())
+            .unwrap_or_default();
+        let path = request.uri().path();
+        let (method, path) = match &request.local_cache(|| OriginalRequest(None)).0 {
+            Some((method, path)) => (*method, path.as_str()),
+            None => (request.method(), path.as_str()),
+        };
+        let record = LogRecord {
+            method: method.as_str(),
+            path,
+            status: response.status().code,
+            duration,
+            request_size: request
+                .headers()
+                .get_one("Content-Length")
+                .and_then(|size| size.parse().ok()),
+            response_size: response.body().preset_size(),
+        };
+
+        if let Ok(mut output) = self.output.lock() {
+            let _ = writeln!(output, "{}", record.format(self.format));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        io::Write,
+        sync::{Arc, Mutex},
+    };
+
+    use rocket::local::blocking::Client;
+
+    use crate::{
+        logger::{LogFormat, RequestLogger},
+        rate_limit::RateLimiter,
+    };
+
+    /// A buffer that can be shared between the logger and the test.
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[post("/echo", data = "<body>")]
+    fn echo(body: String) -> String {
+        body
+    }
+
+    fn logs_for(format: LogFormat) -> String {
+        let buffer = SharedBuffer::default();
+        let rocket = rocket::build()
+            .attach(RequestLogger::with_writer(format, Box::new(buffer.clone())))
+            .mount("/", routes![echo]);
+        let client = Client::untracked(rocket).unwrap();
+        client.post("/echo").body("hello").dispatch();
+        let logs = buffer.0.lock().unwrap().clone();
+        String::from_utf8(logs).unwrap()
+    }
+
+    #[test]
+    fn test_rate_limited_log() {
+        let buffer = SharedBuffer::default();
+        let rocket = rocket::build()
+            .attach(RateLimiter::new(0.001, 1, vec![]))
+            .attach(RequestLogger::with_writer(
+                LogFormat::Plain,
+                Box::new(buffer.clone()),
+            ))
+            .mount("/", routes![echo]);
+        let client = Client::untracked(rocket).unwrap();
+        for _ in 0..2 {
+            client
+                .post("/echo")
+                .remote("10.0.0.1:1234".parse().unwrap())
+                .body("hello")
+                .dispatch();
+        }
+
+        // The throttled request is logged as the client sent it.
+        let logs = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines = logs.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("POST /echo 200 "));
+        assert!(lines[1].starts_with("POST /echo 429 "));
+    }
+
+    #[test]
+    fn test_plain_log() {
+        let logs = logs_for(LogFormat::Plain);
+        assert_eq!(logs.lines().count(), 1);
+        assert!(logs.starts_with("POST /echo 200 "));
+        assert!(logs.trim_end().ends_with("res=5B"));
+    }
+
+    #[test]
+    fn test_json_log() {
+        let logs = logs_for(LogFormat::Json);
+        let record: serde_json::Value = serde_json::from_str(logs.trim_end()).unwrap();
+        assert_eq!(record["method"], "POST");
+        assert_eq!(record["path"], "/echo");
+        assert_eq!(record["status"], 200);
+        assert_eq!(record["response_size"], 5);
+        assert!(record["duration_ms"].as_f64().is_some());
+        assert!(record.get("body").is_none());
+    }
+}
@@ -5,23 +5,49 @@ extern crate lazy_static;
 
 use std::{net::IpAddr, str::FromStr};
 
-use rocket::{routes, Config};
+use rocket::{
+    fairing::{AdHoc, Fairing},
+    routes, Config,
+};
 
-use crate::data::DATA;
+use crate::data::{DATA, LOCALE_DATA};
 
+mod compression;
 mod cors;
 mod data;
 mod deref;
 mod error;
 mod filter;
+mod health;
 mod items;
+mod logger;
+mod meta;
 mod misc;
 mod monsters;
 mod options;
 mod pets;
+mod rate_limit;
 mod rocket_utils;
 mod sirscor;
 mod skills;
+mod static_;
+
+/// Attach the fairing created from the environment on ignition.
+/// If the environment is misconfigured, the error is printed and the launch aborted.
+fn attach_from_env<F: Fairing>(
+    name: &'static str,
+    from_env: fn() -> Result<F, ornaguide_rs::error::Error>,
+) -> AdHoc {
+    AdHoc::try_on_ignite(name, move |rocket| async move {
+        match from_env() {
+            Ok(fairing) => Ok(rocket.attach(fairing)),
+            Err(e) => {
+                eprintln!("{}: {}", name, e);
+                Err(rocket)
+            }
+        }
+    })
+}
 
 #[launch]
 fn rocket() -> _ {
@@ -34,21 +60,46 @@ fn rocket() -> _ {
     if let Err(e) = DATA.as_ref() {
         panic!("{}", e);
     }
+    // Translations are optional. Requests with a locale fail if they could not be loaded.
+    if let Err(e) = LOCALE_DATA.as_ref() {
+        eprintln!("Failed to load translations: {}", e.error);
+    }
 
     rocket::custom(&config)
         .attach(cors::Cors)
+        .attach(compression::Compression::new(&[
+            "/api/v0.1/items",
+            "/api/v0.1/monsters",
+            "/api/v0.1/pets",
+            "/api/v0.1/skills",
+        ]))
+        // The rate limiter must come first for the logger to see the status of rate-limited
+        // requests.
+        .attach(attach_from_env(
+            "Rate limiter",
+            rate_limit::RateLimiter::from_env,
+        ))
+        .attach(attach_from_env(
+            "Request logger",
+            logger::RequestLogger::from_env,
+        ))
         .mount(
             "/api/v0.1",
             routes![
                 items::options,
                 items::post,
+                meta::counts,
                 monsters::options,
                 monsters::post,
                 pets::options,
                 pets::post,
                 skills::options,
                 skills::post,
+                static_::get,
+                static_::get_table,
+                static_::options,
+                static_::options_table,
             ],
         )
-        .mount("/", routes![sirscor::get])
+        .mount("/", routes![health::get, sirscor::get])
 }
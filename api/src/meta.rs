@@ -0,0 +1,49 @@
+use ornaguide_rs::data::OrnaData;
+use serde_json::{json, Value};
+
+use crate::{data::with_data, error::MaybeResponse};
+
+/// Build the counts of items and skills, by type.
+fn counts_from(data: &OrnaData) -> Value {
+    json!({
+        "items": data.guide.items.counts_by_type(&data.guide.static_),
+        "skills": data.guide.skills.counts_by_type(&data.guide.static_),
+    })
+}
+
+/// Return the number of items and skills of each type.
+#[get("/meta/counts")]
+pub fn counts() -> MaybeResponse {
+    MaybeResponse {
+        contents: with_data(|data| Ok(counts_from(data))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ornaguide_rs::{
+        data::OrnaData,
+        guide::SkillType,
+        skills::admin::{AdminSkill, AdminSkills},
+        with_defaults,
+    };
+    use serde_json::json;
+
+    use crate::meta::counts_from;
+
+    #[test]
+    fn test_counts() {
+        let mut data = OrnaData::default();
+        data.guide.static_.skill_types = vec![SkillType {
+            id: 4,
+            name: "Magic".to_string(),
+        }];
+        let skill = with_defaults!(AdminSkill { type_: 4 });
+        data.guide.skills = AdminSkills::from(vec![skill.clone(), skill]);
+
+        assert_eq!(
+            counts_from(&data),
+            json!({ "items": {}, "skills": { "Magic": 2 } })
+        );
+    }
+}
@@ -4,7 +4,13 @@
 ///   - Get the array we're interested in from the `OrnaData`
 ///   - Apply filters (if there are)
 ///   - Apply sorting (if there is)
+///   - Apply pagination (if there is)
 ///   - Convert to JSON
+///
+/// When `offset` or `limit` is set in the options, the entities are returned in an object
+/// alongside the total number of entities matching the filters:
+/// `{ "total_count": 42, "entities": [...] }`. Otherwise, the array of entities is returned.
+///
 /// The function has the following signature:
 /// `fn (mut $filter_type) -> Result<serde_json::Value, $crate::error::Error>`
 #[macro_export]
@@ -14,6 +20,7 @@ macro_rules! make_post_impl {
         pub fn post_impl(
             mut filters: $filter_type,
         ) -> Result<serde_json::Value, $crate::error::Error> {
+            filters.check_bounds()?;
             let options = filters.options.extract();
             with_locale_data(
                 |data| {
@@ -34,19 +41,28 @@ macro_rules! make_post_impl {
                 <$filter_type>::apply_sort(&options, &mut entity)?;
                 Ok(entity)
             })
-            .and_then(|entities| {
+            .map(|entities| options.paginate(entities))
+            .and_then(|(entities, total_count)| {
                 serde_json::to_value(entities)
+                    .map(|entities| (entities, total_count))
                     .map_err(ornaguide_rs::error::Error::from)
                     .to_internal_server_error()
             })
-            .and_then(|mut entities| {
+            .and_then(|(mut entities, total_count)| {
                 if options.deref {
                     with_locale_data(
                         |data| <$filter_type>::deref(&mut entities, data),
                         &options.lang,
                     )?
                 }
-                Ok(entities)
+                if options.is_paginated() {
+                    Ok(serde_json::json!({
+                        "total_count": total_count,
+                        "entities": entities,
+                    }))
+                } else {
+                    Ok(entities)
+                }
             })
         }
     };
@@ -29,6 +29,12 @@ pub struct MonsterFilters<'a> {
     pub name: Filter<'a, String>,
     /// Filter by tier.
     pub tier: Filter<'a, u8>,
+    /// Only keep monsters whose tier is at least this value.
+    #[filter_min(tier)]
+    pub tier_min: Option<u8>,
+    /// Only keep monsters whose tier is at most this value.
+    #[filter_max(tier)]
+    pub tier_max: Option<u8>,
     /// Filter by family.
     pub family: Filter<'a, Option<u32>>,
     /// Filter by image_name.
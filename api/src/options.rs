@@ -13,6 +13,10 @@ pub struct Options {
     pub sort_by: Option<String>,
     /// Whether sort should be descending (default is ascending).
     pub sort_descending: bool,
+    /// Number of entities to skip, after filtering and sorting.
+    pub offset: Option<usize>,
+    /// Maximum number of entities to return, after filtering and sorting.
+    pub limit: Option<usize>,
 }
 
 impl Options {
@@ -24,16 +28,62 @@ impl Options {
             deref: self.deref,
             sort_by: self.sort_by.replace(String::new()),
             sort_descending: self.sort_descending,
+            offset: self.offset,
+            limit: self.limit,
         };
         *self = Self::default();
         ret
     }
+
+    /// Whether the request asked for a page of the results rather than all of them.
+    pub fn is_paginated(&self) -> bool {
+        self.offset.is_some() || self.limit.is_some()
+    }
+
+    /// Keep only the page of `entities` requested by `offset` and `limit`.
+    /// Returns the page and the total number of entities.
+    pub fn paginate<T>(&self, entities: Vec<T>) -> (Vec<T>, usize) {
+        let total_count = entities.len();
+        let page = entities
+            .into_iter()
+            .skip(self.offset.unwrap_or(0))
+            .take(self.limit.unwrap_or(usize::MAX))
+            .collect();
+        (page, total_count)
+    }
 }
 
 /// Trait to be implemented by entity holding IDs which can be dereferenced.
 /// For instance, monsters have abilities that the API will by default return as IDs. Running the
 /// monster through this trait will change the IDs to the abilities' names.
+#[allow(dead_code)]
 pub trait IdDerefable {
     /// Turn `self` to a serde value and replace IDs to names.
     fn id_deref(&self, data: &OrnaData) -> Result<serde_json::Value, Error>;
 }
+
+#[cfg(test)]
+mod test {
+    use crate::options::Options;
+
+    #[test]
+    fn test_paginate() {
+        let entities = (0..10).collect::<Vec<_>>();
+        let page = |offset, limit| {
+            Options {
+                offset,
+                limit,
+                ..Options::default()
+            }
+            .paginate(entities.clone())
+        };
+
+        assert!(!Options::default().is_paginated());
+        assert_eq!(page(None, None), (entities.clone(), 10));
+        assert_eq!(page(Some(2), Some(3)), (vec![2, 3, 4], 10));
+        assert_eq!(page(Some(8), Some(5)), (vec![8, 9], 10));
+        assert_eq!(page(None, Some(2)), (vec![0, 1], 10));
+        assert_eq!(page(Some(42), Some(5)), (vec![], 10));
+        assert_eq!(page(Some(42), None), (vec![], 10));
+    }
+}
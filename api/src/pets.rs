@@ -30,6 +30,12 @@ pub struct PetFilters<'a> {
     pub name: Filter<'a, String>,
     /// Filter by tier.
     pub tier: Filter<'a, u8>,
+    /// Only keep pets whose tier is at least this value.
+    #[filter_min(tier)]
+    pub tier_min: Option<u8>,
+    /// Only keep pets whose tier is at most this value.
+    #[filter_max(tier)]
+    pub tier_max: Option<u8>,
     /// Filter by image_name.
     pub image_name: Filter<'a, String>,
     /// Filter by description.
@@ -0,0 +1,230 @@
+use std::{
+    collections::HashMap, io::Cursor, net::IpAddr, str::FromStr, sync::Mutex, time::Instant,
+};
+
+use ornaguide_rs::error::Error;
+use rocket::{
+    fairing::{Fairing, Info, Kind},
+    http::{Method, Status},
+    Data, Request, Response,
+};
+
+use crate::logger::OriginalRequest;
+
+/// The path to which rate-limited requests are redirected.
+/// No route is mounted there, so that no handler runs for rate-limited requests.
+const RATE_LIMITED_PATH: &str = "/__rate_limited";
+
+/// Above this number of tracked clients, buckets that are full again are dropped.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// Whether the request was rate-limited, stored in the request-local cache.
+struct RateLimited(bool);
+
+/// The tokens left for a client.
+struct Bucket {
+    /// Number of requests the client can still send right away.
+    tokens: f64,
+    /// Last time the bucket was refilled.
+    last_refill: Instant,
+}
+
+/// Fairing limiting the number of requests per client IP, using a token bucket.
+/// Each client may send `burst` requests at once, after which they are allowed `rate` requests
+/// per second. Requests past the limit are answered with a `429 Too Many Requests` without
+/// reaching their handler.
+pub struct RateLimiter {
+    /// Number of tokens regained per second.
+    rate: f64,
+    /// Maximum number of tokens in a bucket.
+    burst: f64,
+    /// Clients that are never rate-limited.
+    allowlist: Vec<IpAddr>,
+    /// Buckets of the clients, by IP.
+    buckets: Mutex<HashMap<IpAddr, Bucket>>,
+}
+
+impl RateLimiter {
+    /// Create a rate limiter allowing `rate` requests per second, with bursts of `burst` requests.
+    pub fn new(rate: f64, burst: u32, allowlist: Vec<IpAddr>) -> Self {
+        Self {
+            rate,
+            burst: burst as f64,
+            allowlist,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Create a rate limiter configured from environment variables:
+    ///   - `API_RATE_LIMIT`: requests per second (defaults to 10).
+    ///   - `API_RATE_BURST`: size of bursts (defaults to 20).
+    ///   - `API_RATE_ALLOWLIST`: comma-separated list of IPs that are not rate-limited.
+    ///
+    /// Returns an error if any of the variables is malformed.
+    pub fn from_env() -> Result<Self, Error> {
+        fn var<T: FromStr>(name: &str, default: T) -> Result<T, Error> {
+            match std::env::var(name) {
+                Ok(value) => value
+                    .parse()
+                    .map_err(|_| Error::Misc(format!("Invalid value for {}: {}", name, value))),
+                Err(_) => Ok(default),
+            }
+        }
+
+        let allowlist = std::env::var("API_RATE_ALLOWLIST")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|ip| !ip.is_empty())
+            .map(|ip| {
+                ip.parse()
+                    .map_err(|_| Error::Misc(format!("Invalid IP in API_RATE_ALLOWLIST: {}", ip)))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self::new(
+            var("API_RATE_LIMIT", 10.0)?,
+            var("API_RATE_BURST", 20)?,
+            allowlist,
+        ))
+    }
+
+    /// Take a token from the bucket of the given client.
+    /// Returns whether the client is allowed to proceed.
+    fn try_acquire(&self, ip: IpAddr, now: Instant) -> bool {
+        if self.allowlist.contains(&ip) {
+            return true;
+        }
+
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.len() >= MAX_TRACKED_CLIENTS {
+            let (rate, burst) = (self.rate, self.burst);
+            buckets.retain(|_, bucket| {
+                bucket.tokens + now.duration_since(bucket.last_refill).as_secs_f64() * rate < burst
+            });
+        }
+
+        let bucket = buckets.entry(ip).or_insert(Bucket {
+            tokens: self.burst,
+            last_refill: now,
+        });
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.rate).min(self.burst);
+        bucket.last_refill = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for RateLimiter {
+    fn info(&self) -> Info {
+        Info {
+            name: "Rate-limit clients",
+            kind: Kind::Request | Kind::Response,
+        }
+    }
+
+    async fn on_request(&self, request: &mut Request<'_>, _: &mut Data<'_>) {
+        // Requests we cannot attribute to a client are let through.
+        if let Some(ip) = request.client_ip() {
+            if !self.try_acquire(ip, Instant::now()) {
+                let original = (request.method(), request.uri().path().to_string());
+                request.local_cache(|| OriginalRequest(Some(original)));
+                request.local_cache(|| RateLimited(true));
+                request.set_method(Method::Get);
+                request.set_uri(rocket::http::uri::Origin::parse(RATE_LIMITED_PATH).unwrap());
+            }
+        }
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        // Replace the 404 the redirected request got.
+        if request.local_cache(|| RateLimited(false)).0 {
+            response.set_status(Status::TooManyRequests);
+            response.remove_header("Content-Type");
+            response.set_sized_body(0, Cursor::new(Vec::new()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rocket::{http::Status, local::blocking::Client};
+
+    use crate::rate_limit::RateLimiter;
+
+    #[post("/ping")]
+    fn ping() -> &'static str {
+        "pong"
+    }
+
+    fn client(allowlist: Vec<std::net::IpAddr>) -> Client {
+        let rocket = rocket::build()
+            .attach(RateLimiter::new(0.001, 3, allowlist))
+            .mount("/", routes![ping]);
+        Client::untracked(rocket).unwrap()
+    }
+
+    #[test]
+    fn test_requests_past_limit_are_rejected() {
+        let client = client(vec![]);
+        let statuses = (0..5)
+            .map(|_| {
+                client
+                    .post("/ping")
+                    .remote("10.0.0.1:1234".parse().unwrap())
+                    .dispatch()
+                    .status()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            vec![
+                Status::Ok,
+                Status::Ok,
+                Status::Ok,
+                Status::TooManyRequests,
+                Status::TooManyRequests
+            ]
+        );
+        let response = client
+            .post("/ping")
+            .remote("10.0.0.1:1234".parse().unwrap())
+            .dispatch();
+        assert_eq!(response.status(), Status::TooManyRequests);
+        assert!(response.into_string().unwrap_or_default().is_empty());
+
+        // Other clients have their own bucket.
+        let response = client
+            .post("/ping")
+            .remote("10.0.0.2:1234".parse().unwrap())
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    #[test]
+    fn test_rate_limited_path_is_not_routed() {
+        let client = client(vec![]);
+        let response = client
+            .get("/__rate_limited")
+            .remote("10.0.0.1:1234".parse().unwrap())
+            .dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn test_allowlist_is_not_limited() {
+        let client = client(vec!["127.0.0.1".parse().unwrap()]);
+        for _ in 0..5 {
+            let response = client
+                .post("/ping")
+                .remote("127.0.0.1:1234".parse().unwrap())
+                .dispatch();
+            assert_eq!(response.status(), Status::Ok);
+        }
+    }
+}
@@ -1,8 +1,14 @@
-use std::{fmt::Write, io::Cursor};
+use std::{
+    collections::hash_map::DefaultHasher,
+    convert::Infallible,
+    fmt::Write,
+    hash::{Hash, Hasher},
+    io::Cursor,
+};
 
 use rocket::{
-    http::ContentType,
-    request::Request,
+    http::{ContentType, Header, Status},
+    request::{FromRequest, Outcome, Request},
     response::{self, Responder, Response},
 };
 
@@ -33,6 +39,81 @@ where
     }
 }
 
+/// Compute a strong ETag for the given contents.
+pub fn etag_of<T: Hash + ?Sized>(contents: &T) -> String {
+    let mut hasher = DefaultHasher::new();
+    contents.hash(&mut hasher);
+    format!("\"{:016x}\"", hasher.finish())
+}
+
+/// The ETags listed in the `If-None-Match` headers of a request.
+pub struct IfNoneMatch(Vec<String>);
+
+impl IfNoneMatch {
+    /// Whether the client already has the version of the resource with the given ETag.
+    pub fn matches(&self, etag: &str) -> bool {
+        self.0
+            .iter()
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .any(|tag| tag == "*" || tag == etag || tag.strip_prefix("W/") == Some(etag))
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for IfNoneMatch {
+    type Error = Infallible;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(IfNoneMatch(
+            request
+                .headers()
+                .get("If-None-Match")
+                .map(str::to_string)
+                .collect(),
+        ))
+    }
+}
+
+/// A response that carries an ETag, answered with `304 Not Modified` if the client already has
+/// it.
+pub enum Cached<R> {
+    /// The client has the resource with that ETag already.
+    NotModified(String),
+    /// The resource, with its ETag.
+    Fresh(String, R),
+}
+
+impl<R> Cached<R> {
+    /// Return `Cached::NotModified` if `if_none_match` matches `etag`. Otherwise, return the
+    /// resource built by `f`.
+    pub fn new<F: FnOnce() -> R>(etag: &str, if_none_match: &IfNoneMatch, f: F) -> Self {
+        if if_none_match.matches(etag) {
+            Cached::NotModified(etag.to_string())
+        } else {
+            Cached::Fresh(etag.to_string(), f())
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl<'r, R> Responder<'r, 'static> for Cached<R>
+where
+    R: Responder<'r, 'static>,
+{
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static> {
+        match self {
+            Cached::NotModified(etag) => Response::build()
+                .status(Status::NotModified)
+                .header(Header::new("ETag", etag))
+                .ok(),
+            Cached::Fresh(etag, inner) => Response::build_from(inner.respond_to(request)?)
+                .header(Header::new("ETag", etag))
+                .ok(),
+        }
+    }
+}
+
 /// Write an `li` HTML tag for the given entity to the given string.
 pub fn entity_to_li(
     entity_kind: &str,
@@ -1,6 +1,6 @@
 use std::fmt::Write;
 
-use lazy_static::__Deref;
+use lazy_static::lazy_static;
 use ornaguide_rs::{
     data::OrnaData, items::admin::AdminItem, monsters::admin::AdminMonster, pets::admin::AdminPet,
     skills::admin::AdminSkill,
@@ -8,7 +8,7 @@ use ornaguide_rs::{
 
 use crate::{
     data::DATA,
-    rocket_utils::{entity_to_li, make_list, Html, STYLE},
+    rocket_utils::{entity_to_li, etag_of, make_list, Cached, Html, IfNoneMatch, STYLE},
 };
 
 /// Write an `li` HTML tag for the given item to the given string.
@@ -170,11 +170,32 @@ fn check_pet_missing_price(data: &OrnaData, response: &mut String) -> Result<(),
     )
 }
 
-#[get("/sirscor")]
-pub fn get() -> Html<String> {
-    let lock = DATA.as_ref().unwrap();
-    let lock = lock.read();
-    let data = lock.as_ref().unwrap().deref();
+/// The HTML page served on `/sirscor`, along with its ETag.
+struct Page {
+    /// The ETag of the page.
+    etag: String,
+    /// The contents of the page.
+    html: String,
+}
+
+impl Page {
+    /// Generate the page for the given data.
+    fn new(data: &OrnaData) -> Self {
+        let html = page(data);
+        Self {
+            etag: etag_of(&html),
+            html,
+        }
+    }
+
+    /// Respond with the page, or with `304 Not Modified` if the client has it already.
+    fn respond(&'static self, if_none_match: &IfNoneMatch) -> Cached<Html<&'static str>> {
+        Cached::new(&self.etag, if_none_match, || self.html.as_str().into())
+    }
+}
+
+/// Generate the HTML page listing entities with missing fields.
+fn page(data: &OrnaData) -> String {
     let mut response = format!("<html>{}<body>", STYLE);
 
     Ok(())
@@ -194,5 +215,54 @@ pub fn get() -> Html<String> {
         .and_then(|_| writeln!(&mut response, "</body></html>"))
         .map(move |_| response)
         .unwrap_or_else(|err| format!("Error: {}", err))
-        .into()
+}
+
+lazy_static! {
+    /// `DATA` is only loaded once, so is the page generated from it.
+    static ref PAGE: Page = {
+        let lock = DATA.as_ref().unwrap();
+        let data = lock.read().unwrap();
+        Page::new(&data)
+    };
+}
+
+/// List entities with missing fields.
+/// The page has an ETag. Requests with a matching `If-None-Match` get a `304 Not Modified`.
+#[get("/sirscor")]
+pub fn get(if_none_match: IfNoneMatch) -> Cached<Html<&'static str>> {
+    PAGE.respond(&if_none_match)
+}
+
+#[cfg(test)]
+mod test {
+    use rocket::{
+        http::{Header, Status},
+        local::blocking::Client,
+    };
+
+    use crate::sirscor::get;
+
+    #[test]
+    fn test_etag() {
+        let client = Client::untracked(rocket::build().mount("/", routes![get])).unwrap();
+
+        let response = client.get("/sirscor").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let etag = response.headers().get_one("ETag").unwrap().to_string();
+        assert!(response.into_string().unwrap().contains("Cat"));
+
+        let response = client
+            .get("/sirscor")
+            .header(Header::new("If-None-Match", etag.clone()))
+            .dispatch();
+        assert_eq!(response.status(), Status::NotModified);
+        assert_eq!(response.headers().get_one("ETag"), Some(etag.as_str()));
+        assert!(response.into_bytes().unwrap_or_default().is_empty());
+
+        let response = client
+            .get("/sirscor")
+            .header(Header::new("If-None-Match", "\"0000000000000000\""))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+    }
 }
@@ -26,6 +26,12 @@ pub struct SkillFilters<'a> {
     pub name: Filter<'a, String>,
     /// Filter by tier.
     pub tier: Filter<'a, u8>,
+    /// Only keep skills whose tier is at least this value.
+    #[filter_min(tier)]
+    pub tier_min: Option<u8>,
+    /// Only keep skills whose tier is at most this value.
+    #[filter_max(tier)]
+    pub tier_max: Option<u8>,
     /// Filter by type_.
     pub type_: Filter<'a, u32>,
     /// Filter by is_magic.
@@ -40,6 +46,12 @@ pub struct SkillFilters<'a> {
     pub offhand: Filter<'a, bool>,
     /// Filter by cost.
     pub cost: Filter<'a, u64>,
+    /// Only keep skills whose cost is at least this value.
+    #[filter_min(cost)]
+    pub cost_min: Option<u64>,
+    /// Only keep skills whose cost is at most this value.
+    #[filter_max(cost)]
+    pub cost_max: Option<u64>,
     /// Filter by bought.
     pub bought: Filter<'a, bool>,
     /// Filter by skill_power.
@@ -125,3 +137,77 @@ pub fn post(filters: Json<SkillFilters>) -> MaybeResponse {
 pub fn options() -> &'static str {
     ""
 }
+
+#[cfg(test)]
+mod test {
+    use itertools::Itertools;
+    use ornaguide_rs::{data::OrnaData, skills::admin::AdminSkill, with_defaults};
+
+    use crate::{filter::Filter, skills::SkillFilters};
+
+    fn data() -> OrnaData {
+        let mut data = OrnaData::default();
+        for (id, name, cost, bought) in [
+            (1, "Attack", 0, false),
+            (2, "Fireball", 1_000, true),
+            (3, "Ice Blast", 5_000, true),
+            (4, "Hurricane", 50_000, true),
+        ] {
+            data.guide.skills.skills.push(with_defaults!(AdminSkill {
+                id,
+                name: name.to_string(),
+                cost,
+                bought,
+            }));
+        }
+        data
+    }
+
+    fn filter_names(filters: SkillFilters, data: &OrnaData) -> Vec<String> {
+        let filters = filters.compiled().ok().unwrap().into_fn_vec();
+        data.guide
+            .skills
+            .skills
+            .iter()
+            .filter(|skill| filters.iter().all(|f| f(skill)))
+            .map(|skill| skill.name.clone())
+            .collect_vec()
+    }
+
+    #[test]
+    fn test_filter_bought() {
+        let data = data();
+        let filters = SkillFilters {
+            bought: Filter::Value(true),
+            ..SkillFilters::default()
+        };
+        assert!(!filters.is_none());
+        assert_eq!(
+            filter_names(filters, &data),
+            vec!["Fireball", "Ice Blast", "Hurricane"]
+        );
+    }
+
+    #[test]
+    fn test_filter_cost_range() {
+        let data = data();
+        let range = |cost_min, cost_max| SkillFilters {
+            cost_min,
+            cost_max,
+            ..SkillFilters::default()
+        };
+
+        assert!(range(None, None).is_none());
+        assert!(!range(Some(1), None).is_none());
+        assert_eq!(
+            filter_names(range(Some(1_000), Some(5_000)), &data),
+            vec!["Fireball", "Ice Blast"]
+        );
+        assert_eq!(
+            filter_names(range(Some(1_001), None), &data),
+            vec!["Ice Blast", "Hurricane"]
+        );
+        assert_eq!(filter_names(range(None, Some(999)), &data), vec!["Attack"]);
+        assert!(filter_names(range(Some(5_001), Some(49_999)), &data).is_empty());
+    }
+}
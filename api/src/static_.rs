@@ -0,0 +1,82 @@
+use ornaguide_rs::{data::OrnaData, error::Error as OError};
+use serde_json::Value;
+
+use crate::{
+    data::with_data,
+    error::{Error, MaybeResponse, ToErrorable},
+};
+
+/// Serialize the static resources of the guide.
+/// If `table` is given, only the sub-table with that name (e.g.: `elements`, `status_effects`) is
+/// returned.
+fn static_from(data: &OrnaData, table: Option<&str>) -> Result<Value, Error> {
+    let static_ = serde_json::to_value(&data.guide.static_)
+        .map_err(OError::from)
+        .to_internal_server_error()?;
+
+    match table {
+        None => Ok(static_),
+        Some(table) => static_
+            .get(table)
+            .cloned()
+            .ok_or_else(|| OError::Misc(format!("No static table named {}", table)))
+            .to_bad_request(),
+    }
+}
+
+/// Return all the static resources of the guide (elements, status effects, item types, ...).
+#[get("/static")]
+pub fn get() -> MaybeResponse {
+    MaybeResponse {
+        contents: with_data(|data| static_from(data, None)),
+    }
+}
+
+/// Return a single table of the static resources of the guide (e.g.: `/static/elements`).
+#[get("/static/<table>")]
+pub fn get_table(table: &str) -> MaybeResponse {
+    MaybeResponse {
+        contents: with_data(|data| static_from(data, Some(table))),
+    }
+}
+
+/// This route is needded when making a CORS call to the API.
+#[options("/static")]
+pub fn options() -> &'static str {
+    ""
+}
+
+/// This route is needded when making a CORS call to the API.
+#[options("/static/<_table>")]
+pub fn options_table(_table: &str) -> &'static str {
+    ""
+}
+
+#[cfg(test)]
+mod test {
+    use ornaguide_rs::{data::OrnaData, guide::Element};
+    use rocket::http::Status;
+    use serde_json::json;
+
+    use crate::static_::static_from;
+
+    #[test]
+    fn test_static() {
+        let mut data = OrnaData::default();
+        data.guide.static_.elements = vec![Element {
+            id: 1,
+            name: "Fire".to_string(),
+        }];
+
+        let all = static_from(&data, None).ok().unwrap();
+        assert!(!all["elements"].as_array().unwrap().is_empty());
+        assert_eq!(
+            static_from(&data, Some("elements")).ok().unwrap(),
+            json!([{ "id": 1, "name": "Fire" }])
+        );
+        assert_eq!(
+            static_from(&data, Some("foo")).err().unwrap().status,
+            Status::BadRequest
+        );
+    }
+}
@@ -1,4 +1,7 @@
-use std::path::{Path, PathBuf};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
 
 use itertools::Itertools;
 use ornaguide_rs::{
@@ -26,17 +29,77 @@ pub struct Backup {
     pub manual_locales: LocaleDB,
 }
 
+/// Information about a backup archive, retrieved without loading it.
+#[derive(Debug, PartialEq, Eq)]
+pub struct BackupInfo {
+    /// The version of the archive format.
+    ///   - `1`: Orna data only.
+    ///   - `2`: Orna data and translation databases (`i18n/` and `i18n/manual/`).
+    pub format_version: u32,
+    /// Number of entities per file, keyed by file name without the `.json` extension
+    /// (`codex_items`, `guide_spawns`, ...).
+    pub counts: BTreeMap<String, usize>,
+    /// Locales in the translation database.
+    pub locales: Vec<String>,
+    /// Locales in the manual translation database.
+    pub manual_locales: Vec<String>,
+}
+
+/// A problem found in a backup archive by [`Backup::verify`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum BackupIssue {
+    /// The archive could not be read (missing, truncated, not a bzipped tarball, ...).
+    Unreadable(String),
+    /// An expected json member is missing from the archive.
+    MissingMember(String),
+    /// A json member failed to deserialize.
+    MalformedMember(String, String),
+    /// The archive contains a file that is not expected.
+    UnexpectedMember(String),
+}
+
+impl std::fmt::Display for BackupIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BackupIssue::Unreadable(err) => write!(f, "unreadable archive: {}", err),
+            BackupIssue::MissingMember(name) => write!(f, "missing member {}", name),
+            BackupIssue::MalformedMember(name, err) => {
+                write!(f, "malformed member {}: {}", name, err)
+            }
+            BackupIssue::UnexpectedMember(name) => write!(f, "unexpected member {}", name),
+        }
+    }
+}
+
 impl Backup {
     /// Save the backup to a bzipped archive in `path` and with name `name`. To the name will be
     /// appended a timestamp and the `.tar.bz2` extension.
-    pub fn save_to<P: AsRef<Path>>(&self, path: P, name: &str) -> Result<(), Error> {
-        io::save_to(self, path, name)
+    /// Json files are serialized one at a time to a temporary file next to the archive, then
+    /// copied into the archive. Neither the whole backup nor a whole json file is ever serialized
+    /// in memory.
+    pub fn save_streaming<P: AsRef<Path>>(&self, path: P, name: &str) -> Result<(), Error> {
+        io::save_streaming(self, path, name)
     }
 
     /// Load the backup from the archive at the given path.
     pub fn load_from<P: AsRef<Path>>(archive_path: P) -> Result<Backup, Error> {
         io::load_from(archive_path)
     }
+
+    /// Load the backup from the archive at the given path, failing if it has any issue.
+    /// Unlike `load_from`, missing or malformed members are not tolerated. Unlike `verify`
+    /// followed by `load_from`, the archive is only read once.
+    pub fn load_validated<P: AsRef<Path>>(archive_path: P) -> Result<Backup, Vec<BackupIssue>> {
+        io::load_validated(archive_path)
+    }
+
+    /// Check that the archive at the given path is readable, contains every expected json member
+    /// and that they all deserialize. Members are checked one at a time and dropped, so that the
+    /// whole backup is never held in memory.
+    /// Returns information about the archive, or every issue found in it.
+    pub fn verify<P: AsRef<Path>>(archive_path: P) -> Result<BackupInfo, Vec<BackupIssue>> {
+        io::verify(archive_path)
+    }
 }
 
 /// Iterate through all backup archives we can extract.
@@ -116,7 +179,32 @@ pub fn merge<P: AsRef<Path>>(backups_path: P, output_path: P) -> Result<(), Erro
         ),
     };
 
-    backup.save_to(output_path, "merge")
+    backup.save_streaming(output_path, "merge")
+}
+
+/// Verify the backup archive at `path`. Print what it contains, or what is wrong with it.
+fn verify(path: &str) -> Result<(), Error> {
+    match Backup::verify(path) {
+        Ok(info) => {
+            println!("Format version {}", info.format_version);
+            for (member, count) in info.counts.iter() {
+                println!("\t- {:25}: {}", member, count);
+            }
+            println!("Locales: {}", info.locales.join(", "));
+            println!("Manual locales: {}", info.manual_locales.join(", "));
+            Ok(())
+        }
+        Err(issues) => {
+            for issue in issues.iter() {
+                println!("\t- {}", issue);
+            }
+            Err(Error::Misc(format!(
+                "{}: {} issues found",
+                path,
+                issues.len()
+            )))
+        }
+    }
 }
 
 /// Execute a CLI subcommand on backups.
@@ -124,5 +212,6 @@ pub fn cli(command: cli::backups::Command, _: &OrnaAdminGuide, _: OrnaData) -> R
     match command {
         cli::backups::Command::Merge => merge("data/backups/current_entries", "data/merges"),
         cli::backups::Command::Prune => prune("data/backups/current_entries"),
+        cli::backups::Command::Verify(cmd) => verify(&cmd.path),
     }
 }
@@ -141,15 +141,9 @@ impl GuideDataMerger {
     /// Consume `self` and aggregate data to a `GuideData`.
     pub fn into_guide_data(self) -> GuideData {
         GuideData {
-            items: AdminItems {
-                items: self.items.into_values().collect(),
-            },
-            monsters: AdminMonsters {
-                monsters: self.monsters.into_values().collect(),
-            },
-            skills: AdminSkills {
-                skills: self.skills.into_values().collect(),
-            },
+            items: AdminItems::from(self.items.into_values().collect::<Vec<_>>()),
+            monsters: AdminMonsters::from(self.monsters.into_values().collect::<Vec<_>>()),
+            skills: AdminSkills::from(self.skills.into_values().collect::<Vec<_>>()),
             pets: AdminPets {
                 pets: self.pets.into_values().collect(),
             },
@@ -1,34 +1,122 @@
 use std::{
+    collections::BTreeMap,
     fs::File,
-    io::{Cursor, Read, Write},
+    io::{BufReader, BufWriter, Read, Seek, Write},
     path::{Path, PathBuf},
 };
 
 use bzip2::{read::BzDecoder, write::BzEncoder, Compression};
-use ornaguide_rs::{
-    codex::translation::{LocaleDB, LocaleStrings},
-    data::OrnaData,
-    error::Error,
-};
+use ornaguide_rs::{codex::translation::LocaleStrings, data::OrnaData, error::Error};
 use tar::{Archive, Builder, EntryType, Header};
 
-use crate::{backups::Backup, misc::json_read};
+use crate::{
+    backups::{Backup, BackupInfo, BackupIssue},
+    misc::json_read,
+};
+
+/// Deserialize a json file at the root of a backup archive into its field in `data`.
+/// Returns the number of entities in the file.
+type LoadMember = fn(&mut dyn Read, &str, &mut OrnaData) -> Result<usize, Error>;
 
-/// See [`crate::backups::Backup::save_to`].
-pub(crate) fn save_to<P: AsRef<Path>>(backup: &Backup, path: P, name: &str) -> Result<(), Error> {
+/// The json files at the root of a backup archive, without their `.json` extension, along with
+/// how to load them.
+const DATA_MEMBERS: [(&str, LoadMember); 18] = [
+    ("codex_bosses", |reader, path, data| {
+        data.codex.bosses = json_read(reader, path)?;
+        Ok(data.codex.bosses.bosses.len())
+    }),
+    ("codex_followers", |reader, path, data| {
+        data.codex.followers = json_read(reader, path)?;
+        Ok(data.codex.followers.followers.len())
+    }),
+    ("codex_items", |reader, path, data| {
+        data.codex.items = json_read(reader, path)?;
+        Ok(data.codex.items.items.len())
+    }),
+    ("codex_monsters", |reader, path, data| {
+        data.codex.monsters = json_read(reader, path)?;
+        Ok(data.codex.monsters.monsters.len())
+    }),
+    ("codex_raids", |reader, path, data| {
+        data.codex.raids = json_read(reader, path)?;
+        Ok(data.codex.raids.raids.len())
+    }),
+    ("codex_skills", |reader, path, data| {
+        data.codex.skills = json_read(reader, path)?;
+        Ok(data.codex.skills.skills.len())
+    }),
+    ("guide_elements", |reader, path, data| {
+        data.guide.static_.elements = json_read(reader, path)?;
+        Ok(data.guide.static_.elements.len())
+    }),
+    ("guide_equipped_bys", |reader, path, data| {
+        data.guide.static_.equipped_bys = json_read(reader, path)?;
+        Ok(data.guide.static_.equipped_bys.len())
+    }),
+    ("guide_item_categories", |reader, path, data| {
+        data.guide.static_.item_categories = json_read(reader, path)?;
+        Ok(data.guide.static_.item_categories.len())
+    }),
+    ("guide_items", |reader, path, data| {
+        data.guide.items = json_read(reader, path)?;
+        Ok(data.guide.items.items.len())
+    }),
+    ("guide_item_types", |reader, path, data| {
+        data.guide.static_.item_types = json_read(reader, path)?;
+        Ok(data.guide.static_.item_types.len())
+    }),
+    ("guide_monster_families", |reader, path, data| {
+        data.guide.static_.monster_families = json_read(reader, path)?;
+        Ok(data.guide.static_.monster_families.len())
+    }),
+    ("guide_monsters", |reader, path, data| {
+        data.guide.monsters = json_read(reader, path)?;
+        Ok(data.guide.monsters.monsters.len())
+    }),
+    ("guide_pets", |reader, path, data| {
+        data.guide.pets = json_read(reader, path)?;
+        Ok(data.guide.pets.pets.len())
+    }),
+    ("guide_skills", |reader, path, data| {
+        data.guide.skills = json_read(reader, path)?;
+        Ok(data.guide.skills.skills.len())
+    }),
+    ("guide_skill_types", |reader, path, data| {
+        data.guide.static_.skill_types = json_read(reader, path)?;
+        Ok(data.guide.static_.skill_types.len())
+    }),
+    ("guide_spawns", |reader, path, data| {
+        data.guide.static_.spawns = json_read(reader, path)?;
+        Ok(data.guide.static_.spawns.len())
+    }),
+    ("guide_status_effects", |reader, path, data| {
+        data.guide.static_.status_effects = json_read(reader, path)?;
+        Ok(data.guide.static_.status_effects.len())
+    }),
+];
+
+/// See [`crate::backups::Backup::save_streaming`].
+pub(crate) fn save_streaming<P: AsRef<Path>>(
+    backup: &Backup,
+    path: P,
+    name: &str,
+) -> Result<(), Error> {
     // Create archive path, from path, name and timestamp.
     // Keep the archive basename, as it will be the root directory from inside the archive.
     let now = chrono::Local::now();
     let archive_basename = format!("{}-{}", name, now.format("%FT%H-%M"));
     let mut archive_path = path.as_ref().to_path_buf();
     archive_path.push(format!("{}.tar.bz2", archive_basename));
+    // Json files are written there before being copied into the archive.
+    let spool_path = archive_path.with_extension("bz2.part");
 
     // Open the archive.
     let mut archive = Builder::new(BzEncoder::new(
         File::options()
             .write(true)
             .create(true)
-            .open(archive_path)?,
+            .truncate(true)
+            .open(&archive_path)?,
         Compression::best(),
     ));
 
@@ -77,14 +165,26 @@ pub(crate) fn save_to<P: AsRef<Path>>(backup: &Backup, path: P, name: &str) -> R
     archive.append(&header, &*Vec::<u8>::new()).unwrap();
 
     // Create a callback to give the data so it writes into the archive.
+    // The size of an entry must be known before its contents are appended. Rather than
+    // serializing the json file in memory to know its size, it is spooled to a file.
     let mut writer_callback =
         |path: &str, callback: &dyn Fn(&mut dyn Write) -> Result<(), Error>| -> Result<(), Error> {
-            let mut buffer: Vec<u8> = vec![];
-            callback(&mut buffer)?;
+            let mut spool = File::options()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(&spool_path)?;
+            let mut writer = BufWriter::new(&mut spool);
+            callback(&mut writer)?;
+            writer.flush()?;
+            drop(writer);
+
             let mut header = new_header(path, false);
-            header.set_size(buffer.len() as u64);
+            header.set_size(spool.stream_position()?);
             header.set_cksum();
-            archive.append(&header, Cursor::new(buffer)).unwrap();
+            spool.rewind()?;
+            archive.append(&header, BufReader::new(spool))?;
             Ok(())
         };
 
@@ -100,10 +200,112 @@ pub(crate) fn save_to<P: AsRef<Path>>(backup: &Backup, path: P, name: &str) -> R
     backup
         .manual_locales
         .save_to_generic(&manual_locale_dir, &mut writer_callback)?;
+    std::fs::remove_file(&spool_path)?;
 
     Ok(())
 }
 
+/// Read every member of the archive at `archive_path`, collecting issues rather than stopping at
+/// the first one.
+/// If `keep` is set, members are loaded into the returned `Backup`. Otherwise, each member is
+/// dropped as soon as it is checked, so that the whole backup is never held in memory.
+fn read_archive(archive_path: &Path, keep: bool) -> (Backup, BackupInfo, Vec<BackupIssue>) {
+    let mut backup = Backup::default();
+    let mut info = BackupInfo {
+        format_version: 1,
+        counts: BTreeMap::new(),
+        locales: vec![],
+        manual_locales: vec![],
+    };
+    let mut issues = vec![];
+    let mut seen = vec![];
+
+    // Walk through the archive, stopping at the first error that prevents reading further.
+    let mut walk = || -> Result<(), Error> {
+        let mut archive = Archive::new(BzDecoder::new(File::open(archive_path)?));
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let full_path = PathBuf::from(String::from_utf8_lossy(&entry.path_bytes()).as_ref());
+            let full_pathstr = full_path.to_string_lossy().to_string();
+            // Strip the root folder of the tar archive.
+            // All files are `output-YYYY-MM-DD/file.json`, and we need the first component out.
+            let path = full_path.components().skip(1).collect::<PathBuf>();
+            let pathstr = path.to_string_lossy().to_string();
+
+            if path.starts_with("i18n") {
+                info.format_version = 2;
+            }
+            // Ignore folders.
+            if entry.header().entry_type() != EntryType::Regular {
+                continue;
+            }
+
+            let stem = match path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_suffix(".json"))
+            {
+                Some(stem) => stem.to_string(),
+                None => {
+                    issues.push(BackupIssue::UnexpectedMember(pathstr));
+                    continue;
+                }
+            };
+
+            let result = if path.components().count() == 1 {
+                match DATA_MEMBERS.iter().find(|(name, _)| *name == stem) {
+                    Some((name, load)) => {
+                        seen.push(*name);
+                        let mut scratch = OrnaData::default();
+                        let data = if keep { &mut backup.data } else { &mut scratch };
+                        load(&mut entry, &full_pathstr, data).map(|count| {
+                            info.counts.insert(stem, count);
+                        })
+                    }
+                    None => {
+                        issues.push(BackupIssue::UnexpectedMember(pathstr));
+                        continue;
+                    }
+                }
+            } else if path.starts_with("i18n") {
+                let (db, locales) = if path.starts_with("i18n/manual") {
+                    (&mut backup.manual_locales, &mut info.manual_locales)
+                } else {
+                    (&mut backup.locales, &mut info.locales)
+                };
+                json_read::<_, LocaleStrings>(&mut entry, &full_pathstr).map(|strings| {
+                    if keep {
+                        db.locales.insert(stem.clone(), strings);
+                    }
+                    locales.push(stem);
+                })
+            } else {
+                issues.push(BackupIssue::UnexpectedMember(pathstr));
+                continue;
+            };
+            if let Err(err) = result {
+                issues.push(BackupIssue::MalformedMember(pathstr, err.to_string()));
+            }
+        }
+
+        // Make sure the decompressor reaches the end of the stream, so that a truncated trailer
+        // is detected.
+        std::io::copy(&mut archive.into_inner(), &mut std::io::sink())?;
+        Ok(())
+    };
+    if let Err(err) = walk() {
+        issues.push(BackupIssue::Unreadable(err.to_string()));
+    }
+
+    for (member, _) in DATA_MEMBERS.iter() {
+        if !seen.contains(member) {
+            issues.push(BackupIssue::MissingMember(format!("{}.json", member)));
+        }
+    }
+
+    (backup, info, issues)
+}
+
 /// See [`crate::backups::Backup::load_from`].
 pub(crate) fn load_from<P: AsRef<Path>>(archive_path: P) -> Result<Backup, Error> {
     let archive_path: &Path = archive_path.as_ref();
@@ -114,157 +316,210 @@ pub(crate) fn load_from<P: AsRef<Path>>(archive_path: P) -> Result<Backup, Error
         )));
     }
 
-    // Take path, and remove the `.tar.bz2` extension.
-    let file_base = archive_path.file_name().unwrap().to_string_lossy();
-    let file_base = file_base.strip_suffix(".tar.bz2").unwrap();
+    let (backup, _, issues) = read_archive(archive_path, true);
+    // Older archives may lack some members, or have them in a format we can no longer read. The
+    // corresponding fields are left to their default.
+    match issues.into_iter().find(|issue| match issue {
+        BackupIssue::MissingMember(_) => false,
+        BackupIssue::MalformedMember(name, _) => name.starts_with("i18n"),
+        BackupIssue::Unreadable(_) | BackupIssue::UnexpectedMember(_) => true,
+    }) {
+        Some(issue) => Err(Error::Misc(format!("{:?}: {}", archive_path, issue))),
+        None => Ok(backup),
+    }
+}
 
-    // Open archive.
-    let mut archive = Archive::new(BzDecoder::new(File::open(archive_path)?));
+/// See [`crate::backups::Backup::load_validated`].
+pub(crate) fn load_validated<P: AsRef<Path>>(archive_path: P) -> Result<Backup, Vec<BackupIssue>> {
+    match read_archive(archive_path.as_ref(), true) {
+        (backup, _, issues) if issues.is_empty() => Ok(backup),
+        (_, _, issues) => Err(issues),
+    }
+}
 
-    // Set our return value.
-    let mut data = OrnaData::default();
-    let mut locales = LocaleDB::default();
-    let mut manual_locales = LocaleDB::default();
+/// See [`crate::backups::Backup::verify`].
+pub(crate) fn verify<P: AsRef<Path>>(archive_path: P) -> Result<BackupInfo, Vec<BackupIssue>> {
+    match read_archive(archive_path.as_ref(), false) {
+        (_, info, issues) if issues.is_empty() => Ok(info),
+        (_, _, issues) => Err(issues),
+    }
+}
 
-    for entry in archive.entries()? {
-        let entry = entry?;
-        // Ignore folders.
-        if entry.header().entry_type() != EntryType::Regular {
-            continue;
-        }
+#[cfg(test)]
+mod test {
+    use std::{fs::File, path::PathBuf};
 
-        // Strip the root folder of the tar archives.
-        // All files are `output-YYYY-MM-DD/file.json`, and we need the first component out.
-        let base_path = PathBuf::from(String::from_utf8_lossy(&entry.path_bytes()).as_ref());
-        let path = base_path.strip_prefix(file_base).unwrap();
-        let base_path = base_path.to_string_lossy();
-        let base_pathstr = base_path.as_ref();
-        let pathstr = path.to_string_lossy();
-        let pathstr = pathstr.as_ref();
-
-        if path.components().count() == 1 {
-            // TODO(ethiraric, 07/09/2022): Replace with diagnostics.
-            match pathstr {
-                "codex_bosses.json" => {
-                    data.codex.bosses = json_read(entry, base_pathstr).unwrap_or_default();
-                }
-                "codex_followers.json" => {
-                    data.codex.followers = json_read(entry, base_pathstr).unwrap_or_default();
-                }
-                "codex_items.json" => {
-                    data.codex.items = json_read(entry, base_pathstr).unwrap_or_default();
-                }
-                "codex_monsters.json" => {
-                    data.codex.monsters = json_read(entry, base_pathstr).unwrap_or_default();
-                }
-                "codex_raids.json" => {
-                    data.codex.raids = json_read(entry, base_pathstr).unwrap_or_default();
-                }
-                "codex_skills.json" => {
-                    data.codex.skills = json_read(entry, base_pathstr).unwrap_or_default();
-                }
-                "guide_elements.json" => {
-                    data.guide.static_.elements =
-                        json_read(entry, base_pathstr).unwrap_or_default();
-                }
-                "guide_equipped_bys.json" => {
-                    data.guide.static_.equipped_bys =
-                        json_read(entry, base_pathstr).unwrap_or_default();
-                }
-                "guide_item_categories.json" => {
-                    data.guide.static_.item_categories =
-                        json_read(entry, base_pathstr).unwrap_or_default();
-                }
-                "guide_items.json" => {
-                    data.guide.items = json_read(entry, base_pathstr).unwrap_or_default();
-                }
-                "guide_item_types.json" => {
-                    data.guide.static_.item_types =
-                        json_read(entry, base_pathstr).unwrap_or_default();
-                }
-                "guide_monster_families.json" => {
-                    data.guide.static_.monster_families =
-                        json_read(entry, base_pathstr).unwrap_or_default();
-                }
-                "guide_monsters.json" => {
-                    data.guide.monsters = json_read(entry, base_pathstr).unwrap_or_default();
-                }
-                "guide_pets.json" => {
-                    data.guide.pets = json_read(entry, base_pathstr).unwrap_or_default();
-                }
-                "guide_skills.json" => {
-                    data.guide.skills = json_read(entry, base_pathstr).unwrap_or_default();
-                }
-                "guide_skill_types.json" => {
-                    data.guide.static_.skill_types =
-                        json_read(entry, base_pathstr).unwrap_or_default();
-                }
-                "guide_spawns.json" => {
-                    data.guide.static_.spawns = json_read(entry, base_pathstr).unwrap_or_default();
-                }
-                "guide_status_effects.json" => {
-                    data.guide.static_.status_effects =
-                        json_read(entry, base_pathstr).unwrap_or_default();
-                }
-                _ => {
-                    return Err(Error::Misc(format!(
-                        "Unexpected file in {:?}: {:?}",
-                        archive_path, path
-                    )));
-                }
-            }
-        } else {
-            // We have a translation file.
-            if let Ok(file_name) = path.strip_prefix("i18n") {
-                // It's either a manual translation or a regular one.
-                if let Ok(file_name) = file_name.strip_prefix("manual") {
-                    // Add content to the manual database.
-                    load_entry_to_translation_db(
-                        &mut manual_locales,
-                        path.to_string_lossy().as_ref(),
-                        file_name.to_string_lossy().as_ref(),
-                        entry,
-                    )?;
-                } else {
-                    // Add content to the regular database.
-                    load_entry_to_translation_db(
-                        &mut locales,
-                        path.to_string_lossy().as_ref(),
-                        file_name.to_string_lossy().as_ref(),
-                        entry,
-                    )?;
-                }
+    use bzip2::{read::BzDecoder, write::BzEncoder, Compression};
+    use ornaguide_rs::{codex::translation::LocaleStrings, monsters::admin::AdminMonster};
+    use tar::{Archive, Builder};
+
+    use crate::backups::{io::DATA_MEMBERS, Backup, BackupIssue};
+
+    /// Create an empty directory in the temporary directory for the test.
+    fn test_dir(name: &str) -> PathBuf {
+        let mut path = std::env::temp_dir();
+        path.push(format!("ethi-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_verify() {
+        let dir = test_dir("verify");
+        let mut backup = Backup::default();
+        backup.data.guide.monsters.monsters =
+            vec![AdminMonster::default(), AdminMonster::default()];
+        backup.locales.locales.insert(
+            "fr".to_string(),
+            LocaleStrings {
+                locale: "fr".to_string(),
+                ..LocaleStrings::default()
+            },
+        );
+        backup.save_streaming(&dir, "test").unwrap();
+        let archive = std::fs::read_dir(&dir)
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .path();
+
+        // A good archive.
+        let info = Backup::verify(&archive).unwrap();
+        assert_eq!(info.format_version, 2);
+        assert_eq!(info.counts.get("guide_monsters"), Some(&2));
+        assert_eq!(info.counts.get("guide_items"), Some(&0));
+        assert_eq!(info.locales, vec!["fr".to_string()]);
+        assert!(info.manual_locales.is_empty());
+
+        // A truncated archive.
+        let bytes = std::fs::read(&archive).unwrap();
+        let truncated = dir.join("truncated.tar.bz2");
+        std::fs::write(&truncated, &bytes[..bytes.len() / 2]).unwrap();
+        assert!(Backup::verify(&truncated).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_verify_issues() {
+        let dir = test_dir("verify-issues");
+        Backup::default().save_streaming(&dir, "test").unwrap();
+        let archive = std::fs::read_dir(&dir)
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .path();
+
+        // A good archive.
+        assert!(Backup::verify(&archive).is_ok());
+
+        // A truncated archive.
+        let bytes = std::fs::read(&archive).unwrap();
+        let truncated = dir.join("truncated.tar.bz2");
+        std::fs::write(&truncated, &bytes[..bytes.len() / 2]).unwrap();
+        let issues = Backup::verify(&truncated).unwrap_err();
+        assert!(issues
+            .iter()
+            .any(|issue| matches!(issue, BackupIssue::Unreadable(_))));
+
+        // An archive with a missing and a malformed member, rebuilt from the good one.
+        let partial = dir.join("partial.tar.bz2");
+        let mut builder = Builder::new(BzEncoder::new(
+            File::create(&partial).unwrap(),
+            Compression::best(),
+        ));
+        let mut good = Archive::new(BzDecoder::new(File::open(&archive).unwrap()));
+        for entry in good.entries().unwrap() {
+            let mut entry = entry.unwrap();
+            let path = entry.path().unwrap().to_path_buf();
+            let mut header = entry.header().clone();
+            if path.ends_with("codex_bosses.json") {
+                continue;
+            } else if path.ends_with("guide_items.json") {
+                let contents = br#"{"items": 3}"#;
+                header.set_size(contents.len() as u64);
+                header.set_cksum();
+                builder.append(&header, &contents[..]).unwrap();
             } else {
-                return Err(Error::Misc(format!(
-                    "Unexpected file in {:?}: {:?}",
-                    archive_path, path
-                )));
+                builder.append(&header, &mut entry).unwrap();
             }
         }
+        builder.into_inner().unwrap().finish().unwrap();
+        let issues = Backup::verify(&partial).unwrap_err();
+        assert_eq!(issues.len(), 2);
+        assert!(issues.contains(&BackupIssue::MissingMember("codex_bosses.json".to_string())));
+        assert!(matches!(
+            &issues[0],
+            BackupIssue::MalformedMember(name, _) if name == "guide_items.json"
+        ));
+        assert_eq!(Backup::load_validated(&partial).err(), Some(issues));
+
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 
-    Ok(Backup {
-        data,
-        locales,
-        manual_locales,
-    })
-}
+    #[test]
+    fn test_save_and_load() {
+        let dir = test_dir("save-and-load");
+        let mut backup = Backup::default();
+        backup.data.guide.monsters.monsters =
+            vec![AdminMonster::default(), AdminMonster::default()];
+        backup.locales.locales.insert(
+            "fr".to_string(),
+            LocaleStrings {
+                locale: "fr".to_string(),
+                ..LocaleStrings::default()
+            },
+        );
+        backup.save_streaming(&dir, "test").unwrap();
+        let archive = std::fs::read_dir(&dir)
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .path();
 
-/// Load a `LocaleStrings` from a reader into the given `LocaleDB`.
-fn load_entry_to_translation_db<R>(
-    db: &mut LocaleDB,
-    fullpath: &str,
-    filename: &str,
-    reader: R,
-) -> Result<(), Error>
-where
-    R: Read,
-{
-    let strings: LocaleStrings = json_read(reader, fullpath)?;
-    let lang = filename
-        .strip_suffix(".json")
-        .ok_or_else(|| Error::Misc(format!("{}: lang file doesn't end in `.json`", fullpath)))?
-        .to_string();
-    db.locales.insert(lang, strings);
-    Ok(())
+        assert!(Backup::verify(&archive).is_ok());
+        assert!(Backup::load_from(&archive).unwrap() == backup);
+        assert!(Backup::load_validated(&archive).unwrap() == backup);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_save_streaming_layout() {
+        let dir = test_dir("save-streaming-layout");
+        let mut backup = Backup::default();
+        backup.manual_locales.locales.insert(
+            "de".to_string(),
+            LocaleStrings {
+                locale: "de".to_string(),
+                ..LocaleStrings::default()
+            },
+        );
+        backup.save_streaming(&dir, "test").unwrap();
+        // The spool file was removed.
+        let entries = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect::<Vec<_>>();
+        assert_eq!(entries.len(), 1);
+
+        // Every member is at its usual place in the archive.
+        let paths = Archive::new(BzDecoder::new(File::open(&entries[0]).unwrap()))
+            .entries()
+            .unwrap()
+            .map(|entry| entry.unwrap().path().unwrap().to_string_lossy().to_string())
+            .filter_map(|path| path.split_once('/').map(|(_, path)| path.to_string()))
+            .collect::<Vec<_>>();
+        for (name, _) in DATA_MEMBERS.iter() {
+            assert!(paths.contains(&format!("{}.json", name)), "{}", name);
+        }
+        assert!(paths.contains(&"i18n/manual/de.json".to_string()));
+        assert!(Backup::load_validated(&entries[0]).unwrap() == backup);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -0,0 +1,238 @@
+use std::collections::HashSet;
+
+use itertools::Itertools;
+use ornaguide_rs::{data::OrnaData, error::Error};
+
+use crate::guide_match::{duplicates, stale_ids};
+
+/// How serious an issue found by `check` is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// The data is inconsistent and matching against it would give wrong results.
+    Error,
+    /// The data is suspicious and should be looked at.
+    Warning,
+}
+
+/// An issue found in the data.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Issue {
+    /// How serious the issue is.
+    pub severity: Severity,
+    /// The name of the check that found the issue.
+    pub check: &'static str,
+    /// A human-readable description of the issue.
+    pub message: String,
+}
+
+impl Issue {
+    fn error(check: &'static str, message: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            check,
+            message,
+        }
+    }
+
+    fn warning(check: &'static str, message: String) -> Self {
+        Self {
+            severity: Severity::Warning,
+            check,
+            message,
+        }
+    }
+}
+
+/// Guide entities sharing the same `codex_uri`.
+fn check_duplicates(data: &OrnaData, issues: &mut Vec<Issue>) {
+    for (kind, found) in [
+        ("items", duplicates::items(data)),
+        ("monsters", duplicates::monsters(data)),
+        ("skills", duplicates::skills(data)),
+        ("pets", duplicates::pets(data)),
+    ] {
+        for duplicate in found {
+            issues.push(Issue::error(
+                "duplicates",
+                format!(
+                    "{} shared by {} {}",
+                    duplicate.codex_uri,
+                    kind,
+                    duplicate
+                        .entities
+                        .iter()
+                        .map(|(id, name)| format!("{} (#{})", name, id))
+                        .join(", ")
+                ),
+            ));
+        }
+    }
+}
+
+/// Guide entities referencing ids that are missing from `Static`.
+fn check_stale_ids(data: &OrnaData, issues: &mut Vec<Issue>) {
+    for stale in stale_ids::find(data) {
+        issues.push(Issue::error(
+            "stale ids",
+            format!(
+                "{} {} (#{}): {} #{} is not in static data",
+                stale.kind, stale.entity_name, stale.entity_id, stale.field, stale.id
+            ),
+        ));
+    }
+}
+
+/// Codex entities sharing the same URI.
+fn check_duplicate_slugs(data: &OrnaData, issues: &mut Vec<Issue>) {
+    for (uri, count) in data
+        .codex
+        .iter_all()
+        .map(|entity| entity.uri())
+        .counts()
+        .into_iter()
+        .filter(|(_, count)| *count > 1)
+        .sorted()
+    {
+        issues.push(Issue::warning(
+            "duplicate slugs",
+            format!("{} appears {} times in the codex", uri, count),
+        ));
+    }
+}
+
+/// Guide entities whose `codex_uri` does not match any codex entity.
+fn check_orphans(data: &OrnaData, codex_uris: &HashSet<String>, issues: &mut Vec<Issue>) {
+    let guide_entities = data
+        .guide
+        .items
+        .items
+        .iter()
+        .map(|item| ("item", item.id, &item.name, &item.codex_uri))
+        .chain(
+            data.guide
+                .monsters
+                .monsters
+                .iter()
+                .map(|monster| ("monster", monster.id, &monster.name, &monster.codex_uri)),
+        )
+        .chain(
+            data.guide
+                .skills
+                .skills
+                .iter()
+                .map(|skill| ("skill", skill.id, &skill.name, &skill.codex_uri)),
+        )
+        .chain(
+            data.guide
+                .pets
+                .pets
+                .iter()
+                .map(|pet| ("pet", pet.id, &pet.name, &pet.codex_uri)),
+        );
+
+    for (kind, id, name, codex_uri) in guide_entities {
+        if !codex_uri.is_empty() && !codex_uris.contains(codex_uri) {
+            issues.push(Issue::warning(
+                "orphans",
+                format!(
+                    "{} {} (#{}): {} is not in the codex",
+                    kind, name, id, codex_uri
+                ),
+            ));
+        }
+    }
+}
+
+/// Codex items dropped by monsters that are not in the codex.
+fn check_dangling_drops(data: &OrnaData, codex_uris: &HashSet<String>, issues: &mut Vec<Issue>) {
+    for item in data.codex.items.items.iter() {
+        for dropped_by in item
+            .dropped_by
+            .iter()
+            .filter(|dropped_by| !codex_uris.contains(&dropped_by.uri))
+        {
+            issues.push(Issue::warning(
+                "dangling drops",
+                format!(
+                    "{} is dropped by {} ({}), which is not in the codex",
+                    item.slug, dropped_by.name, dropped_by.uri
+                ),
+            ));
+        }
+    }
+}
+
+/// Run all read-only consistency checks on the data.
+/// Issues are sorted by severity, errors first.
+pub fn find_issues(data: &OrnaData) -> Vec<Issue> {
+    let codex_uris = data
+        .codex
+        .iter_all()
+        .map(|entity| entity.uri())
+        .collect::<HashSet<_>>();
+
+    let mut issues = Vec::new();
+    check_duplicates(data, &mut issues);
+    check_stale_ids(data, &mut issues);
+    check_duplicate_slugs(data, &mut issues);
+    check_orphans(data, &codex_uris, &mut issues);
+    check_dangling_drops(data, &codex_uris, &mut issues);
+    issues.sort_by_key(|issue| issue.severity);
+    issues
+}
+
+/// Run all consistency checks on the data and print a report.
+/// Returns an error if any issue was found.
+pub fn cli(data: OrnaData) -> Result<(), Error> {
+    let issues = find_issues(&data);
+    if issues.is_empty() {
+        println!("\x1B[0;32mNo issue found\x1B[0m");
+        return Ok(());
+    }
+
+    for (severity, issues) in &issues.iter().group_by(|issue| issue.severity) {
+        let issues = issues.collect_vec();
+        match severity {
+            Severity::Error => println!("\x1B[0;31m{} errors:\x1B[0m", issues.len()),
+            Severity::Warning => println!("\x1B[0;33m{} warnings:\x1B[0m", issues.len()),
+        }
+        for issue in issues {
+            println!("\t- [{:^15}] {}", issue.check, issue.message);
+        }
+    }
+    Err(Error::Misc(format!(
+        "{} issues found in the data",
+        issues.len()
+    )))
+}
+
+#[cfg(test)]
+mod test {
+    use ornaguide_rs::{data::OrnaData, items::admin::AdminItem, with_defaults};
+
+    use crate::check::{cli, find_issues, Issue, Severity};
+
+    #[test]
+    fn test_check_reports_seeded_issue() {
+        let mut data = OrnaData::default();
+        assert!(find_issues(&data).is_empty());
+        assert!(cli(data.clone()).is_ok());
+
+        // An item whose type isn't in static data.
+        data.guide.items.items.push(with_defaults!(AdminItem {
+            id: 1,
+            name: "Sword".to_string(),
+            type_: 42,
+        }));
+
+        assert_eq!(
+            find_issues(&data),
+            vec![Issue {
+                severity: Severity::Error,
+                check: "stale ids",
+                message: "item Sword (#1): type #42 is not in static data".to_string(),
+            }]
+        );
+        assert!(cli(data).is_err());
+    }
+}
@@ -8,6 +8,15 @@ pub mod backups {
         Merge,
         /// Remove duplicates in backup files.
         Prune,
+        /// Check that a backup archive is readable and complete, without loading it.
+        Verify(VerifyCmd),
+    }
+
+    /// Command to check a backup archive.
+    #[derive(clap::Args, Debug)]
+    pub struct VerifyCmd {
+        /// Path to the `.tar.bz2` archive.
+        pub path: String,
     }
 }
 
@@ -17,11 +26,47 @@ pub mod codex {
     pub enum Command {
         /// Check whether bugs found on the codex has been fixed..
         Bugs,
+        /// Write the inconsistencies found in the codex data to `output/codex_bugs.json`.
+        BugsJson,
         /// Fetch missing codex entry.
         Missing,
     }
 }
 
+pub mod diff {
+    /// Command to list the differences in guide entities between older data and the current data.
+    #[derive(clap::Args, Debug)]
+    pub struct Command {
+        /// Directory of the older data (e.g.: `data/current_entries` from a backup).
+        pub old: String,
+        /// Write the differences to this JSON file rather than to the standard output.
+        #[arg(long)]
+        pub output: Option<String>,
+    }
+}
+
+pub mod find {
+    /// Command to find entities by name.
+    #[derive(clap::Args, Debug)]
+    pub struct Command {
+        /// The name of the entity, case-insensitive.
+        pub name: String,
+    }
+}
+
+pub mod icons {
+    /// Command to list guide items with missing or mismatched icons.
+    #[derive(clap::Args, Debug)]
+    pub struct Command {
+        /// Whether to copy the codex icon onto the guide item.
+        #[arg(short, long, default_value_t = false)]
+        pub fix: bool,
+        /// Also write the items to this JSON file.
+        #[arg(long)]
+        pub json: Option<String>,
+    }
+}
+
 pub mod json {
     /// Commands to manipulate the json output of `ethi`.
     #[derive(clap::Subcommand, Debug)]
@@ -30,12 +75,18 @@ pub mod json {
         FetchAllMatchesFromGuide,
         /// Fetch missing codex entry.
         Refresh(RefreshCmd),
+        /// Export guide items and their base stats to `output/items.csv`.
+        ItemsCsv,
     }
 
     /// Intermediate structure to allow for an `Option`.
     /// Makes `json refresh` a valid command.
     #[derive(clap::Args, Debug)]
     pub struct RefreshCmd {
+        /// Resume an interrupted refresh of the codex, only fetching entities that are not in the
+        /// checkpoints of `data/checkpoints`. Delete that directory to force a clean refresh.
+        #[arg(long, global = true, default_value_t = false)]
+        pub resume: bool,
         /// Subcommand, if any.
         #[command(subcommand)]
         pub c: Option<Refresh>,
@@ -48,6 +99,10 @@ pub mod json {
         Guide(RefreshGuideCmd),
         /// Refresh codex jsons.
         Codex(RefreshCodexCmd),
+        /// Only fetch codex entities that were added or removed since the last refresh.
+        /// Changes to entities that were already fetched are missed in this mode. Guide jsons are
+        /// left untouched.
+        Incremental,
     }
 
     /// Intermediate structure to allow for an `Option`.
@@ -108,6 +163,14 @@ pub mod match_ {
         /// Whether to fix the mismatches when possible.
         #[arg(short, long, default_value_t = false)]
         pub fix: bool,
+        /// Whether to download and compare item icons whose names differ.
+        /// This is slow, as it downloads both images for each mismatch.
+        #[arg(long, default_value_t = false)]
+        pub compare_images: bool,
+        /// Whether to ignore numbers when comparing skill descriptions.
+        /// Numbers in descriptions usually scale with the tier or level of the skill.
+        #[arg(long, default_value_t = false)]
+        pub mask_numbers: bool,
         /// Subcommand, if any.
         #[command(subcommand)]
         pub c: Option<Subcommand>,
@@ -143,11 +206,25 @@ pub mod merge {
         /// Whether to fix the mismatches when possible.
         #[arg(short, long, default_value_t = false)]
         pub fix: bool,
+        /// How to resolve entities that differ between the current data and the merge.
+        #[arg(long, value_enum, default_value_t = Strategy::Incoming)]
+        pub strategy: Strategy,
         /// Subcommand, if any.
         #[command(subcommand)]
         pub c: Option<Match>,
     }
 
+    /// How to resolve conflicts between the current data and the merge archive.
+    #[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum Strategy {
+        /// Keep the entity from the current data.
+        Existing,
+        /// Take the entity from the merge archive.
+        Incoming,
+        /// Take each field from the merge archive, unless it is empty there.
+        IncomingUnlessEmpty,
+    }
+
     #[derive(clap::Subcommand, Debug)]
     pub enum Match {
         // Match only items.
@@ -163,14 +240,64 @@ pub mod merge {
     }
 }
 
+pub mod query {
+    /// Command to query entities from the data.
+    #[derive(clap::Args, Debug)]
+    pub struct Command {
+        /// The kind of entities to query.
+        #[arg(value_enum)]
+        pub kind: Kind,
+        /// The filter expression (e.g.: `tier == 10 && element == 1`).
+        pub expression: String,
+    }
+
+    /// The kinds of entities that can be queried.
+    #[derive(clap::ValueEnum, Clone, Debug)]
+    pub enum Kind {
+        /// Guide items.
+        Items,
+        /// Guide monsters.
+        Monsters,
+        /// Guide pets.
+        Pets,
+        /// Guide skills.
+        Skills,
+        /// Codex items.
+        CodexItems,
+        /// Codex monsters.
+        CodexMonsters,
+        /// Codex bosses.
+        CodexBosses,
+        /// Codex raids.
+        CodexRaids,
+        /// Codex skills.
+        CodexSkills,
+        /// Codex followers.
+        CodexFollowers,
+    }
+}
+
 pub mod translation {
     /// Commands to manipulate translations.
     #[derive(clap::Subcommand, Debug)]
     pub enum Command {
         /// Fetch missing translations.
-        Missing,
+        Missing(MissingCmd),
         /// Fetch missing translations.
         Fetch(FetchCmd),
+        /// Fetch the strings of a single entity in all locales already in the database.
+        FetchEntity(FetchEntityCmd),
+        /// List the strings of a locale that are not translated yet, without querying the codex.
+        Todo(TodoCmd),
+    }
+
+    /// Command to fetch missing translations.
+    #[derive(clap::Args, Debug)]
+    pub struct MissingCmd {
+        /// Only fetch translations for this locale. May be given multiple times.
+        /// All locales in the database are fetched if none is given.
+        #[arg(long = "locale")]
+        pub locales: Vec<String>,
     }
 
     /// Command to fetch data in a specific locale.
@@ -179,6 +306,46 @@ pub mod translation {
         /// The locale in which to query.
         pub locale: String,
     }
+
+    /// Command to fetch the strings of a single entity in all locales.
+    #[derive(clap::Args, Debug)]
+    pub struct FetchEntityCmd {
+        /// The kind of the entity.
+        #[arg(value_enum)]
+        pub kind: Kind,
+        /// The slug of the entity on the codex.
+        pub slug: String,
+    }
+
+    /// Command to list the untranslated strings of a locale.
+    #[derive(clap::Args, Debug)]
+    pub struct TodoCmd {
+        /// The locale whose strings to compare against English.
+        pub locale: String,
+        /// Output the list as CSV rather than JSON.
+        #[arg(long, default_value_t = false)]
+        pub csv: bool,
+        /// Write the list to this file rather than to the standard output.
+        #[arg(long)]
+        pub output: Option<String>,
+    }
+
+    /// The kinds of codex entities that have translations.
+    #[derive(clap::ValueEnum, Clone, Copy, Debug)]
+    pub enum Kind {
+        /// A codex item.
+        Item,
+        /// A codex monster.
+        Monster,
+        /// A codex boss.
+        Boss,
+        /// A codex raid.
+        Raid,
+        /// A codex skill.
+        Skill,
+        /// A codex follower.
+        Follower,
+    }
 }
 
 /// Base enum for subcommands.
@@ -187,9 +354,18 @@ pub enum Command {
     /// Subcommand to manipulate backups.
     #[command(subcommand)]
     Backups(backups::Command),
+    /// Subcommand to run all consistency checks on the data.
+    /// Exits with an error if any issue is found.
+    Check,
     /// Subcommand to manipulate the codex.
     #[command(subcommand)]
     Codex(codex::Command),
+    /// Subcommand to list the differences in guide entities with older data.
+    Diff(diff::Command),
+    /// Subcommand to find guide and codex entities by name.
+    Find(find::Command),
+    /// Subcommand to list guide items with missing or mismatched icons.
+    Icons(icons::Command),
     /// Subcommand to manipulate the json output.
     #[command(subcommand)]
     Json(json::Command),
@@ -198,6 +374,8 @@ pub enum Command {
     /// Subcommand to manipulate merges.
     #[command(subcommand)]
     Merge(merge::Command),
+    /// Subcommand to query entities matching a filter expression.
+    Query(query::Command),
     /// Subcommand to manipulate translations.
     #[command(subcommand)]
     Translation(translation::Command),
@@ -212,6 +390,9 @@ pub enum Command {
     long_about = None
 )]
 pub struct Cli {
+    /// Do not display progress bars.
+    #[arg(long, global = true)]
+    pub no_progress: bool,
     /// Subcommand, if any.
     #[command(subcommand)]
     pub command: Option<Command>,
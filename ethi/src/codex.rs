@@ -2,6 +2,7 @@ use ornaguide_rs::{data::OrnaData, error::Error, guide::OrnaAdminGuide};
 
 use crate::cli;
 
+pub mod checkpoint;
 pub mod fetch;
 
 /// Execute a CLI subcommand on the codex.
@@ -12,6 +13,7 @@ pub fn cli(
 ) -> Result<(), Error> {
     match command {
         cli::codex::Command::Bugs => crate::codex_bugs::check(&data, guide),
+        cli::codex::Command::BugsJson => crate::codex_bugs::dump(&data),
         cli::codex::Command::Missing => fetch::missing(guide, &data).map(|_| ()),
     }
 }
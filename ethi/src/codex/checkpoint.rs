@@ -0,0 +1,94 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use ornaguide_rs::error::Error;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::misc::json_read;
+
+/// Directory in which checkpoints of codex fetches are written.
+pub const CHECKPOINT_DIR: &str = "data/checkpoints";
+
+/// Entities fetched so far, keyed by their slug.
+/// When backed by a file, the entities can be written to it so that an interrupted fetch can be
+/// resumed without fetching them again. Deleting the file forces a clean fetch.
+pub struct Checkpoint<Entity> {
+    /// The file backing the checkpoint, if any.
+    path: Option<PathBuf>,
+    /// The entities fetched so far.
+    entities: HashMap<String, Entity>,
+}
+
+impl<Entity> Checkpoint<Entity>
+where
+    Entity: Serialize + DeserializeOwned,
+{
+    /// Create a checkpoint that is kept in memory only.
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            entities: HashMap::new(),
+        }
+    }
+
+    /// Open the checkpoint of the given kind of entities in `dir`.
+    /// If `resume` is set, the entities of an existing checkpoint are loaded. Otherwise, any
+    /// existing checkpoint is discarded.
+    pub fn open(dir: &Path, kind: &str, resume: bool) -> Result<Self, Error> {
+        let path = dir.join(format!("{}.json", kind));
+        let entities = if resume && path.exists() {
+            json_read(BufReader::new(File::open(&path)?), &path.to_string_lossy())?
+        } else {
+            HashMap::new()
+        };
+        let checkpoint = Self {
+            path: Some(path),
+            entities,
+        };
+        if !resume {
+            checkpoint.discard()?;
+        }
+        Ok(checkpoint)
+    }
+
+    /// Whether the entity with the given slug has already been fetched.
+    pub fn contains(&self, slug: &str) -> bool {
+        self.entities.contains_key(slug)
+    }
+
+    /// Record a fetched entity.
+    pub fn insert(&mut self, slug: &str, entity: Entity) {
+        self.entities.insert(slug.to_string(), entity);
+    }
+
+    /// Remove the entity with the given slug from the checkpoint and return it.
+    pub fn take(&mut self, slug: &str) -> Option<Entity> {
+        self.entities.remove(slug)
+    }
+
+    /// Write the entities fetched so far to the checkpoint file, if any.
+    pub fn save(&self) -> Result<(), Error> {
+        if let Some(path) = self.path.as_ref() {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            let mut writer = BufWriter::new(File::create(path)?);
+            serde_json::to_writer(&mut writer, &self.entities)
+                .map_err(|err| Error::SerdeJson(err, path.to_string_lossy().to_string()))?;
+            writer.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Remove the checkpoint file, if any.
+    pub fn discard(&self) -> Result<(), Error> {
+        match self.path.as_ref() {
+            Some(path) if path.exists() => Ok(std::fs::remove_file(path)?),
+            _ => Ok(()),
+        }
+    }
+}
@@ -1,4 +1,13 @@
 #![allow(dead_code)]
+use std::{
+    path::Path,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
 use itertools::Itertools;
 use ornaguide_rs::{
     codex::{
@@ -11,68 +20,156 @@ use ornaguide_rs::{
     guide::OrnaAdminGuide,
 };
 
-use crate::misc::bar;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    codex::checkpoint::{Checkpoint, CHECKPOINT_DIR},
+    misc::{bar, diff_sorted_slices, Progress},
+};
+
+/// Slugs of the event followers. Those may or may not appear in the follower list, depending on
+/// the time of the year.
+pub const EVENT_FOLLOWERS: [&str; 53] = [
+    "age-old-mimic",
+    "alfar",
+    "alfar-mage",
+    "amadan",
+    "apollyons-apprentice",
+    "apollyons-apprentice-91d096c2",
+    "apollyons-apprentice-d370c676",
+    "apollyons-apprentice-e9f91df6",
+    "apollyons-pupil",
+    "apollyons-pupil-9d70a08e",
+    "apollyons-pupil-dde6d90b",
+    "apollyons-pupil-e453d6d9",
+    "archimedes",
+    "arisen-naggeneen",
+    "ashen-phoenix",
+    "balor-flame",
+    "balor-worm",
+    "carman",
+    "castor",
+    "cerus",
+    "cruel-banshee",
+    "ebon-scruug",
+    "fey-chimera",
+    "fey-dragon",
+    "fey-gazer",
+    "fey-yeti",
+    "glatisant",
+    "great-pegasus",
+    "gullinkambi",
+    "gwyllgi",
+    "hengreon",
+    "kerberos",
+    "kin-of-kerberos",
+    "lindworm",
+    "llamrai",
+    "naggeneen",
+    "phoenix",
+    "pollux",
+    "pumpkinhead",
+    "raging-cerus",
+    "scary-skeleton",
+    "scruug",
+    "spooky-ghost",
+    "steward-cactus",
+    "steward-dragon",
+    "steward-gazer",
+    "steward-golem",
+    "steward-wolf",
+    "surtrs-flame",
+    "surtrs-mighty-flame",
+    "the-mightiest-mimic",
+    "untamed-cerus",
+    "very-scary-skeleton",
+];
+
+/// URIs of the event monsters that have no drops. Those may not appear in the monster list, and
+/// won't show up when listing monsters through item drops either.
+const UNLISTED_MONSTERS_WITHOUT_DROPS: [&str; 1] = ["/codex/monsters/elite-balor-flame/"];
+
+/// Number of entities fetched between two saves of a checkpoint.
+const CHECKPOINT_INTERVAL: usize = 50;
+
+/// How `fetch_loop` records the entities it fetches.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CheckpointMode {
+    /// Fetched entities are kept in memory only.
+    Disabled,
+    /// Fetched entities are written to a checkpoint. Existing checkpoints are discarded.
+    Fresh,
+    /// Fetched entities are written to a checkpoint. Entities already in an existing checkpoint
+    /// are not fetched again.
+    Resume,
+}
 
 /// Retrieve all items from the codex.
-pub fn items(guide: &OrnaAdminGuide) -> Result<CodexItems, Error> {
+pub fn items(guide: &OrnaAdminGuide, mode: CheckpointMode) -> Result<CodexItems, Error> {
     fetch_loop(
         &guide.codex_fetch_item_list()?,
         |slug| guide.codex_fetch_item(slug),
         "CItems",
+        mode,
     )
     .map(|items| CodexItems { items })
 }
 
 /// Retrieve all searchable monsters from the codex.
 /// This does not fetch monsters from non-active events.
-pub fn monsters(guide: &OrnaAdminGuide) -> Result<CodexMonsters, Error> {
+pub fn monsters(guide: &OrnaAdminGuide, mode: CheckpointMode) -> Result<CodexMonsters, Error> {
     fetch_loop(
         &guide.codex_fetch_monster_list()?,
         |slug| guide.codex_fetch_monster(slug),
         "CMnstrs",
+        mode,
     )
     .map(|monsters| CodexMonsters { monsters })
 }
 
 /// Retrieve all searchable bosses from the codex.
 /// This does not fetch bosses from non-active events.
-pub fn bosses(guide: &OrnaAdminGuide) -> Result<CodexBosses, Error> {
+pub fn bosses(guide: &OrnaAdminGuide, mode: CheckpointMode) -> Result<CodexBosses, Error> {
     fetch_loop(
         &guide.codex_fetch_boss_list()?,
         |slug| guide.codex_fetch_boss(slug),
         "CBosses",
+        mode,
     )
     .map(|bosses| CodexBosses { bosses })
 }
 
 /// Retrieve all searchable raids from the codex.
 /// This does not fetch raids from non-active events.
-pub fn raids(guide: &OrnaAdminGuide) -> Result<CodexRaids, Error> {
+pub fn raids(guide: &OrnaAdminGuide, mode: CheckpointMode) -> Result<CodexRaids, Error> {
     fetch_loop(
         &guide.codex_fetch_raid_list()?,
         |slug| guide.codex_fetch_raid(slug),
         "CRaids",
+        mode,
     )
     .map(|raids| CodexRaids { raids })
 }
 
 /// Retrieve all skills from the codex.
-pub fn skills(guide: &OrnaAdminGuide) -> Result<CodexSkills, Error> {
+pub fn skills(guide: &OrnaAdminGuide, mode: CheckpointMode) -> Result<CodexSkills, Error> {
     fetch_loop(
         &guide.codex_fetch_skill_list()?,
         |slug| guide.codex_fetch_skill(slug),
         "CSkills",
+        mode,
     )
     .map(|skills| CodexSkills { skills })
 }
 
 /// Retrieve all searchable followers from the codex.
 /// This does not fetch followers from non-active events.
-pub fn followers(guide: &OrnaAdminGuide) -> Result<CodexFollowers, Error> {
+pub fn followers(guide: &OrnaAdminGuide, mode: CheckpointMode) -> Result<CodexFollowers, Error> {
     fetch_loop(
         &guide.codex_fetch_follower_list()?,
         |slug| guide.codex_fetch_follower(slug),
         "CFollwrs",
+        mode,
     )
     .map(|followers| CodexFollowers { followers })
 }
@@ -94,6 +191,7 @@ pub fn missing_items(guide: &OrnaAdminGuide, data: &OrnaData) -> Result<CodexIte
             .collect_vec(),
         |slug| guide.codex_fetch_item(slug),
         "CItems",
+        CheckpointMode::Disabled,
     )
     .map(|items| CodexItems { items })
 }
@@ -116,6 +214,7 @@ pub fn missing_monsters(guide: &OrnaAdminGuide, data: &OrnaData) -> Result<Codex
             .collect_vec(),
         |slug| guide.codex_fetch_monster(slug),
         "CMnstrs",
+        CheckpointMode::Disabled,
     )
     .map(|monsters| CodexMonsters { monsters })
 }
@@ -138,6 +237,7 @@ pub fn missing_bosses(guide: &OrnaAdminGuide, data: &OrnaData) -> Result<CodexBo
             .collect_vec(),
         |slug| guide.codex_fetch_boss(slug),
         "CBosses",
+        CheckpointMode::Disabled,
     )
     .map(|bosses| CodexBosses { bosses })
 }
@@ -160,6 +260,7 @@ pub fn missing_raids(guide: &OrnaAdminGuide, data: &OrnaData) -> Result<CodexRai
             .collect_vec(),
         |slug| guide.codex_fetch_raid(slug),
         "CRaids",
+        CheckpointMode::Disabled,
     )
     .map(|raids| CodexRaids { raids })
 }
@@ -181,6 +282,7 @@ pub fn missing_skills(guide: &OrnaAdminGuide, data: &OrnaData) -> Result<CodexSk
             .collect_vec(),
         |slug| guide.codex_fetch_skill(slug),
         "CSkills",
+        CheckpointMode::Disabled,
     )
     .map(|skills| CodexSkills { skills })
 }
@@ -203,6 +305,7 @@ pub fn missing_followers(guide: &OrnaAdminGuide, data: &OrnaData) -> Result<Code
             .collect_vec(),
         |slug| guide.codex_fetch_follower(slug),
         "CFollwrs",
+        CheckpointMode::Disabled,
     )
     .map(|followers| CodexFollowers { followers })
 }
@@ -219,12 +322,158 @@ pub fn missing(guide: &OrnaAdminGuide, data: &OrnaData) -> Result<CodexData, Err
     })
 }
 
+/// List the URIs of the monsters / bosses / raids we know of that may not be listed on the codex.
+/// Those are the monsters dropping items in `codex` and the event monsters that have no drops.
+/// URIs may be duplicated.
+pub fn known_unlisted_monster_uris(codex: &CodexData) -> impl Iterator<Item = &str> {
+    codex
+        .items
+        .items
+        .iter()
+        .flat_map(|item| item.dropped_by.iter())
+        .map(|dropped_by| dropped_by.uri.as_str())
+        .chain(UNLISTED_MONSTERS_WITHOUT_DROPS.iter().copied())
+}
+
+/// Split the slugs listed on the codex and the slugs of the cached entities into slugs that are
+/// only listed (new entities) and slugs that are only cached (removed entities).
+/// Cached slugs that are in `unlisted` are known not to be listed on the codex (e.g.: event
+/// monsters) and are not considered removed.
+fn diff_slugs(listed: &[&str], cached: &[&str], unlisted: &[&str]) -> (Vec<String>, Vec<String>) {
+    let listed = listed.iter().sorted().dedup().collect_vec();
+    let cached = cached.iter().sorted().dedup().collect_vec();
+    let (new, removed) = diff_sorted_slices(&listed, &cached);
+    (
+        new.into_iter().map(|slug| slug.to_string()).collect(),
+        removed
+            .into_iter()
+            .filter(|slug| !unlisted.contains(slug))
+            .map(|slug| slug.to_string())
+            .collect(),
+    )
+}
+
+/// Update the `cached` entities with the `entries` listed on the codex.
+/// Cached entities that are still listed are kept untouched. Listed entries that aren't cached are
+/// fetched. Cached entities that are no longer listed are fetched again and dropped if that fails
+/// (usually with a 404), unless their slug is in `unlisted`, in which case they are kept untouched.
+fn incremental_loop<Entry, Entity, F>(
+    entries: &[Entry],
+    mut cached: Vec<Entity>,
+    unlisted: &[&str],
+    slug_of: fn(&Entity) -> &str,
+    fetch: F,
+    kind: &str,
+) -> Result<Vec<Entity>, Error>
+where
+    Entry: Sluggable,
+    F: Fn(&str) -> Result<Entity, Error>,
+{
+    let (new, removed) = diff_slugs(
+        &entries.iter().map(Sluggable::slug).collect_vec(),
+        &cached.iter().map(slug_of).collect_vec(),
+        unlisted,
+    );
+    cached.retain(|entity| !removed.iter().any(|slug| slug == slug_of(entity)));
+
+    let slugs = new
+        .iter()
+        .chain(removed.iter())
+        .map(String::as_str)
+        .collect_vec();
+    cached.extend(try_fetch_loop_slugs(&slugs, fetch, kind)?);
+    Ok(cached)
+}
+
+/// Retrieve the codex entities that were added or removed since `codex` was fetched.
+/// Only the lists of entities are fetched, along with the pages of new or removed entities.
+/// Entities that are already in `codex` are kept as-is: changes to their stats are not fetched.
+/// Event monsters and followers, which may not be listed on the codex, are kept as well.
+pub fn incremental(guide: &OrnaAdminGuide, codex: CodexData) -> Result<CodexData, Error> {
+    // Slugs of the unlisted monsters / bosses / raids, by kind.
+    let unlisted_monsters = known_unlisted_monster_uris(&codex)
+        .filter_map(|uri| uri.strip_prefix("/codex/"))
+        .filter_map(|path| path.trim_end_matches('/').split_once('/'))
+        .map(|(kind, slug)| (kind.to_string(), slug.to_string()))
+        .into_group_map();
+    let unlisted = |kind: &str| {
+        unlisted_monsters
+            .get(kind)
+            .map(|slugs| slugs.iter().map(String::as_str).collect_vec())
+            .unwrap_or_default()
+    };
+
+    Ok(CodexData {
+        items: CodexItems {
+            items: incremental_loop(
+                &guide.codex_fetch_item_list()?,
+                codex.items.items,
+                &[],
+                |item| &item.slug,
+                |slug| guide.codex_fetch_item(slug),
+                "CItems",
+            )?,
+        },
+        raids: CodexRaids {
+            raids: incremental_loop(
+                &guide.codex_fetch_raid_list()?,
+                codex.raids.raids,
+                &unlisted("raids"),
+                |raid| &raid.slug,
+                |slug| guide.codex_fetch_raid(slug),
+                "CRaids",
+            )?,
+        },
+        monsters: CodexMonsters {
+            monsters: incremental_loop(
+                &guide.codex_fetch_monster_list()?,
+                codex.monsters.monsters,
+                &unlisted("monsters"),
+                |monster| &monster.slug,
+                |slug| guide.codex_fetch_monster(slug),
+                "CMnstrs",
+            )?,
+        },
+        bosses: CodexBosses {
+            bosses: incremental_loop(
+                &guide.codex_fetch_boss_list()?,
+                codex.bosses.bosses,
+                &unlisted("bosses"),
+                |boss| &boss.slug,
+                |slug| guide.codex_fetch_boss(slug),
+                "CBosses",
+            )?,
+        },
+        skills: CodexSkills {
+            skills: incremental_loop(
+                &guide.codex_fetch_skill_list()?,
+                codex.skills.skills,
+                &[],
+                |skill| &skill.slug,
+                |slug| guide.codex_fetch_skill(slug),
+                "CSkills",
+            )?,
+        },
+        followers: CodexFollowers {
+            followers: incremental_loop(
+                &guide.codex_fetch_follower_list()?,
+                codex.followers.followers,
+                &EVENT_FOLLOWERS,
+                |follower| &follower.slug,
+                |slug| guide.codex_fetch_follower(slug),
+                "CFollwrs",
+            )?,
+        },
+    })
+}
+
 /// Retrieve all items from the codex.
 pub fn items_translations(guide: &OrnaAdminGuide, locale: &str) -> Result<CodexItems, Error> {
     fetch_loop(
         &guide.codex_fetch_item_list()?,
         |slug| guide.codex_fetch_item_with_locale(slug, locale),
         "CItems",
+        CheckpointMode::Disabled,
     )
     .map(|items| CodexItems { items })
 }
@@ -236,6 +485,7 @@ pub fn monsters_translations(guide: &OrnaAdminGuide, locale: &str) -> Result<Cod
         &guide.codex_fetch_monster_list()?,
         |slug| guide.codex_fetch_monster_with_locale(slug, locale),
         "CMnstrs",
+        CheckpointMode::Disabled,
     )
     .map(|monsters| CodexMonsters { monsters })
 }
@@ -247,6 +497,7 @@ pub fn bosses_translations(guide: &OrnaAdminGuide, locale: &str) -> Result<Codex
         &guide.codex_fetch_boss_list()?,
         |slug| guide.codex_fetch_boss_with_locale(slug, locale),
         "CBosses",
+        CheckpointMode::Disabled,
     )
     .map(|bosses| CodexBosses { bosses })
 }
@@ -258,6 +509,7 @@ pub fn raids_translations(guide: &OrnaAdminGuide, locale: &str) -> Result<CodexR
         &guide.codex_fetch_raid_list()?,
         |slug| guide.codex_fetch_raid_with_locale(slug, locale),
         "CRaids",
+        CheckpointMode::Disabled,
     )
     .map(|raids| CodexRaids { raids })
 }
@@ -268,6 +520,7 @@ pub fn skills_translations(guide: &OrnaAdminGuide, locale: &str) -> Result<Codex
         &guide.codex_fetch_skill_list()?,
         |slug| guide.codex_fetch_skill_with_locale(slug, locale),
         "CSkills",
+        CheckpointMode::Disabled,
     )
     .map(|skills| CodexSkills { skills })
 }
@@ -282,6 +535,7 @@ pub fn followers_translations(
         &guide.codex_fetch_follower_list()?,
         |slug| guide.codex_fetch_follower_with_locale(slug, locale),
         "CFollwrs",
+        CheckpointMode::Disabled,
     )
     .map(|followers| CodexFollowers { followers })
 }
@@ -329,6 +583,7 @@ pub fn missing_items_translations(
             .collect_vec(),
         |slug| guide.codex_fetch_item_with_locale(slug, locale),
         "CItems",
+        CheckpointMode::Disabled,
     )
     .map(|items| CodexItems { items })
 }
@@ -347,6 +602,7 @@ pub fn missing_monsters_translations(
             .collect_vec(),
         |slug| guide.codex_fetch_monster_with_locale(slug, locale),
         "CMnstrs",
+        CheckpointMode::Disabled,
     )
     .map(|monsters| CodexMonsters { monsters })
 }
@@ -365,6 +621,7 @@ pub fn missing_bosses_translations(
             .collect_vec(),
         |slug| guide.codex_fetch_boss_with_locale(slug, locale),
         "CBosses",
+        CheckpointMode::Disabled,
     )
     .map(|bosses| CodexBosses { bosses })
 }
@@ -383,6 +640,7 @@ pub fn missing_raids_translations(
             .collect_vec(),
         |slug| guide.codex_fetch_raid_with_locale(slug, locale),
         "CRaids",
+        CheckpointMode::Disabled,
     )
     .map(|raids| CodexRaids { raids })
 }
@@ -401,6 +659,7 @@ pub fn missing_skills_translations(
             .collect_vec(),
         |slug| guide.codex_fetch_skill_with_locale(slug, locale),
         "CSkills",
+        CheckpointMode::Disabled,
     )
     .map(|skills| CodexSkills { skills })
 }
@@ -419,20 +678,48 @@ pub fn missing_followers_translations(
             .collect_vec(),
         |slug| guide.codex_fetch_follower_with_locale(slug, locale),
         "CFollwrs",
+        CheckpointMode::Disabled,
     )
     .map(|followers| CodexFollowers { followers })
 }
 
+/// Select the locales of `locale_db` whose name is in `filter`.
+/// If `filter` is empty, all locales are selected. Returns an error if a locale of `filter` is not
+/// in `locale_db`.
+fn select_locales<'a>(
+    locale_db: &'a LocaleDB,
+    filter: &[String],
+) -> Result<Vec<(&'a String, &'a LocaleStrings)>, Error> {
+    if let Some(unknown) = filter
+        .iter()
+        .find(|locale| !locale_db.locales.contains_key(*locale))
+    {
+        return Err(Error::Misc(format!(
+            "Locale {} is not in the locale database",
+            unknown
+        )));
+    }
+
+    Ok(locale_db
+        .locales
+        .iter()
+        .filter(|(locale, _)| filter.is_empty() || filter.contains(*locale))
+        .collect())
+}
+
 /// Retrieve all missing translations from the already-known locales in `locale_db`.
+/// If `filter` is not empty, only the locales it contains are fetched. They must all be in
+/// `locale_db`.
 /// Returns a new instance of a db, that may be merged with `locale_db` if needed.
 pub fn missing_translations(
     guide: &OrnaAdminGuide,
     data: &OrnaData,
     locale_db: &LocaleDB,
+    filter: &[String],
 ) -> Result<LocaleDB, Error> {
     let mut ret = LocaleDB::default();
 
-    for (locale, db) in locale_db.locales.iter() {
+    for (locale, db) in select_locales(locale_db, filter)? {
         println!("Fetching missing translations for locale {}", locale);
         let mut strings = LocaleStrings::default();
 
@@ -497,33 +784,177 @@ pub fn follower_slugs(guide: &OrnaAdminGuide, slugs: &[&str]) -> Result<CodexFol
         .map(|followers| CodexFollowers { followers })
 }
 
+/// Fetch entities from multiple threads, updating the progress bar as fetches complete.
+/// At most `concurrency` fetches are running at once, and the starts of two fetches are at least
+/// `spacing` apart.
+/// Results are returned in the order of `slugs`.
+fn fetch_concurrently<F, Entity>(
+    slugs: &[&str],
+    fetch: F,
+    concurrency: usize,
+    spacing: Duration,
+    bar: &dyn Progress,
+) -> Vec<Result<Entity, Error>>
+where
+    F: Fn(&str) -> Result<Entity, Error> + Sync,
+    Entity: Send,
+{
+    let next_slug = AtomicUsize::new(0);
+    let next_start = Mutex::new(Instant::now());
+
+    let mut results = std::thread::scope(|scope| {
+        let workers = (0..concurrency.clamp(1, slugs.len().max(1)))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = Vec::new();
+                    loop {
+                        let i = next_slug.fetch_add(1, Ordering::Relaxed);
+                        let slug = match slugs.get(i) {
+                            Some(slug) => *slug,
+                            None => break,
+                        };
+
+                        // Reserve the next start slot and wait for it.
+                        let wait = {
+                            let mut next_start = next_start.lock().unwrap();
+                            let now = Instant::now();
+                            let start = (*next_start).max(now);
+                            *next_start = start + spacing;
+                            start - now
+                        };
+                        std::thread::sleep(wait);
+
+                        bar.set_message(slug);
+                        results.push((i, fetch(slug)));
+                        bar.inc(1);
+                    }
+                    results
+                })
+            })
+            .collect_vec();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap())
+            .collect_vec()
+    });
+
+    results.sort_by_key(|(i, _)| *i);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// Fetch entities one after the other, waiting `sleep` after each fetch.
+fn fetch_serially<F, Entity>(
+    slugs: &[&str],
+    fetch: F,
+    sleep: Duration,
+    bar: &dyn Progress,
+) -> Vec<Result<Entity, Error>>
+where
+    F: Fn(&str) -> Result<Entity, Error>,
+{
+    slugs
+        .iter()
+        .map(|slug| {
+            bar.set_message(slug);
+            let result = fetch(slug);
+            bar.inc(1);
+            if !sleep.is_zero() {
+                std::thread::sleep(sleep);
+            }
+            result
+        })
+        .collect()
+}
+
+/// Fetch the entities with the given slugs in chunks, recording them in `checkpoint`.
+/// Slugs that are already in the checkpoint are not fetched again. The checkpoint is saved after
+/// each chunk and discarded once all entities have been fetched.
+/// Failed fetches are logged and skipped.
+/// Entities are returned in the order of `slugs`.
+fn fetch_with_checkpoint<F, Entity>(
+    slugs: &[&str],
+    mut fetch_chunk: F,
+    kind: &str,
+    checkpoint: &mut Checkpoint<Entity>,
+    bar: &dyn Progress,
+) -> Result<Vec<Entity>, Error>
+where
+    F: FnMut(&[&str]) -> Vec<Result<Entity, Error>>,
+    Entity: Serialize + DeserializeOwned,
+{
+    let to_fetch = slugs
+        .iter()
+        .copied()
+        .filter(|slug| !checkpoint.contains(slug))
+        .collect_vec();
+    bar.inc((slugs.len() - to_fetch.len()) as u64);
+
+    let mut failed = false;
+    for chunk in to_fetch.chunks(CHECKPOINT_INTERVAL) {
+        for (slug, result) in chunk.iter().zip(fetch_chunk(chunk)) {
+            match result {
+                Ok(entity) => checkpoint.insert(slug, entity),
+                Err(x) => {
+                    failed = true;
+                    eprintln!("Failed to fetch {} {}: {}\n", kind, slug, x);
+                }
+            }
+        }
+        checkpoint.save()?;
+    }
+    if !failed {
+        checkpoint.discard()?;
+    }
+
+    Ok(slugs
+        .iter()
+        .filter_map(|slug| checkpoint.take(slug))
+        .collect())
+}
+
 /// Loop fetching entities and displaying a progress bar.
-/// Errors out after the first failed fetch.
+/// Failed fetches are logged and skipped.
+/// Entities are fetched concurrently (see `playorna_concurrency`), unless `playorna_sleep` is set.
+/// Fetched entities are recorded in a checkpoint according to `mode`.
 fn fetch_loop<Entry, F, Entity>(
     entries: &[Entry],
     fetch: F,
     kind: &str,
+    mode: CheckpointMode,
 ) -> Result<Vec<Entity>, Error>
 where
     Entry: Sluggable,
-    F: Fn(&str) -> Result<Entity, Error>,
+    F: Fn(&str) -> Result<Entity, Error> + Sync,
+    Entity: Send + Serialize + DeserializeOwned,
 {
-    let sleep = crate::config::playorna_sleep()? as u64;
-    let mut ret = Vec::with_capacity(entries.len());
-    let bar = bar(entries.len() as u64);
-    for entry in entries.iter() {
-        let slug = entry.slug();
-        bar.set_message(slug.to_string());
-        match fetch(slug) {
-            Ok(item) => ret.push(item),
-            Err(x) => eprintln!("Failed to fetch {} {}: {}\n", kind, slug, x),
-        }
-        bar.inc(1);
-        if sleep > 0 {
-            std::thread::sleep(std::time::Duration::from_secs(sleep));
-        }
-    }
-    bar.finish_with_message(format!("{:7 } fetched", kind));
+    let sleep = Duration::from_secs(crate::config::playorna_sleep()? as u64);
+    let concurrency = crate::config::playorna_concurrency()?;
+    let spacing = Duration::from_millis(crate::config::playorna_spacing()?);
+    let mut checkpoint = match mode {
+        CheckpointMode::Disabled => Checkpoint::in_memory(),
+        mode => Checkpoint::open(
+            Path::new(CHECKPOINT_DIR),
+            kind,
+            mode == CheckpointMode::Resume,
+        )?,
+    };
+
+    let slugs = entries.iter().map(|entry| entry.slug()).collect_vec();
+    let bar = bar(slugs.len() as u64);
+    let ret = fetch_with_checkpoint(
+        &slugs,
+        |chunk| {
+            if sleep.is_zero() && concurrency > 1 {
+                fetch_concurrently(chunk, &fetch, concurrency, spacing, bar.as_ref())
+            } else {
+                fetch_serially(chunk, &fetch, sleep, bar.as_ref())
+            }
+        },
+        kind,
+        &mut checkpoint,
+        bar.as_ref(),
+    )?;
+    bar.finish_with_message(&format!("{:7 } fetched", kind));
     Ok(ret)
 }
 
@@ -541,7 +972,7 @@ where
     let mut ret = Vec::with_capacity(slugs.len());
     let bar = bar(slugs.len() as u64);
     for slug in slugs.iter() {
-        bar.set_message(slug.to_string());
+        bar.set_message(slug);
         match fetch(slug) {
             Ok(item) => ret.push(item),
             Err(x) => eprintln!("Failed to fetch {} {}: {}\n", kind, slug, x),
@@ -551,6 +982,159 @@ where
             std::thread::sleep(std::time::Duration::from_secs(sleep));
         }
     }
-    bar.finish_with_message(format!("{:7 } fetched", kind));
+    bar.finish_with_message(&format!("{:7 } fetched", kind));
     Ok(ret)
 }
+
+#[cfg(test)]
+mod test {
+    use ornaguide_rs::codex::translation::{LocaleDB, LocaleStrings};
+
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    use ornaguide_rs::error::Error;
+
+    use crate::{
+        codex::{
+            checkpoint::Checkpoint,
+            fetch::{diff_slugs, fetch_concurrently, fetch_with_checkpoint, select_locales},
+        },
+        misc::NoProgress,
+    };
+
+    #[test]
+    fn test_fetch_resume() {
+        let dir = std::env::temp_dir().join(format!("ethi-checkpoint-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let slugs = ["sword", "bow", "axe", "dagger", "staff"];
+        let fetched = std::cell::RefCell::new(Vec::new());
+        let fetch_chunk = |chunk: &[&str], fail_from: &str| {
+            chunk
+                .iter()
+                .map(|slug| {
+                    fetched.borrow_mut().push(slug.to_string());
+                    if *slug >= fail_from {
+                        Err(Error::Misc("Connection reset".to_string()))
+                    } else {
+                        Ok(slug.to_uppercase())
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // The connection fails halfway through (slugs sorting after `c`). The checkpoint is kept.
+        let mut checkpoint = Checkpoint::open(&dir, "CItems", false).unwrap();
+        let partial = fetch_with_checkpoint(
+            &slugs,
+            |chunk| fetch_chunk(chunk, "c"),
+            "CItems",
+            &mut checkpoint,
+            &NoProgress,
+        )
+        .unwrap();
+        assert_eq!(partial, vec!["BOW", "AXE"]);
+        assert!(dir.join("CItems.json").exists());
+
+        // Resuming only fetches the remaining slugs and removes the checkpoint once done.
+        fetched.borrow_mut().clear();
+        let mut checkpoint = Checkpoint::open(&dir, "CItems", true).unwrap();
+        let all = fetch_with_checkpoint(
+            &slugs,
+            |chunk| fetch_chunk(chunk, "z"),
+            "CItems",
+            &mut checkpoint,
+            &NoProgress,
+        )
+        .unwrap();
+        assert_eq!(*fetched.borrow(), vec!["sword", "dagger", "staff"]);
+        assert_eq!(all, vec!["SWORD", "BOW", "AXE", "DAGGER", "STAFF"]);
+        assert!(!dir.join("CItems.json").exists());
+
+        // Not resuming discards an existing checkpoint.
+        let mut checkpoint = Checkpoint::open(&dir, "CItems", false).unwrap();
+        checkpoint.insert("sword", "SWORD".to_string());
+        checkpoint.save().unwrap();
+        let checkpoint = Checkpoint::<String>::open(&dir, "CItems", false).unwrap();
+        assert!(!checkpoint.contains("sword"));
+        assert!(!dir.join("CItems.json").exists());
+    }
+
+    #[test]
+    fn test_fetch_concurrently() {
+        let slugs = (0..20).map(|i| format!("slug-{}", i)).collect::<Vec<_>>();
+        let slugs = slugs.iter().map(String::as_str).collect::<Vec<_>>();
+        let running = AtomicUsize::new(0);
+        let max_running = AtomicUsize::new(0);
+
+        let results = fetch_concurrently(
+            &slugs,
+            |slug| {
+                let now_running = running.fetch_add(1, Ordering::SeqCst) + 1;
+                max_running.fetch_max(now_running, Ordering::SeqCst);
+                // Finish later slugs first, so that completion order differs from input order.
+                let i = slug["slug-".len()..].parse::<u64>().unwrap();
+                std::thread::sleep(Duration::from_millis(20 - i));
+                running.fetch_sub(1, Ordering::SeqCst);
+                if i == 7 {
+                    Err(Error::Misc("Not found".to_string()))
+                } else {
+                    Ok(slug.to_string())
+                }
+            },
+            3,
+            Duration::ZERO,
+            &NoProgress,
+        );
+
+        assert_eq!(results.len(), slugs.len());
+        for (slug, result) in slugs.iter().zip(results.iter()) {
+            match result {
+                Ok(fetched) => assert_eq!(fetched, slug),
+                Err(_) => assert_eq!(*slug, "slug-7"),
+            }
+        }
+        assert!(max_running.load(Ordering::SeqCst) <= 3);
+        assert!(max_running.load(Ordering::SeqCst) > 1);
+    }
+
+    #[test]
+    fn test_diff_slugs() {
+        let (new, removed) = diff_slugs(&["sword", "axe", "bow"], &["bow", "sword", "dagger"], &[]);
+        assert_eq!(new, vec!["axe".to_string()]);
+        assert_eq!(removed, vec!["dagger".to_string()]);
+
+        let (new, removed) = diff_slugs(&["sword", "sword"], &["sword"], &[]);
+        assert!(new.is_empty());
+        assert!(removed.is_empty());
+
+        // Unlisted entities are not removed.
+        let (new, removed) = diff_slugs(&["slime"], &["slime", "ghost", "bat"], &["ghost", "imp"]);
+        assert!(new.is_empty());
+        assert_eq!(removed, vec!["bat".to_string()]);
+    }
+
+    #[test]
+    fn test_select_locales() {
+        let mut locales = LocaleDB::default();
+        for locale in ["fr", "de"] {
+            locales.locales.insert(
+                locale.to_string(),
+                LocaleStrings {
+                    locale: locale.to_string(),
+                    ..Default::default()
+                },
+            );
+        }
+
+        let selected = select_locales(&locales, &["de".to_string()]).unwrap();
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].0, "de");
+        assert_eq!(selected[0].1.locale, "de");
+
+        assert_eq!(select_locales(&locales, &[]).unwrap().len(), 2);
+        assert!(select_locales(&locales, &["de".to_string(), "es".to_string()]).is_err());
+    }
+}
@@ -1,48 +1,42 @@
+use std::{
+    fmt::Display,
+    fs::File,
+    io::{BufWriter, Write},
+};
+
 use ornaguide_rs::{
-    codex::{Codex, CodexBoss, CodexMonster, CodexRaid, Tag},
+    codex::{weapon_inflicted_statuses, Codex, CodexElement, CodexGenericMonsterOwned, Tag},
     data::OrnaData,
     error::Error,
     guide::OrnaAdminGuide,
 };
+use serde::Serialize;
 
-use crate::guide_match::items::get_iter_element_statuses;
-
-enum Status {
-    Fixed,
-    PartiallyFixed,
-    NotFixed,
+/// An inconsistency found in the codex data.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct CodexBug {
+    /// The kind of inconsistency (e.g.: `item_dropped_by_unknown_monster`).
+    pub kind: &'static str,
+    /// The URI of the codex entity on which the inconsistency was found.
+    pub entity_uri: String,
+    /// A human-readable description of the inconsistency.
+    pub detail: String,
 }
 
-enum CodexGenericMonsterOwned {
-    Monster(CodexMonster),
-    Boss(CodexBoss),
-    Raid(CodexRaid),
+impl CodexBug {
+    /// Create a new bug of the given kind for the entity with the given URI.
+    fn new(kind: &'static str, entity_uri: impl ToString, detail: impl ToString) -> Self {
+        Self {
+            kind,
+            entity_uri: entity_uri.to_string(),
+            detail: detail.to_string(),
+        }
+    }
 }
 
-/// Fetch a generic monster from an URI on the codex.
-fn get_generic_monster(
-    guide: &OrnaAdminGuide,
-    uri: &str,
-) -> Result<CodexGenericMonsterOwned, Error> {
-    let uri = uri
-        .strip_prefix("/codex/")
-        .unwrap()
-        .strip_suffix('/')
-        .unwrap();
-    let pos = uri.find('/').unwrap();
-    let kind = &uri[0..pos];
-    let slug = &uri[pos + 1..];
-    match kind {
-        "monsters" => guide
-            .codex_fetch_monster(slug)
-            .map(CodexGenericMonsterOwned::Monster),
-        "bosses" => guide
-            .codex_fetch_boss(slug)
-            .map(CodexGenericMonsterOwned::Boss),
-        "raids" => guide
-            .codex_fetch_raid(slug)
-            .map(CodexGenericMonsterOwned::Raid),
-        _ => panic!("Unknown kind: {} ({})", kind, slug),
+impl Display for CodexBug {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] {}: {}", self.kind, self.entity_uri, self.detail)
     }
 }
 
@@ -50,7 +44,7 @@ fn get_generic_monster(
 fn weapons_missing_elemental_status_effects(
     data: &OrnaData,
     guide: &OrnaAdminGuide,
-) -> Result<Status, Error> {
+) -> Result<Vec<CodexBug>, Error> {
     let demeter = match guide.codex_fetch_item("arisen-demeters-staff") {
         Ok(x) => x,
         Err(msg) => {
@@ -61,38 +55,56 @@ fn weapons_missing_elemental_status_effects(
         }
     };
 
-    if demeter.causes.iter().any(|effect| effect.name == "Rot") {
-        for item in data.codex.items.items.iter().filter(|item| {
-            if let Some(element) = item.stats.as_ref().and_then(|stats| stats.element.as_ref()) {
-                get_iter_element_statuses(Some(element)).count() > 0
-            } else {
-                false
-            }
-        }) {
-            // Ignore 404s.
-            let item = match guide.codex_fetch_item(&item.slug) {
-                Ok(x) => x,
-                Err(Error::ResponseError(_, _, 404, _)) => continue,
-                Err(x) => return Err(x),
-            };
+    if !demeter.causes.iter().any(|effect| effect.name == "Rot") {
+        return Ok(vec![CodexBug::new(
+            "missing_elemental_status_effects",
+            "/codex/items/arisen-demeters-staff/",
+            "Does not cause Rot",
+        )]);
+    }
 
-            // Check that all elemental statuses appear.
-            if !get_iter_element_statuses(
-                item.stats.as_ref().and_then(|stats| stats.element.as_ref()),
-            )
-            .all(|status| item.causes.iter().any(|cause| cause.name == status))
-            {
-                return Ok(Status::PartiallyFixed);
-            }
+    let mut bugs = Vec::new();
+    for item in data.codex.items.items.iter().filter(|item| {
+        if let Some(element) = item.stats.as_ref().and_then(|stats| stats.element.as_ref()) {
+            !element.inflicted_statuses().is_empty()
+        } else {
+            false
+        }
+    }) {
+        // Ignore 404s.
+        let item = match guide.codex_fetch_item(&item.slug) {
+            Ok(x) => x,
+            Err(Error::ResponseError(_, _, 404, _)) => continue,
+            Err(x) => return Err(x),
+        };
+
+        // Check that all elemental statuses appear.
+        let missing = item
+            .stats
+            .as_ref()
+            .and_then(|stats| stats.element.as_ref())
+            .map(CodexElement::inflicted_statuses)
+            .unwrap_or_default()
+            .iter()
+            .filter(|status| !item.causes.iter().any(|cause| cause.name == **status))
+            .copied()
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            bugs.push(CodexBug::new(
+                "missing_elemental_status_effects",
+                format!("/codex/items/{}/", item.slug),
+                format!("Does not cause {}", missing.join(", ")),
+            ));
         }
-        Ok(Status::Fixed)
-    } else {
-        Ok(Status::NotFixed)
     }
+    Ok(bugs)
 }
 
-/// Check for weapons that are missing the Bind or Bite skills.
-fn monsters_missing_bind_bite(data: &OrnaData, guide: &OrnaAdminGuide) -> Result<Status, Error> {
+/// Check for monsters that are missing the Bind or Bite skills.
+fn monsters_missing_bind_bite(
+    data: &OrnaData,
+    guide: &OrnaAdminGuide,
+) -> Result<Vec<CodexBug>, Error> {
     let gull = match guide.codex_fetch_monster("gull") {
         Ok(x) => x,
         Err(msg) => {
@@ -100,119 +112,146 @@ fn monsters_missing_bind_bite(data: &OrnaData, guide: &OrnaAdminGuide) -> Result
         }
     };
 
-    if gull.abilities.iter().any(|skill| skill.name == "Bite") {
-        let bind = data
-            .guide
-            .skills
-            .skills
-            .iter()
-            .find(|skill| skill.name == "Bind")
-            .ok_or_else(|| Error::Misc("Failed to find Bind".to_string()))?;
-        let bite = data
-            .guide
-            .skills
+    if !gull.abilities.iter().any(|skill| skill.name == "Bite") {
+        return Ok(vec![CodexBug::new(
+            "missing_bind_bite",
+            "/codex/monsters/gull/",
+            "Does not have Bite",
+        )]);
+    }
+
+    let bind = data
+        .guide
+        .skills
+        .skills
+        .iter()
+        .find(|skill| skill.name == "Bind")
+        .ok_or_else(|| Error::Misc("Failed to find Bind".to_string()))?;
+    let bite = data
+        .guide
+        .skills
+        .skills
+        .iter()
+        .find(|skill| skill.name == "Bite")
+        .ok_or_else(|| Error::Misc("Failed to find Bite".to_string()))?;
+    let mut bugs = Vec::new();
+    for monster in data.guide.monsters.monsters.iter().filter(|monster| {
+        monster
             .skills
             .iter()
-            .find(|skill| skill.name == "Bite")
-            .ok_or_else(|| Error::Misc("Failed to find Bite".to_string()))?;
-        for monster in data.guide.monsters.monsters.iter().filter(|monster| {
+            .any(|id| *id == bind.id || *id == bite.id)
+    }) {
+        let codex_monster = guide.codex_fetch_monster(
             monster
-                .skills
-                .iter()
-                .any(|id| *id == bind.id || *id == bite.id)
-        }) {
-            let monster = guide.codex_fetch_monster(
-                monster
-                    .codex_uri
-                    .strip_prefix("/codex/monsters/")
-                    .and_then(|s| s.strip_suffix('/'))
-                    .unwrap(),
-            )?;
-            if !monster
-                .abilities
-                .iter()
-                .any(|ability| ability.name == "Bind" || ability.name == "Bite")
-            {
-                return Ok(Status::PartiallyFixed);
-            }
+                .codex_uri
+                .strip_prefix("/codex/monsters/")
+                .and_then(|s| s.strip_suffix('/'))
+                .unwrap(),
+        )?;
+        if !codex_monster
+            .abilities
+            .iter()
+            .any(|ability| ability.name == "Bind" || ability.name == "Bite")
+        {
+            bugs.push(CodexBug::new(
+                "missing_bind_bite",
+                &monster.codex_uri,
+                "Does not have Bind or Bite",
+            ));
         }
-        Ok(Status::Fixed)
-    } else {
-        Ok(Status::NotFixed)
     }
+    Ok(bugs)
 }
 
 /// Check for Yggdrasils' raid tags.
-fn trees_missing_raid_tags(_: &OrnaData, guide: &OrnaAdminGuide) -> Result<Status, Error> {
-    let ygg = guide.codex_fetch_raid("yggdrasil")?;
-    let aygg = guide.codex_fetch_raid("arisen-yggdrasil")?;
-
-    if ygg.tags.contains(&ornaguide_rs::codex::Tag::WorldRaid)
-        && aygg.tags.contains(&ornaguide_rs::codex::Tag::WorldRaid)
-    {
-        Ok(Status::Fixed)
-    } else {
-        Ok(Status::NotFixed)
+fn trees_missing_raid_tags(_: &OrnaData, guide: &OrnaAdminGuide) -> Result<Vec<CodexBug>, Error> {
+    let mut bugs = Vec::new();
+    for slug in ["yggdrasil", "arisen-yggdrasil"] {
+        if !guide.codex_fetch_raid(slug)?.tags.contains(&Tag::WorldRaid) {
+            bugs.push(CodexBug::new(
+                "missing_world_raid_tag",
+                format!("/codex/raids/{}/", slug),
+                "Does not have the World Raid tag",
+            ));
+        }
     }
+    Ok(bugs)
 }
 
 /// Check for Swansong's "Blind" cause.
-fn swansong_missing_blind(_: &OrnaData, guide: &OrnaAdminGuide) -> Result<Status, Error> {
+fn swansong_missing_blind(_: &OrnaData, guide: &OrnaAdminGuide) -> Result<Vec<CodexBug>, Error> {
     let swansong = guide.codex_fetch_item("swansong")?;
 
     if swansong.causes.iter().any(|cause| cause.name == "Blind") {
-        Ok(Status::Fixed)
+        Ok(vec![])
     } else {
-        Ok(Status::NotFixed)
+        Ok(vec![CodexBug::new(
+            "missing_cause",
+            "/codex/items/swansong/",
+            "Does not cause Blind",
+        )])
     }
 }
 
-/// Check for Kerberos monsters / raids / bosses missing their Rise/Return of Kerberos event.
-fn kerberos_missing_event(data: &OrnaData, guide: &OrnaAdminGuide) -> Result<Status, Error> {
-    for monster in data.guide.monsters.monsters.iter().filter(|monster|
-                // Rise of Kerberos
-                monster.spawns.contains(&18) ||
-                // Return of Kerberos
-                monster.spawns.contains(&50))
+/// Check for monsters / raids / bosses spawning during any of the events with the given guide ids
+/// that are missing any of the given events in the codex.
+fn monsters_missing_events(
+    data: &OrnaData,
+    guide: &OrnaAdminGuide,
+    spawns: &[u32],
+    events: &[&str],
+) -> Result<Vec<CodexBug>, Error> {
+    let mut bugs = Vec::new();
+    for monster in data
+        .guide
+        .monsters
+        .monsters
+        .iter()
+        .filter(|monster| spawns.iter().any(|spawn| monster.spawns.contains(spawn)))
     {
-        let events = match get_generic_monster(guide, &monster.codex_uri)? {
-            CodexGenericMonsterOwned::Monster(x) => x.events,
-            CodexGenericMonsterOwned::Boss(x) => x.events,
-            CodexGenericMonsterOwned::Raid(x) => x.events,
-        };
-        if !events.contains(&"Rise of Kerberos".to_string())
-            || !events.contains(&"Return of Kerberos".to_string())
-        {
-            return Ok(Status::NotFixed);
+        let codex_monster = guide.codex_fetch_generic_monster_by_uri(&monster.codex_uri)?;
+        let codex_events = codex_monster.events();
+        let missing = events
+            .iter()
+            .filter(|event| !codex_events.iter().any(|codex_event| codex_event == *event))
+            .copied()
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            bugs.push(CodexBug::new(
+                "missing_event",
+                &monster.codex_uri,
+                format!("Missing events {}", missing.join(", ")),
+            ));
         }
     }
-    Ok(Status::Fixed)
+    Ok(bugs)
+}
+
+/// Check for Kerberos monsters / raids / bosses missing their Rise/Return of Kerberos event.
+fn kerberos_missing_event(data: &OrnaData, guide: &OrnaAdminGuide) -> Result<Vec<CodexBug>, Error> {
+    // Rise of Kerberos and Return of Kerberos.
+    monsters_missing_events(
+        data,
+        guide,
+        &[18, 50],
+        &["Rise of Kerberos", "Return of Kerberos"],
+    )
 }
 
 /// Check for Phoenix monsters / raids / bosses missing their Rise/Return of the Phoenix event.
-fn phoenix_missing_event(data: &OrnaData, guide: &OrnaAdminGuide) -> Result<Status, Error> {
-    for monster in data.guide.monsters.monsters.iter().filter(|monster|
-                // Rise of the Phoenix
-                monster.spawns.contains(&28) ||
-                // Return of the Phoenix
-                monster.spawns.contains(&38))
-    {
-        let events = match get_generic_monster(guide, &monster.codex_uri)? {
-            CodexGenericMonsterOwned::Monster(x) => x.events,
-            CodexGenericMonsterOwned::Boss(x) => x.events,
-            CodexGenericMonsterOwned::Raid(x) => x.events,
-        };
-        if !events.contains(&"Rise of the Phoenix".to_string())
-            || !events.contains(&"Return of the Phoenix".to_string())
-        {
-            return Ok(Status::NotFixed);
-        }
-    }
-    Ok(Status::Fixed)
+fn phoenix_missing_event(data: &OrnaData, guide: &OrnaAdminGuide) -> Result<Vec<CodexBug>, Error> {
+    // Rise of the Phoenix and Return of the Phoenix.
+    monsters_missing_events(
+        data,
+        guide,
+        &[28, 38],
+        &["Rise of the Phoenix", "Return of the Phoenix"],
+    )
 }
 
 /// Check for "Of Giants and Titans" raids missing their "World Raid" tag.
-fn giants_titans_tag(data: &OrnaData, guide: &OrnaAdminGuide) -> Result<Status, Error> {
+fn giants_titans_tag(data: &OrnaData, guide: &OrnaAdminGuide) -> Result<Vec<CodexBug>, Error> {
+    let mut bugs = Vec::new();
     for monster in data
         .guide
         .monsters
@@ -220,7 +259,7 @@ fn giants_titans_tag(data: &OrnaData, guide: &OrnaAdminGuide) -> Result<Status,
         .iter()
         .filter(|monster| monster.name.ends_with("of Olympia"))
     {
-        let tags = match get_generic_monster(guide, &monster.codex_uri)? {
+        let tags = match guide.codex_fetch_generic_monster_by_uri(&monster.codex_uri)? {
             CodexGenericMonsterOwned::Raid(x) => x.tags,
             _ => {
                 return Err(Error::Misc(format!(
@@ -230,39 +269,157 @@ fn giants_titans_tag(data: &OrnaData, guide: &OrnaAdminGuide) -> Result<Status,
             }
         };
         if !tags.contains(&Tag::WorldRaid) {
-            return Ok(Status::NotFixed);
+            bugs.push(CodexBug::new(
+                "missing_world_raid_tag",
+                &monster.codex_uri,
+                "Does not have the World Raid tag",
+            ));
         }
     }
-    Ok(Status::Fixed)
+    Ok(bugs)
 }
 
 /// Check Twin Attack missing its " (Off-hand)" suffix.
 fn twin_attack_missing_offhand_suffix(
     _: &OrnaData,
     guide: &OrnaAdminGuide,
-) -> Result<Status, Error> {
+) -> Result<Vec<CodexBug>, Error> {
     let twin_attack = guide.codex_fetch_skill("twin-attack")?;
     if twin_attack.name == "Twin Attack (Off-hand)" {
-        Ok(Status::Fixed)
+        Ok(vec![])
     } else {
-        Ok(Status::NotFixed)
+        Ok(vec![CodexBug::new(
+            "wrong_name",
+            "/codex/spells/twin-attack/",
+            format!("Named {}", twin_attack.name),
+        )])
+    }
+}
+
+/// List items that are dropped by monsters / bosses / raids we have no codex entry for.
+/// Drops with a malformed URI are reported as well.
+fn items_dropped_by_unknown_monsters(data: &OrnaData) -> Vec<CodexBug> {
+    let mut bugs = Vec::new();
+    for item in data.codex.items.items.iter() {
+        for dropped_by in item.dropped_by.iter() {
+            let detail = match data.codex.find_generic_monster_from_uri(&dropped_by.uri) {
+                Ok(Some(_)) => continue,
+                Ok(None) => format!(
+                    "Dropped by {} ({}) which is not in the codex",
+                    dropped_by.name, dropped_by.uri
+                ),
+                Err(err) => format!("Dropped by {}: {}", dropped_by.name, err),
+            };
+            bugs.push(CodexBug {
+                kind: "item_dropped_by_unknown_monster",
+                entity_uri: format!("/codex/items/{}/", item.slug),
+                detail,
+            });
+        }
     }
+    bugs
+}
+
+/// List monsters / bosses / raids that drop items whose slug is not in the codex.
+fn drops_with_missing_slugs(data: &OrnaData) -> Vec<CodexBug> {
+    data.codex
+        .iter_all_monsters()
+        .flat_map(|monster| {
+            monster
+                .drops()
+                .iter()
+                .filter(|drop| data.codex.items.find_by_uri(&drop.uri).is_none())
+                .map(move |drop| CodexBug {
+                    kind: "missing_slug",
+                    entity_uri: monster.uri(),
+                    detail: format!(
+                        "Drops {} ({}) which is not in the codex",
+                        drop.name, drop.uri
+                    ),
+                })
+        })
+        .collect()
+}
+
+/// List weapons that do not cause all the statuses their element inflicts.
+fn items_with_mismatched_elements(data: &OrnaData) -> Vec<CodexBug> {
+    data.codex
+        .items
+        .items
+        .iter()
+        .filter_map(|item| {
+            let element = item.stats.as_ref().and_then(|stats| stats.element.as_ref());
+            let missing = weapon_inflicted_statuses(element, &item.name)
+                .into_iter()
+                .filter(|status| !item.causes.iter().any(|cause| cause.name == *status))
+                .collect::<Vec<_>>();
+            if missing.is_empty() {
+                None
+            } else {
+                Some(CodexBug {
+                    kind: "mismatched_element",
+                    entity_uri: format!("/codex/items/{}/", item.slug),
+                    detail: format!("Does not cause {}", missing.join(", ")),
+                })
+            }
+        })
+        .collect()
+}
+
+/// A check for inconsistencies in the codex data that does not query the codex.
+type Detector = fn(&OrnaData) -> Vec<CodexBug>;
+
+/// The checks that do not query the codex, along with their description.
+const DETECTORS: [(&str, Detector); 3] = [
+    (
+        "Items dropped by monsters not in the codex",
+        items_dropped_by_unknown_monsters,
+    ),
+    (
+        "Monsters dropping items not in the codex",
+        drops_with_missing_slugs,
+    ),
+    (
+        "Weapons not causing their elemental statuses",
+        items_with_mismatched_elements,
+    ),
+];
+
+/// List all inconsistencies we can find in the codex data, without querying the codex.
+pub fn find_bugs(data: &OrnaData) -> Vec<CodexBug> {
+    DETECTORS
+        .iter()
+        .flat_map(|(_, detector)| detector(data))
+        .collect()
+}
+
+/// Write the inconsistencies found in the codex data to `output/codex_bugs.json`.
+pub fn dump(data: &OrnaData) -> Result<(), Error> {
+    let bugs = find_bugs(data);
+    std::fs::create_dir_all("output")?;
+    let mut writer = BufWriter::new(File::create("output/codex_bugs.json")?);
+    serde_json::to_writer_pretty(&mut writer, &bugs)
+        .map_err(|err| Error::SerdeJson(err, "output/codex_bugs.json".to_string()))?;
+    writer.flush()?;
+    Ok(())
 }
 
 /// Check whether a specific bug we found on the codex has been fixed and display the results.
-fn do_check<F>(data: &OrnaData, guide: &OrnaAdminGuide, name: &str, checker: F) -> Result<(), Error>
+/// Each entity on which the bug is still present is listed.
+fn do_check<F>(data: &OrnaData, guide: &OrnaAdminGuide, name: &str, checker: F)
 where
-    F: FnOnce(&OrnaData, &OrnaAdminGuide) -> Result<Status, Error>,
+    F: FnOnce(&OrnaData, &OrnaAdminGuide) -> Result<Vec<CodexBug>, Error>,
 {
     match checker(data, guide) {
-        Ok(Status::NotFixed) => println!("[\x1B[0;31m{:^15}\x1B[0m] {}", "Not fixed", name),
-        Ok(Status::PartiallyFixed) => {
-            println!("[\x1B[0;33m{:^15}\x1B[0m] {}", "Partially fixed", name)
+        Ok(bugs) if bugs.is_empty() => println!("[\x1B[0;32m{:^15}\x1B[0m] {}", "Fixed", name),
+        Ok(bugs) => {
+            println!("[\x1B[0;31m{:^15}\x1B[0m] {}", "Not fixed", name);
+            for bug in bugs {
+                println!("{:17} {}", "", bug);
+            }
         }
-        Ok(Status::Fixed) => println!("[\x1B[0;32m{:^15}\x1B[0m] {}", "Fixed", name),
         Err(x) => println!("[\x1B[41;30m{:^15}\x1B[0m] {}: {}", "Errored", name, x),
     }
-    Ok(())
 }
 
 /// Check whether the bugs we found on the codex have been fixed.
@@ -272,48 +429,100 @@ pub fn check(data: &OrnaData, guide: &OrnaAdminGuide) -> Result<(), Error> {
         guide,
         "Weapons missing elemental status effects",
         weapons_missing_elemental_status_effects,
-    )?;
+    );
     do_check(
         data,
         guide,
         "Monsters missing skills Bind or Bite",
         monsters_missing_bind_bite,
-    )?;
+    );
     do_check(
         data,
         guide,
         "Yggdrasils missing their raid tags",
         trees_missing_raid_tags,
-    )?;
+    );
     do_check(
         data,
         guide,
         "Swansong missing its Blind cause",
         swansong_missing_blind,
-    )?;
+    );
     do_check(
         data,
         guide,
         "Kerberos missing Rise of Kerberos event",
         kerberos_missing_event,
-    )?;
+    );
     do_check(
         data,
         guide,
         "Phoenix missing Rise of the Phoenix event",
         phoenix_missing_event,
-    )?;
+    );
     do_check(
         data,
         guide,
         "Giants missing their World Raid tag",
         giants_titans_tag,
-    )?;
+    );
     do_check(
         data,
         guide,
         "Twin attack missing its \" (Off-hand)\" suffix",
         twin_attack_missing_offhand_suffix,
-    )?;
+    );
+    for (name, detector) in DETECTORS.iter() {
+        do_check(data, guide, name, |data, _| Ok(detector(data)));
+    }
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use ornaguide_rs::{
+        codex::{CodexItem, CodexMonster, ItemDroppedBy},
+        data::OrnaData,
+    };
+
+    use crate::codex_bugs::{items_dropped_by_unknown_monsters, CodexBug};
+
+    #[test]
+    fn test_items_dropped_by_unknown_monsters() {
+        let dropped_by = |slug: &str| ItemDroppedBy {
+            name: slug.to_string(),
+            uri: format!("/codex/monsters/{}/", slug),
+            icon: String::new(),
+        };
+        let mut data = OrnaData::default();
+        data.codex.monsters.monsters.push(CodexMonster {
+            slug: "slime".to_string(),
+            name: "Slime".to_string(),
+            icon: String::new(),
+            events: vec![],
+            family: String::new(),
+            rarity: String::new(),
+            tier: 1,
+            abilities: vec![],
+            drops: vec![],
+            spawns: vec![],
+        });
+        data.codex.items.items.push(CodexItem {
+            slug: "goo".to_string(),
+            name: "Goo".to_string(),
+            tier: 1,
+            dropped_by: vec![dropped_by("slime"), dropped_by("ghost")],
+            ..CodexItem::default()
+        });
+
+        assert_eq!(
+            items_dropped_by_unknown_monsters(&data),
+            vec![CodexBug {
+                kind: "item_dropped_by_unknown_monster",
+                entity_uri: "/codex/items/goo/".to_string(),
+                detail: "Dropped by ghost (/codex/monsters/ghost/) which is not in the codex"
+                    .to_string(),
+            }]
+        );
+    }
+}
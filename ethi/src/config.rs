@@ -19,13 +19,26 @@ pub struct Config {
     /// Environment variable: `ORNAGUIDE_HOST`
     pub ornaguide_host: String,
     /// Connection cookie to the guide.
-    /// Default: None, errors if missing.
+    /// Default: None, errors if missing and no `ornaguide_username` is given.
     /// Environment variable: `ORNAGUIDE_COOKIE`
     pub ornaguide_cookie: String,
+    /// Username to log in to the guide with. If set, a fresh session cookie is retrieved upon
+    /// startup and `ornaguide_cookie` is ignored.
+    /// Default: None
+    /// Environment variable: `ORNAGUIDE_USERNAME`
+    pub ornaguide_username: Option<String>,
+    /// Password to log in to the guide with, along with `ornaguide_username`.
+    /// Default: None
+    /// Environment variable: `ORNAGUIDE_PASSWORD`
+    pub ornaguide_password: Option<String>,
     /// Number of seconds to wait between each call to the guide.
     /// Default: 0
     /// Environment variable: `ORNAGUIDE_SLEEP`
     pub ornaguide_sleep: u32,
+    /// Maximum number of requests sent at once to the guide when fetching entities in batches.
+    /// Default: 4
+    /// Environment variable: `ORNAGUIDE_CONCURRENCY`
+    pub ornaguide_concurrency: usize,
     /// Host string to `playorna.com`. Must not have a trailing slash.
     /// Can be used to have a localhost mirror.
     /// Default: `https://playorna.com`
@@ -35,6 +48,15 @@ pub struct Config {
     /// Default: 0
     /// Environment variable: `PLAYORNA_SLEEP`
     pub playorna_sleep: u32,
+    /// Maximum number of requests sent at once to playorna when fetching codex entities.
+    /// Requests are sent one at a time when `playorna_sleep` is set.
+    /// Default: 4
+    /// Environment variable: `PLAYORNA_CONCURRENCY`
+    pub playorna_concurrency: usize,
+    /// Minimum number of milliseconds between the start of two concurrent calls to playorna.
+    /// Default: 100
+    /// Environment variable: `PLAYORNA_SPACING`
+    pub playorna_spacing: u64,
 }
 
 lazy_static! {
@@ -53,23 +75,39 @@ fn sanitize_config(config: &mut Config) {
 /// Load the config from the environment.
 fn load() -> Result<Config, Error> {
     let _ = dotenv().map_err(|err| Error::Misc(format!("Failed to load .env: {}", err)))?;
+    let ornaguide_username = dotenv::var("ORNAGUIDE_USERNAME").ok();
     let mut config = Config {
         ornaguide_host: dotenv::var("ORNAGUIDE_HOST")
             .unwrap_or_else(|_| "https://orna.guide".to_string()),
-        ornaguide_cookie: dotenv::var("ORNAGUIDE_COOKIE").map_err(|err| {
-            Error::Misc(format!(
-                "Failed to get ORNAGUIDE_COOKIE env variable: {}",
-                err
-            ))
-        })?,
+        ornaguide_cookie: match dotenv::var("ORNAGUIDE_COOKIE") {
+            Ok(cookie) => cookie,
+            Err(_) if ornaguide_username.is_some() => String::new(),
+            Err(err) => {
+                return Err(Error::Misc(format!(
+                    "Failed to get ORNAGUIDE_COOKIE env variable: {}",
+                    err
+                )))
+            }
+        },
+        ornaguide_password: dotenv::var("ORNAGUIDE_PASSWORD").ok(),
+        ornaguide_username,
         ornaguide_sleep: dotenv::var("ORNAGUIDE_SLEEP")
             .unwrap_or_else(|_| "0".to_string())
             .parse()?,
+        ornaguide_concurrency: dotenv::var("ORNAGUIDE_CONCURRENCY")
+            .unwrap_or_else(|_| "4".to_string())
+            .parse()?,
         playorna_host: dotenv::var("PLAYORNA_HOST")
             .unwrap_or_else(|_| "https://playorna.com".to_string()),
         playorna_sleep: dotenv::var("PLAYORNA_SLEEP")
             .unwrap_or_else(|_| "0".to_string())
             .parse()?,
+        playorna_concurrency: dotenv::var("PLAYORNA_CONCURRENCY")
+            .unwrap_or_else(|_| "4".to_string())
+            .parse()?,
+        playorna_spacing: dotenv::var("PLAYORNA_SPACING")
+            .unwrap_or_else(|_| "100".to_string())
+            .parse()?,
     };
     sanitize_config(&mut config);
 
@@ -95,7 +133,22 @@ pub fn ornaguide_sleep() -> Result<u32, Error> {
     with_config(|config| Ok(config.ornaguide_sleep))
 }
 
+/// Return the `ornaguide_concurrency` config value.
+pub fn ornaguide_concurrency() -> Result<usize, Error> {
+    with_config(|config| Ok(config.ornaguide_concurrency))
+}
+
 /// Return the `playorna_sleep` config value.
 pub fn playorna_sleep() -> Result<u32, Error> {
     with_config(|config| Ok(config.playorna_sleep))
 }
+
+/// Return the `playorna_concurrency` config value.
+pub fn playorna_concurrency() -> Result<usize, Error> {
+    with_config(|config| Ok(config.playorna_concurrency))
+}
+
+/// Return the `playorna_spacing` config value.
+pub fn playorna_spacing() -> Result<u64, Error> {
+    with_config(|config| Ok(config.playorna_spacing))
+}
@@ -0,0 +1,26 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+};
+
+use ornaguide_rs::{data::OrnaData, error::Error};
+
+use crate::cli;
+
+/// Print or write the differences in guide entities between an older data directory and the
+/// current data.
+pub fn cli(command: cli::diff::Command, data: OrnaData) -> Result<(), Error> {
+    let old = OrnaData::load_from(&command.old)?;
+    let diff = old.diff(&data);
+
+    match command.output.as_ref() {
+        Some(path) => {
+            let mut writer = BufWriter::new(File::create(path)?);
+            serde_json::to_writer_pretty(&mut writer, &diff)
+                .map_err(|err| Error::SerdeJson(err, path.to_string()))?;
+            writer.flush()?;
+        }
+        None => println!("{}", serde_json::to_string_pretty(&diff)?),
+    }
+    Ok(())
+}
@@ -0,0 +1,13 @@
+use ornaguide_rs::{data::OrnaData, error::Error};
+
+use crate::cli;
+
+/// Print every guide and codex entity with the given name.
+pub fn cli(command: cli::find::Command, data: OrnaData) -> Result<(), Error> {
+    let matches = data.find_by_name(&command.name);
+    for entity in matches.iter() {
+        println!("\t- {}", entity);
+    }
+    println!("{} matches", matches.len());
+    Ok(())
+}
@@ -24,7 +24,7 @@ pub fn items(guide: &OrnaAdminGuide) -> Result<AdminItems, Error> {
             let cloned_bar = bar.clone();
             async move {
                 let admin_item = retry_once!(guide.async_admin_retrieve_item_by_id(item.id).await)?;
-                cloned_bar.set_message(item.name.clone());
+                cloned_bar.set_message(&item.name);
                 cloned_bar.inc(1);
                 if sleep > 0 {
                     tokio::time::sleep(std::time::Duration::from_secs(sleep)).await
@@ -36,7 +36,7 @@ pub fn items(guide: &OrnaAdminGuide) -> Result<AdminItems, Error> {
         .try_collect::<Vec<AdminItem>>(),
     )?;
     bar.finish_with_message("AItems  fetched");
-    Ok(AdminItems { items: ret })
+    Ok(AdminItems::from(ret))
 }
 
 pub fn monsters(guide: &OrnaAdminGuide) -> Result<AdminMonsters, Error> {
@@ -45,7 +45,7 @@ pub fn monsters(guide: &OrnaAdminGuide) -> Result<AdminMonsters, Error> {
     let mut ret = Vec::with_capacity(monsters.len());
     let bar = bar(monsters.len() as u64);
     for monster in monsters.iter() {
-        bar.set_message(monster.name.clone());
+        bar.set_message(&monster.name);
         ret.push(retry_once!(guide.admin_retrieve_monster_by_id(monster.id))?);
         bar.inc(1);
         if sleep > 0 {
@@ -53,7 +53,7 @@ pub fn monsters(guide: &OrnaAdminGuide) -> Result<AdminMonsters, Error> {
         }
     }
     bar.finish_with_message("AMnstrs fetched");
-    Ok(AdminMonsters { monsters: ret })
+    Ok(AdminMonsters::from(ret))
 }
 
 pub fn skills(guide: &OrnaAdminGuide) -> Result<AdminSkills, Error> {
@@ -62,7 +62,7 @@ pub fn skills(guide: &OrnaAdminGuide) -> Result<AdminSkills, Error> {
     let mut ret = Vec::with_capacity(skills.len());
     let bar = bar(skills.len() as u64);
     for skill in skills.iter() {
-        bar.set_message(skill.name.clone());
+        bar.set_message(&skill.name);
         ret.push(retry_once!(guide.admin_retrieve_skill_by_id(skill.id))?);
         bar.inc(1);
         if sleep > 0 {
@@ -70,7 +70,7 @@ pub fn skills(guide: &OrnaAdminGuide) -> Result<AdminSkills, Error> {
         }
     }
     bar.finish_with_message("ASkills fetched");
-    Ok(AdminSkills { skills: ret })
+    Ok(AdminSkills::from(ret))
 }
 
 pub fn pets(guide: &OrnaAdminGuide) -> Result<AdminPets, Error> {
@@ -79,7 +79,7 @@ pub fn pets(guide: &OrnaAdminGuide) -> Result<AdminPets, Error> {
     let mut ret = Vec::with_capacity(pets.len());
     let bar = bar(pets.len() as u64);
     for pet in pets.iter() {
-        bar.set_message(pet.name.clone());
+        bar.set_message(&pet.name);
         ret.push(retry_once!(guide.admin_retrieve_pet_by_id(pet.id))?);
         bar.inc(1);
         if sleep > 0 {
@@ -3,18 +3,30 @@ use ornaguide_rs::{data::OrnaData, error::Error, guide::OrnaAdminGuide};
 use crate::cli;
 
 pub mod checker;
+pub mod duplicates;
 pub mod items;
 pub mod misc;
 pub mod monsters;
 pub mod pets;
 pub mod skills;
+pub mod stale_ids;
 pub mod status_effects;
 
 /// Match all entities from codex to the guide.
-pub fn all(data: &mut OrnaData, fix: bool, guide: &OrnaAdminGuide) -> Result<(), Error> {
+/// If `compare_images` is set, item icons whose names differ are compared by content.
+/// If `mask_numbers` is set, numbers are ignored when comparing skill descriptions.
+pub fn all(
+    data: &mut OrnaData,
+    fix: bool,
+    compare_images: bool,
+    mask_numbers: bool,
+    guide: &OrnaAdminGuide,
+) -> Result<(), Error> {
+    stale_ids::perform(data)?;
+    duplicates::perform(data)?;
     status_effects::perform(data, fix, guide)?;
-    skills::perform(data, fix, guide)?;
-    items::perform(data, fix, guide)?;
+    skills::perform(data, fix, mask_numbers, guide)?;
+    items::perform(data, fix, compare_images, guide)?;
     monsters::perform(data, fix, guide)?;
     pets::perform(data, fix, guide)?;
 
@@ -28,14 +40,24 @@ pub fn cli(
     mut data: OrnaData,
 ) -> Result<(), Error> {
     let fix = command.fix;
+    let compare_images = command.compare_images;
+    let mask_numbers = command.mask_numbers;
+    if command.c.is_some() {
+        stale_ids::perform(&data)?;
+        duplicates::perform(&data)?;
+    }
     match command.c {
-        Some(cli::match_::Subcommand::Items) => items::perform(&mut data, fix, guide),
+        Some(cli::match_::Subcommand::Items) => {
+            items::perform(&mut data, fix, compare_images, guide)
+        }
         Some(cli::match_::Subcommand::Monsters) => monsters::perform(&mut data, fix, guide),
-        Some(cli::match_::Subcommand::Pets) => monsters::perform(&mut data, fix, guide),
-        Some(cli::match_::Subcommand::Skills) => skills::perform(&mut data, fix, guide),
+        Some(cli::match_::Subcommand::Pets) => pets::perform(&mut data, fix, guide),
+        Some(cli::match_::Subcommand::Skills) => {
+            skills::perform(&mut data, fix, mask_numbers, guide)
+        }
         Some(cli::match_::Subcommand::StatusEffects) => {
             status_effects::perform(&mut data, fix, guide)
         }
-        None => all(&mut data, fix, guide),
+        None => all(&mut data, fix, compare_images, mask_numbers, guide),
     }
 }
@@ -1,12 +1,96 @@
 use ornaguide_rs::{data::OrnaData, error::Error};
 
-use std::fmt::{Debug, Display};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    fmt::{Debug, Display},
+    sync::Mutex,
+};
 
 use crate::misc::diff_sorted_slices;
 
+thread_local! {
+    /// If set, the mismatches reported on this thread are appended here rather than printed.
+    static CAPTURED_REPORT: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Print a line reporting a mismatch, or append it to the captured report if one is being
+/// captured on this thread.
+pub fn report_line(line: String) {
+    CAPTURED_REPORT.with(|captured| match captured.borrow_mut().as_mut() {
+        Some(report) => {
+            report.push_str(&line);
+            report.push('\n');
+        }
+        None => println!("{}", line),
+    });
+}
+
+/// Run `f`, capturing the mismatches it reports instead of printing them.
+/// Returns the result of `f` along with the captured report.
+pub fn capture_report<T, F: FnOnce() -> T>(f: F) -> (T, String) {
+    let previous = CAPTURED_REPORT.with(|captured| captured.replace(Some(String::new())));
+    let ret = f();
+    let report = CAPTURED_REPORT.with(|captured| captured.replace(previous));
+    (ret, report.unwrap_or_default())
+}
+
+/// Report a mismatch. Works like `println!`, but honors `capture_report`.
+macro_rules! report {
+    ($($arg:tt)*) => {
+        $crate::guide_match::checker::report_line(format!($($arg)*))
+    };
+}
+pub(crate) use report;
+
+/// Mismatches detected by `Checker`s, accumulated over a whole check.
+/// Mismatches are counted per field name. In a dry-run, this gives an idea of how many changes a
+/// fix would make.
+#[derive(Default)]
+pub struct MismatchSummary {
+    /// The number of mismatches per field name, and the ids of the entities that mismatched.
+    inner: Mutex<(BTreeMap<String, usize>, BTreeSet<u32>)>,
+}
+
+impl MismatchSummary {
+    /// Record a mismatch of the given field on the given entity.
+    pub fn record(&self, field_name: &str, entity_id: u32) {
+        let mut inner = self.inner.lock().unwrap();
+        *inner.0.entry(field_name.to_string()).or_default() += 1;
+        inner.1.insert(entity_id);
+    }
+
+    /// The number of mismatches detected, per field name.
+    pub fn counts(&self) -> BTreeMap<String, usize> {
+        self.inner.lock().unwrap().0.clone()
+    }
+
+    /// The number of distinct entities on which at least one mismatch was detected.
+    pub fn entity_count(&self) -> usize {
+        self.inner.lock().unwrap().1.len()
+    }
+
+    /// Print a table of the number of mismatches per field.
+    pub fn print(&self) {
+        let counts = self.counts();
+        if counts.is_empty() {
+            println!("No mismatch found.");
+            return;
+        }
+        println!(
+            "{} mismatching fields across {} entities:",
+            counts.values().sum::<usize>(),
+            self.entity_count()
+        );
+        for (field_name, count) in counts.iter() {
+            println!("\t{:20} {:>6}", field_name, count);
+        }
+    }
+}
+
 /// Compare the option in a field and fix it to what is expected.
 /// The conversion function is used to translate from the codex to the guide.
-pub fn fix_option_field<'a, AdminEntity, AdminToOption, T: 'a, U, FnConvert>(
+pub fn fix_option_field<'a, AdminEntity, AdminToOption, T, U, FnConvert>(
     admin: &'a mut AdminEntity,
     admin_to_option: AdminToOption,
     expected_option: &Option<U>,
@@ -14,7 +98,7 @@ pub fn fix_option_field<'a, AdminEntity, AdminToOption, T: 'a, U, FnConvert>(
 ) -> Result<(), Error>
 where
     AdminToOption: FnOnce(&'a mut AdminEntity) -> Result<&'a mut Option<T>, Error>,
-    T: std::cmp::Ord + std::fmt::Debug,
+    T: 'a + std::cmp::Ord + std::fmt::Debug,
     FnConvert: FnOnce(&U) -> Result<T, Error>,
 {
     let admin_option = admin_to_option(admin)?;
@@ -29,16 +113,7 @@ where
 
 /// Compare the list of elements in a field and split them into a list to add and one to remove.
 /// Call the given callable accordingly.
-pub fn fix_vec_field<
-    'a,
-    AdminEntity,
-    AdminToVec,
-    T: 'a,
-    FnRemove,
-    FnAdd,
-    FnToDebuggable,
-    Debuggable,
->(
+pub fn fix_vec_field<'a, AdminEntity, AdminToVec, T, FnRemove, FnAdd, FnToDebuggable, Debuggable>(
     admin: &mut AdminEntity,
     admin_to_vec: AdminToVec,
     expected_vec: &'a [T],
@@ -48,7 +123,7 @@ pub fn fix_vec_field<
 ) -> Result<(), Error>
 where
     AdminToVec: FnOnce(&mut AdminEntity) -> Result<&'a Vec<T>, Error>,
-    T: std::cmp::Ord,
+    T: 'a + std::cmp::Ord,
     FnRemove: FnOnce(&mut AdminEntity, &Vec<&'a T>) -> Result<(), Error>,
     FnAdd: FnOnce(&mut AdminEntity, &Vec<&'a T>) -> Result<(), Error>,
     FnToDebuggable: Fn(&T) -> Debuggable,
@@ -59,13 +134,13 @@ where
     // Compute the diff between it and that from the codex.
     let (to_add, to_remove) = diff_sorted_slices(expected_vec, admin_vec);
     if !to_add.is_empty() {
-        println!(
+        report!(
             "\x1B[0;32mSuggest adding: {:?}\x1B[0m",
             to_add.iter().map(|t| to_str(t)).collect::<Vec<_>>()
         );
     }
     if !to_remove.is_empty() {
-        println!(
+        report!(
             "\x1B[0;31mSuggest removing: {:?}\x1B[0m",
             to_remove.iter().map(|t| to_str(t)).collect::<Vec<_>>()
         );
@@ -240,7 +315,7 @@ where
     CDebuggable: Debug,
 {
     if admin_field != codex_field {
-        println!(
+        report!(
             "\x1B[0;34m{:30}:{:11}:\x1B[0m\ncodex= {:?}\nguide= {:?}",
             entity_name,
             field_name,
@@ -282,9 +357,12 @@ where
     GuideSaver: FnOnce(AdminEntity) -> Result<(), Error>,
 {
     if admin_field != codex_field {
-        println!(
+        report!(
             "\x1B[0;34m{:30}:{:11}:\x1B[0m\ncodex= {:?}\nguide= {:?}",
-            entity_name, field_name, codex_field, admin_field
+            entity_name,
+            field_name,
+            codex_field,
+            admin_field
         );
         if fix {
             let mut entity = guide_retriever(entity_id)?;
@@ -320,9 +398,12 @@ where
     GuideSaver: FnOnce(AdminEntity) -> Result<(), Error>,
 {
     if admin_field != codex_field {
-        println!(
+        report!(
             "\x1B[0;34m{:30}:{:11}:\x1B[0m codex= {:<20} guide= {:<20}",
-            entity_name, field_name, codex_field, admin_field
+            entity_name,
+            field_name,
+            codex_field,
+            admin_field
         );
         if fix {
             let mut entity = guide_retriever(entity_id)?;
@@ -354,6 +435,8 @@ where
     pub golden: Retriever,
     /// The function used to commit the entity to the guide.
     pub saver: Saver,
+    /// Where detected mismatches are counted.
+    pub summary: &'a MismatchSummary,
 }
 
 impl<'a, AdminEntity, Retriever, Saver> Checker<'a, AdminEntity, Retriever, Saver>
@@ -361,6 +444,14 @@ where
     Retriever: Fn(u32) -> Result<AdminEntity, Error>,
     Saver: Fn(AdminEntity) -> Result<(), Error>,
 {
+    /// Record the mismatch in the summary if the field did not match.
+    fn tally(&self, field_name: &str, matched: Result<bool, Error>) -> Result<bool, Error> {
+        if let Ok(false) = matched {
+            self.summary.record(field_name, self.entity_id);
+        }
+        matched
+    }
+
     /// Check a particular field.
     /// The field's values (`admin_field` and `codex_field`) must implement `std::fmt::Display`.
     pub fn display<AS, CS, Fixer>(
@@ -375,7 +466,7 @@ where
         CS: Display,
         Fixer: FnOnce(&mut AdminEntity, &CS) -> Result<(), Error>,
     {
-        check_field(
+        let matched = check_field(
             field_name,
             self.entity_name,
             self.entity_id,
@@ -385,7 +476,8 @@ where
             fixer,
             &self.golden,
             &self.saver,
-        )
+        );
+        self.tally(field_name, matched)
     }
 
     /// Check a particular field.
@@ -402,7 +494,7 @@ where
         CS: Debug + ?Sized,
         Fixer: FnOnce(&mut AdminEntity, &CS) -> Result<(), Error>,
     {
-        check_field_debug(
+        let matched = check_field_debug(
             field_name,
             self.entity_name,
             self.entity_id,
@@ -412,7 +504,8 @@ where
             fixer,
             &self.golden,
             &self.saver,
-        )
+        );
+        self.tally(field_name, matched)
     }
 
     /// Check a particular field.
@@ -435,7 +528,7 @@ where
         ADebuggable: Debug,
         CDebuggable: Debug,
     {
-        check_field_vec_formatter(
+        let matched = check_field_vec_formatter(
             field_name,
             self.entity_name,
             self.entity_id,
@@ -447,7 +540,8 @@ where
             &self.saver,
             admin_formatter,
             codex_formatter,
-        )
+        );
+        self.tally(field_name, matched)
     }
 
     /// Check a field containing guide skill ids.
@@ -580,3 +674,39 @@ where
         )
     }
 }
+
+#[cfg(test)]
+mod test {
+    use ornaguide_rs::error::Error;
+
+    use crate::guide_match::checker::{capture_report, Checker, MismatchSummary};
+
+    #[test]
+    fn test_mismatch_summary() {
+        let summary = MismatchSummary::default();
+        let checker = |entity_id| Checker {
+            entity_name: "entity",
+            entity_id,
+            fix: false,
+            golden: |_: u32| -> Result<(), Error> { Ok(()) },
+            saver: |_: ()| -> Result<(), Error> { Ok(()) },
+            summary: &summary,
+        };
+
+        let ((), report) = capture_report(|| {
+            let check = checker(1);
+            assert!(!check.display("attack", &1, &2, |_, _| Ok(())).unwrap());
+            assert!(check.display("magic", &1, &1, |_, _| Ok(())).unwrap());
+            assert!(!check.debug("tier", &1, &2, |_, _| Ok(())).unwrap());
+            let check = checker(2);
+            assert!(!check.display("attack", &3, &2, |_, _| Ok(())).unwrap());
+        });
+        assert_eq!(report.lines().count(), 5);
+
+        assert_eq!(
+            summary.counts().into_iter().collect::<Vec<_>>(),
+            vec![("attack".to_string(), 2), ("tier".to_string(), 1)]
+        );
+        assert_eq!(summary.entity_count(), 2);
+    }
+}
@@ -0,0 +1,149 @@
+use itertools::Itertools;
+use ornaguide_rs::{data::OrnaData, error::Error};
+
+/// A set of guide entities that share the same `codex_uri`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Duplicate<'a> {
+    /// The `codex_uri` the entities share.
+    pub codex_uri: &'a str,
+    /// The `(id, name)` of each of the guide entities sharing the `codex_uri`, sorted by id.
+    pub entities: Vec<(u32, &'a str)>,
+}
+
+/// Find the `codex_uri`s that are shared by more than one entity.
+/// The iterator must yield the `(id, name, codex_uri)` of each entity.
+/// Entities with an empty `codex_uri` are ignored.
+fn find_duplicates<'a, I>(entities: I) -> Vec<Duplicate<'a>>
+where
+    I: Iterator<Item = (u32, &'a str, &'a str)>,
+{
+    entities
+        .filter(|(_, _, codex_uri)| !codex_uri.is_empty())
+        .into_group_map_by(|(_, _, codex_uri)| *codex_uri)
+        .into_iter()
+        .filter(|(_, entities)| entities.len() > 1)
+        .map(|(codex_uri, entities)| Duplicate {
+            codex_uri,
+            entities: entities
+                .into_iter()
+                .map(|(id, name, _)| (id, name))
+                .sorted()
+                .collect(),
+        })
+        .sorted_by(|a, b| a.codex_uri.cmp(b.codex_uri))
+        .collect()
+}
+
+/// Find guide items sharing the same `codex_uri`.
+pub fn items(data: &OrnaData) -> Vec<Duplicate<'_>> {
+    find_duplicates(
+        data.guide
+            .items
+            .items
+            .iter()
+            .map(|item| (item.id, item.name.as_str(), item.codex_uri.as_str())),
+    )
+}
+
+/// Find guide monsters sharing the same `codex_uri`.
+pub fn monsters(data: &OrnaData) -> Vec<Duplicate<'_>> {
+    find_duplicates(data.guide.monsters.monsters.iter().map(|monster| {
+        (
+            monster.id,
+            monster.name.as_str(),
+            monster.codex_uri.as_str(),
+        )
+    }))
+}
+
+/// Find guide skills sharing the same `codex_uri`.
+pub fn skills(data: &OrnaData) -> Vec<Duplicate<'_>> {
+    find_duplicates(
+        data.guide
+            .skills
+            .skills
+            .iter()
+            .map(|skill| (skill.id, skill.name.as_str(), skill.codex_uri.as_str())),
+    )
+}
+
+/// Find guide pets sharing the same `codex_uri`.
+pub fn pets(data: &OrnaData) -> Vec<Duplicate<'_>> {
+    find_duplicates(
+        data.guide
+            .pets
+            .pets
+            .iter()
+            .map(|pet| (pet.id, pet.name.as_str(), pet.codex_uri.as_str())),
+    )
+}
+
+/// Print the list of duplicates, if any.
+/// `kind` is the plural name of the entities on the guide (`items`, `monsters`, ...).
+fn report(kind: &str, duplicates: &[Duplicate]) {
+    if duplicates.is_empty() {
+        return;
+    }
+
+    println!(
+        "\x1B[0;31m{} codex URIs shared by multiple {} on guide:\x1B[0m",
+        duplicates.len(),
+        kind
+    );
+    for duplicate in duplicates.iter() {
+        println!("\t- {}", duplicate.codex_uri);
+        for (id, name) in duplicate.entities.iter() {
+            println!(
+                "\t\t- {:20} (https://orna.guide/{}?show={})",
+                name, kind, id
+            );
+        }
+    }
+}
+
+/// Report guide entities that share the same `codex_uri`.
+/// Such duplicates make matching against the codex ambiguous and are better fixed by hand before
+/// anything else.
+pub fn perform(data: &OrnaData) -> Result<(), Error> {
+    println!("\x1B[0;35mChecking guide duplicates\x1B[0m");
+    report("items", &items(data));
+    report("monsters", &monsters(data));
+    report("skills", &skills(data));
+    report("pets", &pets(data));
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use ornaguide_rs::{data::OrnaData, monsters::admin::AdminMonster, with_defaults};
+
+    use crate::guide_match::duplicates::{monsters, Duplicate};
+
+    fn monster(id: u32, name: &str, codex_uri: &str) -> AdminMonster {
+        with_defaults!(AdminMonster {
+            id,
+            name: name.to_string(),
+            codex_uri: codex_uri.to_string(),
+        })
+    }
+
+    #[test]
+    fn test_monsters_sharing_codex_uri() {
+        let mut data = OrnaData::default();
+        data.guide.monsters.monsters = vec![
+            monster(3, "Bat", "/codex/monsters/bat/"),
+            monster(1, "Slime", "/codex/monsters/slime/"),
+            monster(2, "Slime (copy)", "/codex/monsters/slime/"),
+            monster(4, "Unlinked", ""),
+            monster(5, "Unlinked too", ""),
+        ];
+
+        assert_eq!(
+            monsters(&data),
+            vec![Duplicate {
+                codex_uri: "/codex/monsters/slime/",
+                entities: vec![(1, "Slime"), (2, "Slime (copy)")],
+            }]
+        );
+    }
+}
@@ -1,25 +1,30 @@
-use itertools::Itertools;
+use itertools::{Either, Itertools};
 use ornaguide_rs::{
-    codex::{CodexElement, ItemStatusEffects},
-    data::OrnaData,
+    codex::{weapon_inflicted_statuses, CodexItem, ItemStatusEffects},
+    data::{MatchKind, OrnaData},
     error::Error,
-    guide::{AdminGuide, OrnaAdminGuide, VecElements},
+    guide::{AdminGuide, OrnaAdminGuide, StatusEffect, VecElements},
+    items::admin::AdminItem,
 };
+use rayon::prelude::*;
 
 use crate::{
     guide_match::{
         checker::{
-            fix_option_field, fix_status_effects_field, fix_vec_field, fix_vec_id_field, Checker,
+            capture_report, fix_option_field, fix_status_effects_field, fix_vec_field,
+            fix_vec_id_field, report, Checker, MismatchSummary,
         },
         misc::{ItemDroppedBys, ItemUpgradeMaterials},
     },
-    misc::sanitize_guide_name,
+    misc::{normalize_description, sanitize_guide_name},
     retry_once,
 };
 
 /// List items that are on the guide and not the codex, or on the codex and not on the guide.
 fn list_missing(data: &mut OrnaData, fix: bool, guide: &OrnaAdminGuide) -> Result<(), Error> {
-    let missing_on_guide = data
+    // Items that only match through heuristics have no codex URI on the guide. They are
+    // reported, but not created again.
+    let (heuristic_matches, missing_on_guide): (Vec<_>, Vec<_>) = data
         .codex
         .items
         .items
@@ -36,9 +41,13 @@ fn list_missing(data: &mut OrnaData, fix: bool, guide: &OrnaAdminGuide) -> Resul
                 && item.slug != "steadfast-charm"
                 && item.slug != "super-exp-potion"
         })
-        .filter(|item| data.guide.items.get_by_slug(&item.slug).is_err())
         .sorted_by_key(|item| &item.slug)
-        .collect_vec();
+        .filter_map(|item| match data.guide.find_match_for_codex_item(item) {
+            Ok((_, MatchKind::Slug)) => None,
+            Ok((guide_item, MatchKind::Heuristic)) => Some(Either::Left((item, guide_item))),
+            Err(_) => Some(Either::Right(item)),
+        })
+        .partition_map(|match_| match_);
     let not_on_codex = data
         .guide
         .items
@@ -58,6 +67,18 @@ fn list_missing(data: &mut OrnaData, fix: bool, guide: &OrnaAdminGuide) -> Resul
             );
         }
     }
+    if !heuristic_matches.is_empty() {
+        println!(
+            "{} items only matched by name or icon:",
+            heuristic_matches.len()
+        );
+        for (item, guide_item) in heuristic_matches.iter() {
+            println!(
+                "\t- {:20} (https://playorna.com/codex/items/{}) may be {} (https://orna.guide/items?show={})",
+                item.name, item.slug, guide_item.name, guide_item.id
+            );
+        }
+    }
     if !not_on_codex.is_empty() {
         println!("{} items not on codex:", not_on_codex.len());
         for item in not_on_codex.iter() {
@@ -70,29 +91,26 @@ fn list_missing(data: &mut OrnaData, fix: bool, guide: &OrnaAdminGuide) -> Resul
 
     // Create the new items on the guide, if asked to.
     if fix && !missing_on_guide.is_empty() {
+        let mut new_ids = Vec::with_capacity(missing_on_guide.len());
         for item in missing_on_guide.iter() {
-            retry_once!(guide.admin_add_item(item.try_to_admin_item(&data.guide)?))?;
+            new_ids.push(retry_once!(
+                guide.admin_add_item(item.try_to_admin_item(&data.guide)?)
+            )?);
         }
 
-        // Retrieve the new list of items, and keep only those we didn't know of before.
-        let all_items = retry_once!(guide.admin_retrieve_items_list())?;
-        let new_items = all_items
-            .iter()
-            .filter(|item| data.guide.items.find_by_id(item.id).is_none())
-            .filter_map(
-                // Retrieve the `AdminItem` entry.
-                |item| match retry_once!(guide.admin_retrieve_item_by_id(item.id)) {
-                    Ok(x) => Some(x),
-                    Err(x) => {
-                        println!(
-                            "Failed to retrieve item #{} (https://orna.guide/items?show={}): {}",
-                            item.id, item.id, x
-                        );
-                        None
-                    }
-                },
-            )
-            .collect_vec();
+        // Retrieve the `AdminItem` entries.
+        let mut new_items = Vec::with_capacity(new_ids.len());
+        for (id, result) in
+            guide.admin_retrieve_items_batch(&new_ids, crate::config::ornaguide_concurrency()?)
+        {
+            match result.or_else(|_| guide.admin_retrieve_item_by_id(id)) {
+                Ok(x) => new_items.push(x),
+                Err(x) => println!(
+                    "Failed to retrieve item #{} (https://orna.guide/items?show={}): {}",
+                    id, id, x
+                ),
+            }
+        }
 
         // Log what was added.
         println!(
@@ -114,534 +132,990 @@ fn list_missing(data: &mut OrnaData, fix: bool, guide: &OrnaAdminGuide) -> Resul
     Ok(())
 }
 
-/// Return an iterator over the status afflictions a weapon with the given element may inflict.
-pub fn get_iter_element_statuses(element: Option<&CodexElement>) -> std::vec::IntoIter<&str> {
-    match element {
-        Some(CodexElement::Fire) => vec!["Burning"].into_iter(),
-        Some(CodexElement::Water) => vec!["Frozen"].into_iter(),
-        Some(CodexElement::Earthen) => vec!["Rot"].into_iter(),
-        Some(CodexElement::Lightning) => vec!["Paralyzed"].into_iter(),
-        Some(CodexElement::Holy) => vec!["Blind"].into_iter(),
-        Some(CodexElement::Dark) => vec!["Asleep"].into_iter(),
-        Some(CodexElement::Arcane) => vec!["Burning", "Frozen", "Rot", "Paralyzed"].into_iter(),
-        Some(CodexElement::Dragon) => vec!["Blight"].into_iter(),
-        _ => vec![].into_iter(),
+/// List the status effects referred to by codex items that do not exist on the guide.
+/// Effects are named after the guide.
+fn unknown_status_effects(data: &OrnaData) -> Vec<String> {
+    let static_ = &data.guide.static_;
+    data.item_pairs()
+        .flat_map(|(codex_item, _)| {
+            [
+                codex_item.causes.try_to_guide_ids(static_),
+                codex_item.cures.try_to_guide_ids(static_),
+                codex_item.gives.try_to_guide_ids(static_),
+                codex_item.immunities.try_to_guide_ids(static_),
+            ]
+        })
+        .flat_map(|result| match result {
+            Err(Error::PartialCodexStatusEffectsConversion(_, unknown)) => unknown,
+            _ => vec![],
+        })
+        .sorted()
+        .dedup()
+        .collect()
+}
+
+/// Create the status effects that codex items refer to and that do not exist on the guide.
+/// Status effects are created with `add`, after which the guide status effects are reloaded with
+/// `refresh`. This is done only once: status effects that are still unknown afterwards are not
+/// created again.
+/// Failures are reported rather than returned, so that they do not prevent items from being
+/// checked. Return the names of the status effects that are still unknown.
+fn create_missing_status_effects<Add, Refresh>(
+    data: &mut OrnaData,
+    mut add: Add,
+    refresh: Refresh,
+) -> Vec<String>
+where
+    Add: FnMut(&str) -> Result<(), Error>,
+    Refresh: FnOnce() -> Result<Vec<StatusEffect>, Error>,
+{
+    let missing = unknown_status_effects(data);
+    if missing.is_empty() {
+        return missing;
+    }
+
+    for name in missing.iter() {
+        println!("\x1B[0;32mCreating status effect {}\x1B[0m", name);
+        if let Err(err) = add(name) {
+            println!(
+                "\x1B[0;31mFailed to create status effect {}: {}\x1B[0m",
+                name, err
+            );
+        }
+    }
+    match refresh() {
+        Ok(status_effects) => data.guide.static_.status_effects = status_effects,
+        Err(err) => println!(
+            "\x1B[0;31mFailed to retrieve status effects: {}\x1B[0m",
+            err
+        ),
+    }
+
+    let still_missing = unknown_status_effects(data);
+    if !still_missing.is_empty() {
+        println!(
+            "\x1B[0;31mStatus effects missing on guide: {}\x1B[0m",
+            still_missing.join(", ")
+        );
     }
+    still_missing
 }
 
-/// Check for mismatches in the stats.
-fn check_stats(data: &OrnaData, fix: bool, guide: &OrnaAdminGuide) -> Result<(), Error> {
-    let guide_weapon_id = data
-        .guide
+/// Check whether the guide image and the codex icon are the same picture.
+/// If the names differ, both images are downloaded and their contents compared, so that a renamed
+/// but otherwise identical icon is not reported as a mismatch.
+fn images_match<FG, FC>(
+    guide_image: &str,
+    codex_icon: &str,
+    fetch_guide: FG,
+    fetch_codex: FC,
+) -> Result<bool, Error>
+where
+    FG: FnOnce(&str) -> Result<Vec<u8>, Error>,
+    FC: FnOnce(&str) -> Result<Vec<u8>, Error>,
+{
+    if guide_image == codex_icon {
+        return Ok(true);
+    }
+    Ok(fetch_guide(guide_image)? == fetch_codex(codex_icon)?)
+}
+
+/// Names of the guide item types that can grant view distance.
+const VIEW_DISTANCE_ITEM_TYPES: [&str; 4] = ["Weapon", "Off-hand", "Accessory", "Adornment"];
+
+/// Whether the guide item is of a type that can grant view distance.
+fn has_view_distance(data: &OrnaData, guide_item: &AdminItem) -> bool {
+    data.guide
         .static_
         .item_types
         .iter()
-        .find(|type_| type_.name == "Weapon")
-        .unwrap()
-        .id;
-    for codex_item in data
-        .codex
-        .items
-        .items
-        .iter()
-        .sorted_by_key(|item| &item.slug)
-    {
-        if let Ok(guide_item) = data.guide.items.get_by_slug(&codex_item.slug) {
-            let check = Checker {
-                entity_name: &guide_item.name,
-                entity_id: guide_item.id,
-                fix,
-                golden: |id| guide.admin_retrieve_item_by_id(id),
-                saver: |item| guide.admin_save_item(item),
-            };
-
-            // Icon
-            check.display(
-                "icon",
-                &guide_item.image_name,
-                &codex_item.icon,
-                |item, icon| {
-                    item.image_name = icon.to_string();
-                    Ok(())
-                },
-            )?;
-
-            // Description
-            check.display(
-                "description",
-                &guide_item.description,
-                &codex_item.description,
-                |item, description| {
-                    item.description = description.to_string();
-                    Ok(())
-                },
-            )?;
+        .find(|type_| type_.id == guide_item.type_)
+        .map(|type_| VIEW_DISTANCE_ITEM_TYPES.contains(&type_.name.as_str()))
+        .unwrap_or(false)
+}
 
-            // Attack
-            check.display(
-                "attack",
-                &guide_item.attack,
-                &codex_item
-                    .stats
-                    .as_ref()
-                    .and_then(|stats| stats.attack)
-                    .unwrap_or(0),
-                |item, attack| {
-                    item.attack = *attack;
-                    Ok(())
-                },
-            )?;
+/// Parameters shared by the checks of all items.
+#[derive(Clone, Copy)]
+struct CheckContext<'a> {
+    data: &'a OrnaData,
+    guide: &'a OrnaAdminGuide,
+    /// The id of the "Weapon" item type on the guide.
+    guide_weapon_id: u32,
+    /// Whether to fix the mismatches on the guide.
+    fix: bool,
+    /// Whether to download and compare icons whose names differ.
+    compare_images: bool,
+    /// Where mismatches are counted.
+    summary: &'a MismatchSummary,
+}
 
-            // Magic
-            check.display(
-                "magic",
-                &guide_item.magic,
-                &codex_item
-                    .stats
-                    .as_ref()
-                    .and_then(|stats| stats.magic)
-                    .unwrap_or(0),
-                |item, magic| {
-                    item.magic = *magic;
-                    Ok(())
-                },
-            )?;
+/// Check for mismatches in the stats of a single item.
+fn check_item(
+    ctx: &CheckContext,
+    codex_item: &CodexItem,
+    guide_item: &AdminItem,
+) -> Result<(), Error> {
+    let CheckContext {
+        data,
+        guide,
+        guide_weapon_id,
+        fix,
+        compare_images,
+        summary,
+    } = *ctx;
+    let check = Checker {
+        entity_name: &guide_item.name,
+        entity_id: guide_item.id,
+        fix,
+        golden: |id| guide.admin_retrieve_item_by_id(id),
+        saver: |item| guide.admin_save_item(item),
+        summary,
+    };
 
-            // HP
-            check.display(
-                "hp",
-                &guide_item.hp,
-                &codex_item
-                    .stats
-                    .as_ref()
-                    .and_then(|stats| stats.hp)
-                    .unwrap_or(0),
-                |item, hp| {
-                    item.hp = *hp;
-                    Ok(())
-                },
-            )?;
+    // Icon
+    let same_icon = compare_images
+        && images_match(
+            &guide_item.image_name,
+            &codex_item.icon,
+            |path| guide.fetch_guide_image(path),
+            |path| guide.fetch_codex_image(path),
+        )
+        .unwrap_or_else(|err| {
+            report!(
+                "\x1B[0;31mFailed to compare icons of {} (#{}): {}\x1B[0m",
+                guide_item.name,
+                guide_item.id,
+                err
+            );
+            false
+        });
+    if !same_icon {
+        check.display(
+            "icon",
+            &guide_item.image_name,
+            &codex_item.icon,
+            |item, icon| {
+                item.image_name = icon.to_string();
+                Ok(())
+            },
+        )?;
+    }
 
-            // Mana
-            check.display(
-                "mana",
-                &guide_item.mana,
-                &codex_item
-                    .stats
-                    .as_ref()
-                    .and_then(|stats| stats.mana)
-                    .unwrap_or(0),
-                |item, mana| {
-                    item.mana = *mana;
-                    Ok(())
-                },
-            )?;
+    // Description
+    check.display(
+        "description",
+        &normalize_description(&guide_item.description),
+        &normalize_description(&codex_item.description),
+        |item, description| {
+            item.description = description.to_string();
+            Ok(())
+        },
+    )?;
 
-            // Defense
-            check.display(
-                "defense",
-                &guide_item.defense,
-                &codex_item
-                    .stats
-                    .as_ref()
-                    .and_then(|stats| stats.defense)
-                    .unwrap_or(0),
-                |item, defense| {
-                    item.defense = *defense;
-                    Ok(())
-                },
-            )?;
+    // Attack
+    check.display(
+        "attack",
+        &guide_item.attack,
+        &codex_item
+            .stats
+            .as_ref()
+            .and_then(|stats| stats.attack)
+            .unwrap_or(0),
+        |item, attack| {
+            item.attack = *attack;
+            Ok(())
+        },
+    )?;
 
-            // Resistance
-            check.display(
-                "resistance",
-                &guide_item.resistance,
-                &codex_item
-                    .stats
-                    .as_ref()
-                    .and_then(|stats| stats.resistance)
-                    .unwrap_or(0),
-                |item, resistance| {
-                    item.resistance = *resistance;
-                    Ok(())
-                },
-            )?;
+    // Magic
+    check.display(
+        "magic",
+        &guide_item.magic,
+        &codex_item
+            .stats
+            .as_ref()
+            .and_then(|stats| stats.magic)
+            .unwrap_or(0),
+        |item, magic| {
+            item.magic = *magic;
+            Ok(())
+        },
+    )?;
 
-            // Ward
-            check.display(
-                "ward",
-                &guide_item.ward,
-                &codex_item
-                    .stats
-                    .as_ref()
-                    .and_then(|stats| stats.ward)
-                    .unwrap_or(0),
-                |item, ward| {
-                    item.ward = *ward;
-                    Ok(())
-                },
-            )?;
+    // HP
+    check.display(
+        "hp",
+        &guide_item.hp,
+        &codex_item
+            .stats
+            .as_ref()
+            .and_then(|stats| stats.hp)
+            .unwrap_or(0),
+        |item, hp| {
+            item.hp = *hp;
+            Ok(())
+        },
+    )?;
 
-            // Dexterity
-            check.display(
-                "dexterity",
-                &guide_item.dexterity,
-                &codex_item
-                    .stats
-                    .as_ref()
-                    .and_then(|stats| stats.dexterity)
-                    .unwrap_or(0),
-                |item, dexterity| {
-                    item.dexterity = *dexterity;
-                    Ok(())
-                },
-            )?;
+    // Mana
+    check.display(
+        "mana",
+        &guide_item.mana,
+        &codex_item
+            .stats
+            .as_ref()
+            .and_then(|stats| stats.mana)
+            .unwrap_or(0),
+        |item, mana| {
+            item.mana = *mana;
+            Ok(())
+        },
+    )?;
 
-            // Crit
-            check.display(
-                "crit",
-                &guide_item.crit,
-                &codex_item
-                    .stats
-                    .as_ref()
-                    .and_then(|stats| stats.crit)
-                    .unwrap_or(0),
-                |item, crit| {
-                    item.crit = *crit;
-                    Ok(())
-                },
-            )?;
+    // Defense
+    check.display(
+        "defense",
+        &guide_item.defense,
+        &codex_item
+            .stats
+            .as_ref()
+            .and_then(|stats| stats.defense)
+            .unwrap_or(0),
+        |item, defense| {
+            item.defense = *defense;
+            Ok(())
+        },
+    )?;
 
-            // Foresight
-            check.display(
-                "foresight",
-                &guide_item.foresight,
-                &codex_item
-                    .stats
-                    .as_ref()
-                    .and_then(|stats| stats.foresight)
-                    .unwrap_or(0),
-                |item, foresight| {
-                    item.foresight = *foresight;
-                    Ok(())
-                },
-            )?;
+    // Resistance
+    check.display(
+        "resistance",
+        &guide_item.resistance,
+        &codex_item
+            .stats
+            .as_ref()
+            .and_then(|stats| stats.resistance)
+            .unwrap_or(0),
+        |item, resistance| {
+            item.resistance = *resistance;
+            Ok(())
+        },
+    )?;
 
-            // Adorn slots
-            check.display(
-                "adorn slots",
-                &guide_item.base_adornment_slots,
-                &codex_item
-                    .stats
-                    .as_ref()
-                    .and_then(|stats| stats.adornment_slots)
-                    .unwrap_or(0),
-                |item, slots| {
-                    item.base_adornment_slots = *slots;
-                    item.has_slots = *slots != 0;
-                    Ok(())
-                },
-            )?;
+    // Ward
+    check.display(
+        "ward",
+        &guide_item.ward,
+        &codex_item
+            .stats
+            .as_ref()
+            .and_then(|stats| stats.ward)
+            .unwrap_or(0),
+        |item, ward| {
+            item.ward = *ward;
+            Ok(())
+        },
+    )?;
 
-            // Element
-            let guide_element = &guide_item.element.map(|element_id| {
-                data.guide
-                    .static_
-                    .elements
-                    .find_element_by_id(element_id)
-                    .unwrap()
-                    .name
-                    .as_str()
-            });
-            let codex_element = &codex_item
+    // Dexterity
+    check.display(
+        "dexterity",
+        &guide_item.dexterity,
+        &codex_item
+            .stats
+            .as_ref()
+            .and_then(|stats| stats.dexterity)
+            .unwrap_or(0),
+        |item, dexterity| {
+            item.dexterity = *dexterity;
+            Ok(())
+        },
+    )?;
+
+    // Crit
+    check.display(
+        "crit",
+        &guide_item.crit,
+        &codex_item
+            .stats
+            .as_ref()
+            .and_then(|stats| stats.crit)
+            .unwrap_or(0),
+        |item, crit| {
+            item.crit = *crit;
+            Ok(())
+        },
+    )?;
+
+    // Foresight
+    check.display(
+        "foresight",
+        &guide_item.foresight,
+        &codex_item
+            .stats
+            .as_ref()
+            .and_then(|stats| stats.foresight)
+            .unwrap_or(0),
+        |item, foresight| {
+            item.foresight = *foresight;
+            Ok(())
+        },
+    )?;
+
+    // View distance
+    if has_view_distance(data, guide_item) {
+        check.display(
+            "view_distance",
+            &guide_item.view_distance,
+            &codex_item
                 .stats
                 .as_ref()
-                .and_then(|stats| stats.element.as_ref())
-                .map(|element| element.to_string());
-            check.debug(
-                "element",
-                guide_element,
-                &codex_element.as_deref(),
-                |item, element| {
-                    fix_option_field(
-                        item,
-                        |item| Ok(&mut item.element),
-                        element,
-                        |element| Ok(data.guide.static_.elements.get_element_by_name(element)?.id),
-                    )
-                },
-            )?;
+                .and_then(|stats| stats.view_distance)
+                .map(u32::from)
+                .unwrap_or(0),
+            |item, view_distance| {
+                item.view_distance = *view_distance;
+                Ok(())
+            },
+        )?;
+    }
 
-            // Ability
-            let guide_ability = guide_item
-                .ability
-                .and_then(|ability_id| {
+    // Adorn slots
+    check.display(
+        "adorn slots",
+        &guide_item.base_adornment_slots,
+        &codex_item
+            .stats
+            .as_ref()
+            .and_then(|stats| stats.adornment_slots)
+            .unwrap_or(0),
+        |item, slots| {
+            item.base_adornment_slots = *slots;
+            item.has_slots = *slots != 0;
+            Ok(())
+        },
+    )?;
+
+    // Element
+    let guide_element = &guide_item.element.map(|element_id| {
+        data.guide
+            .static_
+            .elements
+            .find_element_by_id(element_id)
+            .unwrap()
+            .name
+            .as_str()
+    });
+    let codex_element = &codex_item
+        .stats
+        .as_ref()
+        .and_then(|stats| stats.element.as_ref())
+        .map(|element| element.to_string());
+    check.debug(
+        "element",
+        guide_element,
+        &codex_element.as_deref(),
+        |item, element| {
+            fix_option_field(
+                item,
+                |item| Ok(&mut item.element),
+                element,
+                |element| Ok(data.guide.static_.elements.get_element_by_name(element)?.id),
+            )
+        },
+    )?;
+
+    // Ability
+    let guide_ability = guide_item
+        .ability
+        .and_then(|ability_id| {
+            data.guide
+                .skills
+                .skills
+                .iter()
+                .find(|skill| skill.id == ability_id)
+        })
+        .map(|skill| sanitize_guide_name(&skill.name));
+    let codex_ability = codex_item
+        .ability
+        .as_ref()
+        .map(|ability| ability.name.as_str())
+        .map(|name| format!("{} (Off-hand)", name));
+    check.debug(
+        "ability",
+        &guide_ability,
+        &codex_ability.as_deref(),
+        |item, ability_name| {
+            fix_option_field(
+                item,
+                |item| Ok(&mut item.ability),
+                ability_name,
+                |ability_name| {
                     data.guide
                         .skills
-                        .skills
-                        .iter()
-                        .find(|skill| skill.id == ability_id)
-                })
-                .map(|skill| sanitize_guide_name(&skill.name));
-            let codex_ability = codex_item
-                .ability
-                .as_ref()
-                .map(|ability| ability.name.as_str())
-                .map(|name| format!("{} (Off-hand)", name));
-            check.debug(
-                "ability",
-                &guide_ability,
-                &codex_ability.as_deref(),
-                |item, ability_name| {
-                    fix_option_field(
-                        item,
-                        |item| Ok(&mut item.ability),
-                        ability_name,
-                        |ability_name| {
-                            data.guide
-                                .skills
-                                .get_offhand_from_name(ability_name)
-                                .map(|skill| skill.id)
-                        },
-                    )
+                        .get_offhand_from_name(ability_name)
+                        .map(|skill| skill.id)
                 },
-            )?;
+            )
+        },
+    )?;
 
-            // Causes
-            let guide_causes = guide_item.causes.iter().cloned().sorted().collect_vec();
-            let codex_causes = codex_item
-                .causes
-                .try_to_guide_ids(&data.guide.static_)
-                // TODO(ethiraric, 27/07/2022): Add diagnostics.
-                .unwrap_or_else(|err| match err {
-                    Error::PartialCodexStatusEffectsConversion(x, _) => x,
-                    _ => panic!("try_to_guide_ids returned a weird error"),
-                })
-                .into_iter()
-                // TODO(ethiraric, 04/06/2022): Remove this chain and the dedup call below once
-                // the codex fixes elemental statuses for weapons.
-                .chain(if guide_item.type_ == guide_weapon_id {
-                    get_iter_element_statuses(
-                        codex_item
-                            .stats
-                            .as_ref()
-                            .and_then(|stats| stats.element.as_ref()),
-                    )
-                    .map(|status| {
-                        data.guide
-                            .static_
-                            .status_effects
-                            .iter()
-                            .find(|effect| effect.name == status)
-                            .map(|effect| effect.id)
-                            .unwrap()
-                    })
-                    .collect_vec()
-                    .into_iter()
-                } else {
-                    Vec::<u32>::new().into_iter()
-                })
-                // TODO(ethiraric, 01/08/2022): Remove this chain and the dedup call below once
-                // the codex fixes the blind for swansong
-                .chain(if guide_item.name == "Swansong" {
-                    data.guide
-                        .static_
-                        .status_effects
-                        .iter()
-                        .find(|effect| effect.name == "Blind")
-                        .map(|effect| effect.id)
-                        .into_iter()
-                } else {
-                    None.into_iter()
-                })
-                .sorted()
-                .dedup()
-                .collect_vec();
-            check.status_effect_id_vec(
-                "causes",
-                &guide_causes,
-                &codex_causes,
-                |item, codex_causes| {
-                    fix_status_effects_field(item, &guide_causes, data, codex_causes, |item| {
-                        &mut item.causes
-                    })
-                },
-                data,
-            )?;
+    // Causes
+    let guide_causes = guide_item.causes.iter().cloned().sorted().collect_vec();
+    let codex_causes = codex_item
+        .causes
+        .try_to_guide_ids(&data.guide.static_)
+        // TODO(ethiraric, 27/07/2022): Add diagnostics.
+        .unwrap_or_else(|err| match err {
+            Error::PartialCodexStatusEffectsConversion(x, _) => x,
+            _ => panic!("try_to_guide_ids returned a weird error"),
+        })
+        .into_iter()
+        // TODO(ethiraric, 04/06/2022): Remove this chain and the dedup call below once
+        // the codex fixes elemental statuses for weapons and the blind for swansong.
+        .chain(if guide_item.type_ == guide_weapon_id {
+            weapon_inflicted_statuses(
+                codex_item
+                    .stats
+                    .as_ref()
+                    .and_then(|stats| stats.element.as_ref()),
+                &guide_item.name,
+            )
+            .into_iter()
+            .map(|status| {
+                data.guide
+                    .static_
+                    .status_effects
+                    .iter()
+                    .find(|effect| effect.name == status)
+                    .map(|effect| effect.id)
+                    .unwrap()
+            })
+            .collect_vec()
+            .into_iter()
+        } else {
+            Vec::<u32>::new().into_iter()
+        })
+        .sorted()
+        .dedup()
+        .collect_vec();
+    check.status_effect_id_vec(
+        "causes",
+        &guide_causes,
+        &codex_causes,
+        |item, codex_causes| {
+            fix_status_effects_field(item, &guide_causes, data, codex_causes, |item| {
+                &mut item.causes
+            })
+        },
+        data,
+    )?;
 
-            // Cures
-            let guide_cures = guide_item.cures.iter().cloned().sorted().collect_vec();
-            let codex_cures = codex_item
-                .cures
-                .try_to_guide_ids(&data.guide.static_)?
-                .into_iter()
-                .sorted()
-                .collect_vec();
-            check.status_effect_id_vec(
-                "cures",
-                &guide_cures,
-                &codex_cures,
-                |item, codex_cures| {
-                    fix_status_effects_field(item, &guide_cures, data, codex_cures, |item| {
-                        &mut item.cures
-                    })
-                },
-                data,
-            )?;
-
-            // Gives
-            let guide_gives = guide_item.gives.iter().cloned().sorted().collect_vec();
-            let codex_gives = codex_item
-                .gives
-                .try_to_guide_ids(&data.guide.static_)?
-                .into_iter()
-                .sorted()
-                .collect_vec();
-            check.status_effect_id_vec(
-                "gives",
-                &guide_gives,
-                &codex_gives,
-                |item, codex_gives| {
-                    fix_status_effects_field(item, &guide_gives, data, codex_gives, |item| {
-                        &mut item.gives
-                    })
-                },
-                data,
-            )?;
-
-            // Immunities
-            let guide_immunities = guide_item.prevents.iter().cloned().sorted().collect_vec();
-            let codex_immunities = codex_item
-                .immunities
-                .try_to_guide_ids(&data.guide.static_)?
-                .into_iter()
-                .sorted()
-                .collect_vec();
-            check.status_effect_id_vec(
-                "immunities",
-                &guide_immunities,
-                &codex_immunities,
-                |item, codex_immunities| {
-                    fix_status_effects_field(
-                        item,
-                        &guide_immunities,
-                        data,
-                        codex_immunities,
-                        |item| &mut item.prevents,
-                    )
-                },
-                data,
-            )?;
-
-            // Dropped by
-            let guide_dropped_by_ids = data
-                .guide
-                .monsters
-                .monsters
+    // Cures
+    let guide_cures = guide_item.cures.iter().cloned().sorted().collect_vec();
+    let codex_cures = codex_item
+        .cures
+        .try_to_guide_ids(&data.guide.static_)?
+        .into_iter()
+        .sorted()
+        .collect_vec();
+    check.status_effect_id_vec(
+        "cures",
+        &guide_cures,
+        &codex_cures,
+        |item, codex_cures| {
+            fix_status_effects_field(item, &guide_cures, data, codex_cures, |item| {
+                &mut item.cures
+            })
+        },
+        data,
+    )?;
+
+    // Gives
+    let guide_gives = guide_item.gives.iter().cloned().sorted().collect_vec();
+    let codex_gives = codex_item
+        .gives
+        .try_to_guide_ids(&data.guide.static_)?
+        .into_iter()
+        .sorted()
+        .collect_vec();
+    check.status_effect_id_vec(
+        "gives",
+        &guide_gives,
+        &codex_gives,
+        |item, codex_gives| {
+            fix_status_effects_field(item, &guide_gives, data, codex_gives, |item| {
+                &mut item.gives
+            })
+        },
+        data,
+    )?;
+
+    // Immunities
+    let guide_immunities = guide_item.prevents.iter().cloned().sorted().collect_vec();
+    let codex_immunities = codex_item
+        .immunities
+        .try_to_guide_ids(&data.guide.static_)?
+        .into_iter()
+        .sorted()
+        .collect_vec();
+    check.status_effect_id_vec(
+        "immunities",
+        &guide_immunities,
+        &codex_immunities,
+        |item, codex_immunities| {
+            fix_status_effects_field(item, &guide_immunities, data, codex_immunities, |item| {
+                &mut item.prevents
+            })
+        },
+        data,
+    )?;
+
+    // Dropped by
+    let guide_dropped_by_ids = data
+        .guide
+        .monsters
+        .monsters
+        .iter()
+        .filter_map(|monster| {
+            monster
+                .drops
                 .iter()
-                .filter_map(|monster| {
-                    monster
-                        .drops
-                        .iter()
-                        .find(|id| **id == guide_item.id)
-                        .map(|_| monster)
-                })
-                // Filter out entries without a codex_uri.
-                // This should remove Vulcan and The Fools entries.
-                .filter(|monster| !monster.codex_uri.is_empty())
-                // Map them to their ids.
-                .map(|monster| monster.id)
-                .sorted()
-                .collect_vec();
-            let codex_dropped_by_ids = codex_item
-                .dropped_by
-                .try_to_guide_ids(&data.guide.monsters)
-                // TODO(ethiraric, 27/07/2022): Add diagnostics.
-                .unwrap_or_else(|err| match err {
-                    Error::PartialCodexItemDroppedBysConversion(ok, _) => ok,
-                    _ => panic!("try_to_guide_ids returned a weird error"),
-                })
-                .into_iter()
-                .sorted()
-                .collect_vec();
-            check.monster_id_vec(
-                "dropped_by",
-                &guide_dropped_by_ids,
-                &codex_dropped_by_ids,
-                |item, dropped_by| {
-                    fix_vec_field(
-                        item,
-                        |_| Ok(&guide_dropped_by_ids),
-                        dropped_by,
-                        |_, ids| {
-                            // For each monster thet has one too much a drop.
-                            for id in ids.iter() {
-                                // Fetch the monster.
-                                let mut monster = guide.admin_retrieve_monster_by_id(**id)?;
-                                // Check whether the drop was not just present in the cache.
-                                if monster.drops.contains(&guide_item.id) {
-                                    // Remove the drop from the monster and save it.
-                                    monster.drops.retain(|id| *id != guide_item.id);
-                                    guide.admin_save_monster(monster)?;
-                                    guide.admin_retrieve_monster_by_id(**id)?;
-                                }
-                            }
-                            Ok(())
-                        },
-                        |_, ids| {
-                            // For each monster that is missing a drop.
-                            for id in ids.iter() {
-                                // Fetch the monster.
-                                let mut monster = guide.admin_retrieve_monster_by_id(**id)?;
-                                // Check whether the drop was not just missing from the cache.
-                                if !monster.drops.contains(&guide_item.id) {
-                                    // Add the drop to the monster and save it.
-                                    monster.drops.push(guide_item.id);
-                                    guide.admin_save_monster(monster)?;
-                                    guide.admin_retrieve_monster_by_id(**id)?;
-                                }
-                            }
-                            Ok(())
-                        },
-                        |id| data.guide.monsters.get_by_id(*id).map(|item| &item.name),
-                    )
+                .find(|id| **id == guide_item.id)
+                .map(|_| monster)
+        })
+        // Filter out entries without a codex_uri.
+        // This should remove Vulcan and The Fools entries.
+        .filter(|monster| !monster.codex_uri.is_empty())
+        // Map them to their ids.
+        .map(|monster| monster.id)
+        .sorted()
+        .collect_vec();
+    let codex_dropped_by_ids = codex_item
+        .dropped_by
+        .try_to_guide_ids(&data.guide.monsters)
+        // TODO(ethiraric, 27/07/2022): Add diagnostics.
+        .unwrap_or_else(|err| match err {
+            Error::PartialCodexItemDroppedBysConversion(ok, _) => ok,
+            _ => panic!("try_to_guide_ids returned a weird error"),
+        })
+        .into_iter()
+        .sorted()
+        .collect_vec();
+    check.monster_id_vec(
+        "dropped_by",
+        &guide_dropped_by_ids,
+        &codex_dropped_by_ids,
+        |item, dropped_by| {
+            fix_vec_field(
+                item,
+                |_| Ok(&guide_dropped_by_ids),
+                dropped_by,
+                |_, ids| {
+                    // For each monster thet has one too much a drop.
+                    for id in ids.iter() {
+                        // Fetch the monster.
+                        let mut monster = guide.admin_retrieve_monster_by_id(**id)?;
+                        // Check whether the drop was not just present in the cache.
+                        if monster.drops.contains(&guide_item.id) {
+                            // Remove the drop from the monster and save it.
+                            monster.drops.retain(|id| *id != guide_item.id);
+                            guide.admin_save_monster(monster)?;
+                            guide.admin_retrieve_monster_by_id(**id)?;
+                        }
+                    }
+                    Ok(())
                 },
-                data,
-            )?;
-
-            // Upgrade Materials
-            let guide_upgrade_materials =
-                guide_item.materials.iter().cloned().sorted().collect_vec();
-            let codex_upgrade_materials = codex_item
-                .upgrade_materials
-                .try_to_guide_ids(&data.guide.items)?
-                .into_iter()
-                .sorted()
-                .collect_vec();
-            check.item_id_vec(
-                "upgrade materials",
-                &guide_upgrade_materials,
-                &codex_upgrade_materials,
-                |item, materials| {
-                    fix_vec_id_field(
-                        item,
-                        &guide_upgrade_materials,
-                        materials,
-                        |item| &mut item.materials,
-                        |id| data.guide.items.get_by_id(*id).map(|item| &item.name),
-                    )
+                |_, ids| {
+                    // For each monster that is missing a drop.
+                    for id in ids.iter() {
+                        // Fetch the monster.
+                        let mut monster = guide.admin_retrieve_monster_by_id(**id)?;
+                        // Check whether the drop was not just missing from the cache.
+                        if !monster.drops.contains(&guide_item.id) {
+                            // Add the drop to the monster and save it.
+                            monster.drops.push(guide_item.id);
+                            guide.admin_save_monster(monster)?;
+                            guide.admin_retrieve_monster_by_id(**id)?;
+                        }
+                    }
+                    Ok(())
                 },
-                data,
-            )?;
+                |id| data.guide.monsters.get_by_id(*id).map(|item| &item.name),
+            )
+        },
+        data,
+    )?;
+
+    // Upgrade Materials
+    let guide_upgrade_materials = guide_item.materials.iter().cloned().sorted().collect_vec();
+    let codex_upgrade_materials = codex_item
+        .upgrade_materials
+        .try_to_guide_ids(&data.guide.items)?
+        .into_iter()
+        .sorted()
+        .collect_vec();
+    check.item_id_vec(
+        "upgrade materials",
+        &guide_upgrade_materials,
+        &codex_upgrade_materials,
+        |item, materials| {
+            fix_vec_id_field(
+                item,
+                &guide_upgrade_materials,
+                materials,
+                |item| &mut item.materials,
+                |id| data.guide.items.get_by_id(*id).map(|item| &item.name),
+            )
+        },
+        data,
+    )?;
+    Ok(())
+}
+
+/// Check all items for mismatches, without fixing them, regardless of `ctx.fix`.
+/// Items are checked in parallel if `parallel` is set. Returns, for each item and in order, the
+/// result of the check and the mismatches that were reported.
+fn check_stats_read_only(ctx: &CheckContext, parallel: bool) -> Vec<(Result<(), Error>, String)> {
+    let ctx = CheckContext { fix: false, ..*ctx };
+    let pairs = ctx
+        .data
+        .item_pairs()
+        .sorted_by_key(|(codex_item, _)| &codex_item.slug)
+        .collect_vec();
+    let check = |(codex_item, guide_item): &(&CodexItem, &AdminItem)| {
+        capture_report(|| check_item(&ctx, codex_item, guide_item))
+    };
+    if parallel {
+        pairs.par_iter().map(check).collect()
+    } else {
+        pairs.iter().map(check).collect()
+    }
+}
+
+/// Check for mismatches in the stats.
+/// If `compare_images` is set, icons with different names are downloaded and compared by content.
+/// When not fixing and not comparing images, items are checked in parallel. Fixes are applied
+/// sequentially, so that writes to the guide happen in order.
+fn check_stats(
+    data: &OrnaData,
+    fix: bool,
+    compare_images: bool,
+    guide: &OrnaAdminGuide,
+    summary: &MismatchSummary,
+) -> Result<(), Error> {
+    let guide_weapon_id = data
+        .guide
+        .static_
+        .item_types
+        .iter()
+        .find(|type_| type_.name == "Weapon")
+        .unwrap()
+        .id;
+    let ctx = CheckContext {
+        data,
+        guide,
+        guide_weapon_id,
+        fix,
+        compare_images,
+        summary,
+    };
+    if !fix {
+        // Comparing images downloads them from the codex. Those downloads are not throttled, so
+        // items are checked sequentially.
+        for (result, report) in check_stats_read_only(&ctx, !compare_images) {
+            print!("{}", report);
+            result?;
         }
+        return Ok(());
+    }
+
+    for (codex_item, guide_item) in data
+        .item_pairs()
+        .sorted_by_key(|(codex_item, _)| &codex_item.slug)
+    {
+        check_item(&ctx, codex_item, guide_item)?;
     }
     Ok(())
 }
 
 /// Check for any mismatch between the guide items and the codex items.
-pub fn perform(data: &mut OrnaData, fix: bool, guide: &OrnaAdminGuide) -> Result<(), Error> {
+/// If `compare_images` is set, icons whose names differ are downloaded and compared by content.
+pub fn perform(
+    data: &mut OrnaData,
+    fix: bool,
+    compare_images: bool,
+    guide: &OrnaAdminGuide,
+) -> Result<(), Error> {
     println!("\x1B[0;35mMatching Items\x1B[0m");
     list_missing(data, fix, guide)?;
-    check_stats(data, fix, guide)?;
+    if fix {
+        create_missing_status_effects(
+            data,
+            |name| retry_once!(guide.admin_add_status_effect(name)),
+            || retry_once!(guide.admin_retrieve_status_effects_list()),
+        );
+    }
+    let summary = MismatchSummary::default();
+    check_stats(data, fix, compare_images, guide, &summary)?;
+    summary.print();
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use std::cell::RefCell;
+
+    use ornaguide_rs::{
+        codex::{CodexItem, ItemStats, ItemStatusEffects},
+        data::OrnaData,
+        error::Error,
+        guide::{ItemType, OrnaAdminGuide, StatusEffect},
+        items::admin::AdminItem,
+        with_defaults,
+    };
+
+    use crate::guide_match::{
+        checker::MismatchSummary,
+        items::{check_stats_read_only, create_missing_status_effects, images_match, CheckContext},
+    };
+
+    fn codex_item(slug: &str) -> CodexItem {
+        CodexItem {
+            slug: slug.to_string(),
+            name: slug.to_string(),
+            icon: format!("items/{}.png", slug),
+            tier: 1,
+            ..CodexItem::default()
+        }
+    }
+
+    fn guide_item(id: u32, slug: &str, image_name: String) -> AdminItem {
+        with_defaults!(AdminItem {
+            id,
+            codex_uri: format!("/codex/items/{}/", slug),
+            name: slug.to_string(),
+            image_name,
+            tier: 1,
+        })
+    }
+
+    /// A read-only context in which the "Weapon" item type has id 1.
+    fn context<'a>(
+        data: &'a OrnaData,
+        guide: &'a OrnaAdminGuide,
+        summary: &'a MismatchSummary,
+    ) -> CheckContext<'a> {
+        CheckContext {
+            data,
+            guide,
+            guide_weapon_id: 1,
+            fix: false,
+            compare_images: false,
+            summary,
+        }
+    }
+
+    #[test]
+    fn test_parallel_check_matches_sequential() {
+        let mut data = OrnaData::default();
+        data.guide.static_.item_types = vec![ItemType {
+            id: 1,
+            name: "Weapon".to_string(),
+        }];
+        let slugs = (0..50).map(|i| format!("item-{}", i)).collect::<Vec<_>>();
+        data.codex.items.items = slugs.iter().map(|slug| codex_item(slug)).collect();
+        data.guide.items.items = slugs
+            .iter()
+            .enumerate()
+            .map(|(id, slug)| {
+                // Every 7th item has a stale icon.
+                let image_name = if id % 7 == 0 {
+                    "items/old.png".to_string()
+                } else {
+                    format!("items/{}.png", slug)
+                };
+                guide_item(id as u32, slug, image_name)
+            })
+            .collect();
+        let guide = OrnaAdminGuide::new("").unwrap();
+
+        let summary = MismatchSummary::default();
+        let report = |parallel| {
+            check_stats_read_only(&context(&data, &guide, &summary), parallel)
+                .into_iter()
+                .map(|(result, report)| {
+                    result.unwrap();
+                    report
+                })
+                .collect::<Vec<_>>()
+        };
+        let sequential = report(false);
+        assert_eq!(sequential.iter().filter(|r| r.contains("icon")).count(), 8);
+        assert_eq!(report(true), sequential);
+    }
+
+    #[test]
+    fn test_view_distance_mismatch() {
+        let mut data = OrnaData::default();
+        data.guide.static_.item_types = vec![
+            ItemType {
+                id: 1,
+                name: "Weapon".to_string(),
+            },
+            ItemType {
+                id: 2,
+                name: "Material".to_string(),
+            },
+        ];
+        let with_view_distance = |slug: &str| CodexItem {
+            stats: Some(ItemStats {
+                view_distance: Some(10),
+                ..ItemStats::default()
+            }),
+            ..codex_item(slug)
+        };
+        data.codex.items.items = vec![
+            with_view_distance("lantern"),
+            with_view_distance("sword"),
+            with_view_distance("stone"),
+        ];
+        let mut lantern = guide_item(0, "lantern", "items/lantern.png".to_string());
+        lantern.type_ = 1;
+        let mut sword = guide_item(1, "sword", "items/sword.png".to_string());
+        sword.type_ = 1;
+        sword.view_distance = 10;
+        // Materials have no view distance and are not checked.
+        let mut stone = guide_item(2, "stone", "items/stone.png".to_string());
+        stone.type_ = 2;
+        data.guide.items.items = vec![lantern, sword, stone];
+        let guide = OrnaAdminGuide::new("").unwrap();
+
+        let summary = MismatchSummary::default();
+        let reports = check_stats_read_only(&context(&data, &guide, &summary), false)
+            .into_iter()
+            .map(|(result, report)| {
+                result.unwrap();
+                report
+            })
+            .collect::<Vec<_>>();
+        assert!(reports[0].contains("view_distance"));
+        assert!(!reports[1].contains("view_distance"));
+        assert!(!reports[2].contains("view_distance"));
+        assert_eq!(summary.counts().get("view_distance"), Some(&1));
+    }
+
+    #[test]
+    fn test_renamed_identical_icon_matches() {
+        let fetch = |path: &str| -> Result<Vec<u8>, Error> {
+            match path {
+                "items/sword.png" | "items/sword_new.png" => Ok(vec![0x89, b'P', b'N', b'G', 1]),
+                _ => Ok(vec![0x89, b'P', b'N', b'G', 2]),
+            }
+        };
+
+        assert!(images_match("items/sword.png", "items/sword_new.png", fetch, fetch).unwrap());
+        assert!(!images_match("items/sword.png", "items/axe.png", fetch, fetch).unwrap());
+    }
+
+    fn status_effect(id: u32, name: &str) -> StatusEffect {
+        StatusEffect {
+            id,
+            name: name.to_string(),
+        }
+    }
+
+    /// Data with a dagger that causes poison and blindness and cures freezing. Only poison exists
+    /// on the guide.
+    fn status_effects_data() -> OrnaData {
+        let mut data = OrnaData::default();
+        data.guide.static_.status_effects = vec![status_effect(1, "Poisoned")];
+        let mut dagger = codex_item("dagger");
+        dagger.causes = serde_json::from_str(
+            r#"[{"name": "Poisoned", "icon": ""}, {"name": "Blind", "icon": ""}]"#,
+        )
+        .unwrap();
+        dagger.cures = serde_json::from_str(r#"[{"name": "Frozen", "icon": ""}]"#).unwrap();
+        data.codex.items.items = vec![dagger];
+        data.guide.items.items = vec![guide_item(1, "dagger", "items/dagger.png".to_string())];
+        data
+    }
+
+    #[test]
+    fn test_create_missing_status_effects() {
+        let mut data = status_effects_data();
+        let created = RefCell::new(vec![]);
+        let still_missing = create_missing_status_effects(
+            &mut data,
+            |name| {
+                created.borrow_mut().push(name.to_string());
+                Ok(())
+            },
+            || {
+                Ok(vec![
+                    status_effect(1, "Poisoned"),
+                    status_effect(2, "Blind"),
+                    status_effect(3, "Frozen"),
+                ])
+            },
+        );
+
+        assert!(still_missing.is_empty());
+        assert_eq!(created.into_inner(), vec!["Blind", "Frozen"]);
+        let dagger = &data.codex.items.items[0];
+        let static_ = &data.guide.static_;
+        assert_eq!(dagger.causes.try_to_guide_ids(static_).unwrap(), vec![1, 2]);
+        assert_eq!(dagger.cures.try_to_guide_ids(static_).unwrap(), vec![3]);
+    }
+
+    #[test]
+    fn test_create_missing_status_effects_once() {
+        let mut data = status_effects_data();
+        let mut adds = 0;
+        let still_missing = create_missing_status_effects(
+            &mut data,
+            |_| {
+                adds += 1;
+                Ok(())
+            },
+            // The guide did not create the status effects.
+            || Ok(vec![status_effect(1, "Poisoned")]),
+        );
+
+        assert_eq!(still_missing, vec!["Blind", "Frozen"]);
+        assert_eq!(adds, 2);
+    }
+
+    #[test]
+    fn test_create_missing_status_effects_failure() {
+        let mut data = status_effects_data();
+        let created = RefCell::new(vec![]);
+        // Creating the first status effect fails, which must not prevent creating the second.
+        let still_missing = create_missing_status_effects(
+            &mut data,
+            |name| {
+                if name == "Blind" {
+                    return Err(Error::Misc("Failed".to_string()));
+                }
+                created.borrow_mut().push(name.to_string());
+                Ok(())
+            },
+            || {
+                Ok(vec![
+                    status_effect(1, "Poisoned"),
+                    status_effect(3, "Frozen"),
+                ])
+            },
+        );
+
+        assert_eq!(created.into_inner(), vec!["Frozen"]);
+        assert_eq!(still_missing, vec!["Blind"]);
+        assert_eq!(
+            data.codex.items.items[0]
+                .cures
+                .try_to_guide_ids(&data.guide.static_)
+                .unwrap(),
+            vec![3]
+        );
+    }
+}
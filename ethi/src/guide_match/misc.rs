@@ -1,6 +1,6 @@
 use itertools::Itertools;
 use ornaguide_rs::{
-    codex::{FollowerAbility, ItemDroppedBy, ItemUpgradeMaterial, MonsterAbility},
+    codex::{FollowerAbility, ItemDroppedBy, ItemUpgradeMaterial, MonsterAbility, SkillBuffedBy},
     error::Error,
     guide::Static,
     items::admin::AdminItems,
@@ -38,6 +38,36 @@ impl ItemDroppedBys for Vec<ItemDroppedBy> {
     }
 }
 
+/// A trait to extend `Vec<SkillBuffedBy>` specifically.
+pub trait SkillBuffedBys {
+    /// Convert `self` to a `Vec<u32>`, with `u32`s being the guide monster ids.
+    /// Returns `Error::PartialCodexSkillBuffedBysConversion` if all fields have not been
+    /// successfully converted.
+    fn try_to_guide_ids(&self, monsters: &AdminMonsters) -> Result<Vec<u32>, Error>;
+}
+
+impl SkillBuffedBys for Vec<SkillBuffedBy> {
+    fn try_to_guide_ids(&self, monsters: &AdminMonsters) -> Result<Vec<u32>, Error> {
+        let (successes, failures): (Vec<_>, Vec<_>) = self
+            .iter()
+            .map(|buffed_by| {
+                monsters
+                    .get_by_uri(&buffed_by.uri)
+                    .map(|monster| monster.id)
+                    .map_err(|_| buffed_by.uri.clone())
+            })
+            .partition_result();
+
+        if failures.is_empty() {
+            Ok(successes)
+        } else {
+            Err(Error::PartialCodexSkillBuffedBysConversion(
+                successes, failures,
+            ))
+        }
+    }
+}
+
 /// A trait to extend `Vec<ItemUpgradeMaterial>` specifically.
 pub trait ItemUpgradeMaterials {
     /// Try to convert `self` to a `Vec<u32>`, with `u32`s being the guide item ids.
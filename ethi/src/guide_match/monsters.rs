@@ -2,13 +2,13 @@ use itertools::Itertools;
 use ornaguide_rs::{
     data::{CodexGenericMonster, OrnaData},
     error::Error,
-    guide::{AdminGuide, OrnaAdminGuide},
+    guide::{AdminGuide, OrnaAdminGuide, Static},
     monsters::admin::AdminMonster,
 };
 
 use crate::{
     guide_match::{
-        checker::{fix_abilities_field, fix_option_field, fix_spawn_field, Checker},
+        checker::{fix_abilities_field, fix_spawn_field, Checker, MismatchSummary},
         misc::{CodexAbilities, EventsNames},
     },
     retry_once,
@@ -17,6 +17,7 @@ use crate::{
 /// List monsters that are either:
 ///   - On the guide, but missing on the codex.
 ///   - On the codex, but missing on the guide.
+///
 /// None of these should happen. We can query the codex for monsters outside of their event.
 fn list_missing(data: &mut OrnaData, fix: bool, guide: &OrnaAdminGuide) -> Result<(), Error> {
     let missing_on_guide = data
@@ -86,30 +87,20 @@ fn list_missing(data: &mut OrnaData, fix: bool, guide: &OrnaAdminGuide) -> Resul
 
     // Create the new monsters on the guide, if asked to.
     if fix && !missing_on_guide.is_empty() {
+        let mut new_monsters = Vec::with_capacity(missing_on_guide.len());
         for monster in missing_on_guide.iter() {
-            retry_once!(guide.admin_add_monster(monster.try_to_admin_monster(&data.guide)?))?;
+            let id =
+                retry_once!(guide.admin_add_monster(monster.try_to_admin_monster(&data.guide)?))?;
+            // Retrieve the `AdminMonster` entry.
+            match retry_once!(guide.admin_retrieve_monster_by_id(id)) {
+                Ok(x) => new_monsters.push(x),
+                Err(x) => println!(
+                    "Failed to retrieve monster #{} (https://orna.guide/monsters?show={}): {}",
+                    id, id, x
+                ),
+            }
         }
 
-        // Retrieve the new list of monsters, and keep only those we didn't know of before.
-        let all_monsters = retry_once!(guide.admin_retrieve_monsters_list())?;
-        let new_monsters = all_monsters
-            .iter()
-            .filter(|monster| data.guide.monsters.find_by_id(monster.id).is_none())
-            .filter_map(
-                // Retrieve the `AdminMonster` entry.
-                |monster| match retry_once!(guide.admin_retrieve_monster_by_id(monster.id)) {
-                    Ok(x) => Some(x),
-                    Err(x) => {
-                        println!(
-                            "Failed to retrieve monster #{} (https://orna.guide/monsters?show={}): {}",
-                            monster.id, monster.id, x
-                        );
-                        None
-                    }
-                },
-            )
-            .collect_vec();
-
         // Log what was added.
         println!(
             "Added {}/{} monsters on the guide:",
@@ -130,7 +121,42 @@ fn list_missing(data: &mut OrnaData, fix: bool, guide: &OrnaAdminGuide) -> Resul
     Ok(())
 }
 
-fn check_fields(data: &mut OrnaData, fix: bool, guide: &OrnaAdminGuide) -> Result<(), Error> {
+/// Resolve the family of the codex monster to the id of the guide family it should have.
+/// Returns `Ok(None)` if the codex has no notion of family for the monster (raids), in which case
+/// the family should not be checked.
+fn expected_family_id(
+    codex_monster: CodexGenericMonster,
+    static_: &Static,
+) -> Result<Option<Option<u32>>, Error> {
+    match codex_monster.family() {
+        None => Ok(None),
+        Some(name) if name.is_empty() => Ok(Some(None)),
+        Some(name) => static_
+            .monster_families
+            .iter()
+            .find(|family| family.name == *name)
+            .map(|family| Some(Some(family.id)))
+            .ok_or_else(|| Error::Misc(format!("Failed to find family {} on the guide", name))),
+    }
+}
+
+/// Get the name of the guide family with the given id, if any.
+fn family_name(id: Option<u32>, static_: &Static) -> Option<&str> {
+    id.and_then(|id| {
+        static_
+            .monster_families
+            .iter()
+            .find(|family| family.id == id)
+            .map(|family| family.name.as_str())
+    })
+}
+
+fn check_fields(
+    data: &mut OrnaData,
+    fix: bool,
+    guide: &OrnaAdminGuide,
+    summary: &MismatchSummary,
+) -> Result<(), Error> {
     for codex_monster in data.codex.iter_all_monsters() {
         if let Ok(admin_monster) = data
             .guide
@@ -143,6 +169,7 @@ fn check_fields(data: &mut OrnaData, fix: bool, guide: &OrnaAdminGuide) -> Resul
                 fix,
                 golden: |id| guide.admin_retrieve_monster_by_id(id),
                 saver: |monster| guide.admin_save_monster(monster),
+                summary,
             };
 
             // Image name
@@ -206,43 +233,34 @@ fn check_fields(data: &mut OrnaData, fix: bool, guide: &OrnaAdminGuide) -> Resul
             )?;
 
             // Family
-            let admin_family = admin_monster.family.as_ref().and_then(|id| {
-                data.guide
-                    .static_
-                    .monster_families
-                    .iter()
-                    .find(|family| family.id == *id)
-                    .map(|family| &family.name)
-            });
-            check.debug(
-                "family",
-                &admin_family,
-                &codex_monster.family(),
-                |monster: &mut AdminMonster, name| {
-                    fix_option_field(
-                        monster,
-                        |monster| Ok(&mut monster.family),
-                        name,
-                        |name| {
-                            data.guide
-                                .static_
-                                .monster_families
-                                .iter()
-                                .find(|family| family.name == **name)
-                                .map(|family| family.id)
-                                .ok_or_else(|| {
-                                    Error::Misc(format!(
-                                        "Failed to find family {} for monster {} (#{})",
-                                        name, admin_monster.name, admin_monster.id
-                                    ))
-                                })
+            match expected_family_id(codex_monster, &data.guide.static_) {
+                Ok(Some(expected_family)) => {
+                    check.debug(
+                        "family",
+                        &family_name(admin_monster.family, &data.guide.static_),
+                        &family_name(expected_family, &data.guide.static_),
+                        |monster: &mut AdminMonster, _| {
+                            monster.family = expected_family;
+                            Ok(())
                         },
-                    )
-                },
-            )?;
+                    )?;
+                }
+                // Raids have no family on the codex.
+                Ok(None) => {}
+                Err(err) => println!(
+                    "\x1B[0;31m{} (#{}): {}\x1B[0m",
+                    admin_monster.name, admin_monster.id, err
+                ),
+            }
 
-            // Tags
-            let admin_tags = admin_monster.get_raid_spawns(&data.guide.static_.spawns);
+            // Tags and spawn locations
+            // Spawn locations are only reconciled if the codex lists some for the monster.
+            let check_locations = !codex_monster.spawns().is_empty();
+            let mut admin_tags = admin_monster.get_raid_spawns(&data.guide.static_.spawns);
+            if check_locations {
+                admin_tags.extend(admin_monster.get_location_spawns(&data.guide.static_.spawns));
+                admin_tags.sort_unstable();
+            }
             static WRB_STR: &str = "World Raid";
             let codex_tags = codex_monster
                 .tags_as_guide_spawns()
@@ -278,7 +296,11 @@ fn check_fields(data: &mut OrnaData, fix: bool, guide: &OrnaAdminGuide) -> Resul
                             .spawns
                             .iter()
                             .find(|spawn| spawn.id == *spawn_id)
-                            .map(|spawn| spawn.name != "Kingdom Raid" && spawn.name != "World Raid")
+                            .map(|spawn| {
+                                spawn.name != "Kingdom Raid"
+                                    && spawn.name != "World Raid"
+                                    && (!check_locations || !spawn.event_name().is_empty())
+                            })
                             .unwrap_or(false)
                     });
                     for tag in tags_strs.iter() {
@@ -352,10 +374,251 @@ fn check_fields(data: &mut OrnaData, fix: bool, guide: &OrnaAdminGuide) -> Resul
     Ok(())
 }
 
+/// List the spawns (events, locations and raid tags) of codex monsters that are missing on the
+/// guide. Events are named the way the guide names them (`Event: <name>`).
+fn missing_spawns(data: &OrnaData) -> Vec<String> {
+    let spawns = &data.guide.static_;
+    data.codex
+        .iter_all_monsters()
+        .flat_map(|monster| {
+            let events = monster
+                .events()
+                .iter()
+                .filter(|event| {
+                    !spawns
+                        .iter_events()
+                        .any(|spawn| spawn.event_name() == *event)
+                })
+                .map(|event| format!("Event: {}", event));
+            let locations = monster
+                .tags_as_guide_spawns()
+                .into_iter()
+                .filter(|name| !spawns.spawns.iter().any(|spawn| spawn.name == *name))
+                .map(str::to_string);
+            events.chain(locations).collect_vec()
+        })
+        .sorted()
+        .dedup()
+        .collect()
+}
+
+/// List spawns that are on the codex and not on the guide, and add them to the guide if asked to.
+fn list_missing_spawns(
+    data: &mut OrnaData,
+    fix: bool,
+    guide: &OrnaAdminGuide,
+) -> Result<(), Error> {
+    let missing_on_guide = missing_spawns(data);
+    if missing_on_guide.is_empty() {
+        return Ok(());
+    }
+
+    println!("{} spawns missing on guide:", missing_on_guide.len());
+    for spawn in missing_on_guide.iter() {
+        println!("\t- {}", spawn);
+    }
+
+    // Create the new spawns on the guide, if asked to.
+    if fix {
+        for spawn in missing_on_guide.iter() {
+            retry_once!(guide.admin_add_spawn(spawn))?;
+        }
+        data.guide.static_.spawns = retry_once!(guide.admin_retrieve_spawns_list())?;
+    }
+
+    Ok(())
+}
+
 /// Check for any mismatch between the guide monsters and the codex monsters.
 pub fn perform(data: &mut OrnaData, fix: bool, guide: &OrnaAdminGuide) -> Result<(), Error> {
     println!("\x1B[0;35mMatching Monsters\x1B[0m");
     list_missing(data, fix, guide)?;
-    check_fields(data, fix, guide)?;
+    list_missing_spawns(data, fix, guide)?;
+    let summary = MismatchSummary::default();
+    check_fields(data, fix, guide, &summary)?;
+    summary.print();
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use ornaguide_rs::{
+        codex::{CodexMonster, CodexRaid, Tag},
+        data::{CodexGenericMonster, OrnaData},
+        guide::{MonsterFamily, OrnaAdminGuide, Spawn, Static},
+        monsters::admin::AdminMonster,
+        with_defaults,
+    };
+
+    use crate::guide_match::{
+        checker::MismatchSummary,
+        monsters::{check_fields, expected_family_id, family_name, missing_spawns},
+    };
+
+    fn static_() -> Static {
+        Static {
+            monster_families: vec![
+                MonsterFamily {
+                    id: 1,
+                    name: "Dragon".to_string(),
+                },
+                MonsterFamily {
+                    id: 2,
+                    name: "Undead".to_string(),
+                },
+            ],
+            ..Static::default()
+        }
+    }
+
+    #[test]
+    fn test_monster_family_differs() {
+        let static_ = static_();
+        let monster = CodexMonster {
+            slug: "wyvern".to_string(),
+            name: "Wyvern".to_string(),
+            icon: String::new(),
+            events: vec![],
+            family: "Dragon".to_string(),
+            rarity: String::new(),
+            tier: 3,
+            abilities: vec![],
+            drops: vec![],
+            spawns: vec![],
+        };
+
+        // The guide has the monster as an undead.
+        let admin_family = Some(2);
+        let expected = expected_family_id(CodexGenericMonster::Monster(&monster), &static_);
+        assert_eq!(expected.unwrap(), Some(Some(1)));
+        assert_ne!(
+            family_name(admin_family, &static_),
+            family_name(Some(1), &static_)
+        );
+        assert_eq!(family_name(Some(1), &static_), Some("Dragon"));
+
+        let unknown = CodexMonster {
+            family: "Plant".to_string(),
+            ..monster
+        };
+        assert!(expected_family_id(CodexGenericMonster::Monster(&unknown), &static_).is_err());
+    }
+
+    #[test]
+    fn test_raid_family_skipped() {
+        let raid = CodexRaid {
+            slug: "balor".to_string(),
+            name: "Balor".to_string(),
+            description: String::new(),
+            icon: String::new(),
+            events: vec![],
+            tier: 7,
+            tags: vec![],
+            abilities: vec![],
+            drops: vec![],
+        };
+        assert_eq!(
+            expected_family_id(CodexGenericMonster::Raid(&raid), &static_()).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_check_family() {
+        let codex_monster = |slug: &str, family: &str| CodexMonster {
+            slug: slug.to_string(),
+            name: slug.to_string(),
+            icon: String::new(),
+            events: vec![],
+            family: family.to_string(),
+            rarity: String::new(),
+            tier: 1,
+            abilities: vec![],
+            drops: vec![],
+            spawns: vec![],
+        };
+        let admin_monster = |id: u32, uri: &str, family: Option<u32>| {
+            with_defaults!(AdminMonster {
+                id,
+                name: uri.to_string(),
+                codex_uri: uri.to_string(),
+                tier: 1,
+                family,
+            })
+        };
+        let mut data = OrnaData::default();
+        data.guide.static_ = static_();
+        data.codex.monsters.monsters = vec![
+            codex_monster("wyvern", "Dragon"),
+            // The codex has no family for the monster.
+            codex_monster("slime", ""),
+        ];
+        data.codex.raids.raids = vec![CodexRaid {
+            slug: "balor".to_string(),
+            name: "Balor".to_string(),
+            description: String::new(),
+            icon: String::new(),
+            events: vec![],
+            tier: 1,
+            tags: vec![],
+            abilities: vec![],
+            drops: vec![],
+        }];
+        data.guide.monsters.monsters = vec![
+            // The guide has the wyvern as an undead.
+            admin_monster(1, "/codex/monsters/wyvern/", Some(2)),
+            admin_monster(2, "/codex/monsters/slime/", None),
+            // Raids have no family on the codex and their family on the guide is kept.
+            admin_monster(3, "/codex/raids/balor/", Some(1)),
+        ];
+        let guide = OrnaAdminGuide::new("").unwrap();
+
+        let summary = MismatchSummary::default();
+        check_fields(&mut data, false, &guide, &summary).unwrap();
+        assert_eq!(summary.counts().get("family"), Some(&1));
+    }
+
+    #[test]
+    fn test_missing_spawns() {
+        let mut data = OrnaData::default();
+        data.guide.static_.spawns = vec![
+            Spawn {
+                id: 1,
+                name: "World Raid".to_string(),
+            },
+            Spawn {
+                id: 2,
+                name: "Past Event: Fall of Rome".to_string(),
+            },
+        ];
+        data.codex.raids.raids = vec![CodexRaid {
+            slug: "balor".to_string(),
+            name: "Balor".to_string(),
+            description: String::new(),
+            icon: String::new(),
+            events: vec!["Fall of Rome".to_string(), "Blood Moon".to_string()],
+            tier: 7,
+            // Tags that are not spawns on the guide are ignored.
+            tags: vec![Tag::WorldRaid, Tag::OtherRealmsRaid, Tag::FoundInArena],
+            abilities: vec![],
+            drops: vec![],
+        }];
+        data.codex.monsters.monsters = vec![CodexMonster {
+            slug: "wolf".to_string(),
+            name: "Wolf".to_string(),
+            icon: String::new(),
+            events: vec![],
+            family: String::new(),
+            rarity: String::new(),
+            tier: 1,
+            abilities: vec![],
+            drops: vec![],
+            spawns: vec!["Grassland".to_string()],
+        }];
+
+        assert_eq!(
+            missing_spawns(&data),
+            vec!["Event: Blood Moon".to_string(), "Grassland".to_string()]
+        );
+    }
+}
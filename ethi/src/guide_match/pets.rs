@@ -1,5 +1,6 @@
 use itertools::Itertools;
 use ornaguide_rs::{
+    codex::CodexFollower,
     data::OrnaData,
     error::Error,
     guide::{AdminGuide, OrnaAdminGuide},
@@ -7,7 +8,8 @@ use ornaguide_rs::{
 };
 
 use crate::{
-    guide_match::checker::{fix_abilities_field, Checker},
+    guide_match::checker::{fix_abilities_field, Checker, MismatchSummary},
+    misc::normalize_description,
     retry_once,
 };
 
@@ -16,6 +18,7 @@ use super::misc::CodexAbilities;
 /// List pets that are either:
 ///   - On the guide, but missing on the codex.
 ///   - On the codex, but missing on the guide.
+///
 /// None of these should happen.
 fn list_missing(data: &mut OrnaData, fix: bool, guide: &OrnaAdminGuide) -> Result<(), Error> {
     let missing_on_guide = data
@@ -23,7 +26,7 @@ fn list_missing(data: &mut OrnaData, fix: bool, guide: &OrnaAdminGuide) -> Resul
         .followers
         .followers
         .iter()
-        .filter(|follower| data.guide.pets.get_by_slug(&follower.slug).is_err())
+        .filter(|follower| data.guide.find_match_for_codex_follower(follower).is_err())
         .collect_vec();
     let not_on_codex = data
         .guide
@@ -51,30 +54,19 @@ fn list_missing(data: &mut OrnaData, fix: bool, guide: &OrnaAdminGuide) -> Resul
 
     // Create the new pets on the guide, if asked to.
     if fix && !missing_on_guide.is_empty() {
+        let mut new_pets = Vec::with_capacity(missing_on_guide.len());
         for pet in missing_on_guide.iter() {
-            retry_once!(guide.admin_add_pet(pet.try_to_admin_pet(&data.guide)?))?;
+            let id = retry_once!(guide.admin_add_pet(pet.try_to_admin_pet(&data.guide)?))?;
+            // Retrieve the `AdminPet` entry.
+            match retry_once!(guide.admin_retrieve_pet_by_id(id)) {
+                Ok(x) => new_pets.push(x),
+                Err(x) => println!(
+                    "Failed to retrieve pet #{} (https://orna.guide/pets?show={}): {}",
+                    id, id, x
+                ),
+            }
         }
 
-        // Retrieve the new list of pets, and keep only those we didn't know of before.
-        let all_pets = retry_once!(guide.admin_retrieve_pets_list())?;
-        let new_pets = all_pets
-            .iter()
-            .filter(|pet| data.guide.pets.find_by_id(pet.id).is_none())
-            .filter_map(
-                // Retrieve the `AdminPet` entry.
-                |pet| match retry_once!(guide.admin_retrieve_pet_by_id(pet.id)) {
-                    Ok(x) => Some(x),
-                    Err(x) => {
-                        println!(
-                            "Failed to retrieve pet #{} (https://orna.guide/pets?show={}): {}",
-                            pet.id, pet.id, x
-                        );
-                        None
-                    }
-                },
-            )
-            .collect_vec();
-
         // Log what was added.
         println!(
             "Added {}/{} pets on the guide:",
@@ -95,117 +87,148 @@ fn list_missing(data: &mut OrnaData, fix: bool, guide: &OrnaAdminGuide) -> Resul
     Ok(())
 }
 
-/// Compare fields of every codex follower and their counterpart on the guide.
-/// Attempt to fix discrepancies.
-fn check_fields(data: &OrnaData, fix: bool, guide: &OrnaAdminGuide) -> Result<(), Error> {
-    for follower in data.codex.followers.followers.iter() {
-        if let Ok(pet) = data.guide.pets.get_by_slug(&follower.slug) {
-            let check = Checker {
-                entity_name: &pet.name,
-                entity_id: pet.id,
-                fix,
-                golden: |id| guide.admin_retrieve_pet_by_id(id),
-                saver: |pet| guide.admin_save_pet(pet),
-            };
-
-            // Name
-            check.display(
-                "name",
-                &pet.name,
-                &follower.name,
-                |pet: &mut AdminPet, name| {
-                    pet.name = name.clone();
-                    Ok(())
-                },
-            )?;
+/// Compare fields of a codex follower and its counterpart on the guide.
+/// Attempt to fix discrepancies. When fixing, the pet is retrieved from the guide with `golden`
+/// and saved with `saver`.
+fn check_pet<Retriever, Saver>(
+    data: &OrnaData,
+    follower: &CodexFollower,
+    pet: &AdminPet,
+    fix: bool,
+    golden: Retriever,
+    saver: Saver,
+    summary: &MismatchSummary,
+) -> Result<(), Error>
+where
+    Retriever: Fn(u32) -> Result<AdminPet, Error>,
+    Saver: Fn(AdminPet) -> Result<(), Error>,
+{
+    let check = Checker {
+        entity_name: &pet.name,
+        entity_id: pet.id,
+        fix,
+        golden,
+        saver,
+        summary,
+    };
 
-            // Image name
-            check.display(
-                "image_name",
-                &pet.image_name,
-                &follower.icon,
-                |pet: &mut AdminPet, image_name| {
-                    pet.image_name = image_name.clone();
-                    Ok(())
-                },
-            )?;
+    // Name
+    check.display(
+        "name",
+        &pet.name,
+        &follower.name,
+        |pet: &mut AdminPet, name| {
+            pet.name = name.clone();
+            Ok(())
+        },
+    )?;
 
-            // Description
-            let follower_description = if !follower.description.is_empty() {
-                follower.description.clone()
-            } else {
-                ".".to_string()
-            };
-            check.display(
-                "description",
-                &pet.description,
-                &follower_description,
-                |pet: &mut AdminPet, description| {
-                    pet.description = description.to_string();
-                    Ok(())
-                },
-            )?;
+    // Image name
+    check.display(
+        "image_name",
+        &pet.image_name,
+        &follower.icon,
+        |pet: &mut AdminPet, image_name| {
+            pet.image_name = image_name.clone();
+            Ok(())
+        },
+    )?;
 
-            // Tier
-            check.display(
-                "tier",
-                &pet.tier,
-                &follower.tier,
-                |skill: &mut AdminPet, tier| {
-                    skill.tier = *tier;
-                    Ok(())
-                },
-            )?;
+    // Description
+    let follower_description = normalize_description(&follower.description);
+    let follower_description = if !follower_description.is_empty() {
+        follower_description
+    } else {
+        ".".to_string()
+    };
+    check.display(
+        "description",
+        &normalize_description(&pet.description),
+        &follower_description,
+        |pet: &mut AdminPet, description| {
+            pet.description = description.to_string();
+            Ok(())
+        },
+    )?;
 
-            // Abilities
-            let pet_skills_ids = pet
+    // Tier
+    check.display(
+        "tier",
+        &pet.tier,
+        &follower.tier,
+        |skill: &mut AdminPet, tier| {
+            skill.tier = *tier;
+            Ok(())
+        },
+    )?;
+
+    // Abilities
+    let pet_skills_ids = pet
+        .skills
+        .iter()
+        .cloned()
+        // TODO(ethiraric, 11/07/2022): Remove filter when the codex fixes Bind and Bite.
+        .filter(|id| {
+            !data
+                .guide
                 .skills
-                .iter()
-                .cloned()
-                // TODO(ethiraric, 11/07/2022): Remove filter when the codex fixes Bind and Bite.
-                .filter(|id| {
-                    !data
-                        .guide
-                        .skills
-                        .get_by_id(*id)
-                        .unwrap()
-                        .codex_uri
-                        .is_empty()
-                })
-                .sorted()
-                .collect_vec();
-            let expected_skills_ids = follower
-                .abilities
-                .try_to_guide_ids(&data.guide.skills)
-                // TODO(ethiraric, 27/07/2022): Add diagnostics.
-                .unwrap_or_else(|err| match err {
-                    Error::PartialCodexFollowerAbilitiesConversion(ok, _) => ok,
-                    _ => panic!("try_to_guide_ids returned a weird error"),
+                .get_by_id(*id)
+                .unwrap()
+                .codex_uri
+                .is_empty()
+        })
+        .sorted()
+        .collect_vec();
+    let expected_skills_ids = follower
+        .abilities
+        .try_to_guide_ids(&data.guide.skills)
+        // TODO(ethiraric, 27/07/2022): Add diagnostics.
+        .unwrap_or_else(|err| match err {
+            Error::PartialCodexFollowerAbilitiesConversion(ok, _) => ok,
+            _ => panic!("try_to_guide_ids returned a weird error"),
+        })
+        .into_iter()
+        .sorted()
+        .collect_vec();
+    // TODO(ethiraric, 17/10/22): Remove once we cycle all events. Skill slugs were
+    // kebab-caseified.
+    if !expected_skills_ids.is_empty() {
+        check.skill_id_vec(
+            "abilities",
+            &pet_skills_ids,
+            &expected_skills_ids,
+            |pet: &mut AdminPet, _| {
+                fix_abilities_field(pet, &pet_skills_ids, data, &expected_skills_ids, |pet| {
+                    &mut pet.skills
                 })
-                .into_iter()
-                .sorted()
-                .collect_vec();
-            // TODO(ethiraric, 17/10/22): Remove once we cycle all events. Skill slugs were
-            // kebab-caseified.
-            if !expected_skills_ids.is_empty() {
-                check.skill_id_vec(
-                    "abilities",
-                    &pet_skills_ids,
-                    &expected_skills_ids,
-                    |pet: &mut AdminPet, _| {
-                        fix_abilities_field(
-                            pet,
-                            &pet_skills_ids,
-                            data,
-                            &expected_skills_ids,
-                            |pet| &mut pet.skills,
-                        )
-                    },
-                    data,
-                )?;
-            } else {
-                // println!("Follower {} has no ability on codex.", follower.name);
-            }
+            },
+            data,
+        )?;
+    } else {
+        // println!("Follower {} has no ability on codex.", follower.name);
+    }
+    Ok(())
+}
+
+/// Compare fields of every codex follower and their counterpart on the guide.
+/// Attempt to fix discrepancies.
+fn check_fields(
+    data: &OrnaData,
+    fix: bool,
+    guide: &OrnaAdminGuide,
+    summary: &MismatchSummary,
+) -> Result<(), Error> {
+    for follower in data.codex.followers.followers.iter() {
+        if let Ok(pet) = data.guide.find_match_for_codex_follower(follower) {
+            check_pet(
+                data,
+                follower,
+                pet,
+                fix,
+                |id| guide.admin_retrieve_pet_by_id(id),
+                |pet| guide.admin_save_pet(pet),
+                summary,
+            )?;
         }
     }
     Ok(())
@@ -215,6 +238,143 @@ fn check_fields(data: &OrnaData, fix: bool, guide: &OrnaAdminGuide) -> Result<()
 pub fn perform(data: &mut OrnaData, fix: bool, guide: &OrnaAdminGuide) -> Result<(), Error> {
     println!("\x1B[0;35mMatching Pets\x1B[0m");
     list_missing(data, fix, guide)?;
-    check_fields(data, fix, guide)?;
+    let summary = MismatchSummary::default();
+    check_fields(data, fix, guide, &summary)?;
+    summary.print();
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use std::cell::RefCell;
+
+    use ornaguide_rs::{
+        codex::CodexFollower, data::OrnaData, pets::admin::AdminPet, with_defaults,
+    };
+
+    use crate::guide_match::{
+        checker::{capture_report, MismatchSummary},
+        pets::check_pet,
+    };
+
+    fn follower(slug: &str, name: &str) -> CodexFollower {
+        CodexFollower {
+            name: name.to_string(),
+            slug: slug.to_string(),
+            icon: format!("followers/{}.png", slug),
+            description: "A loyal companion.".to_string(),
+            events: vec![],
+            rarity: "Common".to_string(),
+            tier: 3,
+            abilities: vec![],
+        }
+    }
+
+    fn pet(id: u32, slug: &str, name: &str, image_name: &str) -> AdminPet {
+        with_defaults!(AdminPet {
+            id,
+            codex_uri: format!("/codex/followers/{}/", slug),
+            name: name.to_string(),
+            image_name: image_name.to_string(),
+            description: "A loyal companion.".to_string(),
+            tier: 3,
+        })
+    }
+
+    /// Check the given pet against the follower and return the fields that mismatched.
+    fn mismatches(follower: &CodexFollower, pet: &AdminPet) -> Vec<String> {
+        let data = OrnaData::default();
+        let summary = MismatchSummary::default();
+        let (result, _) = capture_report(|| {
+            check_pet(
+                &data,
+                follower,
+                pet,
+                false,
+                |_| unreachable!("Pets are not retrieved without fixing"),
+                |_| unreachable!("Pets are not saved without fixing"),
+                &summary,
+            )
+        });
+        result.unwrap();
+        summary.counts().into_keys().collect()
+    }
+
+    #[test]
+    fn test_name_and_icon_match() {
+        let follower = follower("bat", "Bat");
+        let pet = pet(1, "bat", "Bat", "followers/bat.png");
+        assert!(mismatches(&follower, &pet).is_empty());
+    }
+
+    #[test]
+    fn test_name_mismatch() {
+        let follower = follower("bat", "Vampire Bat");
+        let pet = pet(1, "bat", "Bat", "followers/bat.png");
+        assert_eq!(mismatches(&follower, &pet), vec!["name".to_string()]);
+    }
+
+    #[test]
+    fn test_icon_mismatch() {
+        let follower = follower("bat", "Bat");
+        let pet = pet(1, "bat", "Bat", "followers/old_bat.png");
+        assert_eq!(mismatches(&follower, &pet), vec!["image_name".to_string()]);
+    }
+
+    #[test]
+    fn test_fix() {
+        let follower = follower("bat", "Vampire Bat");
+        let pet = pet(1, "bat", "Bat", "followers/old_bat.png");
+        // The pet was edited on the guide since it was read.
+        let mut golden = pet.clone();
+        golden.description = "A bat.".to_string();
+        let saved = RefCell::new(vec![]);
+        let data = OrnaData::default();
+        let summary = MismatchSummary::default();
+
+        let (result, _) = capture_report(|| {
+            check_pet(
+                &data,
+                &follower,
+                &pet,
+                true,
+                |id| {
+                    assert_eq!(id, 1);
+                    Ok(saved.borrow().last().cloned().unwrap_or(golden.clone()))
+                },
+                |pet| {
+                    saved.borrow_mut().push(pet);
+                    Ok(())
+                },
+                &summary,
+            )
+        });
+        result.unwrap();
+
+        // Each mismatching field is fixed on top of the pet retrieved from the guide.
+        let saved = saved.into_inner();
+        assert_eq!(saved.len(), 2);
+        let fixed = saved.last().unwrap();
+        assert_eq!(fixed.name, "Vampire Bat");
+        assert_eq!(fixed.image_name, "followers/bat.png");
+        assert_eq!(fixed.description, "A bat.");
+    }
+
+    #[test]
+    fn test_match_without_codex_uri() {
+        let mut data = OrnaData::default();
+        let mut unlinked = pet(1, "bat", "Bat", "followers/bat.png");
+        unlinked.codex_uri.clear();
+        data.guide.pets.pets = vec![pet(2, "wolf", "Wolf", "followers/wolf.png"), unlinked];
+
+        let found = data
+            .guide
+            .find_match_for_codex_follower(&follower("bat", "Bat"))
+            .unwrap();
+        assert_eq!(found.id, 1);
+        assert!(data
+            .guide
+            .find_match_for_codex_follower(&follower("owl", "Owl"))
+            .is_err());
+    }
+}
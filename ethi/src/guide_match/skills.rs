@@ -8,13 +8,18 @@ use ornaguide_rs::{
 };
 
 use crate::{
-    guide_match::checker::{fix_status_effects_field, Checker},
+    guide_match::{
+        checker::{fix_status_effects_field, fix_vec_id_field, Checker, MismatchSummary},
+        misc::SkillBuffedBys,
+    },
+    misc::{descriptions_match, normalize_description},
     retry_once,
 };
 
 /// List skills that are either:
 ///   - On the guide, but missing on the codex.
 ///   - On the codex, but missing on the guide.
+///
 /// None of these should happen.
 fn list_missing(data: &mut OrnaData, fix: bool, guide: &OrnaAdminGuide) -> Result<(), Error> {
     // Passives are not listed on the codex. We get the id to filter out passive skills.
@@ -65,30 +70,20 @@ fn list_missing(data: &mut OrnaData, fix: bool, guide: &OrnaAdminGuide) -> Resul
 
     // Create the new skills on the guide, if asked to.
     if fix && !missing_on_guide.is_empty() {
+        let mut new_skills = Vec::with_capacity(missing_on_guide.len());
         for skill in missing_on_guide.iter() {
-            retry_once!(guide.admin_add_skill(skill.try_to_admin_skill(&data.guide.static_)?))?;
+            let id =
+                retry_once!(guide.admin_add_skill(skill.try_to_admin_skill(&data.guide.static_)?))?;
+            // Retrieve the `AdminSkill` entry.
+            match retry_once!(guide.admin_retrieve_skill_by_id(id)) {
+                Ok(x) => new_skills.push(x),
+                Err(x) => println!(
+                    "Failed to retrieve skill #{} (https://orna.guide/skills?show={}): {}",
+                    id, id, x
+                ),
+            }
         }
 
-        // Retrieve the new list of skills, and keep only those we didn't know of before.
-        let all_skills = retry_once!(guide.admin_retrieve_skills_list())?;
-        let new_skills = all_skills
-            .iter()
-            .filter(|skill| data.guide.skills.find_by_id(skill.id).is_none())
-            .filter_map(
-                // Retrieve the `AdminSkill` entry.
-                |skill| match retry_once!(guide.admin_retrieve_skill_by_id(skill.id)) {
-                    Ok(x) => Some(x),
-                    Err(x) => {
-                        println!(
-                            "Failed to retrieve skill #{} (https://orna.guide/skills?show={}): {}",
-                            skill.id, skill.id, x
-                        );
-                        None
-                    }
-                },
-            )
-            .collect_vec();
-
         // Log what was added.
         println!(
             "Added {}/{} skills on the guide:",
@@ -111,113 +106,176 @@ fn list_missing(data: &mut OrnaData, fix: bool, guide: &OrnaAdminGuide) -> Resul
 
 /// Compare fields of every codex skill and their counterpart on the guide.
 /// Attempt to fix discrepancies.
-fn check_fields(data: &OrnaData, fix: bool, guide: &OrnaAdminGuide) -> Result<(), Error> {
-    for codex_skill in data.codex.skills.skills.iter().sorted_by_key(|x| &x.slug) {
-        if let Ok(admin_skill) = data.guide.skills.get_by_slug(&codex_skill.slug) {
-            let check = Checker {
-                entity_name: &admin_skill.name,
-                entity_id: admin_skill.id,
-                fix,
-                golden: |id| guide.admin_retrieve_skill_by_id(id),
-                saver: |skill| guide.admin_save_skill(skill),
-            };
-
-            // Name
-            let codex_name = codex_skill.name.as_str();
-            let admin_name = admin_skill.name
-                [0..admin_skill.name.find('[').unwrap_or(admin_skill.name.len())]
-                .trim();
-            // TODO(ethiraric, 10/02/2023): Remove this once codex is updated.
-            if codex_name != "Twin Attack" {
-                check.display("name", &admin_name, &codex_name, |skill, name| {
-                    skill.name = name.to_string();
-                    Ok(())
-                })?;
-            }
+/// If `mask_numbers` is set, numbers are ignored when comparing descriptions.
+fn check_fields(
+    data: &OrnaData,
+    fix: bool,
+    mask_numbers: bool,
+    guide: &OrnaAdminGuide,
+    summary: &MismatchSummary,
+) -> Result<(), Error> {
+    for (codex_skill, admin_skill) in data
+        .skill_pairs()
+        .sorted_by_key(|(codex_skill, _)| &codex_skill.slug)
+    {
+        let check = Checker {
+            entity_name: &admin_skill.name,
+            entity_id: admin_skill.id,
+            fix,
+            golden: |id| guide.admin_retrieve_skill_by_id(id),
+            saver: |skill| guide.admin_save_skill(skill),
+            summary,
+        };
 
-            // Description
-            let codex_description = if !codex_skill.description.is_empty() {
-                codex_skill.description.clone()
-            } else {
-                ".".to_string()
-            };
+        // Name
+        let codex_name = codex_skill.name.as_str();
+        let admin_name = admin_skill.name
+            [0..admin_skill.name.find('[').unwrap_or(admin_skill.name.len())]
+            .trim();
+        // TODO(ethiraric, 10/02/2023): Remove this once codex is updated.
+        if codex_name != "Twin Attack" {
+            check.display("name", &admin_name, &codex_name, |skill, name| {
+                skill.name = name.to_string();
+                Ok(())
+            })?;
+        }
+
+        // Description
+        let codex_description = normalize_description(&codex_skill.description);
+        let codex_description = if !codex_description.is_empty() {
+            codex_description
+        } else {
+            ".".to_string()
+        };
+        let admin_description = normalize_description(&admin_skill.description);
+        if !descriptions_match(&admin_description, &codex_description, mask_numbers) {
             check.display(
                 "description",
-                &admin_skill.description,
+                &admin_description,
                 &codex_description,
                 |skill, description| {
                     skill.description = description.clone();
                     Ok(())
                 },
             )?;
+        }
 
-            // Tier
-            check.display(
-                "tier",
-                &admin_skill.tier,
-                &codex_skill.tier,
-                |skill, tier| {
-                    skill.tier = *tier;
-                    Ok(())
-                },
-            )?;
+        // Tier
+        check.display(
+            "tier",
+            &admin_skill.tier,
+            &codex_skill.tier,
+            |skill, tier| {
+                skill.tier = *tier;
+                Ok(())
+            },
+        )?;
 
-            // Bought at arcanist
-            check.display(
-                "bought",
-                &admin_skill.bought,
-                &codex_skill.bought_at_arcanist(),
-                |skill, bought| {
-                    skill.bought = *bought;
-                    Ok(())
-                },
-            )?;
+        // Bought at arcanist
+        check.display(
+            "bought",
+            &admin_skill.bought,
+            &codex_skill.bought_at_arcanist(),
+            |skill, bought| {
+                skill.bought = *bought;
+                Ok(())
+            },
+        )?;
+
+        // Causes
+        let admin_causes = admin_skill.causes.iter().cloned().sorted().collect_vec();
+        let codex_causes = codex_skill
+            .causes
+            .try_to_guide_ids(&data.guide.static_)?
+            .into_iter()
+            .sorted()
+            .collect_vec();
+        check.status_effect_id_vec(
+            "causes",
+            &admin_causes,
+            &codex_causes,
+            |skill: &mut AdminSkill, _| {
+                fix_status_effects_field(skill, &admin_causes, data, &codex_causes, |skill| {
+                    &mut skill.causes
+                })
+            },
+            data,
+        )?;
 
-            // Causes
-            let admin_causes = admin_skill.causes.iter().cloned().sorted().collect_vec();
-            let codex_causes = codex_skill
-                .causes
+        // Gives
+        // I think I have no way of translating those two.
+        if codex_skill.slug != "defend-2" && codex_skill.slug != "defend-3" {
+            let admin_gives = admin_skill.gives.iter().cloned().sorted().collect_vec();
+            let codex_gives = codex_skill
+                .gives
                 .try_to_guide_ids(&data.guide.static_)?
                 .into_iter()
                 .sorted()
                 .collect_vec();
-            check.status_effect_id_vec(
-                "causes",
-                &admin_causes,
-                &codex_causes,
-                |skill: &mut AdminSkill, _| {
-                    fix_status_effects_field(skill, &admin_causes, data, &codex_causes, |skill| {
-                        &mut skill.causes
-                    })
-                },
-                data,
-            )?;
-
-            // Gives
-            // I think I have no way of translating those two.
-            if codex_skill.slug != "defend-2" && codex_skill.slug != "defend-3" {
-                let admin_gives = admin_skill.gives.iter().cloned().sorted().collect_vec();
-                let codex_gives = codex_skill
-                    .gives
-                    .try_to_guide_ids(&data.guide.static_)?
-                    .into_iter()
-                    .sorted()
-                    .collect_vec();
-                check.debug("gives", &admin_gives, &codex_gives, |skill, _| {
-                    fix_status_effects_field(skill, &admin_gives, data, &codex_gives, |skill| {
-                        &mut skill.gives
-                    })
-                })?;
-            }
+            check.debug("gives", &admin_gives, &codex_gives, |skill, _| {
+                fix_status_effects_field(skill, &admin_gives, data, &codex_gives, |skill| {
+                    &mut skill.gives
+                })
+            })?;
         }
+
+        // Buffed by
+        // Only passives are buffed by monsters. Other skills have no `buffed_by` on either
+        // side, which trivially matches.
+        let admin_buffed_by = admin_skill.buffed_by.iter().cloned().sorted().collect_vec();
+        let codex_buffed_by = codex_skill
+            .buffed_by
+            .try_to_guide_ids(&data.guide.monsters)
+            .unwrap_or_else(|err| match err {
+                Error::PartialCodexSkillBuffedBysConversion(ok, not_found) => {
+                    println!(
+                        "Failed to find buffing monsters for skill {}: {:?}",
+                        admin_skill.name, not_found
+                    );
+                    ok
+                }
+                _ => panic!("try_to_guide_ids returned a weird error"),
+            })
+            .into_iter()
+            .sorted()
+            .collect_vec();
+        check.monster_id_vec(
+            "buffed_by",
+            &admin_buffed_by,
+            &codex_buffed_by,
+            |skill: &mut AdminSkill, _| {
+                fix_vec_id_field(
+                    skill,
+                    &admin_buffed_by,
+                    &codex_buffed_by,
+                    |skill| &mut skill.buffed_by,
+                    |id| {
+                        data.guide
+                            .monsters
+                            .get_by_id(*id)
+                            .map(|monster| &monster.name)
+                    },
+                )
+            },
+            data,
+        )?;
     }
     Ok(())
 }
 
 /// Check for any mismatch between the guide skills and the codex skills.
-pub fn perform(data: &mut OrnaData, fix: bool, guide: &OrnaAdminGuide) -> Result<(), Error> {
+/// If `mask_numbers` is set, numbers (which scale with tier or level) are ignored when comparing
+/// descriptions.
+pub fn perform(
+    data: &mut OrnaData,
+    fix: bool,
+    mask_numbers: bool,
+    guide: &OrnaAdminGuide,
+) -> Result<(), Error> {
     println!("\x1B[0;35mMatching Skills\x1B[0m");
     list_missing(data, fix, guide)?;
-    check_fields(data, fix, guide)?;
+    let summary = MismatchSummary::default();
+    check_fields(data, fix, mask_numbers, guide, &summary)?;
+    summary.print();
     Ok(())
 }
@@ -0,0 +1,218 @@
+use ornaguide_rs::{data::OrnaData, error::Error, guide::Static};
+
+/// A guide entity referencing an id that isn't in the current `Static`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct StaleId<'a> {
+    /// The kind of entity (`item`, `monster`, ...).
+    pub kind: &'static str,
+    /// The id of the entity on the guide.
+    pub entity_id: u32,
+    /// The name of the entity on the guide.
+    pub entity_name: &'a str,
+    /// The field referencing the id.
+    pub field: &'static str,
+    /// The id that could not be found.
+    pub id: u32,
+}
+
+/// Sets of the ids present in `Static`.
+struct StaticIds {
+    spawns: Vec<u32>,
+    item_categories: Vec<u32>,
+    item_types: Vec<u32>,
+    monster_families: Vec<u32>,
+    status_effects: Vec<u32>,
+    elements: Vec<u32>,
+    equipped_bys: Vec<u32>,
+    skill_types: Vec<u32>,
+}
+
+impl StaticIds {
+    fn new(static_: &Static) -> Self {
+        Self {
+            spawns: static_.spawns.iter().map(|x| x.id).collect(),
+            item_categories: static_.item_categories.iter().map(|x| x.id).collect(),
+            item_types: static_.item_types.iter().map(|x| x.id).collect(),
+            monster_families: static_.monster_families.iter().map(|x| x.id).collect(),
+            status_effects: static_.status_effects.iter().map(|x| x.id).collect(),
+            elements: static_.elements.iter().map(|x| x.id).collect(),
+            equipped_bys: static_.equipped_bys.iter().map(|x| x.id).collect(),
+            skill_types: static_.skill_types.iter().map(|x| x.id).collect(),
+        }
+    }
+}
+
+/// Push a `StaleId` for each of `referenced` that isn't in `known`.
+fn push_stale<'a>(
+    stale: &mut Vec<StaleId<'a>>,
+    (kind, entity_id, entity_name): (&'static str, u32, &'a str),
+    field: &'static str,
+    referenced: &[u32],
+    known: &[u32],
+) {
+    for id in referenced.iter().filter(|id| !known.contains(id)) {
+        stale.push(StaleId {
+            kind,
+            entity_id,
+            entity_name,
+            field,
+            id: *id,
+        });
+    }
+}
+
+/// Find ids referenced by guide entities which are missing from the current `Static`.
+pub fn find(data: &OrnaData) -> Vec<StaleId<'_>> {
+    let ids = StaticIds::new(&data.guide.static_);
+    let mut stale = Vec::new();
+
+    for item in data.guide.items.items.iter() {
+        let mut check = |field, referenced: &[u32], known: &[u32]| {
+            push_stale(
+                &mut stale,
+                ("item", item.id, &item.name),
+                field,
+                referenced,
+                known,
+            )
+        };
+        check("type", &[item.type_], &ids.item_types);
+        check("element", item.element.as_slice(), &ids.elements);
+        check("category", item.category.as_slice(), &ids.item_categories);
+        check("equipped_by", &item.equipped_by, &ids.equipped_bys);
+        check("causes", &item.causes, &ids.status_effects);
+        check("cures", &item.cures, &ids.status_effects);
+        check("gives", &item.gives, &ids.status_effects);
+        check("prevents", &item.prevents, &ids.status_effects);
+    }
+
+    for monster in data.guide.monsters.monsters.iter() {
+        let mut check = |field, referenced: &[u32], known: &[u32]| {
+            push_stale(
+                &mut stale,
+                ("monster", monster.id, &monster.name),
+                field,
+                referenced,
+                known,
+            )
+        };
+        check("family", monster.family.as_slice(), &ids.monster_families);
+        check("spawns", &monster.spawns, &ids.spawns);
+        check("weak_to", &monster.weak_to, &ids.elements);
+        check("resistant_to", &monster.resistant_to, &ids.elements);
+        check("immune_to", &monster.immune_to, &ids.elements);
+        check(
+            "immune_to_status",
+            &monster.immune_to_status,
+            &ids.status_effects,
+        );
+        check(
+            "vulnerable_to_status",
+            &monster.vulnerable_to_status,
+            &ids.status_effects,
+        );
+    }
+
+    for skill in data.guide.skills.skills.iter() {
+        let mut check = |field, referenced: &[u32], known: &[u32]| {
+            push_stale(
+                &mut stale,
+                ("skill", skill.id, &skill.name),
+                field,
+                referenced,
+                known,
+            )
+        };
+        check("type", &[skill.type_], &ids.skill_types);
+        check("element", skill.element.as_slice(), &ids.elements);
+        check("causes", &skill.causes, &ids.status_effects);
+        check("cures", &skill.cures, &ids.status_effects);
+        check("gives", &skill.gives, &ids.status_effects);
+    }
+
+    for pet in data.guide.pets.pets.iter() {
+        push_stale(
+            &mut stale,
+            ("pet", pet.id, &pet.name),
+            "event",
+            &pet.event,
+            &ids.spawns,
+        );
+    }
+
+    stale
+}
+
+/// Make sure that all ids referenced by guide entities exist in the current `Static`.
+/// If `Static` was refreshed but not the entities, ids may reference entries that were removed,
+/// which would make the matchers fail in confusing ways.
+pub fn perform(data: &OrnaData) -> Result<(), Error> {
+    let stale = find(data);
+    if stale.is_empty() {
+        return Ok(());
+    }
+
+    println!(
+        "\x1B[0;31m{} ids referenced by guide entities are missing from static data:\x1B[0m",
+        stale.len()
+    );
+    for stale_id in stale.iter() {
+        println!(
+            "\t- [{:^7}] {:20} (#{}): {} #{}",
+            stale_id.kind, stale_id.entity_name, stale_id.entity_id, stale_id.field, stale_id.id
+        );
+    }
+    Err(Error::Misc(format!(
+        "{} stale static ids in guide data. Refresh your data (`ethi json refresh guide`) before \
+         matching.",
+        stale.len()
+    )))
+}
+
+#[cfg(test)]
+mod test {
+    use ornaguide_rs::{
+        data::OrnaData,
+        guide::{ItemType, StatusEffect},
+        items::admin::AdminItem,
+        with_defaults,
+    };
+
+    use crate::guide_match::stale_ids::{find, perform, StaleId};
+
+    #[test]
+    fn test_nonexistent_status_id() {
+        let mut data = OrnaData::default();
+        data.guide.static_.status_effects = vec![StatusEffect {
+            id: 1,
+            name: "Burning".to_string(),
+        }];
+        data.guide.static_.item_types = vec![ItemType {
+            id: 2,
+            name: "Weapon".to_string(),
+        }];
+
+        data.guide.items.items = vec![with_defaults!(AdminItem {
+            id: 42,
+            name: "Torch".to_string(),
+            type_: 2,
+            causes: vec![1, 7],
+        })];
+
+        assert_eq!(
+            find(&data),
+            vec![StaleId {
+                kind: "item",
+                entity_id: 42,
+                entity_name: "Torch",
+                field: "causes",
+                id: 7,
+            }]
+        );
+        assert!(perform(&data).is_err());
+
+        data.guide.items.items[0].causes = vec![1];
+        assert!(find(&data).is_empty());
+        assert!(perform(&data).is_ok());
+    }
+}
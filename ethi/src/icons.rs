@@ -0,0 +1,177 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+};
+
+use ornaguide_rs::{
+    data::OrnaData,
+    error::Error,
+    guide::{AdminGuide, OrnaAdminGuide},
+};
+use serde::Serialize;
+
+use crate::{cli, retry_once};
+
+/// A guide item whose icon is missing or differs from the icon of its codex counterpart.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct IconIssue {
+    /// Id of the item on the guide.
+    pub id: u32,
+    /// Name of the item on the guide.
+    pub name: String,
+    /// Icon of the item on the guide, empty if missing.
+    pub image_name: String,
+    /// Icon of the matching codex item, if the item has a codex counterpart.
+    pub codex_icon: Option<String>,
+}
+
+/// List guide items with an empty `image_name` or whose `image_name` differs from the matching
+/// codex item's icon.
+fn find_issues(data: &OrnaData) -> Vec<IconIssue> {
+    data.guide
+        .items
+        .items
+        .iter()
+        .filter_map(|guide_item| {
+            let codex_icon = Some(guide_item.slug())
+                .filter(|slug| !slug.is_empty())
+                .and_then(|slug| data.codex.items.find_by_slug(slug))
+                .map(|codex_item| codex_item.icon.clone());
+            let mismatch = codex_icon
+                .as_ref()
+                .map(|icon| *icon != guide_item.image_name)
+                .unwrap_or(false);
+            if guide_item.image_name.is_empty() || mismatch {
+                Some(IconIssue {
+                    id: guide_item.id,
+                    name: guide_item.name.clone(),
+                    image_name: guide_item.image_name.clone(),
+                    codex_icon,
+                })
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Copy the codex icon of the item onto the guide.
+fn fix_issue(guide: &OrnaAdminGuide, issue: &IconIssue) -> Result<(), Error> {
+    if let Some(codex_icon) = issue.codex_icon.as_ref() {
+        let mut item = retry_once!(guide.admin_retrieve_item_by_id(issue.id))?;
+        item.image_name = codex_icon.clone();
+        retry_once!(guide.admin_save_item(item.clone()))?;
+    }
+    Ok(())
+}
+
+/// Write the icon issues to the given JSON file.
+fn dump(issues: &[IconIssue], path: &str) -> Result<(), Error> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(&mut writer, issues)
+        .map_err(|err| Error::SerdeJson(err, path.to_string()))?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// List guide items with missing or mismatched icons, optionally fixing them.
+pub fn cli(
+    command: cli::icons::Command,
+    guide: &OrnaAdminGuide,
+    data: OrnaData,
+) -> Result<(), Error> {
+    let issues = find_issues(&data);
+    for issue in issues.iter() {
+        match issue.codex_icon.as_ref() {
+            Some(codex_icon) => println!(
+                "\t- {} (#{}): {:?} (codex: {:?})",
+                issue.name, issue.id, issue.image_name, codex_icon
+            ),
+            None => println!("\t- {} (#{}): no icon", issue.name, issue.id),
+        }
+    }
+    println!("{} items with missing or mismatched icons", issues.len());
+
+    if let Some(path) = command.json.as_ref() {
+        dump(&issues, path)?;
+    }
+
+    if command.fix {
+        for issue in issues.iter() {
+            if let Err(err) = fix_issue(guide, issue) {
+                println!(
+                    "\x1B[0;31mFailed to fix icon of {} (#{}): {}\x1B[0m",
+                    issue.name, issue.id, err
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use ornaguide_rs::{codex::CodexItem, data::OrnaData, items::admin::AdminItem, with_defaults};
+
+    use crate::icons::{find_issues, IconIssue};
+
+    #[test]
+    fn test_find_issues() {
+        let mut data = OrnaData::default();
+        for (slug, icon) in [
+            ("sword", "items/sword.png"),
+            ("bow", "items/bow.png"),
+            ("axe", "items/axe.png"),
+        ] {
+            data.codex.items.items.push(CodexItem {
+                slug: slug.to_string(),
+                name: slug.to_string(),
+                icon: icon.to_string(),
+                tier: 1,
+                ..CodexItem::default()
+            });
+        }
+        for (id, slug, image_name) in [
+            (1, "sword", ""),
+            (2, "bow", "items/old_bow.png"),
+            (3, "axe", "items/axe.png"),
+            (4, "", ""),
+            (5, "", "items/stick.png"),
+        ] {
+            data.guide.items.items.push(with_defaults!(AdminItem {
+                id,
+                name: format!("Item {}", id),
+                codex_uri: if slug.is_empty() {
+                    String::new()
+                } else {
+                    format!("/codex/items/{}/", slug)
+                },
+                image_name: image_name.to_string(),
+            }));
+        }
+
+        assert_eq!(
+            find_issues(&data),
+            vec![
+                IconIssue {
+                    id: 1,
+                    name: "Item 1".to_string(),
+                    image_name: String::new(),
+                    codex_icon: Some("items/sword.png".to_string()),
+                },
+                IconIssue {
+                    id: 2,
+                    name: "Item 2".to_string(),
+                    image_name: "items/old_bow.png".to_string(),
+                    codex_icon: Some("items/bow.png".to_string()),
+                },
+                IconIssue {
+                    id: 4,
+                    name: "Item 4".to_string(),
+                    image_name: String::new(),
+                    codex_icon: None,
+                },
+            ]
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use std::{path::PathBuf, time::Instant};
+use std::{path::PathBuf, process::ExitCode, time::Instant};
 
 use crate::backups::Backup;
 use clap::Parser;
@@ -13,16 +13,21 @@ use ornaguide_rs::{
 };
 
 mod backups;
+mod check;
 mod cli;
 mod codex;
 mod codex_bugs;
 mod config;
+mod diff;
+mod find;
 mod guide;
 mod guide_html;
 mod guide_match;
+mod icons;
 mod merge;
 mod misc;
 mod output;
+mod query;
 mod translation;
 
 /// Retrieve the latest merge archive (both its path and contents).
@@ -41,12 +46,15 @@ fn get_merge_archive() -> Result<(PathBuf, Backup), Error> {
         // Sort them. The names are chronological, so it orders them oldest first, which is why we
         // compare `b` to `a` and not the other way around.
         .sorted_by(|a, b| b.path().cmp(&a.path()))
-        // Try to open them. Ignore those we fail to open.
+        // Try to open them. Skip those that are corrupt, reporting why.
         // Oldest archives have a different format and may not be loadable.
-        .find_map(|entry| match Backup::load_from(entry.path()) {
+        .find_map(|entry| match Backup::load_validated(entry.path()) {
             Ok(backup) => Some((entry.path(), backup)),
-            Err(x) => {
-                println!("Failed to load {:?}: {}", entry.path(), x);
+            Err(issues) => {
+                println!("Skipping invalid archive {:?}:", entry.path());
+                for issue in issues.iter() {
+                    println!("\t- {}", issue);
+                }
                 None
             }
         })
@@ -64,10 +72,10 @@ fn ethi(guide: &OrnaAdminGuide, mut data: OrnaData) -> Result<(), Error> {
     let (path, archive) = get_merge_archive()?;
     println!("Found archive {}", path.display());
 
-    // guide_match::all(&mut data, fix, guide)?;
+    // guide_match::all(&mut data, fix, false, false, guide)?;
     // guide_match::status_effects::perform(&mut data, fix, guide)?;
-    // guide_match::skills::perform(&mut data, fix, guide)?;
-    // guide_match::items::perform(&mut data, fix, guide)?;
+    // guide_match::skills::perform(&mut data, fix, false, guide)?;
+    // guide_match::items::perform(&mut data, fix, false, guide)?;
     // guide_match::monsters::perform(&mut data, fix, guide)?;
     // guide_match::pets::perform(&mut data, fix, guide)?;
 
@@ -76,22 +84,38 @@ fn ethi(guide: &OrnaAdminGuide, mut data: OrnaData) -> Result<(), Error> {
 
 fn main2() -> Result<(), Error> {
     let guide = config::with_config(|config| {
-        OrnaAdminGuide::new_with_hosts(
+        let mut guide = OrnaAdminGuide::new_with_hosts(
             &config.ornaguide_cookie,
             config.ornaguide_host.clone(),
             config.playorna_host.clone(),
-        )
+        )?;
+        if let (Some(username), Some(password)) =
+            (&config.ornaguide_username, &config.ornaguide_password)
+        {
+            guide.login(username, password)?;
+        }
+        Ok(guide)
     })?;
     let data = || OrnaData::load_from("data/current_entries");
     let localedb = || LocaleDB::load_from("data/current_entries/i18n");
 
-    match cli::Cli::parse().command {
+    let cli = cli::Cli::parse();
+    if cli.no_progress {
+        misc::disable_progress();
+    }
+
+    match cli.command {
         Some(command) => match command {
             cli::Command::Backups(cmd) => backups::cli(cmd, &guide, data()?),
+            cli::Command::Check => check::cli(data()?),
             cli::Command::Codex(cmd) => codex::cli(cmd, &guide, data()?),
+            cli::Command::Diff(cmd) => diff::cli(cmd, data()?),
+            cli::Command::Find(cmd) => find::cli(cmd, data()?),
+            cli::Command::Icons(cmd) => icons::cli(cmd, &guide, data()?),
             cli::Command::Json(cmd) => output::cli(cmd, &guide, data),
             cli::Command::Match(cmd) => guide_match::cli(cmd, &guide, data()?),
             cli::Command::Merge(cmd) => merge::cli(cmd, &guide, data()?),
+            cli::Command::Query(cmd) => query::cli(cmd, data()?),
             cli::Command::Translation(cmd) => translation::cli(cmd, &guide, data()?, localedb()?),
         },
         None => ethi(&guide, data()?),
@@ -112,13 +136,20 @@ fn main2() -> Result<(), Error> {
     // }
 }
 
-fn main() {
+fn main() -> ExitCode {
     let begin = Instant::now();
-    match main2() {
-        Ok(_) => println!("OK"),
-        Err(err) => eprintln!("Error: {}", err),
-    }
+    let code = match main2() {
+        Ok(_) => {
+            println!("OK");
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            ExitCode::FAILURE
+        }
+    };
     let end = Instant::now();
     let elapsed = end.duration_since(begin);
     println!("Executed in {}ms", elapsed.as_millis());
+    code
 }
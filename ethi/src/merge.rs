@@ -1,11 +1,16 @@
-use std::path::PathBuf;
+use std::{collections::HashMap, fmt::Display, hash::Hash, path::PathBuf};
 
 use itertools::Itertools;
 use ornaguide_rs::{data::OrnaData, error::Error, guide::OrnaAdminGuide};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
 
 use crate::{
     backups::Backup,
-    cli::{self, merge::Match},
+    cli::{
+        self,
+        merge::{Match, Strategy},
+    },
     guide_match,
 };
 
@@ -25,67 +30,291 @@ fn get_merge_archive() -> Result<(PathBuf, Backup), Error> {
         // Sort them. The names are chronological, so it orders them oldest first, which is why we
         // compare `b` to `a` and not the other way around.
         .sorted_by(|a, b| b.path().cmp(&a.path()))
-        // Try to open them. Ignore those we fail to open.
+        // Try to open them. Skip those that are corrupt, reporting why.
         // Oldest archives have a different format and may not be loadable.
-        .find_map(|entry| match Backup::load_from(entry.path()) {
+        .find_map(|entry| match Backup::load_validated(entry.path()) {
             Ok(backup) => Some((entry.path(), backup)),
-            Err(x) => {
-                println!("Failed to load {:?}: {}", entry.path(), x);
+            Err(issues) => {
+                println!("Skipping invalid archive {:?}:", entry.path());
+                for issue in issues.iter() {
+                    println!("\t- {}", issue);
+                }
                 None
             }
         })
         .ok_or_else(|| Error::Misc("Failed to find a merge file".to_string()))
 }
 
-pub fn match_(fix: bool, guide: &OrnaAdminGuide) -> Result<(), Error> {
-    let (path, mut merge) = get_merge_archive()?;
-    println!("Matching with merge archive {}", path.to_string_lossy());
-    guide_match::all(&mut merge.data, fix, guide)
+/// Whether a JSON value holds no information (`null`, empty string, array or object).
+fn is_empty_value(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(s) => s.is_empty(),
+        Value::Array(a) => a.is_empty(),
+        Value::Object(o) => o.is_empty(),
+        _ => false,
+    }
 }
 
-pub fn match_status_effects(fix: bool, guide: &OrnaAdminGuide) -> Result<(), Error> {
-    let (path, mut merge) = get_merge_archive()?;
-    println!("Matching with merge archive {}", path.to_string_lossy());
-    guide_match::status_effects::perform(&mut merge.data, fix, guide)
+/// Merge two versions of an entity field by field.
+/// Fields are taken from `incoming`, unless they are empty there, in which case they are taken
+/// from `existing`. Returns the merged entity and the names of the fields kept from `existing`.
+fn merge_fields<T>(existing: &T, incoming: &T) -> Result<(T, Vec<String>), Error>
+where
+    T: Serialize + DeserializeOwned,
+{
+    let existing = serde_json::to_value(existing)?;
+    let mut merged = serde_json::to_value(incoming)?;
+    let mut kept = vec![];
+    if let (Value::Object(existing), Value::Object(merged)) = (existing, &mut merged) {
+        for (field, existing_value) in existing {
+            let merged_value = merged.entry(field.clone()).or_insert(Value::Null);
+            if is_empty_value(merged_value) && !is_empty_value(&existing_value) {
+                *merged_value = existing_value;
+                kept.push(field);
+            }
+        }
+    }
+    Ok((serde_json::from_value(merged)?, kept))
 }
 
-pub fn match_skills(fix: bool, guide: &OrnaAdminGuide) -> Result<(), Error> {
-    let (path, mut merge) = get_merge_archive()?;
-    println!("Matching with merge archive {}", path.to_string_lossy());
-    guide_match::skills::perform(&mut merge.data, fix, guide)
+/// Resolve a conflict between the `existing` and `incoming` versions of an entity.
+/// `kind` and `key` are used to log which branch of the strategy was taken.
+fn resolve<T>(
+    strategy: Strategy,
+    kind: &str,
+    key: &str,
+    existing: &T,
+    incoming: &T,
+) -> Result<T, Error>
+where
+    T: Clone + PartialEq + Serialize + DeserializeOwned,
+{
+    if existing == incoming {
+        return Ok(incoming.clone());
+    }
+    match strategy {
+        Strategy::Existing => {
+            println!("{:8} {:30}: kept existing", kind, key);
+            Ok(existing.clone())
+        }
+        Strategy::Incoming => {
+            println!("{:8} {:30}: took incoming", kind, key);
+            Ok(incoming.clone())
+        }
+        Strategy::IncomingUnlessEmpty => {
+            let (merged, kept) = merge_fields(existing, incoming)?;
+            if kept.is_empty() {
+                println!("{:8} {:30}: took incoming", kind, key);
+            } else {
+                println!("{:8} {:30}: kept existing {}", kind, key, kept.join(", "));
+            }
+            Ok(merged)
+        }
+    }
 }
 
-pub fn match_items(fix: bool, guide: &OrnaAdminGuide) -> Result<(), Error> {
-    let (path, mut merge) = get_merge_archive()?;
-    println!("Matching with merge archive {}", path.to_string_lossy());
-    guide_match::items::perform(&mut merge.data, fix, guide)
+/// Resolve conflicts between the entities of `existing` and those of `incoming`.
+/// Entities are matched by the key returned by `key_of`. Entities that are not in `incoming` are
+/// not added to it.
+fn resolve_all<T, K>(
+    strategy: Strategy,
+    kind: &str,
+    existing: &[T],
+    incoming: &mut [T],
+    key_of: fn(&T) -> K,
+) -> Result<(), Error>
+where
+    T: Clone + PartialEq + Serialize + DeserializeOwned,
+    K: Hash + Eq + Display,
+{
+    let existing = existing
+        .iter()
+        .map(|entity| (key_of(entity), entity))
+        .collect::<HashMap<_, _>>();
+    for entity in incoming.iter_mut() {
+        let key = key_of(entity);
+        if let Some(existing) = existing.get(&key) {
+            *entity = resolve(strategy, kind, &key.to_string(), *existing, entity)?;
+        }
+    }
+    Ok(())
 }
 
-pub fn match_monsters(fix: bool, guide: &OrnaAdminGuide) -> Result<(), Error> {
-    let (path, mut merge) = get_merge_archive()?;
-    println!("Matching with merge archive {}", path.to_string_lossy());
-    guide_match::monsters::perform(&mut merge.data, fix, guide)
+/// Resolve conflicts between the current data and that of a merge archive, according to
+/// `strategy`. `incoming` is updated in place.
+fn resolve_conflicts(
+    existing: &OrnaData,
+    incoming: &mut OrnaData,
+    strategy: Strategy,
+) -> Result<(), Error> {
+    // Taking the incoming entity is what happens when there is no resolution.
+    if strategy == Strategy::Incoming {
+        return Ok(());
+    }
+
+    let (guide, codex) = (&existing.guide, &existing.codex);
+    resolve_all(
+        strategy,
+        "item",
+        &guide.items.items,
+        &mut incoming.guide.items.items,
+        |item| item.id,
+    )?;
+    resolve_all(
+        strategy,
+        "monster",
+        &guide.monsters.monsters,
+        &mut incoming.guide.monsters.monsters,
+        |monster| monster.id,
+    )?;
+    resolve_all(
+        strategy,
+        "skill",
+        &guide.skills.skills,
+        &mut incoming.guide.skills.skills,
+        |skill| skill.id,
+    )?;
+    resolve_all(
+        strategy,
+        "pet",
+        &guide.pets.pets,
+        &mut incoming.guide.pets.pets,
+        |pet| pet.id,
+    )?;
+    resolve_all(
+        strategy,
+        "item",
+        &codex.items.items,
+        &mut incoming.codex.items.items,
+        |item| item.slug.clone(),
+    )?;
+    resolve_all(
+        strategy,
+        "monster",
+        &codex.monsters.monsters,
+        &mut incoming.codex.monsters.monsters,
+        |monster| monster.slug.clone(),
+    )?;
+    resolve_all(
+        strategy,
+        "boss",
+        &codex.bosses.bosses,
+        &mut incoming.codex.bosses.bosses,
+        |boss| boss.slug.clone(),
+    )?;
+    resolve_all(
+        strategy,
+        "raid",
+        &codex.raids.raids,
+        &mut incoming.codex.raids.raids,
+        |raid| raid.slug.clone(),
+    )?;
+    resolve_all(
+        strategy,
+        "skill",
+        &codex.skills.skills,
+        &mut incoming.codex.skills.skills,
+        |skill| skill.slug.clone(),
+    )?;
+    resolve_all(
+        strategy,
+        "follower",
+        &codex.followers.followers,
+        &mut incoming.codex.followers.followers,
+        |follower| follower.slug.clone(),
+    )?;
+    Ok(())
 }
 
-pub fn match_pets(fix: bool, guide: &OrnaAdminGuide) -> Result<(), Error> {
+/// Load the latest merge archive and resolve its conflicts with `data`.
+fn load_merge(data: &OrnaData, strategy: Strategy) -> Result<OrnaData, Error> {
     let (path, mut merge) = get_merge_archive()?;
     println!("Matching with merge archive {}", path.to_string_lossy());
-    guide_match::pets::perform(&mut merge.data, fix, guide)
+    resolve_conflicts(data, &mut merge.data, strategy)?;
+    Ok(merge.data)
 }
 
 /// Execute a CLI subcommand on merges.
-pub fn cli(command: cli::merge::Command, guide: &OrnaAdminGuide, _: OrnaData) -> Result<(), Error> {
+pub fn cli(
+    command: cli::merge::Command,
+    guide: &OrnaAdminGuide,
+    data: OrnaData,
+) -> Result<(), Error> {
     match command {
         cli::merge::Command::Match(cmd) => {
             let fix = cmd.fix;
+            let mut merge = load_merge(&data, cmd.strategy)?;
+            let merge = &mut merge;
             match cmd.c {
-                Some(Match::Items) => match_items(fix, guide),
-                Some(Match::Monsters) => match_monsters(fix, guide),
-                Some(Match::Pets) => match_pets(fix, guide),
-                Some(Match::Skills) => match_skills(fix, guide),
-                Some(Match::StatusEffects) => match_status_effects(fix, guide),
-                None => match_(fix, guide),
+                Some(Match::Items) => guide_match::items::perform(merge, fix, false, guide),
+                Some(Match::Monsters) => guide_match::monsters::perform(merge, fix, guide),
+                Some(Match::Pets) => guide_match::pets::perform(merge, fix, guide),
+                Some(Match::Skills) => guide_match::skills::perform(merge, fix, false, guide),
+                Some(Match::StatusEffects) => {
+                    guide_match::status_effects::perform(merge, fix, guide)
+                }
+                None => guide_match::all(merge, fix, false, false, guide),
             }
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use ornaguide_rs::{data::OrnaData, items::admin::AdminItem, with_defaults};
+
+    use crate::{cli::merge::Strategy, merge::resolve_conflicts};
+
+    fn item(id: u32, name: &str, description: &str, tier: u8) -> AdminItem {
+        with_defaults!(AdminItem {
+            id,
+            name: name.to_string(),
+            description: description.to_string(),
+            tier,
+        })
+    }
+
+    /// Resolve a conflict on two items and return the resolved items.
+    fn resolve_items(strategy: Strategy) -> Vec<AdminItem> {
+        let mut existing = OrnaData::default();
+        existing.guide.items.items = vec![
+            item(1, "Sword", "An existing sword.", 1),
+            item(2, "Bow", "An existing bow.", 1),
+        ];
+        let mut incoming = OrnaData::default();
+        incoming.guide.items.items = vec![item(1, "Sword", "", 2), item(2, "Longbow", "A bow.", 3)];
+        resolve_conflicts(&existing, &mut incoming, strategy).unwrap();
+        incoming.guide.items.items
+    }
+
+    #[test]
+    fn test_existing() {
+        assert_eq!(
+            resolve_items(Strategy::Existing),
+            vec![
+                item(1, "Sword", "An existing sword.", 1),
+                item(2, "Bow", "An existing bow.", 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_incoming() {
+        assert_eq!(
+            resolve_items(Strategy::Incoming),
+            vec![item(1, "Sword", "", 2), item(2, "Longbow", "A bow.", 3)]
+        );
+    }
+
+    #[test]
+    fn test_incoming_unless_empty() {
+        assert_eq!(
+            resolve_items(Strategy::IncomingUnlessEmpty),
+            vec![
+                item(1, "Sword", "An existing sword.", 2),
+                item(2, "Longbow", "A bow.", 3),
+            ]
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use std::{
     fs::File,
-    io::{BufReader, Read},
+    io::{BufReader, IsTerminal, Read},
+    sync::atomic::{AtomicBool, Ordering},
 };
 
 use futures::Future;
@@ -12,21 +13,217 @@ use serde::{
     Deserialize, Deserializer,
 };
 
-pub fn bar(len: u64) -> ProgressBar {
+/// A handle to report the progress of a long operation.
+pub trait Progress: Send + Sync {
+    /// Set the message displayed next to the progress.
+    fn set_message(&self, msg: &str);
+    /// Advance the progress by `delta`.
+    fn inc(&self, delta: u64);
+    /// Mark the operation as finished, with a final message.
+    fn finish_with_message(&self, msg: &str);
+}
+
+impl Progress for ProgressBar {
+    fn set_message(&self, msg: &str) {
+        ProgressBar::set_message(self, msg.to_string());
+    }
+
+    fn inc(&self, delta: u64) {
+        ProgressBar::inc(self, delta);
+    }
+
+    fn finish_with_message(&self, msg: &str) {
+        ProgressBar::finish_with_message(self, msg.to_string());
+    }
+}
+
+/// A progress handle that does not output anything.
+pub struct NoProgress;
+
+impl Progress for NoProgress {
+    fn set_message(&self, _: &str) {}
+
+    fn inc(&self, _: u64) {}
+
+    fn finish_with_message(&self, _: &str) {}
+}
+
+/// Whether progress bars were disabled from the command line (`--no-progress`).
+static PROGRESS_DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// Disable progress bars for the rest of the execution.
+pub fn disable_progress() {
+    PROGRESS_DISABLED.store(true, Ordering::Relaxed);
+}
+
+/// Whether progress bars should be displayed.
+/// They are not when disabled, when running in CI (`CI` is set) or when the output isn't a TTY.
+fn progress_enabled(disabled: bool, ci: bool, is_tty: bool) -> bool {
+    !disabled && !ci && is_tty
+}
+
+/// Create a progress handle for an operation with `len` steps.
+/// Progress bars are drawn on stderr. If they shouldn't be displayed, a no-op handle is returned.
+pub fn bar(len: u64) -> Box<dyn Progress> {
+    if !progress_enabled(
+        PROGRESS_DISABLED.load(Ordering::Relaxed),
+        std::env::var_os("CI").is_some(),
+        std::io::stderr().is_terminal(),
+    ) {
+        return Box::new(NoProgress);
+    }
+
     let bar = ProgressBar::new(len);
     bar.set_style(
         ProgressStyle::default_bar()
             .template("{msg:15!} {eta:>3} [{wide_bar}] {pos:>4}/{len:4}")
             .progress_chars("=> "),
     );
-    bar
+    Box::new(bar)
+}
+
+/// Return the tag of a guide name, without its brackets, if any.
+/// Some entities on the guide have a tag appended to their name to distinguish homonyms (e.g.:
+/// `Bite [Monster]`). The tag is what lies in the first pair of brackets.
+#[cfg(test)]
+pub fn guide_name_tag(name: &str) -> Option<&str> {
+    let start = name.find('[')?;
+    let tag = &name[start + 1..];
+    Some(tag.find(']').map_or(tag, |end| &tag[..end]))
+}
+
+/// Remove the tag (e.g.: ` [Monster]`) from a guide name. Everything from the first `[` onwards
+/// is removed.
+pub fn strip_guide_name_tag(name: &str) -> &str {
+    name.find('[').map_or(name, |pos| &name[..pos])
 }
 
+/// Remove the trailing whitespace of a guide name, usually left over after stripping its tag.
+pub fn trim_guide_name(name: &str) -> &str {
+    name.trim_end()
+}
+
+/// Transform a guide name so it can be compared with the name of the entity on the codex.
+/// The tag is stripped from the name, along with the whitespace that separated it from the name.
+/// Other parts of the name (apostrophes, articles, parentheses) are left untouched.
+///
+/// The inverse of this function is `tag_guide_name`.
 pub fn sanitize_guide_name(name: &str) -> &str {
-    if let Some(pos) = name.find('[') {
-        name.split_at(pos - 1).0
+    trim_guide_name(strip_guide_name_tag(name))
+}
+
+/// Rebuild a guide name from a name sanitized with `sanitize_guide_name` and the tag that was
+/// stripped from it (as returned by `guide_name_tag`).
+#[cfg(test)]
+pub fn tag_guide_name(name: &str, tag: Option<&str>) -> String {
+    match tag {
+        Some(tag) => format!("{} [{}]", name, tag),
+        None => name.to_string(),
+    }
+}
+
+/// Decode an HTML entity, given without its leading `&` and trailing `;` (`amp`, `#39`, ...).
+fn decode_html_entity(entity: &str) -> Option<char> {
+    match entity {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some(' '),
+        _ => {
+            let code = if let Some(hex) = entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+            {
+                u32::from_str_radix(hex, 16).ok()?
+            } else {
+                entity.strip_prefix('#')?.parse().ok()?
+            };
+            char::from_u32(code)
+        }
+    }
+}
+
+/// Normalize a description so that descriptions from the codex and the guide can be compared.
+/// HTML tags are stripped, HTML entities are decoded and whitespace (including non-breaking
+/// spaces) is collapsed into single spaces. Leading and trailing whitespace is removed.
+pub fn normalize_description(description: &str) -> String {
+    let mut text = String::with_capacity(description.len());
+    let mut chars = description.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '<' => {
+                // Skip the tag. Tags are replaced with a space so that `a<br>b` becomes `a b`.
+                for c in chars.by_ref() {
+                    if c == '>' {
+                        break;
+                    }
+                }
+                text.push(' ');
+            }
+            '&' => {
+                let rest = chars.as_str();
+                match rest
+                    .find(';')
+                    .filter(|end| *end <= 10)
+                    .and_then(|end| decode_html_entity(&rest[..end]).map(|c| (end, c)))
+                {
+                    Some((end, decoded)) => {
+                        text.push(decoded);
+                        chars = rest[end + 1..].chars();
+                    }
+                    None => text.push('&'),
+                }
+            }
+            c => text.push(c),
+        }
+    }
+
+    // `char::is_whitespace` includes non-breaking spaces.
+    text.split_whitespace().join(" ")
+}
+
+/// Replace numbers in a description with a `#` placeholder.
+/// Numbers in descriptions often scale with the tier or level of the entity. Masking them leaves
+/// only the textual template of the description. A decimal number (`1.5`) is a single number.
+pub fn mask_description_numbers(description: &str) -> String {
+    let mut masked = String::with_capacity(description.len());
+    let mut chars = description.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_ascii_digit() {
+            while let Some(next) = chars.peek() {
+                if next.is_ascii_digit() {
+                    chars.next();
+                } else if *next == '.' {
+                    // Only consume the dot if it is followed by a digit.
+                    let mut lookahead = chars.clone();
+                    lookahead.next();
+                    if lookahead.peek().is_some_and(char::is_ascii_digit) {
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                } else {
+                    break;
+                }
+            }
+            masked.push('#');
+        } else {
+            masked.push(c);
+        }
+    }
+    masked
+}
+
+/// Compare two normalized descriptions.
+/// If `mask_numbers` is set, numbers are masked before comparison (see
+/// `mask_description_numbers`).
+pub fn descriptions_match(a: &str, b: &str, mask_numbers: bool) -> bool {
+    if mask_numbers {
+        mask_description_numbers(a) == mask_description_numbers(b)
     } else {
-        name
+        a == b
     }
 }
 
@@ -74,6 +271,7 @@ pub fn diff_sorted_slices<'a, T: PartialEq + PartialOrd>(
 /// A trait to extend `Vec<u32>` specifically.
 /// Use with caution, as this should only be used on `Vec`s that hold `u32`s representing skill
 /// ids.
+#[allow(dead_code)]
 pub trait VecSkillIds {
     /// Convert the `Vec` of skill ids to a sorted `Vec` of codex URIs for the skills.
     fn guide_skill_ids_to_codex_uri<'a>(&self, data: &'a OrnaData) -> Vec<&'a str>;
@@ -99,6 +297,7 @@ impl VecSkillIds for Vec<u32> {
 /// A trait to extend `Vec<u32>` specifically.
 /// Use with caution, as this should only be used on `Vec`s that hold `u32`s representing status
 /// effect ids.
+#[allow(dead_code)]
 pub trait VecStatusEffectIds {
     /// Convert the `Vec` of status effect ids to a sorted `Vec` of codex URIs for the status
     /// effects.
@@ -147,9 +346,8 @@ where
 
 /// Same as a `serde_json::from_reader` with a file, but adds the filename to the error message, if any.
 #[allow(dead_code)]
-pub fn json_from_file<R, T>(path: &str) -> Result<T, Error>
+pub fn json_from_file<T>(path: &str) -> Result<T, Error>
 where
-    R: Read,
     T: serde::de::DeserializeOwned,
 {
     json_read(BufReader::new(File::open(path)?), path)
@@ -246,3 +444,113 @@ pub fn block_on_this_thread<F: Future>(future: F) -> F::Output {
         .unwrap()
         .block_on(future)
 }
+
+#[cfg(test)]
+mod test {
+    use crate::misc::{
+        descriptions_match, guide_name_tag, mask_description_numbers, normalize_description,
+        progress_enabled, sanitize_guide_name, strip_guide_name_tag, tag_guide_name,
+        trim_guide_name,
+    };
+
+    #[test]
+    fn test_mask_description_numbers() {
+        assert_eq!(
+            mask_description_numbers("Deals 120% damage, 1.5x for 3 turns."),
+            "Deals #% damage, #x for # turns."
+        );
+        let low = "Heals 20 HP and grants 10% defense.";
+        let high = "Heals 45 HP and grants 12% defense.";
+        assert!(!descriptions_match(low, high, false));
+        assert!(descriptions_match(low, high, true));
+        assert!(!descriptions_match(
+            low,
+            "Heals 45 MP and grants 12% defense.",
+            true
+        ));
+    }
+
+    #[test]
+    fn test_normalize_description_entities() {
+        assert_eq!(
+            normalize_description("Sword &amp; Shield &lt;3 &#39;hi&#x27;"),
+            "Sword & Shield <3 'hi'"
+        );
+        assert_eq!(normalize_description("AT&T & co"), "AT&T & co");
+    }
+
+    #[test]
+    fn test_normalize_description_whitespace() {
+        assert_eq!(
+            normalize_description("A\u{a0}sharp&nbsp;blade.  \n"),
+            "A sharp blade."
+        );
+        assert_eq!(
+            normalize_description("A sharp blade. "),
+            normalize_description("A sharp blade.")
+        );
+    }
+
+    #[test]
+    fn test_normalize_description_tags() {
+        assert_eq!(
+            normalize_description("<p>A <b>sharp</b><br>blade.</p>"),
+            "A sharp blade."
+        );
+    }
+
+    #[test]
+    fn test_progress_enabled() {
+        assert!(progress_enabled(false, false, true));
+        assert!(!progress_enabled(true, false, true));
+        assert!(!progress_enabled(false, true, true));
+        assert!(!progress_enabled(false, false, false));
+    }
+
+    #[test]
+    fn test_guide_name_rules() {
+        assert_eq!(guide_name_tag("Bite [Monster]"), Some("Monster"));
+        assert_eq!(guide_name_tag("Bite [Monster"), Some("Monster"));
+        assert_eq!(guide_name_tag("Bite"), None);
+        assert_eq!(strip_guide_name_tag("Bite [Monster]"), "Bite ");
+        assert_eq!(strip_guide_name_tag("[Monster]"), "");
+        assert_eq!(trim_guide_name("Bite "), "Bite");
+        assert_eq!(trim_guide_name(" Bite"), " Bite");
+    }
+
+    #[test]
+    fn test_sanitize_guide_name() {
+        assert_eq!(sanitize_guide_name("Mage's Ring"), "Mage's Ring");
+        assert_eq!(sanitize_guide_name("Bag of Tricks"), "Bag of Tricks");
+        assert_eq!(
+            sanitize_guide_name("The Ring of Kings"),
+            "The Ring of Kings"
+        );
+        assert_eq!(
+            sanitize_guide_name("Summon Wolf (Summon)"),
+            "Summon Wolf (Summon)"
+        );
+        assert_eq!(sanitize_guide_name("Bite [Monster]"), "Bite");
+        assert_eq!(sanitize_guide_name("Mage's Ring [Old]"), "Mage's Ring");
+        assert_eq!(
+            sanitize_guide_name("Summon Wolf (Summon) [Pet]"),
+            "Summon Wolf (Summon)"
+        );
+        // No whitespace between the name and the tag, or no name at all.
+        assert_eq!(sanitize_guide_name("Bite[Monster]"), "Bite");
+        assert_eq!(sanitize_guide_name("[Monster]"), "");
+    }
+
+    #[test]
+    fn test_sanitize_guide_name_round_trip() {
+        for name in [
+            "Mage's Ring",
+            "Bag of Tricks",
+            "Bite [Monster]",
+            "Summon Wolf (Summon) [Pet]",
+        ] {
+            let sanitized = sanitize_guide_name(name);
+            assert_eq!(tag_guide_name(sanitized, guide_name_tag(name)), name);
+        }
+    }
+}
@@ -1,9 +1,15 @@
+use std::{
+    borrow::Cow,
+    fs::File,
+    io::{BufWriter, Write},
+};
+
 use itertools::Itertools;
 use ornaguide_rs::{
-    codex::Codex,
+    codex::{Codex, CodexGenericMonsterOwned},
     data::{CodexData, GuideData, OrnaData},
     error::Error,
-    guide::{AdminGuide, OrnaAdminGuide},
+    guide::{AdminGuide, OrnaAdminGuide, VecElements},
 };
 
 use crate::{
@@ -11,78 +17,46 @@ use crate::{
         self,
         json::{RefreshCodex, RefreshGuide},
     },
+    codex::fetch::{known_unlisted_monster_uris, CheckpointMode, EVENT_FOLLOWERS},
     misc::bar,
 };
 
+/// List the URIs of the monsters / bosses / raids in item drops we couldn't find.
+/// Also lists event monsters that have no drops we couldn't find.
+/// Malformed URIs are reported and skipped, as we would not be able to fetch them either.
+fn unlisted_monster_uris(data: &CodexData) -> Vec<String> {
+    let mut uris = Vec::new();
+    for uri in known_unlisted_monster_uris(data) {
+        // Keep only the URI of those those we can't find a codex monster for.
+        match data.find_generic_monster_from_uri(uri) {
+            Ok(Some(_)) => {}
+            Ok(None) => uris.push(uri.to_string()),
+            Err(err) => println!("Skipping monster {}: {}", uri, err),
+        }
+    }
+    // Remove duplicates.
+    uris.into_iter().sorted().dedup().collect()
+}
+
 /// Add unlisted monsters / bosses / raids to the data.
 /// Walks through item drops and lists monsters in those drops we couldn't find.
 /// Also adds event monsters that have no drops.
 /// Modifies `data` in-place.
 fn add_unlisted_monsters(guide: &OrnaAdminGuide, data: &mut CodexData) -> Result<(), Error> {
-    // Monsters that are not necessarily listed (i.e.: belong to an event) and that have no drops.
-    // These won't show up when listing through item drops.
-    let unlisted_without_drops = &["/codex/monsters/elite-balor-flame/".to_string()];
-
-    let uris = data
-        .items
-        .items
-        .iter()
-        // List all drops from all items.
-        .flat_map(|item| item.dropped_by.iter())
-        // Keep only the URI of those those we can't find a codex monster for.
-        .filter(|dropped_by| {
-            data.find_generic_monster_from_uri(&dropped_by.uri)
-                .is_none()
-        })
-        .map(|dropped_by| &dropped_by.uri)
-        // Add event monsters we don't have that do not drop any item.
-        .chain(
-            unlisted_without_drops
-                .iter()
-                .filter(|uri| data.find_generic_monster_from_uri(uri).is_none()),
-        )
-        // Remove duplicates.
-        .sorted()
-        .dedup()
-        .collect::<Vec<_>>();
-
+    let uris = unlisted_monster_uris(data);
     let bar = bar(uris.len() as u64);
-    for uri in uris {
-        // Strip `/codex/` and trailing slash from the uri.
-        let uri = uri[7..].trim_end_matches('/');
-        bar.set_message(uri.to_string());
-        if let Some(pos) = uri.find('/') {
-            let kind = &uri[0..pos];
-            let slug = &uri[pos + 1..];
-            let result = || -> Result<(), Error> {
-                match kind {
-                    "monsters" => {
-                        data.monsters
-                            .monsters
-                            .push(guide.codex_fetch_monster(slug)?);
-                    }
-                    "bosses" => {
-                        data.bosses.bosses.push(guide.codex_fetch_boss(slug)?);
-                    }
-                    "raids" => {
-                        data.raids.raids.push(guide.codex_fetch_raid(slug)?);
-                    }
-                    _ => {
-                        println!("Unknown monster kind for URI {}", uri);
-                    }
-                }
-                Ok(())
-            }();
+    for uri in uris.iter() {
+        bar.set_message(uri);
+        match guide.codex_fetch_generic_monster_by_uri(uri) {
+            Ok(CodexGenericMonsterOwned::Monster(monster)) => data.monsters.monsters.push(monster),
+            Ok(CodexGenericMonsterOwned::Boss(boss)) => data.bosses.bosses.push(boss),
+            Ok(CodexGenericMonsterOwned::Raid(raid)) => data.raids.raids.push(raid),
             // Ignore 404s.
-            match result {
-                Err(Error::ResponseError(_, _, 404, _)) => {}
-                Err(x) => return Err(x),
-                _ => {}
-            }
-            bar.inc(1);
-        } else {
-            println!("Failed to parse monster for URI {}", uri);
+            Err(Error::ResponseError(_, _, 404, _)) => {}
+            Err(err @ Error::InvalidField(..)) => println!("{}", err),
+            Err(x) => return Err(x),
         }
+        bar.inc(1);
     }
     bar.finish_with_message("CUnlstM fetched");
     Ok(())
@@ -91,67 +65,9 @@ fn add_unlisted_monsters(guide: &OrnaAdminGuide, data: &mut CodexData) -> Result
 /// Add unlisted followers to the data.
 /// Modifies `data` in-place.
 fn add_event_followers(guide: &OrnaAdminGuide, data: &mut CodexData) -> Result<(), Error> {
-    // List of event pet slugs. Those may or may not appear in the follower list, depending on the
-    // time of the year.
-    let event_pets = &[
-        "age-old-mimic",
-        "alfar",
-        "alfar-mage",
-        "amadan",
-        "apollyons-apprentice",
-        "apollyons-apprentice-91d096c2",
-        "apollyons-apprentice-d370c676",
-        "apollyons-apprentice-e9f91df6",
-        "apollyons-pupil",
-        "apollyons-pupil-9d70a08e",
-        "apollyons-pupil-dde6d90b",
-        "apollyons-pupil-e453d6d9",
-        "archimedes",
-        "arisen-naggeneen",
-        "ashen-phoenix",
-        "balor-flame",
-        "balor-worm",
-        "carman",
-        "castor",
-        "cerus",
-        "cruel-banshee",
-        "ebon-scruug",
-        "fey-chimera",
-        "fey-dragon",
-        "fey-gazer",
-        "fey-yeti",
-        "glatisant",
-        "great-pegasus",
-        "gullinkambi",
-        "gwyllgi",
-        "hengreon",
-        "kerberos",
-        "kin-of-kerberos",
-        "lindworm",
-        "llamrai",
-        "naggeneen",
-        "phoenix",
-        "pollux",
-        "pumpkinhead",
-        "raging-cerus",
-        "scary-skeleton",
-        "scruug",
-        "spooky-ghost",
-        "steward-cactus",
-        "steward-dragon",
-        "steward-gazer",
-        "steward-golem",
-        "steward-wolf",
-        "surtrs-flame",
-        "surtrs-mighty-flame",
-        "the-mightiest-mimic",
-        "untamed-cerus",
-        "very-scary-skeleton",
-    ];
-
-    let bar = bar(event_pets.len() as u64);
-    for slug in event_pets {
-        bar.set_message(slug.to_string());
+    let bar = bar(EVENT_FOLLOWERS.len() as u64);
+    for slug in EVENT_FOLLOWERS.iter() {
+        bar.set_message(slug);
         // Don't include a follower twice.
         if !data
             .followers
@@ -173,15 +89,15 @@ fn add_event_followers(guide: &OrnaAdminGuide, data: &mut CodexData) -> Result<(
 
 /// Refresh all output jsons. Fetches all codex and guide entities.
 /// Adds unlisted event monsters, bosses, raids and followers.
-pub fn refresh(guide: &OrnaAdminGuide) -> Result<OrnaData, Error> {
+pub fn refresh(guide: &OrnaAdminGuide, mode: CheckpointMode) -> Result<OrnaData, Error> {
     let mut data = OrnaData {
         codex: CodexData {
-            items: crate::codex::fetch::items(guide)?,
-            raids: crate::codex::fetch::raids(guide)?,
-            monsters: crate::codex::fetch::monsters(guide)?,
-            bosses: crate::codex::fetch::bosses(guide)?,
-            skills: crate::codex::fetch::skills(guide)?,
-            followers: crate::codex::fetch::followers(guide)?,
+            items: crate::codex::fetch::items(guide, mode)?,
+            raids: crate::codex::fetch::raids(guide, mode)?,
+            monsters: crate::codex::fetch::monsters(guide, mode)?,
+            bosses: crate::codex::fetch::bosses(guide, mode)?,
+            skills: crate::codex::fetch::skills(guide, mode)?,
+            followers: crate::codex::fetch::followers(guide, mode)?,
         },
         guide: GuideData {
             items: crate::guide::fetch::items(guide)?,
@@ -308,15 +224,19 @@ pub fn refresh_guide_skills(guide: &OrnaAdminGuide, data: OrnaData) -> Result<Or
 }
 
 /// Refresh all codex output jsons. Fetches all codex entities.
-pub fn refresh_codex(guide: &OrnaAdminGuide, guide_data: GuideData) -> Result<OrnaData, Error> {
+pub fn refresh_codex(
+    guide: &OrnaAdminGuide,
+    guide_data: GuideData,
+    mode: CheckpointMode,
+) -> Result<OrnaData, Error> {
     let mut data = OrnaData {
         codex: CodexData {
-            items: crate::codex::fetch::items(guide)?,
-            raids: crate::codex::fetch::raids(guide)?,
-            monsters: crate::codex::fetch::monsters(guide)?,
-            bosses: crate::codex::fetch::bosses(guide)?,
-            skills: crate::codex::fetch::skills(guide)?,
-            followers: crate::codex::fetch::followers(guide)?,
+            items: crate::codex::fetch::items(guide, mode)?,
+            raids: crate::codex::fetch::raids(guide, mode)?,
+            monsters: crate::codex::fetch::monsters(guide, mode)?,
+            bosses: crate::codex::fetch::bosses(guide, mode)?,
+            skills: crate::codex::fetch::skills(guide, mode)?,
+            followers: crate::codex::fetch::followers(guide, mode)?,
         },
         guide: guide_data,
     };
@@ -329,13 +249,17 @@ pub fn refresh_codex(guide: &OrnaAdminGuide, guide_data: GuideData) -> Result<Or
 }
 
 /// Refresh the codex's bosses.
-pub fn refresh_codex_bosses(guide: &OrnaAdminGuide, data: OrnaData) -> Result<OrnaData, Error> {
+pub fn refresh_codex_bosses(
+    guide: &OrnaAdminGuide,
+    data: OrnaData,
+    mode: CheckpointMode,
+) -> Result<OrnaData, Error> {
     let data = OrnaData {
         codex: CodexData {
             items: data.codex.items,
             raids: data.codex.raids,
             monsters: data.codex.monsters,
-            bosses: crate::codex::fetch::bosses(guide)?,
+            bosses: crate::codex::fetch::bosses(guide, mode)?,
             skills: data.codex.skills,
             followers: data.codex.followers,
         },
@@ -348,7 +272,11 @@ pub fn refresh_codex_bosses(guide: &OrnaAdminGuide, data: OrnaData) -> Result<Or
 }
 
 /// Refresh the codex's followers.
-pub fn refresh_codex_followers(guide: &OrnaAdminGuide, data: OrnaData) -> Result<OrnaData, Error> {
+pub fn refresh_codex_followers(
+    guide: &OrnaAdminGuide,
+    data: OrnaData,
+    mode: CheckpointMode,
+) -> Result<OrnaData, Error> {
     let data = OrnaData {
         codex: CodexData {
             items: data.codex.items,
@@ -356,7 +284,7 @@ pub fn refresh_codex_followers(guide: &OrnaAdminGuide, data: OrnaData) -> Result
             monsters: data.codex.monsters,
             bosses: data.codex.bosses,
             skills: data.codex.skills,
-            followers: crate::codex::fetch::followers(guide)?,
+            followers: crate::codex::fetch::followers(guide, mode)?,
         },
         guide: data.guide,
     };
@@ -367,10 +295,14 @@ pub fn refresh_codex_followers(guide: &OrnaAdminGuide, data: OrnaData) -> Result
 }
 
 /// Refresh the codex's items.
-pub fn refresh_codex_items(guide: &OrnaAdminGuide, data: OrnaData) -> Result<OrnaData, Error> {
+pub fn refresh_codex_items(
+    guide: &OrnaAdminGuide,
+    data: OrnaData,
+    mode: CheckpointMode,
+) -> Result<OrnaData, Error> {
     let data = OrnaData {
         codex: CodexData {
-            items: crate::codex::fetch::items(guide)?,
+            items: crate::codex::fetch::items(guide, mode)?,
             raids: data.codex.raids,
             monsters: data.codex.monsters,
             bosses: data.codex.bosses,
@@ -386,12 +318,16 @@ pub fn refresh_codex_items(guide: &OrnaAdminGuide, data: OrnaData) -> Result<Orn
 }
 
 /// Refresh the codex's monsters.
-pub fn refresh_codex_monsters(guide: &OrnaAdminGuide, data: OrnaData) -> Result<OrnaData, Error> {
+pub fn refresh_codex_monsters(
+    guide: &OrnaAdminGuide,
+    data: OrnaData,
+    mode: CheckpointMode,
+) -> Result<OrnaData, Error> {
     let data = OrnaData {
         codex: CodexData {
             items: data.codex.items,
             raids: data.codex.raids,
-            monsters: crate::codex::fetch::monsters(guide)?,
+            monsters: crate::codex::fetch::monsters(guide, mode)?,
             bosses: data.codex.bosses,
             skills: data.codex.skills,
             followers: data.codex.followers,
@@ -405,11 +341,15 @@ pub fn refresh_codex_monsters(guide: &OrnaAdminGuide, data: OrnaData) -> Result<
 }
 
 /// Refresh the codex's raids.
-pub fn refresh_codex_raids(guide: &OrnaAdminGuide, data: OrnaData) -> Result<OrnaData, Error> {
+pub fn refresh_codex_raids(
+    guide: &OrnaAdminGuide,
+    data: OrnaData,
+    mode: CheckpointMode,
+) -> Result<OrnaData, Error> {
     let data = OrnaData {
         codex: CodexData {
             items: data.codex.items,
-            raids: crate::codex::fetch::raids(guide)?,
+            raids: crate::codex::fetch::raids(guide, mode)?,
             monsters: data.codex.monsters,
             bosses: data.codex.bosses,
             skills: data.codex.skills,
@@ -424,14 +364,18 @@ pub fn refresh_codex_raids(guide: &OrnaAdminGuide, data: OrnaData) -> Result<Orn
 }
 
 /// Refresh the codex's skills.
-pub fn refresh_codex_skills(guide: &OrnaAdminGuide, data: OrnaData) -> Result<OrnaData, Error> {
+pub fn refresh_codex_skills(
+    guide: &OrnaAdminGuide,
+    data: OrnaData,
+    mode: CheckpointMode,
+) -> Result<OrnaData, Error> {
     let data = OrnaData {
         codex: CodexData {
             items: data.codex.items,
             raids: data.codex.raids,
             monsters: data.codex.monsters,
             bosses: data.codex.bosses,
-            skills: crate::codex::fetch::skills(guide)?,
+            skills: crate::codex::fetch::skills(guide, mode)?,
             followers: data.codex.followers,
         },
         guide: data.guide,
@@ -442,6 +386,23 @@ pub fn refresh_codex_skills(guide: &OrnaAdminGuide, data: OrnaData) -> Result<Or
     Ok(data)
 }
 
+/// Refresh the codex output jsons incrementally.
+/// Only the codex lists are fetched, along with the pages of entities that were added or removed
+/// since the last refresh. Entities that were already fetched are carried over untouched, which
+/// means that changes to their stats are missed. Guide entities are left untouched.
+pub fn refresh_incremental(guide: &OrnaAdminGuide, data: OrnaData) -> Result<OrnaData, Error> {
+    let mut data = OrnaData {
+        codex: crate::codex::fetch::incremental(guide, data.codex)?,
+        guide: data.guide,
+    };
+    add_unlisted_monsters(guide, &mut data.codex)?;
+    add_event_followers(guide, &mut data.codex)?;
+
+    data.save_to("data/current_entries")?;
+
+    Ok(data)
+}
+
 /// Iterate over all of the guide entries and fetch every corresponding entity from the codex that
 /// we have the URI for.
 pub fn fetch_all_matches_from_guide(
@@ -529,12 +490,76 @@ pub fn fetch_all_matches_from_guide(
     Ok(data)
 }
 
+/// Quote a CSV field if it contains a comma, a double quote or a newline.
+/// Double quotes inside the field are doubled.
+pub(crate) fn csv_field(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+/// Write the guide items along with their base stats as CSV to `writer`.
+/// Rows are written one item at a time, without building the whole CSV in memory.
+pub fn write_items_csv<W: Write>(data: &OrnaData, mut writer: W) -> Result<(), Error> {
+    writeln!(
+        writer,
+        "id,name,tier,type,attack,magic,hp,mana,defense,resistance,ward,foresight,element"
+    )?;
+    for item in data.guide.items.items.iter() {
+        let type_ = data
+            .guide
+            .static_
+            .item_types
+            .iter()
+            .find(|type_| type_.id == item.type_)
+            .map(|type_| type_.name.as_str())
+            .unwrap_or_default();
+        let element = item
+            .element
+            .and_then(|id| data.guide.static_.elements.find_element_by_id(id))
+            .map(|element| element.name.as_str())
+            .unwrap_or_default();
+        writeln!(
+            writer,
+            "{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            item.id,
+            csv_field(&item.name),
+            item.tier,
+            csv_field(type_),
+            item.attack,
+            item.magic,
+            item.hp,
+            item.mana,
+            item.defense,
+            item.resistance,
+            item.ward,
+            item.foresight,
+            csv_field(element),
+        )?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Export the guide items along with their base stats to `output/items.csv`.
+pub fn items_csv(data: &OrnaData) -> Result<(), Error> {
+    std::fs::create_dir_all("output")?;
+    write_items_csv(data, BufWriter::new(File::create("output/items.csv")?))
+}
+
 /// Execute a CLI subcommand on outputs.
 fn cli_refresh(
     command: cli::json::RefreshCmd,
     guide: &OrnaAdminGuide,
     data: OrnaData,
 ) -> Result<(), Error> {
+    let mode = if command.resume {
+        CheckpointMode::Resume
+    } else {
+        CheckpointMode::Fresh
+    };
     match command.c {
         Some(refresh_cmd) => match refresh_cmd {
             cli::json::Refresh::Guide(guide_cmd) => match guide_cmd.c {
@@ -546,16 +571,17 @@ fn cli_refresh(
                 None => refresh_guide(guide, data.codex)?,
             },
             cli::json::Refresh::Codex(codex_cmd) => match codex_cmd.c {
-                Some(RefreshCodex::Bosses) => refresh_codex_bosses(guide, data)?,
-                Some(RefreshCodex::Followers) => refresh_codex_followers(guide, data)?,
-                Some(RefreshCodex::Items) => refresh_codex_items(guide, data)?,
-                Some(RefreshCodex::Monsters) => refresh_codex_monsters(guide, data)?,
-                Some(RefreshCodex::Raids) => refresh_codex_raids(guide, data)?,
-                Some(RefreshCodex::Skills) => refresh_codex_skills(guide, data)?,
-                None => refresh_codex(guide, data.guide)?,
+                Some(RefreshCodex::Bosses) => refresh_codex_bosses(guide, data, mode)?,
+                Some(RefreshCodex::Followers) => refresh_codex_followers(guide, data, mode)?,
+                Some(RefreshCodex::Items) => refresh_codex_items(guide, data, mode)?,
+                Some(RefreshCodex::Monsters) => refresh_codex_monsters(guide, data, mode)?,
+                Some(RefreshCodex::Raids) => refresh_codex_raids(guide, data, mode)?,
+                Some(RefreshCodex::Skills) => refresh_codex_skills(guide, data, mode)?,
+                None => refresh_codex(guide, data.guide, mode)?,
             },
+            cli::json::Refresh::Incremental => refresh_incremental(guide, data)?,
         },
-        None => refresh(guide)?,
+        None => refresh(guide, mode)?,
     };
     Ok(())
 }
@@ -569,6 +595,133 @@ where
         cli::json::Command::FetchAllMatchesFromGuide => {
             fetch_all_matches_from_guide(guide, data()?).map(|_| ())
         }
-        cli::json::Command::Refresh(cmd) => cli_refresh(cmd, guide, data()?),
+        // Load the codex leniently so that fields the codex added since are reported.
+        cli::json::Command::Refresh(cmd) => cli_refresh(
+            cmd,
+            guide,
+            OrnaData::load_from_lenient("data/current_entries")?,
+        ),
+        cli::json::Command::ItemsCsv => items_csv(&data()?),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ornaguide_rs::{
+        codex::{CodexItem, CodexMonster, ItemDroppedBy},
+        data::{CodexData, OrnaData},
+        guide::{Element, ItemType},
+        items::admin::AdminItem,
+        with_defaults,
+    };
+
+    use crate::output::{unlisted_monster_uris, write_items_csv};
+
+    /// Split a CSV line into its fields, unquoting them.
+    fn parse_csv_line(line: &str) -> Vec<String> {
+        let mut fields = vec![String::new()];
+        let mut quoted = false;
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' if quoted && chars.peek() == Some(&'"') => {
+                    chars.next();
+                    fields.last_mut().unwrap().push('"');
+                }
+                '"' => quoted = !quoted,
+                ',' if !quoted => fields.push(String::new()),
+                c => fields.last_mut().unwrap().push(c),
+            }
+        }
+        fields
+    }
+
+    #[test]
+    fn test_items_csv() {
+        let mut data = OrnaData::default();
+        data.guide.static_.item_types = vec![ItemType {
+            id: 2,
+            name: "Weapon".to_string(),
+        }];
+        data.guide.static_.elements = vec![Element {
+            id: 3,
+            name: "Fire".to_string(),
+        }];
+        data.guide.items.items = vec![
+            with_defaults!(AdminItem {
+                id: 1,
+                name: "Sword".to_string(),
+                tier: 2,
+                type_: 2,
+                attack: 12,
+                element: Some(3),
+            }),
+            with_defaults!(AdminItem {
+                id: 4,
+                name: "Bow, \"Long\"".to_string(),
+                tier: 5,
+                type_: 2,
+                ward: -3,
+            }),
+        ];
+
+        let mut csv = Vec::new();
+        write_items_csv(&data, &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let rows = csv.lines().map(parse_csv_line).collect::<Vec<_>>();
+
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0][0], "id");
+        assert_eq!(rows[0][12], "element");
+        assert_eq!(rows[1].join("|"), "1|Sword|2|Weapon|12|0|0|0|0|0|0|0|Fire");
+        assert_eq!(
+            rows[2].join("|"),
+            "4|Bow, \"Long\"|5|Weapon|0|0|0|0|0|0|-3|0|"
+        );
+    }
+
+    #[test]
+    fn test_unlisted_monster_uris() {
+        let dropped_by = |uri: &str| ItemDroppedBy {
+            name: uri.to_string(),
+            uri: uri.to_string(),
+            icon: String::new(),
+        };
+        let mut data = CodexData::default();
+        data.monsters.monsters.push(CodexMonster {
+            slug: "slime".to_string(),
+            name: "Slime".to_string(),
+            icon: String::new(),
+            events: vec![],
+            family: String::new(),
+            rarity: String::new(),
+            tier: 1,
+            abilities: vec![],
+            drops: vec![],
+            spawns: vec![],
+        });
+        data.items.items.push(CodexItem {
+            slug: "goo".to_string(),
+            dropped_by: vec![
+                dropped_by("/codex/monsters/slime/"),
+                dropped_by("/codex/monsters/ghost/"),
+                // Malformed URIs are skipped.
+                dropped_by("/codex/items/sword/"),
+            ],
+            ..CodexItem::default()
+        });
+        data.items.items.push(CodexItem {
+            slug: "ectoplasm".to_string(),
+            dropped_by: vec![dropped_by("/codex/monsters/ghost/")],
+            ..CodexItem::default()
+        });
+
+        assert_eq!(
+            unlisted_monster_uris(&data),
+            vec![
+                "/codex/monsters/elite-balor-flame/".to_string(),
+                "/codex/monsters/ghost/".to_string(),
+            ]
+        );
     }
 }
@@ -0,0 +1,335 @@
+use itertools::Itertools;
+use ornaguide_rs::{data::OrnaData, error::Error};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::cli::{self, query::Kind};
+
+/// A comparison operator in a query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    /// `==`
+    Eq,
+    /// `!=`
+    Ne,
+    /// `<`
+    Lt,
+    /// `<=`
+    Le,
+    /// `>`
+    Gt,
+    /// `>=`
+    Ge,
+    /// `~`: Case-insensitive substring for strings, element membership for arrays.
+    Contains,
+}
+
+/// A single `field op value` comparison.
+#[derive(Debug, Clone, PartialEq)]
+struct Comparison {
+    /// Path to the field, split on dots (`stats.attack` becomes `["stats", "attack"]`).
+    path: Vec<String>,
+    /// The operator to compare with.
+    operator: Operator,
+    /// The value to compare to.
+    value: Value,
+}
+
+/// A query over entities.
+/// The query is in disjunctive normal form: it matches if any of the `Vec<Comparison>` has all of
+/// its comparisons match.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    /// Groups of comparisons joined by `&&`, themselves joined by `||`.
+    alternatives: Vec<Vec<Comparison>>,
+}
+
+/// Split an expression into tokens.
+/// Whitespace separates tokens, except within double quotes. Operators need not be surrounded by
+/// whitespace.
+fn tokenize(expression: &str) -> Result<Vec<String>, Error> {
+    let mut tokens = Vec::new();
+    let mut chars = expression.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '"' => {
+                let mut token = String::from('"');
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => token.push(c),
+                        None => {
+                            return Err(Error::Misc(format!(
+                                "Unterminated string in query '{}'",
+                                expression
+                            )))
+                        }
+                    }
+                }
+                tokens.push(token);
+            }
+            '=' | '!' | '<' | '>' | '&' | '|' | '~' => {
+                let mut token = String::from(c);
+                if let Some(next) = chars.next_if(|next| matches!(next, '=' | '&' | '|')) {
+                    token.push(next);
+                }
+                tokens.push(token);
+            }
+            c => {
+                let mut token = String::from(c);
+                while let Some(c) = chars.next_if(|c| {
+                    !c.is_whitespace()
+                        && !matches!(c, '"' | '=' | '!' | '<' | '>' | '&' | '|' | '~')
+                }) {
+                    token.push(c);
+                }
+                tokens.push(token);
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Parse the value of a comparison.
+/// Quoted tokens are strings. Otherwise, try `null`, booleans and numbers before falling back to a
+/// string.
+fn parse_value(token: &str) -> Value {
+    if let Some(s) = token.strip_prefix('"') {
+        Value::String(s.to_string())
+    } else if token == "null" {
+        Value::Null
+    } else if let Ok(b) = token.parse::<bool>() {
+        Value::Bool(b)
+    } else if let Ok(n) = token.parse::<i64>() {
+        Value::from(n)
+    } else if let Ok(n) = token.parse::<f64>() {
+        Value::from(n)
+    } else {
+        Value::String(token.to_string())
+    }
+}
+
+impl Query {
+    /// Parse a query.
+    /// A query is a list of comparisons `field op value`, joined by `&&` or `||`. `&&` has
+    /// precedence over `||`. Parentheses are not supported.
+    /// Supported operators are `==`, `!=`, `<`, `<=`, `>`, `>=` and `~` (contains).
+    ///
+    /// Examples:
+    ///   - `tier == 10 && element == 1`
+    ///   - `name ~ "sword" || name ~ dagger`
+    ///   - `stats.attack >= 100`
+    pub fn parse(expression: &str) -> Result<Self, Error> {
+        let tokens = tokenize(expression)?;
+        let mut alternatives = vec![vec![]];
+        let mut it = tokens.iter();
+
+        loop {
+            let field = it.next().ok_or_else(|| {
+                Error::Misc(format!("Expected a field name in query '{}'", expression))
+            })?;
+            let operator = match it.next().map(String::as_str) {
+                Some("==") => Operator::Eq,
+                Some("!=") => Operator::Ne,
+                Some("<") => Operator::Lt,
+                Some("<=") => Operator::Le,
+                Some(">") => Operator::Gt,
+                Some(">=") => Operator::Ge,
+                Some("~") => Operator::Contains,
+                x => {
+                    return Err(Error::Misc(format!(
+                        "Expected an operator after '{}' in query '{}', got {:?}",
+                        field, expression, x
+                    )))
+                }
+            };
+            let value = it.next().ok_or_else(|| {
+                Error::Misc(format!(
+                    "Expected a value after '{}' in query '{}'",
+                    field, expression
+                ))
+            })?;
+
+            alternatives.last_mut().unwrap().push(Comparison {
+                path: field.split('.').map(str::to_string).collect(),
+                operator,
+                value: parse_value(value),
+            });
+
+            match it.next().map(String::as_str) {
+                None => break,
+                Some("&&") => {}
+                Some("||") => alternatives.push(vec![]),
+                Some(x) => {
+                    return Err(Error::Misc(format!(
+                        "Expected '&&' or '||' in query '{}', got '{}'",
+                        expression, x
+                    )))
+                }
+            }
+        }
+
+        Ok(Self { alternatives })
+    }
+
+    /// Check whether the given serialized entity matches the query.
+    pub fn matches(&self, entity: &Value) -> bool {
+        self.alternatives.iter().any(|comparisons| {
+            comparisons
+                .iter()
+                .all(|comparison| comparison.matches(entity))
+        })
+    }
+
+    /// Return the entities that match the query.
+    pub fn filter<'a, T>(&self, entities: &'a [T]) -> Result<Vec<&'a T>, Error>
+    where
+        T: Serialize,
+    {
+        entities
+            .iter()
+            .map(|entity| Ok((entity, serde_json::to_value(entity)?)))
+            .filter_ok(|(_, value)| self.matches(value))
+            .map_ok(|(entity, _)| entity)
+            .collect()
+    }
+}
+
+impl Comparison {
+    /// Check whether the comparison holds for the given serialized entity.
+    /// A missing field is treated as `null`, since empty fields may be omitted when serializing.
+    fn matches(&self, entity: &Value) -> bool {
+        let field = self
+            .path
+            .iter()
+            .try_fold(entity, |value, key| value.get(key))
+            .unwrap_or(&Value::Null);
+
+        match self.operator {
+            Operator::Eq => values_eq(field, &self.value),
+            Operator::Ne => !values_eq(field, &self.value),
+            Operator::Contains => match field {
+                Value::Array(values) => values.iter().any(|value| values_eq(value, &self.value)),
+                Value::String(s) => match &self.value {
+                    Value::String(needle) => s.to_lowercase().contains(&needle.to_lowercase()),
+                    needle => s.contains(&needle.to_string()),
+                },
+                _ => false,
+            },
+            Operator::Lt | Operator::Le | Operator::Gt | Operator::Ge => {
+                match (field.as_f64(), self.value.as_f64()) {
+                    (Some(a), Some(b)) => match self.operator {
+                        Operator::Lt => a < b,
+                        Operator::Le => a <= b,
+                        Operator::Gt => a > b,
+                        _ => a >= b,
+                    },
+                    _ => false,
+                }
+            }
+        }
+    }
+}
+
+/// Compare two values for equality. Numbers are compared as floats so that `10` matches `10.0`.
+fn values_eq(a: &Value, b: &Value) -> bool {
+    match (a.as_f64(), b.as_f64()) {
+        (Some(a), Some(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Print the name and id (or slug) of each matching entity.
+fn print_matches<T>(query: &Query, entities: &[T]) -> Result<(), Error>
+where
+    T: Serialize,
+{
+    let matches = query.filter(entities)?;
+    for entity in matches.iter() {
+        let value = serde_json::to_value(entity)?;
+        let name = value.get("name").and_then(Value::as_str).unwrap_or("");
+        match (value.get("id"), value.get("slug").and_then(Value::as_str)) {
+            (Some(id), _) => println!("\t- {:30} (#{})", name, id),
+            (None, Some(slug)) => println!("\t- {:30} ({})", name, slug),
+            (None, None) => println!("\t- {}", name),
+        }
+    }
+    println!("{} matches", matches.len());
+    Ok(())
+}
+
+/// Execute a query on the data.
+pub fn cli(command: cli::query::Command, data: OrnaData) -> Result<(), Error> {
+    let query = Query::parse(&command.expression)?;
+    match command.kind {
+        Kind::Items => print_matches(&query, &data.guide.items.items),
+        Kind::Monsters => print_matches(&query, &data.guide.monsters.monsters),
+        Kind::Pets => print_matches(&query, &data.guide.pets.pets),
+        Kind::Skills => print_matches(&query, &data.guide.skills.skills),
+        Kind::CodexItems => print_matches(&query, &data.codex.items.items),
+        Kind::CodexMonsters => print_matches(&query, &data.codex.monsters.monsters),
+        Kind::CodexBosses => print_matches(&query, &data.codex.bosses.bosses),
+        Kind::CodexRaids => print_matches(&query, &data.codex.raids.raids),
+        Kind::CodexSkills => print_matches(&query, &data.codex.skills.skills),
+        Kind::CodexFollowers => print_matches(&query, &data.codex.followers.followers),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ornaguide_rs::{items::admin::AdminItem, with_defaults};
+
+    use crate::query::Query;
+
+    fn item(id: u32, name: &str, tier: u8, element: Option<u32>, causes: Vec<u32>) -> AdminItem {
+        with_defaults!(AdminItem {
+            id,
+            name: name.to_string(),
+            tier,
+            element,
+            causes,
+        })
+    }
+
+    fn items() -> Vec<AdminItem> {
+        vec![
+            item(1, "Fire Sword", 10, Some(1), vec![3]),
+            item(2, "Water Sword", 10, Some(2), vec![]),
+            item(3, "Fire Dagger", 8, Some(1), vec![3, 4]),
+            item(4, "Stick", 1, None, vec![]),
+        ]
+    }
+
+    fn ids(query: &str) -> Vec<u32> {
+        let items = items();
+        Query::parse(query)
+            .unwrap()
+            .filter(&items)
+            .unwrap()
+            .iter()
+            .map(|item| item.id)
+            .collect()
+    }
+
+    #[test]
+    fn test_and() {
+        assert_eq!(ids("tier == 10 && element == 1"), vec![1]);
+        assert_eq!(ids("tier>=8&&name~\"fire\""), vec![1, 3]);
+    }
+
+    #[test]
+    fn test_or() {
+        assert_eq!(ids("element == null || tier < 9"), vec![3, 4]);
+        assert_eq!(ids("causes ~ 4 || name == \"Water Sword\""), vec![2, 3]);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(Query::parse("tier 10").is_err());
+        assert!(Query::parse("tier == 10 &&").is_err());
+        assert!(Query::parse("name == \"unterminated").is_err());
+    }
+}
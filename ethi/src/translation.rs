@@ -1,8 +1,128 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+};
+
 use ornaguide_rs::{
-    codex::translation::LocaleDB, data::OrnaData, error::Error, guide::OrnaAdminGuide,
+    codex::{
+        translation::{LocaleDB, LocaleStrings, UntranslatedString},
+        Codex, CodexBoss, CodexFollower, CodexItem, CodexMonster, CodexRaid, CodexSkill,
+    },
+    data::OrnaData,
+    error::Error,
+    guide::OrnaAdminGuide,
 };
 
-use crate::cli;
+use crate::{
+    cli::{self, translation::Kind},
+    output::csv_field,
+};
+
+/// A single codex entity, fetched in some locale.
+pub enum TranslatedEntity {
+    Item(CodexItem),
+    Monster(CodexMonster),
+    Boss(CodexBoss),
+    Raid(CodexRaid),
+    Skill(CodexSkill),
+    Follower(CodexFollower),
+}
+
+impl TranslatedEntity {
+    /// Add the strings of the entity to `strings`.
+    fn add_to(self, strings: &mut LocaleStrings, data: &OrnaData) -> Result<(), Error> {
+        match self {
+            TranslatedEntity::Item(item) => {
+                strings.add_items(vec![item]);
+                Ok(())
+            }
+            TranslatedEntity::Monster(monster) => {
+                strings.add_monsters_events_families_and_rarities(vec![monster], data)
+            }
+            TranslatedEntity::Boss(boss) => {
+                strings.add_bosses_events_families_and_rarities(vec![boss], data)
+            }
+            TranslatedEntity::Raid(raid) => strings.add_raids_and_events(vec![raid], data),
+            TranslatedEntity::Skill(skill) => strings.add_skills_and_statuses(vec![skill], data),
+            TranslatedEntity::Follower(follower) => {
+                strings.add_followers_and_events(vec![follower], data)
+            }
+        }
+    }
+}
+
+/// Fetch the strings of a single entity in each of the locales of `locales`.
+/// `fetch` is called with each locale and must return the entity in that locale.
+fn entity_translations<F>(
+    locales: &LocaleDB,
+    data: &OrnaData,
+    mut fetch: F,
+) -> Result<LocaleDB, Error>
+where
+    F: FnMut(&str) -> Result<TranslatedEntity, Error>,
+{
+    let mut ret = LocaleDB::default();
+    for locale in locales.locales.keys() {
+        let mut strings = LocaleStrings {
+            locale: locale.clone(),
+            ..Default::default()
+        };
+        fetch(locale)?.add_to(&mut strings, data)?;
+        ret.locales.insert(locale.clone(), strings);
+    }
+    Ok(ret)
+}
+
+/// Fetch the entity of the given kind and slug from the codex, in the given locale.
+fn fetch_entity(
+    guide: &OrnaAdminGuide,
+    kind: Kind,
+    slug: &str,
+    locale: &str,
+) -> Result<TranslatedEntity, Error> {
+    println!("Fetching {} in locale {}", slug, locale);
+    Ok(match kind {
+        Kind::Item => TranslatedEntity::Item(guide.codex_fetch_item_with_locale(slug, locale)?),
+        Kind::Monster => {
+            TranslatedEntity::Monster(guide.codex_fetch_monster_with_locale(slug, locale)?)
+        }
+        Kind::Boss => TranslatedEntity::Boss(guide.codex_fetch_boss_with_locale(slug, locale)?),
+        Kind::Raid => TranslatedEntity::Raid(guide.codex_fetch_raid_with_locale(slug, locale)?),
+        Kind::Skill => TranslatedEntity::Skill(guide.codex_fetch_skill_with_locale(slug, locale)?),
+        Kind::Follower => {
+            TranslatedEntity::Follower(guide.codex_fetch_follower_with_locale(slug, locale)?)
+        }
+    })
+}
+
+/// List the strings of `locale` that are missing or identical to the English ones.
+/// Both `locale` and `en` must be in `locales`.
+fn todo(locales: &LocaleDB, locale: &str) -> Result<Vec<UntranslatedString>, Error> {
+    let strings = |locale: &str| {
+        locales
+            .locales
+            .get(locale)
+            .ok_or_else(|| Error::Misc(format!("Locale {} is not in the locale database", locale)))
+    };
+    Ok(strings(locale)?.untranslated(strings("en")?))
+}
+
+/// Write the untranslated strings as CSV to `writer`.
+fn write_todo_csv<W: Write>(todo: &[UntranslatedString], mut writer: W) -> Result<(), Error> {
+    writeln!(writer, "category,key,field,english")?;
+    for string in todo.iter() {
+        writeln!(
+            writer,
+            "{},{},{},{}",
+            string.category,
+            csv_field(&string.key),
+            string.field,
+            csv_field(&string.english)
+        )?;
+    }
+    writer.flush()?;
+    Ok(())
+}
 
 /// Execute a CLI subcommand on translations.
 pub fn cli(
@@ -12,14 +132,147 @@ pub fn cli(
     mut locales: LocaleDB,
 ) -> Result<(), Error> {
     match command {
-        cli::translation::Command::Missing => {
-            let missing = crate::codex::fetch::missing_translations(guide, &data, &locales)?;
+        cli::translation::Command::Missing(cmd) => {
+            let missing =
+                crate::codex::fetch::missing_translations(guide, &data, &locales, &cmd.locales)?;
             locales.merge_with(missing);
             locales.save_to("data/current_entries/i18n")
         }
         cli::translation::Command::Fetch(locale) => {
-            crate::codex::fetch::translations(guide, &data, &locale.locale)?
-                .save_to(&format!("data/current_entries/i18n/{}.json", &locale.locale))
+            crate::codex::fetch::translations(guide, &data, &locale.locale)?.save_to(&format!(
+                "data/current_entries/i18n/{}.json",
+                &locale.locale
+            ))
+        }
+        cli::translation::Command::FetchEntity(entity) => {
+            let fetched = entity_translations(&locales, &data, |locale| {
+                fetch_entity(guide, entity.kind, &entity.slug, locale)
+            })?;
+            locales.merge_with(fetched);
+            locales.save_to("data/current_entries/i18n")
+        }
+        cli::translation::Command::Todo(cmd) => {
+            let todo = todo(&locales, &cmd.locale)?;
+            let (mut writer, path): (Box<dyn Write>, &str) = match cmd.output.as_ref() {
+                Some(path) => (Box::new(BufWriter::new(File::create(path)?)), path),
+                None => (Box::new(std::io::stdout().lock()), "<stdout>"),
+            };
+            if cmd.csv {
+                write_todo_csv(&todo, writer)
+            } else {
+                serde_json::to_writer_pretty(&mut writer, &todo)
+                    .map_err(|err| Error::SerdeJson(err, path.to_string()))?;
+                writeln!(writer)?;
+                writer.flush()?;
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ornaguide_rs::{
+        codex::{
+            translation::{ItemTranslation, LocaleDB, LocaleStrings, UntranslatedString},
+            CodexItem,
+        },
+        data::OrnaData,
+    };
+
+    use crate::translation::{entity_translations, todo, write_todo_csv, TranslatedEntity};
+
+    #[test]
+    fn test_entity_translations() {
+        let mut locales = LocaleDB::default();
+        for locale in ["fr", "de"] {
+            locales.locales.insert(
+                locale.to_string(),
+                LocaleStrings {
+                    locale: locale.to_string(),
+                    ..Default::default()
+                },
+            );
         }
+
+        let fetched = entity_translations(&locales, &OrnaData::default(), |locale| {
+            Ok(TranslatedEntity::Item(CodexItem {
+                slug: "sword".to_string(),
+                name: format!("Sword ({})", locale),
+                description: format!("A sword ({})", locale),
+                tier: 1,
+                ..CodexItem::default()
+            }))
+        })
+        .unwrap();
+        locales.merge_with(fetched);
+
+        assert_eq!(locales.item_name("fr", "sword"), Some("Sword (fr)"));
+        assert_eq!(locales.item_name("de", "sword"), Some("Sword (de)"));
+        assert_eq!(
+            locales.item_description("de", "sword"),
+            Some("A sword (de)")
+        );
+    }
+
+    #[test]
+    fn test_todo() {
+        let item = |name: &str, description: &str| ItemTranslation {
+            name: name.to_string(),
+            description: description.to_string(),
+        };
+        let mut english = LocaleStrings {
+            locale: "en".to_string(),
+            ..Default::default()
+        };
+        english
+            .items
+            .insert("sword".to_string(), item("Sword", "A sword."));
+        english
+            .items
+            .insert("bow".to_string(), item("Bow", "A bow."));
+        english
+            .statuses
+            .insert("Burning".to_string(), "Burning".to_string());
+        let mut french = LocaleStrings {
+            locale: "fr".to_string(),
+            ..Default::default()
+        };
+        french
+            .items
+            .insert("sword".to_string(), item("Épée", "A sword."));
+        french
+            .statuses
+            .insert("Burning".to_string(), "Brûlure".to_string());
+        let mut locales = LocaleDB::default();
+        locales.locales.insert("en".to_string(), english);
+        locales.locales.insert("fr".to_string(), french);
+
+        let untranslated = |key: &str, field, english: &str| UntranslatedString {
+            category: "items",
+            key: key.to_string(),
+            field,
+            english: english.to_string(),
+        };
+        let list = todo(&locales, "fr").unwrap();
+        assert_eq!(
+            list,
+            vec![
+                untranslated("bow", "name", "Bow"),
+                untranslated("bow", "description", "A bow."),
+                untranslated("sword", "description", "A sword."),
+            ]
+        );
+        assert!(todo(&locales, "de").is_err());
+
+        let mut csv = Vec::new();
+        write_todo_csv(&list, &mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "category,key,field,english\n\
+             items,bow,name,Bow\n\
+             items,bow,description,A bow.\n\
+             items,sword,description,A sword.\n"
+        );
     }
 }
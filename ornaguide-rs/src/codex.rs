@@ -11,27 +11,30 @@ pub(crate) mod monster;
 pub(crate) mod skill;
 
 pub mod fetch;
+pub mod lenient;
 pub mod translation;
 
 pub use follower::{
     Ability as FollowerAbility, Follower as CodexFollower, Followers as CodexFollowers,
 };
 pub use item::{
-    Ability as ItemAbility, DroppedBy as ItemDroppedBy, Element as CodexElement, Item as CodexItem,
-    ItemStatusEffects, Items as CodexItems, Stats as ItemStats,
-    UpgradeMaterial as ItemUpgradeMaterial,
+    weapon_inflicted_statuses, Ability as ItemAbility, DroppedBy as ItemDroppedBy,
+    Element as CodexElement, Item as CodexItem, ItemStatusEffects, Items as CodexItems,
+    Stats as ItemStats, UpgradeMaterial as ItemUpgradeMaterial,
 };
 pub use monster::{
     Ability as MonsterAbility, Boss as CodexBoss, Bosses as CodexBosses, Drop as MonsterDrop,
     Monster as CodexMonster, Monsters as CodexMonsters, Raid as CodexRaid, Raids as CodexRaids,
     Tag,
 };
-pub use skill::{CodexSkill, CodexSkills, SkillStatusEffect, SkillStatusEffects, SkillSummon};
+pub use skill::{
+    CodexSkill, CodexSkills, SkillBuffedBy, SkillStatusEffect, SkillStatusEffects, SkillSummon,
+};
 
 #[derive(Debug)]
 pub struct SkillEntry {
     pub name: String,
-    pub tier: u32,
+    pub tier: u8,
     pub uri: String,
 }
 
@@ -39,7 +42,7 @@ pub struct SkillEntry {
 pub struct MonsterEntry {
     pub name: String,
     pub family: String,
-    pub tier: u32,
+    pub tier: u8,
     pub uri: String,
 }
 
@@ -47,28 +50,28 @@ pub struct MonsterEntry {
 pub struct BossEntry {
     pub name: String,
     pub family: String,
-    pub tier: u32,
+    pub tier: u8,
     pub uri: String,
 }
 
 #[derive(Debug)]
 pub struct RaidEntry {
     pub name: String,
-    pub tier: u32,
+    pub tier: u8,
     pub uri: String,
 }
 
 #[derive(Debug)]
 pub struct ItemEntry {
     pub name: String,
-    pub tier: u32,
+    pub tier: u8,
     pub uri: String,
 }
 
 #[derive(Debug)]
 pub struct FollowerEntry {
     pub name: String,
-    pub tier: u32,
+    pub tier: u8,
     pub uri: String,
 }
 
@@ -215,4 +218,98 @@ pub trait Codex {
         follower_name: &str,
         locale: &str,
     ) -> Result<CodexFollower, Error>;
+
+    /// Retrieve the details about a monster, boss or raid from the orna codex, given its URI.
+    /// The URI must match `/codex/{monsters,bosses,raids}/{slug}/`. The trailing slash is optional.
+    fn codex_fetch_generic_monster_by_uri(
+        &self,
+        uri: &str,
+    ) -> Result<CodexGenericMonsterOwned, Error> {
+        let (kind, slug) = parse_generic_monster_uri(uri)?;
+        match kind {
+            "monsters" => self
+                .codex_fetch_monster(slug)
+                .map(CodexGenericMonsterOwned::Monster),
+            "bosses" => self
+                .codex_fetch_boss(slug)
+                .map(CodexGenericMonsterOwned::Boss),
+            _ => self
+                .codex_fetch_raid(slug)
+                .map(CodexGenericMonsterOwned::Raid),
+        }
+    }
+}
+
+/// A monster, boss or raid fetched from the codex.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum CodexGenericMonsterOwned {
+    /// A regular monster.
+    Monster(CodexMonster),
+    /// A boss.
+    Boss(CodexBoss),
+    /// A raid.
+    Raid(CodexRaid),
+}
+
+impl CodexGenericMonsterOwned {
+    /// Return the events in which the monster appears.
+    pub fn events(&self) -> &[String] {
+        match self {
+            CodexGenericMonsterOwned::Monster(x) => &x.events,
+            CodexGenericMonsterOwned::Boss(x) => &x.events,
+            CodexGenericMonsterOwned::Raid(x) => &x.events,
+        }
+    }
+}
+
+/// Split the URI of a codex monster, boss or raid into its kind (`monsters`, `bosses` or `raids`)
+/// and its slug.
+pub(crate) fn parse_generic_monster_uri(uri: &str) -> Result<(&str, &str), Error> {
+    let invalid = || {
+        Error::InvalidField(
+            "codex URI".to_string(),
+            "uri".to_string(),
+            Some(uri.to_string()),
+        )
+    };
+    let path = uri.strip_prefix("/codex/").ok_or_else(invalid)?;
+    let path = path.strip_suffix('/').unwrap_or(path);
+    match path.split_once('/') {
+        Some((kind @ ("monsters" | "bosses" | "raids"), slug))
+            if !slug.is_empty() && !slug.contains('/') =>
+        {
+            Ok((kind, slug))
+        }
+        _ => Err(invalid()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::codex::parse_generic_monster_uri;
+
+    #[test]
+    fn test_parse_generic_monster_uri() {
+        assert_eq!(
+            parse_generic_monster_uri("/codex/monsters/bat/").unwrap(),
+            ("monsters", "bat")
+        );
+        assert_eq!(
+            parse_generic_monster_uri("/codex/bosses/dragon").unwrap(),
+            ("bosses", "dragon")
+        );
+        assert_eq!(
+            parse_generic_monster_uri("/codex/raids/yggdrasil/").unwrap(),
+            ("raids", "yggdrasil")
+        );
+        for uri in [
+            "/codex/items/sword/",
+            "/codex/monsters/",
+            "/codex/monsters",
+            "codex/monsters/bat/",
+            "/codex/monsters/bat/extra/",
+        ] {
+            assert!(parse_generic_monster_uri(uri).is_err(), "{}", uri);
+        }
+    }
 }
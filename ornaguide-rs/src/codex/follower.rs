@@ -18,7 +18,10 @@ pub struct Ability {
 }
 
 /// A follower on the codex.
+/// Follower pages on the codex do not show any combat stat. The behavior chances of `AdminPet`
+/// (`attack`, `heal`, `buff`, ...) and its cost are only available on the guide.
 #[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "strict-codex", serde(deny_unknown_fields))]
 pub struct Follower {
     /// The name of the follower.
     pub name: String,
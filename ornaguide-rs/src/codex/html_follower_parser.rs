@@ -113,7 +113,6 @@ fn parse_name_uri_icon_list(
 ) -> impl Iterator<Item = Result<(String, String, String), Error>> {
     iter_node
         .following_siblings()
-        .into_iter()
         .filter(|node| matches!(node.data(), NodeData::Element(_)))
         .map_while(|node| {
             if let NodeData::Element(ElementData {
@@ -218,3 +217,73 @@ pub fn parse_html_codex_follower_translation(
         abilities: vec![],
     })
 }
+
+#[cfg(test)]
+mod test {
+    use crate::codex::{html_follower_parser::parse_html_codex_follower, FollowerAbility};
+
+    static FOLLOWER_HTML: &str = r#"
+<html><body>
+<h1 class="herotext">Puppy</h1>
+<div class="codex-page">
+  <div class="codex-page-icon"><img src="https://playorna.com/static/img/followers/puppy.png"></div>
+  <div class="codex-page-description">A loyal companion.</div>
+  <div class="codex-page-description codex-page-description-highlight">Event: Summer / Winter</div>
+  <div class="codex-page-description">Rarity: Common</div>
+  <div class="codex-page-meta">Tier: &#9733;2</div>
+  <h4>Abilities:</h4>
+  <div class="drop">
+    <a href="/codex/spells/bite/"><img src="https://playorna.com/static/img/skills/bite.png"> Bite</a>
+  </div>
+</div>
+</body></html>
+"#;
+
+    #[test]
+    fn test_parse_follower() {
+        let follower = parse_html_codex_follower(FOLLOWER_HTML, "puppy".to_string()).unwrap();
+        assert_eq!(follower.name, "Puppy");
+        assert_eq!(follower.icon, "followers/puppy.png");
+        assert_eq!(follower.description, "A loyal companion.");
+        assert_eq!(follower.events, vec!["Summer", "Winter"]);
+        assert_eq!(follower.rarity, "Common");
+        assert_eq!(follower.tier, 2);
+        assert_eq!(
+            follower.abilities,
+            vec![FollowerAbility {
+                name: "Bite".to_string(),
+                uri: "/codex/spells/bite/".to_string(),
+                icon: "https://playorna.com/static/img/skills/bite.png".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_follower_multiple_abilities() {
+        let html = FOLLOWER_HTML.replace(
+            "</div>\n</div>\n</body>",
+            r#"</div>
+  <div class="drop">
+    <a href="/codex/spells/howl/"><img src="https://playorna.com/static/img/skills/howl.png"> Howl</a>
+  </div>
+  <div class="drop">
+    <a href="/codex/spells/fetch/"><img src="https://playorna.com/static/img/skills/fetch.png"> Fetch</a>
+  </div>
+</div>
+</body>"#,
+        );
+        let follower = parse_html_codex_follower(&html, "puppy".to_string()).unwrap();
+        assert_eq!(
+            follower
+                .abilities
+                .iter()
+                .map(|ability| (ability.name.as_str(), ability.uri.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Bite", "/codex/spells/bite/"),
+                ("Howl", "/codex/spells/howl/"),
+                ("Fetch", "/codex/spells/fetch/"),
+            ]
+        );
+    }
+}
@@ -134,12 +134,11 @@ fn div_to_name_icon(div: &NodeRef) -> Result<(String, String), Error> {
 }
 
 /// Parse a list of `name`, `uri`, `icon` tuples.
-fn parse_name_uri_icon_list(
+pub(crate) fn parse_name_uri_icon_list(
     iter_node: &NodeRef,
 ) -> impl Iterator<Item = Result<(String, String, String), Error>> {
     iter_node
         .following_siblings()
-        .into_iter()
         .filter(|node| matches!(node.data(), NodeData::Element(_)))
         .map_while(|node| {
             if let NodeData::Element(ElementData {
@@ -172,7 +171,6 @@ fn parse_name_icon_list(
 ) -> impl Iterator<Item = Result<(String, String), Error>> {
     iter_node
         .following_siblings()
-        .into_iter()
         .filter(|node| matches!(node.data(), NodeData::Element(_)))
         .map_while(|node| {
             if let NodeData::Element(ElementData {
@@ -389,7 +387,7 @@ pub fn parse_html_codex_item(contents: &str, slug: String) -> Result<Item, Error
     let mut stats = parse_stats(stats_parent.as_ref().map(|n| n.as_node()))?;
     // Though `place` is in the `codex-page-meta` section, it is in the `stat` structure.
     if let Some(place) = codex_page_meta.place {
-        if let Some(mut stats) = stats.as_mut() {
+        if let Some(stats) = stats.as_mut() {
             stats.place = Some(place);
         } else {
             stats = Some(Stats {
@@ -429,6 +427,9 @@ pub fn parse_html_codex_item(contents: &str, slug: String) -> Result<Item, Error
         icon: parse_icon(icon.as_node())?,
         description,
         tier: codex_page_meta.tier,
+        rarity: codex_page_meta
+            .rarity
+            .or_else(|| codex_page_meta.exotic.then(|| "Exotic".to_string())),
         stats,
         ability: parse_ability(description_it.next().as_ref().map(|n| n.as_node()))?,
         causes,
@@ -445,6 +446,7 @@ pub fn parse_html_codex_item(contents: &str, slug: String) -> Result<Item, Error
 /// The page needs not be in English and only some of the fields are selected.
 /// Fields ignored:
 ///   - tier
+///   - rarity
 ///   - stats
 ///   - causes
 ///   - cures
@@ -476,6 +478,7 @@ pub fn parse_html_codex_item_translation(contents: &str, slug: String) -> Result
         icon: parse_icon(icon.as_node())?,
         description,
         tier: 0,
+        rarity: None,
         stats: None,
         ability: None,
         causes: vec![],
@@ -487,3 +490,36 @@ pub fn parse_html_codex_item_translation(contents: &str, slug: String) -> Result
         tags: vec![],
     })
 }
+
+#[cfg(test)]
+mod test {
+    use crate::codex::html_item_parser::parse_html_codex_item;
+
+    #[test]
+    fn test_parse_rarity() {
+        let item = parse_html_codex_item(
+            include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/fixtures/codex/item_with_rarity.html"
+            )),
+            "ornate-sword".to_string(),
+        )
+        .unwrap();
+        assert_eq!(item.name, "Ornate Sword");
+        assert_eq!(item.tier, 7);
+        assert_eq!(item.rarity.as_deref(), Some("Ornate"));
+        assert_eq!(item.icon, "weapons/ornate_sword.png");
+
+        let item = parse_html_codex_item(
+            include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/fixtures/codex/item_without_rarity.html"
+            )),
+            "stick".to_string(),
+        )
+        .unwrap();
+        assert_eq!(item.name, "Stick");
+        assert_eq!(item.tier, 1);
+        assert_eq!(item.rarity, None);
+    }
+}
@@ -13,7 +13,7 @@ pub struct Entry {
     /// Meta information about the entry.
     pub meta: Option<String>,
     /// Tier of the element.
-    pub tier: u32,
+    pub tier: u8,
     /// Uri to the element.
     pub uri: String,
 }
@@ -33,11 +33,7 @@ fn node_to_entry(node: &NodeRef) -> Result<Entry, Error> {
     let all_contents = all_contents.trim();
     let mut entry = Entry::default();
 
-    let it = node
-        .children()
-        .into_iter()
-        .filter(|n| n.as_element().is_some())
-        .skip(1); // Skip over image.
+    let it = node.children().filter(|n| n.as_element().is_some()).skip(1); // Skip over image.
     let mut it = it.peekable();
 
     if let Some(name_node) = it.next() {
@@ -36,6 +36,8 @@ struct ExtractedInfo {
     pub abilities: Vec<MonsterAbility>,
     /// The items the monster drops.
     pub drops: Vec<MonsterDrop>,
+    /// The locations in which the monster spawns.
+    pub spawns: Vec<String>,
 }
 
 /// The contents of the `codex-page-description` node.
@@ -152,7 +154,6 @@ fn parse_name_uri_icon_list(
 ) -> impl Iterator<Item = Result<(String, String, String), Error>> {
     iter_node
         .following_siblings()
-        .into_iter()
         .filter(|node| matches!(node.data(), NodeData::Element(_)))
         .map_while(|node| {
             if let NodeData::Element(ElementData {
@@ -193,6 +194,20 @@ fn parse_drops(iter_node: &NodeRef) -> Result<Vec<MonsterDrop>, Error> {
         .collect()
 }
 
+/// Parse spawn locations from the `h4` spawns node.
+/// Each location is in its own `div`, until the next `h4` or `hr`.
+fn parse_spawns(iter_node: &NodeRef) -> Vec<String> {
+    iter_node
+        .following_siblings()
+        .filter(|node| matches!(node.data(), NodeData::Element(_)))
+        .map_while(|node| match node.as_element() {
+            Some(element) if &element.name.local == "div" => Some(node_to_text(&node)),
+            _ => None,
+        })
+        .filter(|spawn| !spawn.is_empty())
+        .collect()
+}
+
 /// Parses a monster page from `playorna.com` and returns the details about the given monster.
 fn parse_html_page(
     contents: &str,
@@ -209,6 +224,7 @@ fn parse_html_page(
     let mut tags = Vec::new();
     let mut abilities = vec![];
     let mut drops = vec![];
+    let mut spawns = vec![];
 
     let DescriptionNode {
         description,
@@ -226,6 +242,9 @@ fn parse_html_page(
                 "Drops:" => {
                     drops = parse_drops(h4.as_node())?;
                 }
+                "Spawns in:" | "Spawns:" => {
+                    spawns = parse_spawns(h4.as_node());
+                }
                 x => panic!("{}", x),
             }
         }
@@ -243,6 +262,7 @@ fn parse_html_page(
         tags,
         abilities,
         drops,
+        spawns,
     })
 }
 
@@ -264,6 +284,7 @@ pub fn parse_html_codex_monster(contents: &str, slug: String) -> Result<CodexMon
                 tier: info.tier,
                 abilities: info.abilities,
                 drops: info.drops,
+                spawns: info.spawns,
             })
         })
         .map_err(|err| match err {
@@ -292,6 +313,7 @@ pub fn parse_html_codex_boss(contents: &str, slug: String) -> Result<CodexBoss,
                 tier: info.tier,
                 abilities: info.abilities,
                 drops: info.drops,
+                spawns: info.spawns,
             })
         })
         .map_err(|err| match err {
@@ -353,6 +375,7 @@ pub fn parse_html_codex_monster_translation(
                 tier: info.tier,
                 abilities: vec![],
                 drops: vec![],
+                spawns: vec![],
             })
         })
         .map_err(|err| match err {
@@ -385,6 +408,7 @@ pub fn parse_html_codex_boss_translation(contents: &str, slug: String) -> Result
                 tier: info.tier,
                 abilities: vec![],
                 drops: vec![],
+                spawns: vec![],
             })
         })
         .map_err(|err| match err {
@@ -425,3 +449,40 @@ pub fn parse_html_codex_raid_translation(contents: &str, slug: String) -> Result
             x => x,
         })
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{codex::html_monster_parser::parse_html_codex_monster, data::CodexGenericMonster};
+
+    static MONSTER_HTML: &str = r#"
+<html><body>
+<h1 class="herotext">Slime</h1>
+<div class="codex-page">
+  <div class="codex-page-icon"><img src="https://playorna.com/static/img/monsters/slime.png"></div>
+  <div class="codex-page-description">Family: Slime</div>
+  <div class="codex-page-description">Rarity: Common</div>
+  <div class="codex-page-meta">Tier: &#9733;1</div>
+  <h4>Spawns in:</h4>
+  <div class="drop"><span>Overworld</span></div>
+  <div class="drop"><span>Dungeon</span></div>
+  <hr>
+  <h4>Drops:</h4>
+  <div class="drop">
+    <a href="/codex/items/slime-gel/"><img src="https://playorna.com/static/img/items/slime_gel.png"> Slime Gel</a>
+  </div>
+</div>
+</body></html>
+"#;
+
+    #[test]
+    fn test_parse_spawns() {
+        let monster = parse_html_codex_monster(MONSTER_HTML, "slime".to_string()).unwrap();
+        assert_eq!(monster.family, "Slime");
+        assert_eq!(monster.spawns, vec!["Overworld", "Dungeon"]);
+        assert_eq!(monster.drops.len(), 1);
+        assert_eq!(
+            CodexGenericMonster::Monster(&monster).tags_as_guide_spawns(),
+            vec!["Dungeon", "Overworld"]
+        );
+    }
+}
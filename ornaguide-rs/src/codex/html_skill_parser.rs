@@ -1,10 +1,13 @@
 use kuchiki::{parse_html, traits::TendrilSink, NodeRef};
 
 use crate::{
-    codex::{CodexSkill, SkillStatusEffect, SkillSummon},
+    codex::{
+        html_item_parser::parse_name_uri_icon_list, CodexSkill, SkillBuffedBy, SkillStatusEffect,
+        SkillSummon,
+    },
     error::Error,
     guide::html_utils::{descend_if_tag, is_html_tag_node, parse_name_and_chance, parse_tags},
-    utils::html::{descend_iter, descend_to, node_to_text, parse_icon},
+    utils::html::{descend_iter, descend_to, node_to_text, parse_icon, try_descend_to},
 };
 
 /// Parse the tier of the skill.
@@ -24,6 +27,66 @@ fn parse_tier(node: &NodeRef) -> Result<u8, Error> {
     }
 }
 
+/// Parse the leading number of a `codex-page-meta` value (e.g.: `12` in `12 mana`).
+fn parse_meta_number<T: std::str::FromStr>(value: &str, what: &str) -> Result<T, Error> {
+    let value = value.trim();
+    let end = value
+        .char_indices()
+        .find(|(i, c)| !(c.is_ascii_digit() || (*i == 0 && *c == '-')))
+        .map_or(value.len(), |(i, _)| i);
+    value[..end].parse().map_err(|_| {
+        Error::HTMLParsingError(format!("Failed to parse skill {}: \"{}\"", what, value))
+    })
+}
+
+/// Parse the power and mana cost of the skill from the `codex-page-meta` nodes.
+/// Returns `(power, mana_cost)`. Either is `None` if the page does not display it.
+fn parse_power_and_mana_cost(page: &NodeRef) -> Result<(Option<i32>, Option<u32>), Error> {
+    let mut power = None;
+    let mut mana_cost = None;
+    for meta in descend_iter(page, ".codex-page-meta", "page")? {
+        let text = node_to_text(meta.as_node());
+        if let Some(value) = text.strip_prefix("Power:") {
+            power = Some(parse_meta_number(value, "power")?);
+        } else if let Some(value) = text
+            .strip_prefix("Mana cost:")
+            .or_else(|| text.strip_prefix("Cost:"))
+        {
+            mana_cost = Some(parse_meta_number(value, "mana cost")?);
+        }
+    }
+    Ok((power, mana_cost))
+}
+
+/// Parse the duration and stacks annotation of a status effect (e.g.: `3 turns`, `x2`,
+/// `2 stacks`).
+/// Returns `(duration, stacks)`. Either is `None` if it is not found in the text.
+fn parse_status_effect_annotation(text: &str) -> (Option<u8>, Option<u8>) {
+    let mut duration = None;
+    let mut stacks = None;
+    let words = text
+        .split(|c: char| c.is_whitespace() || "()[],".contains(c))
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>();
+    for (i, word) in words.iter().enumerate() {
+        if let Some(n) = word
+            .strip_prefix('x')
+            .or_else(|| word.strip_prefix('×'))
+            .and_then(|n| n.parse().ok())
+        {
+            stacks = Some(n);
+        } else if let (Ok(n), Some(unit)) = (word.parse(), words.get(i + 1)) {
+            let unit = unit.to_lowercase();
+            if unit.starts_with("turn") {
+                duration = Some(n);
+            } else if unit.starts_with("stack") {
+                stacks = Some(n);
+            }
+        }
+    }
+    (duration, stacks)
+}
+
 /// Parse a status effect section.
 fn parse_status_effects(status_effects_root: &NodeRef) -> Result<Vec<SkillStatusEffect>, Error> {
     status_effects_root
@@ -35,11 +98,25 @@ fn parse_status_effects(status_effects_root: &NodeRef) -> Result<Vec<SkillStatus
                 // Don't attempt to parse what is not a `div`.
                 let span = descend_to(node, "span", "skill status effect")?;
                 let text = span.text_contents();
+                // Anything past the chance, be it in the `span` or next to it, is an annotation
+                // for the duration and stacks of the effect.
+                let (name_and_chance, annotation) = match text.find(')') {
+                    Some(pos) => text.split_at(pos + 1),
+                    None => (text.as_str(), ""),
+                };
+                let annotation = format!(
+                    "{} {}",
+                    annotation,
+                    node.text_contents().replacen(&text, "", 1)
+                );
+                let (duration, stacks) = parse_status_effect_annotation(&annotation);
                 // Split the name of the status effect from its chance.
-                parse_name_and_chance(&text, "status effect").map(|(effect, chance)| {
+                parse_name_and_chance(name_and_chance, "status effect").map(|(effect, chance)| {
                     SkillStatusEffect {
                         effect: effect.to_string(),
                         chance,
+                        duration,
+                        stacks,
                     }
                 })
             })
@@ -104,6 +181,14 @@ fn parse_summons(summons_root: &NodeRef) -> Result<Vec<Vec<SkillSummon>>, Error>
         .collect()
 }
 
+/// Parse the monsters buffing a passive skill.
+/// Monsters are right after a `Buffed by:` `h4` tag, each in a `div` with a link to the monster.
+fn parse_buffed_by(buffed_by_root: &NodeRef) -> Result<Vec<SkillBuffedBy>, Error> {
+    parse_name_uri_icon_list(buffed_by_root)
+        .map(|tupleresult| tupleresult.map(|(name, uri, icon)| SkillBuffedBy { name, uri, icon }))
+        .collect()
+}
+
 /// Parses a skill page from `playorna.com` and returns the details about the given skill.
 pub fn parse_html_codex_skill(contents: &str, slug: String) -> Result<CodexSkill, Error> {
     let html = parse_html().one(contents);
@@ -113,10 +198,12 @@ pub fn parse_html_codex_skill(contents: &str, slug: String) -> Result<CodexSkill
     let icon = descend_to(page.as_node(), ".codex-page-icon", "page")?;
     let description = descend_to(page.as_node(), ".codex-page-description", "page")?;
     let tier = descend_to(page.as_node(), ".codex-page-meta", "page")?;
+    let (power, mana_cost) = parse_power_and_mana_cost(page.as_node())?;
     let tags = parse_tags(descend_iter(page.as_node(), ".codex-page-tag", "page")?)?;
     let mut causes = vec![];
     let mut gives = vec![];
     let mut summons = vec![];
+    let mut buffed_by = vec![];
 
     for h4 in descend_iter(page.as_node(), "h4", "page")? {
         match h4.text_contents().trim() {
@@ -129,6 +216,9 @@ pub fn parse_html_codex_skill(contents: &str, slug: String) -> Result<CodexSkill
             "Summons:" => {
                 summons = parse_summons(h4.as_node())?;
             }
+            "Buffed by:" => {
+                buffed_by = parse_buffed_by(h4.as_node())?;
+            }
             x => panic!("{}", x),
         }
     }
@@ -139,18 +229,24 @@ pub fn parse_html_codex_skill(contents: &str, slug: String) -> Result<CodexSkill
         icon: parse_icon(icon.as_node())?,
         description: node_to_text(description.as_node()),
         tier: parse_tier(tier.as_node())?,
+        power,
+        mana_cost,
         tags,
         causes,
         gives,
         summons,
+        buffed_by,
     })
 }
 
 /// Parses a skill page from `playorna.com` and returns the details about the given skill.
 /// The page needs not be in English and only some of the fields are selected.
 /// Fields ignored:
+///   - power
+///   - mana_cost
 ///   - tags
 ///   - "causes"/"gives": Both are put into `causes`.
+///   - buffed_by
 pub fn parse_html_codex_skill_translation(
     contents: &str,
     slug: String,
@@ -165,7 +261,20 @@ pub fn parse_html_codex_skill_translation(
     let mut causes = vec![];
 
     for h4 in descend_iter(page.as_node(), "h4", "page")? {
-        causes.append(&mut parse_status_effects(h4.as_node())?);
+        // Buffing monsters are the only section that does not list status effects. Since the page
+        // isn't necessarily in English, we can't rely on the text of the `h4`. Status effects are
+        // in `div`s without links, whereas monsters are linked to.
+        let is_monster_list = h4
+            .as_node()
+            .following_siblings()
+            .find(is_html_tag_node)
+            .map(|node| try_descend_to(&node, "a", "skill h4 sibling"))
+            .transpose()?
+            .flatten()
+            .is_some();
+        if !is_monster_list {
+            causes.append(&mut parse_status_effects(h4.as_node())?);
+        }
     }
 
     Ok(CodexSkill {
@@ -174,9 +283,124 @@ pub fn parse_html_codex_skill_translation(
         icon: parse_icon(icon.as_node())?,
         description: node_to_text(description.as_node()),
         tier: parse_tier(tier.as_node())?,
+        power: None,
+        mana_cost: None,
         tags: vec![],
         causes,
         gives: vec![],
         summons: vec![],
+        buffed_by: vec![],
     })
 }
+
+#[cfg(test)]
+mod test {
+    use crate::codex::{
+        html_skill_parser::parse_html_codex_skill, SkillBuffedBy, SkillStatusEffect,
+    };
+
+    static PASSIVE_SKILL_HTML: &str = r#"
+<html><body>
+<h1 class="herotext">Bloodlust</h1>
+<div class="codex-page">
+  <div class="codex-page-icon"><img src="https://playorna.com/static/img/skills/bloodlust.png"></div>
+  <div class="codex-page-description">Increases attack for every kill.</div>
+  <div class="codex-page-meta">Tier: &#9733;3</div>
+  <h4>Buffed by:</h4>
+  <div class="drop">
+    <a href="/codex/monsters/goblin/"><img src="https://playorna.com/static/img/monsters/goblin.png"> Goblin</a>
+  </div>
+  <div class="drop">
+    <a href="/codex/monsters/orc/"><img src="https://playorna.com/static/img/monsters/orc.png"> Orc</a>
+  </div>
+</div>
+</body></html>
+"#;
+
+    static DEBUFF_SKILL_HTML: &str = r#"
+<html><body>
+<h1 class="herotext">Toxic Cloud</h1>
+<div class="codex-page">
+  <div class="codex-page-icon"><img src="https://playorna.com/static/img/skills/toxic_cloud.png"></div>
+  <div class="codex-page-description">Engulfs the foe in a poisonous cloud.</div>
+  <div class="codex-page-meta">Tier: &#9733;4</div>
+  <div class="codex-page-meta">Power: 12</div>
+  <div class="codex-page-meta">Cost: 18 mana</div>
+  <h4>Causes:</h4>
+  <div class="drop"><span>Poisoned (100%)</span> 3 turns</div>
+  <div class="drop"><span>Blind (50%) x2</span></div>
+  <div class="drop"><span>Asleep (10%)</span></div>
+</div>
+</body></html>
+"#;
+
+    #[test]
+    fn test_parse_status_effect_durations() {
+        let skill = parse_html_codex_skill(DEBUFF_SKILL_HTML, "toxic-cloud".to_string()).unwrap();
+        assert_eq!(
+            skill.causes,
+            vec![
+                SkillStatusEffect {
+                    effect: "Poisoned".to_string(),
+                    chance: 100,
+                    duration: Some(3),
+                    stacks: None,
+                },
+                SkillStatusEffect {
+                    effect: "Blind".to_string(),
+                    chance: 50,
+                    duration: None,
+                    stacks: Some(2),
+                },
+                SkillStatusEffect {
+                    effect: "Asleep".to_string(),
+                    chance: 10,
+                    duration: None,
+                    stacks: None,
+                },
+            ]
+        );
+        assert_eq!(
+            skill
+                .causes
+                .iter()
+                .map(|e| e.effect.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Poisoned", "Blind", "Asleep"]
+        );
+    }
+
+    #[test]
+    fn test_parse_power_and_mana_cost() {
+        let skill = parse_html_codex_skill(DEBUFF_SKILL_HTML, "toxic-cloud".to_string()).unwrap();
+        assert_eq!(skill.power, Some(12));
+        assert_eq!(skill.mana_cost, Some(18));
+
+        let skill = parse_html_codex_skill(PASSIVE_SKILL_HTML, "bloodlust".to_string()).unwrap();
+        assert_eq!(skill.power, None);
+        assert_eq!(skill.mana_cost, None);
+    }
+
+    #[test]
+    fn test_parse_passive_buffed_by() {
+        let skill = parse_html_codex_skill(PASSIVE_SKILL_HTML, "bloodlust".to_string()).unwrap();
+        assert_eq!(skill.name, "Bloodlust");
+        assert_eq!(skill.tier, 3);
+        assert!(skill.causes.is_empty());
+        assert_eq!(
+            skill.buffed_by,
+            vec![
+                SkillBuffedBy {
+                    name: "Goblin".to_string(),
+                    uri: "/codex/monsters/goblin/".to_string(),
+                    icon: "monsters/goblin.png".to_string(),
+                },
+                SkillBuffedBy {
+                    name: "Orc".to_string(),
+                    uri: "/codex/monsters/orc/".to_string(),
+                    icon: "monsters/orc.png".to_string(),
+                },
+            ]
+        );
+    }
+}
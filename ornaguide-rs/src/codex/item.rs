@@ -159,7 +159,8 @@ pub struct Immunity {
 }
 
 /// An item on the codex.
-#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "strict-codex", serde(deny_unknown_fields))]
 pub struct Item {
     /// The slug of the item (`https://playorna.com/codex/items/{slug}`).
     pub slug: String,
@@ -171,6 +172,9 @@ pub struct Item {
     pub description: String,
     /// The tier of the item.
     pub tier: u8,
+    /// The rarity of the item (e.g.: `Ornate`), if the codex displays one.
+    #[serde(default)]
+    pub rarity: Option<String>,
     /// Tags attached to the item.
     pub tags: Vec<Tag>,
     /// The stats of the item.
@@ -194,7 +198,7 @@ pub struct Item {
 impl Item {
     /// Return whether the item can be found in shops.
     pub fn found_in_shops(&self) -> bool {
-        self.tags.iter().any(|tag| *tag == Tag::FoundInShops)
+        self.tags.contains(&Tag::FoundInShops)
     }
 
     /// Try to convert `self` to an `AdminItem`.
@@ -316,36 +320,75 @@ impl Item {
     }
 }
 
-impl ToString for Element {
-    fn to_string(&self) -> String {
+/// Statuses that specific weapons inflict in addition to those of their element, by weapon name.
+const WEAPON_EXTRA_INFLICTED_STATUSES: &[(&str, &[&str])] = &[("Swansong", &["Blind"])];
+
+impl Element {
+    /// Return the names of the statuses a weapon of this element may inflict.
+    pub fn inflicted_statuses(&self) -> &'static [&'static str] {
         match self {
-            Element::Fire => "Fire".to_string(),
-            Element::Water => "Water".to_string(),
-            Element::Earthen => "Earthen".to_string(),
-            Element::Lightning => "Lightning".to_string(),
-            Element::Holy => "Holy".to_string(),
-            Element::Dark => "Dark".to_string(),
-            Element::Arcane => "Arcane".to_string(),
-            Element::Dragon => "Dragon".to_string(),
-            Element::Physical => "Physical".to_string(),
+            Element::Fire => &["Burning"],
+            Element::Water => &["Frozen"],
+            Element::Earthen => &["Rot"],
+            Element::Lightning => &["Paralyzed"],
+            Element::Holy => &["Blind"],
+            Element::Dark => &["Asleep"],
+            Element::Arcane => &["Burning", "Frozen", "Rot", "Paralyzed"],
+            Element::Dragon => &["Blight"],
+            Element::Physical => &[],
         }
     }
 }
 
-impl ToString for Place {
-    fn to_string(&self) -> String {
-        match self {
-            Place::Head => "Head".to_string(),
-            Place::Weapon => "Weapon".to_string(),
-            Place::Torso => "Torso".to_string(),
-            Place::OffHand => "Off-hand".to_string(),
-            Place::Legs => "Legs".to_string(),
-            Place::Accessory => "Accessory".to_string(),
-            Place::Armor => "Armor".to_string(),
-            Place::Augment => "Augment".to_string(),
+/// Return the names of the statuses a weapon with the given element and name inflicts.
+/// These are the statuses of the element, along with those some weapons inflict regardless of
+/// their element. The codex does not always list them in the weapon's causes.
+pub fn weapon_inflicted_statuses(element: Option<&Element>, item_name: &str) -> Vec<&'static str> {
+    element
+        .map(Element::inflicted_statuses)
+        .unwrap_or_default()
+        .iter()
+        .chain(
+            WEAPON_EXTRA_INFLICTED_STATUSES
+                .iter()
+                .filter(|(name, _)| *name == item_name)
+                .flat_map(|(_, statuses)| statuses.iter()),
+        )
+        .copied()
+        .unique()
+        .collect()
+}
+
+impl std::fmt::Display for Element {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Element::Fire => "Fire",
+            Element::Water => "Water",
+            Element::Earthen => "Earthen",
+            Element::Lightning => "Lightning",
+            Element::Holy => "Holy",
+            Element::Dark => "Dark",
+            Element::Arcane => "Arcane",
+            Element::Dragon => "Dragon",
+            Element::Physical => "Physical",
+        })
+    }
+}
+
+impl std::fmt::Display for Place {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Place::Head => "Head",
+            Place::Weapon => "Weapon",
+            Place::Torso => "Torso",
+            Place::OffHand => "Off-hand",
+            Place::Legs => "Legs",
+            Place::Accessory => "Accessory",
+            Place::Armor => "Armor",
+            Place::Augment => "Augment",
             // TODO(ethiraric, 26/01/2023): Check if this is a typo.
-            Place::Material => "material".to_string(),
-        }
+            Place::Material => "material",
+        })
     }
 }
 
@@ -455,3 +498,27 @@ impl<'a> Items {
             .ok_or_else(|| Error::Misc(format!("No match for codex item with slug '{}'", needle)))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::codex::item::{weapon_inflicted_statuses, Element};
+
+    #[test]
+    fn test_weapon_inflicted_statuses() {
+        assert_eq!(
+            weapon_inflicted_statuses(Some(&Element::Fire), "Fire Sword"),
+            vec!["Burning"]
+        );
+        assert_eq!(
+            weapon_inflicted_statuses(Some(&Element::Arcane), "Arcane Staff"),
+            vec!["Burning", "Frozen", "Rot", "Paralyzed"]
+        );
+        assert!(weapon_inflicted_statuses(Some(&Element::Physical), "Sword").is_empty());
+        assert!(weapon_inflicted_statuses(None, "Sword").is_empty());
+        assert_eq!(weapon_inflicted_statuses(None, "Swansong"), vec!["Blind"]);
+        assert_eq!(
+            weapon_inflicted_statuses(Some(&Element::Holy), "Swansong"),
+            vec!["Blind"]
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::File,
+    io::BufReader,
+};
+
+use serde::{
+    de::{self, DeserializeOwned, Visitor},
+    forward_to_deserialize_any, Deserializer,
+};
+use serde_json::{Map, Value};
+
+use crate::error::Error;
+
+/// A deserializer that records the names of the fields of the structure that is deserialized
+/// from it, then fails.
+struct FieldsRecorder<'a>(&'a mut &'static [&'static str]);
+
+impl<'de> Deserializer<'de> for FieldsRecorder<'_> {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("Not a structure"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        fields: &'static [&'static str],
+        _: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.0 = fields;
+        Err(de::Error::custom("Fields recorded"))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf option
+        unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier ignored_any
+    }
+}
+
+/// Return the names of the fields `T` deserializes from.
+/// Return an empty slice if `T` is not deserialized from a structure.
+fn struct_fields<T: DeserializeOwned>() -> &'static [&'static str] {
+    let mut fields: &'static [&'static str] = &[];
+    let _ = T::deserialize(FieldsRecorder(&mut fields));
+    fields
+}
+
+/// A codex entity that was deserialized leniently.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lenient<T> {
+    /// The entity itself.
+    pub entity: T,
+    /// The fields of the JSON object that are unknown to the entity, by name.
+    pub extra: HashMap<String, Value>,
+}
+
+/// Deserialize a codex entity from `value`, collecting the fields `T` does not know of rather
+/// than ignoring them or failing.
+/// Only the top-level fields of the entity are checked.
+/// This works regardless of whether the `strict-codex` feature is enabled.
+pub fn from_value_lenient<T: DeserializeOwned>(value: Value) -> Result<Lenient<T>, Error> {
+    let (value, extra) = match value {
+        Value::Object(object) => {
+            let fields = struct_fields::<T>();
+            let (known, extra): (Map<String, Value>, HashMap<String, Value>) =
+                object.into_iter().fold(
+                    (Map::new(), HashMap::new()),
+                    |(mut known, mut extra), (key, value)| {
+                        if fields.contains(&key.as_str()) {
+                            known.insert(key, value);
+                        } else {
+                            extra.insert(key, value);
+                        }
+                        (known, extra)
+                    },
+                );
+            (Value::Object(known), extra)
+        }
+        value => (value, HashMap::new()),
+    };
+
+    Ok(Lenient {
+        entity: serde_json::from_value(value)?,
+        extra,
+    })
+}
+
+/// Load codex entities from a JSON file, leniently.
+/// The file must contain an object with the entities in an array under `key` (e.g.:
+/// `{"items": [...]}`). Fields of the entities that are unknown are printed, along with the
+/// number of entities having them.
+pub fn load_entities_lenient<T: DeserializeOwned>(path: &str, key: &str) -> Result<Vec<T>, Error> {
+    let mut root: Value = serde_json::from_reader(BufReader::new(File::open(path)?))
+        .map_err(|err| Error::SerdeJson(err, path.to_string()))?;
+    let entities = match root.get_mut(key).map(Value::take) {
+        Some(Value::Array(entities)) => entities,
+        _ => {
+            return Err(Error::Misc(format!(
+                "{}: Missing array of entities `{}`",
+                path, key
+            )))
+        }
+    };
+
+    let mut unknown_fields = BTreeMap::<String, usize>::new();
+    let entities = entities
+        .into_iter()
+        .map(|value| {
+            let lenient = from_value_lenient::<T>(value)?;
+            for field in lenient.extra.into_keys() {
+                *unknown_fields.entry(field).or_default() += 1;
+            }
+            Ok(lenient.entity)
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    for (field, count) in unknown_fields.iter() {
+        eprintln!(
+            "{}: Unknown field `{}` in {} entities of `{}`",
+            path, field, count, key
+        );
+    }
+    Ok(entities)
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use crate::codex::{lenient::from_value_lenient, CodexFollower};
+
+    fn follower_json() -> serde_json::Value {
+        json!({
+            "name": "Cat",
+            "slug": "cat",
+            "icon": "followers/cat.png",
+            "description": "A cat.",
+            "events": [],
+            "rarity": "Common",
+            "tier": 1,
+            "abilities": [],
+            "power": 3
+        })
+    }
+
+    #[test]
+    fn test_lenient() {
+        let lenient = from_value_lenient::<CodexFollower>(follower_json()).unwrap();
+        assert_eq!(lenient.entity.slug, "cat");
+        assert_eq!(lenient.entity.tier, 1);
+        assert_eq!(lenient.extra.len(), 1);
+        assert_eq!(lenient.extra["power"], json!(3));
+
+        let mut known = follower_json();
+        known.as_object_mut().unwrap().remove("power");
+        assert!(from_value_lenient::<CodexFollower>(known)
+            .unwrap()
+            .extra
+            .is_empty());
+    }
+
+    #[cfg(feature = "strict-codex")]
+    #[test]
+    fn test_strict() {
+        assert!(serde_json::from_value::<CodexFollower>(follower_json()).is_err());
+    }
+
+    #[cfg(not(feature = "strict-codex"))]
+    #[test]
+    fn test_not_strict() {
+        assert!(serde_json::from_value::<CodexFollower>(follower_json()).is_ok());
+    }
+}
@@ -27,6 +27,7 @@ pub struct Drop {
 
 /// A monster on the codex.
 #[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "strict-codex", serde(deny_unknown_fields))]
 pub struct Monster {
     /// The slug of the monster (`https://playorna.com/codex/monsters/{slug}`).
     pub slug: String,
@@ -46,10 +47,14 @@ pub struct Monster {
     pub abilities: Vec<Ability>,
     /// The items the monster drops.
     pub drops: Vec<Drop>,
+    /// The locations in which the monster spawns.
+    #[serde(default)]
+    pub spawns: Vec<String>,
 }
 
 /// A boss on the codex.
 #[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "strict-codex", serde(deny_unknown_fields))]
 pub struct Boss {
     /// The slug of the boss (`https://playorna.com/codex/bosses/{slug}`).
     pub slug: String,
@@ -69,10 +74,14 @@ pub struct Boss {
     pub abilities: Vec<Ability>,
     /// The items the boss drops.
     pub drops: Vec<Drop>,
+    /// The locations in which the boss spawns.
+    #[serde(default)]
+    pub spawns: Vec<String>,
 }
 
 /// A raid on the codex.
 #[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "strict-codex", serde(deny_unknown_fields))]
 pub struct Raid {
     /// The slug of the raid (`https://playorna.com/codex/raids/{slug}`).
     pub slug: String,
@@ -19,6 +19,12 @@ pub struct SkillStatusEffect {
     pub effect: String,
     /// The chance (0-100) of the effect happening.
     pub chance: i8,
+    /// The number of turns the effect lasts, if the codex displays it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration: Option<u8>,
+    /// The number of stacks applied, if the codex displays it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stacks: Option<u8>,
 }
 
 /// A summon from a skill.
@@ -30,6 +36,17 @@ pub struct SkillSummon {
     pub chance: i8,
 }
 
+/// A monster that buffs a passive skill when killed.
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+pub struct SkillBuffedBy {
+    /// The name of the monster.
+    pub name: String,
+    /// The uri to the monster.
+    pub uri: String,
+    /// The icon of the monster.
+    pub icon: String,
+}
+
 /// A trait to extend `Vec<SkillStatusEffect>` specifically.
 pub trait SkillStatusEffects {
     /// Try to convert `self` to a `Vec<u32>`, with `u32`s being the guide status_effect ids.
@@ -68,6 +85,7 @@ impl SkillStatusEffects for Vec<SkillStatusEffect> {
 /// A skill on the codex.
 #[derive(Default, Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
 #[serde(default)]
+#[cfg_attr(feature = "strict-codex", serde(deny_unknown_fields))]
 pub struct CodexSkill {
     /// The name of the skill.
     pub name: String,
@@ -79,6 +97,10 @@ pub struct CodexSkill {
     pub description: String,
     /// The tier of the skill.
     pub tier: u8,
+    /// The power of the skill, if the codex displays it.
+    pub power: Option<i32>,
+    /// The mana cost of the skill, if the codex displays it.
+    pub mana_cost: Option<u32>,
     /// Tags attached to the skill.
     pub tags: Vec<Tag>,
     /// The effects the skill causes to the opponent.
@@ -87,6 +109,9 @@ pub struct CodexSkill {
     pub gives: Vec<SkillStatusEffect>,
     /// The entities summoned by the spell.
     pub summons: Vec<Vec<SkillSummon>>,
+    /// The monsters that buff the skill when killed.
+    /// Only passive skills can be buffed. This is empty for other skills.
+    pub buffed_by: Vec<SkillBuffedBy>,
 }
 
 impl CodexSkill {
@@ -1,4 +1,5 @@
-use serde::{Deserialize, Serialize};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize, Serializer};
 
 use crate::{
     codex::{CodexBoss, CodexFollower, CodexItem, CodexMonster, CodexRaid, CodexSkill},
@@ -12,7 +13,7 @@ use crate::{
 };
 
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     fs::File,
     io::{BufReader, BufWriter, Write},
 };
@@ -78,6 +79,15 @@ pub struct FollowerTranslation {
     pub description: String,
 }
 
+/// Serialize a map with its keys sorted, so that the output is stable across runs.
+fn serialize_sorted<S, V>(map: &HashMap<String, V>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    V: Serialize,
+{
+    serializer.collect_map(map.iter().collect::<BTreeMap<_, _>>())
+}
+
 /// A set of strings for a particular language.
 #[derive(Default, Serialize, Deserialize, Debug, Eq, PartialEq)]
 pub struct LocaleStrings {
@@ -86,39 +96,150 @@ pub struct LocaleStrings {
     /// The locale in which the structure is.
     pub locale: String,
     /// Items from the codex.
+    #[serde(serialize_with = "serialize_sorted")]
     pub items: HashMap<String, ItemTranslation>,
     /// Raids from the codex.
+    #[serde(serialize_with = "serialize_sorted")]
     pub raids: HashMap<String, RaidTranslation>,
     /// Monsters from the codex.
+    #[serde(serialize_with = "serialize_sorted")]
     pub monsters: HashMap<String, MonsterTranslation>,
     /// Bosses from the codex.
+    #[serde(serialize_with = "serialize_sorted")]
     pub bosses: HashMap<String, BossTranslation>,
     /// Skills from the codex.
+    #[serde(serialize_with = "serialize_sorted")]
     pub skills: HashMap<String, SkillTranslation>,
     /// Followers from the codex.
+    #[serde(serialize_with = "serialize_sorted")]
     pub followers: HashMap<String, FollowerTranslation>,
     /// Statuses that can be inflicted.
     /// The key is the English string, the value is that in the target locale.
+    #[serde(serialize_with = "serialize_sorted")]
     pub statuses: HashMap<String, String>,
     /// Event names.
     /// The key is the English string, the value is that in the target locale.
+    #[serde(serialize_with = "serialize_sorted")]
     pub events: HashMap<String, String>,
     /// Spawn names.
     /// The key is the English string, the value is that in the target locale.
+    #[serde(serialize_with = "serialize_sorted")]
     pub spawns: HashMap<String, String>,
     /// Family names.
     /// The key is the English string, the value is that in the target locale.
+    #[serde(serialize_with = "serialize_sorted")]
     pub families: HashMap<String, String>,
     /// Rarity names.
     /// The key is the English string, the value is that in the target locale.
+    #[serde(serialize_with = "serialize_sorted")]
     pub rarities: HashMap<String, String>,
 }
 
+/// How to resolve keys present in both sides when merging `LocaleStrings`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum MergeStrategy {
+    /// Values from the other set overwrite those of `self`.
+    Overwrite,
+    /// Only keys absent from `self` are inserted. Existing values are left untouched.
+    FillMissing,
+}
+
+/// Merge `other` into `into`, following the given strategy.
+/// Return the keys whose value in `into` was overwritten with a different one, sorted.
+fn merge_map<V: PartialEq>(
+    into: &mut HashMap<String, V>,
+    other: HashMap<String, V>,
+    strategy: MergeStrategy,
+) -> Vec<String> {
+    let mut overwritten = Vec::new();
+    match strategy {
+        MergeStrategy::Overwrite => {
+            for (key, value) in other.into_iter() {
+                if let Some(old) = into.get(&key) {
+                    if *old != value {
+                        overwritten.push(key.clone());
+                    }
+                }
+                into.insert(key, value);
+            }
+        }
+        MergeStrategy::FillMissing => {
+            for (key, value) in other.into_iter() {
+                into.entry(key).or_insert(value);
+            }
+        }
+    }
+    overwritten.sort_unstable();
+    overwritten
+}
+
+/// The keys whose values were overwritten with different ones when merging translations.
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct MergeReport {
+    /// Overwritten keys, by locale then by category (`items`, `skills`, `statuses`, ...).
+    /// Locales and categories without any overwritten key are absent.
+    pub locales: BTreeMap<String, BTreeMap<&'static str, Vec<String>>>,
+}
+
+impl MergeReport {
+    /// Return whether no value was overwritten.
+    pub fn is_empty(&self) -> bool {
+        self.locales.is_empty()
+    }
+}
+
+/// Remove from `map` the entries whose value is the same as that of the same key in `english`.
+fn remove_untranslated<V: PartialEq>(map: &mut HashMap<String, V>, english: &HashMap<String, V>) {
+    map.retain(|key, value| english.get(key) != Some(value));
+}
+
+/// A string that has no translation in a locale, or whose translation is the same as in English.
+#[derive(Debug, Serialize, Clone, Eq, PartialEq)]
+pub struct UntranslatedString {
+    /// The category of the string (`items`, `skills`, `statuses`, ...).
+    pub category: &'static str,
+    /// The key of the entry. This is the slug for codex entities and the English string otherwise.
+    pub key: String,
+    /// The field of the entry that is untranslated (`name`, `description` or `value`).
+    pub field: &'static str,
+    /// The string in English.
+    pub english: String,
+}
+
+/// List the strings of `english` that are absent from `map` or identical in `map`.
+/// `fields` returns the named strings of an entry. Entries are listed sorted by key.
+fn untranslated_fields<V>(
+    category: &'static str,
+    map: &HashMap<String, V>,
+    english: &HashMap<String, V>,
+    fields: fn(&V) -> Vec<(&'static str, &str)>,
+    out: &mut Vec<UntranslatedString>,
+) {
+    for (key, english_entry) in english.iter().sorted_by_key(|(key, _)| *key) {
+        let translated = map.get(key).map(fields).unwrap_or_default();
+        for (field, english_str) in fields(english_entry) {
+            let translation = translated
+                .iter()
+                .find(|(name, _)| *name == field)
+                .map(|(_, translation)| *translation);
+            if translation.is_none_or(|translation| translation == english_str) {
+                out.push(UntranslatedString {
+                    category,
+                    key: key.clone(),
+                    field,
+                    english: english_str.to_string(),
+                });
+            }
+        }
+    }
+}
+
 /// A set of `LocaleStrings`.
 /// Strings organized in their respective locales.
 #[derive(Default, Serialize, Deserialize, Debug, Eq, PartialEq)]
 pub struct LocaleDB {
     /// Map of locales. The key is the locale name.
+    #[serde(serialize_with = "serialize_sorted")]
     pub locales: HashMap<String, LocaleStrings>,
 }
 
@@ -463,18 +584,173 @@ impl LocaleStrings {
     /// For each key in each hash map, the contents of `other` will take precedence over `self` and
     /// overwrite values in case of duplicate keys.
     /// `other.locale` is assumed to match `self.locale`. No check is performed.
-    pub fn merge_with(&mut self, mut other: Self) {
-        self.items.extend(other.items.drain());
-        self.raids.extend(other.raids.drain());
-        self.monsters.extend(other.monsters.drain());
-        self.bosses.extend(other.bosses.drain());
-        self.skills.extend(other.skills.drain());
-        self.followers.extend(other.followers.drain());
-        self.statuses.extend(other.statuses.drain());
-        self.events.extend(other.events.drain());
-        self.spawns.extend(other.spawns.drain());
-        self.families.extend(other.families.drain());
-        self.rarities.extend(other.rarities.drain());
+    pub fn merge_with(&mut self, other: Self) {
+        self.merge_with_strategy(other, MergeStrategy::Overwrite);
+    }
+
+    /// Merge the contents of `self` with that of `other`.
+    /// Duplicate keys are resolved according to `strategy`.
+    /// `other.locale` is assumed to match `self.locale`. No check is performed.
+    pub fn merge_with_strategy(&mut self, other: Self, strategy: MergeStrategy) {
+        self.merge_with_strategy_report(other, strategy);
+    }
+
+    /// Merge the contents of `self` with that of `other`, as `merge_with` does.
+    /// Return, for each category, the keys whose values were overwritten with different ones.
+    pub fn merge_with_report(&mut self, other: Self) -> BTreeMap<&'static str, Vec<String>> {
+        self.merge_with_strategy_report(other, MergeStrategy::Overwrite)
+    }
+
+    /// Merge the contents of `self` with that of `other`, following `strategy`.
+    /// Return, for each category, the keys whose values were overwritten with different ones.
+    fn merge_with_strategy_report(
+        &mut self,
+        other: Self,
+        strategy: MergeStrategy,
+    ) -> BTreeMap<&'static str, Vec<String>> {
+        [
+            ("items", merge_map(&mut self.items, other.items, strategy)),
+            ("raids", merge_map(&mut self.raids, other.raids, strategy)),
+            (
+                "monsters",
+                merge_map(&mut self.monsters, other.monsters, strategy),
+            ),
+            (
+                "bosses",
+                merge_map(&mut self.bosses, other.bosses, strategy),
+            ),
+            (
+                "skills",
+                merge_map(&mut self.skills, other.skills, strategy),
+            ),
+            (
+                "followers",
+                merge_map(&mut self.followers, other.followers, strategy),
+            ),
+            (
+                "statuses",
+                merge_map(&mut self.statuses, other.statuses, strategy),
+            ),
+            (
+                "events",
+                merge_map(&mut self.events, other.events, strategy),
+            ),
+            (
+                "spawns",
+                merge_map(&mut self.spawns, other.spawns, strategy),
+            ),
+            (
+                "families",
+                merge_map(&mut self.families, other.families, strategy),
+            ),
+            (
+                "rarities",
+                merge_map(&mut self.rarities, other.rarities, strategy),
+            ),
+        ]
+        .into_iter()
+        .filter(|(_, overwritten)| !overwritten.is_empty())
+        .collect()
+    }
+
+    /// Remove entries whose strings are identical to those of the same entry in `english`.
+    /// Such entries were not translated and can be looked up in English instead.
+    pub fn remove_empty(&mut self, english: &LocaleStrings) {
+        remove_untranslated(&mut self.items, &english.items);
+        remove_untranslated(&mut self.raids, &english.raids);
+        remove_untranslated(&mut self.monsters, &english.monsters);
+        remove_untranslated(&mut self.bosses, &english.bosses);
+        remove_untranslated(&mut self.skills, &english.skills);
+        remove_untranslated(&mut self.followers, &english.followers);
+        remove_untranslated(&mut self.statuses, &english.statuses);
+        remove_untranslated(&mut self.events, &english.events);
+        remove_untranslated(&mut self.spawns, &english.spawns);
+        remove_untranslated(&mut self.families, &english.families);
+        remove_untranslated(&mut self.rarities, &english.rarities);
+    }
+
+    /// List the strings of `english` that are either missing from `self` or identical to the
+    /// English ones.
+    pub fn untranslated(&self, english: &LocaleStrings) -> Vec<UntranslatedString> {
+        let mut ret = Vec::new();
+        untranslated_fields(
+            "items",
+            &self.items,
+            &english.items,
+            |item| {
+                vec![
+                    ("name", item.name.as_str()),
+                    ("description", item.description.as_str()),
+                ]
+            },
+            &mut ret,
+        );
+        untranslated_fields(
+            "raids",
+            &self.raids,
+            &english.raids,
+            |raid| {
+                vec![
+                    ("name", raid.name.as_str()),
+                    ("description", raid.description.as_str()),
+                ]
+            },
+            &mut ret,
+        );
+        untranslated_fields(
+            "monsters",
+            &self.monsters,
+            &english.monsters,
+            |monster| vec![("name", monster.name.as_str())],
+            &mut ret,
+        );
+        untranslated_fields(
+            "bosses",
+            &self.bosses,
+            &english.bosses,
+            |boss| vec![("name", boss.name.as_str())],
+            &mut ret,
+        );
+        untranslated_fields(
+            "skills",
+            &self.skills,
+            &english.skills,
+            |skill| {
+                vec![
+                    ("name", skill.name.as_str()),
+                    ("description", skill.description.as_str()),
+                ]
+            },
+            &mut ret,
+        );
+        untranslated_fields(
+            "followers",
+            &self.followers,
+            &english.followers,
+            |follower| {
+                vec![
+                    ("name", follower.name.as_str()),
+                    ("description", follower.description.as_str()),
+                ]
+            },
+            &mut ret,
+        );
+        for (category, map, english) in [
+            ("statuses", &self.statuses, &english.statuses),
+            ("events", &self.events, &english.events),
+            ("spawns", &self.spawns, &english.spawns),
+            ("families", &self.families, &english.families),
+            ("rarities", &self.rarities, &english.rarities),
+        ] {
+            untranslated_fields(
+                category,
+                map,
+                english,
+                |value| vec![("value", value.as_str())],
+                &mut ret,
+            );
+        }
+        ret
     }
 }
 
@@ -632,7 +908,7 @@ impl LocaleDB {
     where
         Writer: FnMut(&str, &dyn Fn(&mut dyn Write) -> Result<(), Error>) -> Result<(), Error>,
     {
-        for (lang, db) in self.locales.iter() {
+        for (lang, db) in self.locales_sorted() {
             writer(&format!("{}/{}.json", directory, lang), &|out| {
                 db.save_to_writer(out)
             })?;
@@ -641,9 +917,14 @@ impl LocaleDB {
         Ok(())
     }
 
+    /// Iterate over the locales, sorted by locale name.
+    pub fn locales_sorted(&self) -> impl Iterator<Item = (&String, &LocaleStrings)> {
+        self.locales.iter().sorted_by_key(|(lang, _)| *lang)
+    }
+
     /// Save translations to a set of json files in the given directory.
     pub fn save_to(&self, directory: &str) -> Result<(), Error> {
-        for (lang, db) in self.locales.iter() {
+        for (lang, db) in self.locales_sorted() {
             db.save_to(&format!("{}/{}.json", directory, lang))?;
         }
 
@@ -687,18 +968,66 @@ impl LocaleDB {
         Ok(ret)
     }
 
+    /// Save translations to a single json file, as an object keyed by locale name.
+    pub fn save_to_bundle(&self, file: &str) -> Result<(), Error> {
+        Ok(serde_json::to_writer_pretty(
+            BufWriter::new(File::create(file)?),
+            &self.locales_sorted().collect::<BTreeMap<_, _>>(),
+        )?)
+    }
+
+    /// Load translations from a single json file written by `save_to_bundle`.
+    /// The locale of each `LocaleStrings` is set from its key in the bundle.
+    pub fn load_from_bundle(file: &str) -> Result<Self, Error> {
+        let mut locales: HashMap<String, LocaleStrings> =
+            serde_json::from_reader(BufReader::new(File::open(file)?)).map_err(|err| {
+                Error::Misc(format!(
+                    "Failed to parse json from lang bundle {}: {}",
+                    file, err
+                ))
+            })?;
+        for (lang, db) in locales.iter_mut() {
+            db.locale = lang.clone();
+        }
+        Ok(Self { locales })
+    }
+
     /// Merge the contents of `self` with that of `other`.
     /// For each locale, the contents of `other` will take precedence over `self` and overwrite
     /// values in case of duplicate keys.
     /// If `other` contains a locale not contained in `self`, it will be added to `self`.
     pub fn merge_with(&mut self, other: Self) {
+        self.merge_with_report(other);
+    }
+
+    /// Merge the contents of `self` with that of `other`, as `merge_with` does.
+    /// Return the keys whose values were overwritten with different ones.
+    pub fn merge_with_report(&mut self, other: Self) -> MergeReport {
+        self.merge_with_strategy_report(other, MergeStrategy::Overwrite)
+    }
+
+    /// Merge the contents of `self` with that of `other`.
+    /// For each locale, duplicate keys are resolved according to `strategy`.
+    /// If `other` contains a locale not contained in `self`, it will be added to `self`.
+    pub fn merge_with_strategy(&mut self, other: Self, strategy: MergeStrategy) {
+        self.merge_with_strategy_report(other, strategy);
+    }
+
+    /// Merge the contents of `self` with that of `other`, following `strategy`.
+    /// Return the keys whose values were overwritten with different ones.
+    fn merge_with_strategy_report(&mut self, other: Self, strategy: MergeStrategy) -> MergeReport {
+        let mut report = MergeReport::default();
         for (lang, db) in other.locales {
             if let Some(self_db) = self.locales.get_mut(&lang) {
-                self_db.merge_with(db);
+                let overwritten = self_db.merge_with_strategy_report(db, strategy);
+                if !overwritten.is_empty() {
+                    report.locales.insert(lang, overwritten);
+                }
             } else {
                 self.locales.insert(lang, db);
             }
         }
+        report
     }
 }
 
@@ -757,3 +1086,161 @@ impl TranslationFor<AdminPet> for FollowerTranslation {
         follower.description = self.description.clone();
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::codex::translation::{LocaleDB, LocaleStrings, MergeStrategy, SkillTranslation};
+
+    fn strings(statuses: &[(&str, &str)]) -> LocaleStrings {
+        LocaleStrings {
+            locale: "fr".to_string(),
+            statuses: statuses
+                .iter()
+                .map(|(en, fr)| (en.to_string(), fr.to_string()))
+                .collect(),
+            ..LocaleStrings::default()
+        }
+    }
+
+    #[test]
+    fn test_remove_empty() {
+        let skill = |name: &str| SkillTranslation {
+            name: name.to_string(),
+            description: "Deals damage.".to_string(),
+        };
+        let mut english = strings(&[("Burning", "Burning"), ("Frozen", "Frozen")]);
+        english
+            .skills
+            .insert("fireball".to_string(), skill("Fireball"));
+        english.skills.insert("bite".to_string(), skill("Bite"));
+
+        let mut french = strings(&[("Burning", "Brûlure"), ("Frozen", "Frozen")]);
+        french
+            .skills
+            .insert("fireball".to_string(), skill("Boule de feu"));
+        french.skills.insert("bite".to_string(), skill("Bite"));
+        french.remove_empty(&english);
+
+        let mut expected = strings(&[("Burning", "Brûlure")]);
+        expected
+            .skills
+            .insert("fireball".to_string(), skill("Boule de feu"));
+        assert_eq!(french, expected);
+    }
+
+    #[test]
+    fn test_merge_with_report() {
+        let skill = |name: &str| SkillTranslation {
+            name: name.to_string(),
+            description: "Inflige des dégâts.".to_string(),
+        };
+        let db = |fireball: &str, bite: &str| {
+            let mut strings = strings(&[]);
+            strings
+                .skills
+                .insert("fireball".to_string(), skill(fireball));
+            strings.skills.insert("bite".to_string(), skill(bite));
+            LocaleDB {
+                locales: [("fr".to_string(), strings)].into_iter().collect(),
+            }
+        };
+
+        let mut fetched = db("Boule de feu", "Morsure");
+        let report = fetched.merge_with_report(db("Boule de feu", "Mordre"));
+        assert_eq!(
+            report.locales["fr"],
+            [("skills", vec!["bite".to_string()])].into_iter().collect()
+        );
+        assert_eq!(fetched, db("Boule de feu", "Mordre"));
+        assert!(fetched
+            .merge_with_report(db("Boule de feu", "Mordre"))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_save_is_deterministic() {
+        let save = || {
+            let statuses = (0..50)
+                .map(|i| (format!("Status {}", i), format!("Statut {}", i)))
+                .collect::<Vec<_>>();
+            let statuses = statuses
+                .iter()
+                .map(|(en, fr)| (en.as_str(), fr.as_str()))
+                .collect::<Vec<_>>();
+            let mut out = Vec::new();
+            strings(&statuses).save_to_writer(&mut out).unwrap();
+            out
+        };
+        // Each call builds new `HashMap`s, which have different iteration orders.
+        assert_eq!(save(), save());
+
+        let db = LocaleDB {
+            locales: ["fr", "de", "es", "it", "pt", "ja"]
+                .iter()
+                .map(|lang| (lang.to_string(), strings(&[])))
+                .collect(),
+        };
+        let langs = db
+            .locales_sorted()
+            .map(|(lang, _)| lang.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(langs, vec!["de", "es", "fr", "it", "ja", "pt"]);
+    }
+
+    #[test]
+    fn test_merge_overwrite() {
+        let mut manual = strings(&[("Burning", "Brûlure"), ("Frozen", "Gelé")]);
+        manual.merge_with_strategy(
+            strings(&[("Burning", "En feu"), ("Asleep", "Endormi")]),
+            MergeStrategy::Overwrite,
+        );
+        assert_eq!(
+            manual,
+            strings(&[
+                ("Burning", "En feu"),
+                ("Frozen", "Gelé"),
+                ("Asleep", "Endormi")
+            ])
+        );
+    }
+
+    #[test]
+    fn test_merge_fill_missing() {
+        let mut manual = strings(&[("Burning", "Brûlure"), ("Frozen", "Gelé")]);
+        manual.merge_with_strategy(
+            strings(&[("Burning", "En feu"), ("Asleep", "Endormi")]),
+            MergeStrategy::FillMissing,
+        );
+        assert_eq!(
+            manual,
+            strings(&[
+                ("Burning", "Brûlure"),
+                ("Frozen", "Gelé"),
+                ("Asleep", "Endormi")
+            ])
+        );
+    }
+
+    #[test]
+    fn test_bundle_round_trip() {
+        let mut db = LocaleDB::default();
+        db.locales
+            .insert("fr".to_string(), strings(&[("Burning", "Brûlure")]));
+        db.locales.insert(
+            "de".to_string(),
+            LocaleStrings {
+                locale: "de".to_string(),
+                ..strings(&[("Burning", "Brennend")])
+            },
+        );
+
+        let mut path = std::env::temp_dir();
+        path.push(format!("ornaguide-bundle-{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+        db.save_to_bundle(path).unwrap();
+        let loaded = LocaleDB::load_from_bundle(path);
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(loaded.unwrap(), db);
+    }
+}
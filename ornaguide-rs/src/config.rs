@@ -17,6 +17,20 @@ pub struct Config {
     /// Default: false
     /// Environment variable: `ORNAGUIDERS_DEBUG_URLS`
     pub debug_urls: bool,
+    /// Maximum number of attempts for a GET request failing with a 5xx status or a timeout.
+    /// Default: 3
+    /// Environment variable: `ORNAGUIDERS_HTTP_MAX_ATTEMPTS`
+    pub http_max_attempts: u32,
+    /// Delay, in milliseconds, before the first retry of a failed GET request. The delay doubles
+    /// with each further attempt.
+    /// Default: 500
+    /// Environment variable: `ORNAGUIDERS_HTTP_RETRY_DELAY`
+    pub http_retry_delay: u64,
+    /// Whether to check that admin forms have exactly the fields we expect when retrieving an
+    /// entity from the guide.
+    /// Default: false
+    /// Environment variable: `ORNAGUIDERS_VALIDATE_FORMS`
+    pub validate_forms: bool,
 }
 
 lazy_static! {
@@ -25,11 +39,25 @@ lazy_static! {
 
 /// Load the config from the environment.
 fn load() -> Result<Config, Error> {
-    let _ = dotenv().map_err(|err| Error::Misc(format!("Failed to load .env: {}", err)))?;
+    // Without a `.env` file, options are taken from the environment or their default value.
+    match dotenv() {
+        Ok(_) => {}
+        Err(dotenv::Error::Io(err)) if err.kind() == std::io::ErrorKind::NotFound => {}
+        Err(err) => return Err(Error::Misc(format!("Failed to load .env: {}", err))),
+    }
     let config = Config {
         debug_urls: dotenv::var("ORNAGUIDERS_DEBUG_URLS")
             .unwrap_or_else(|_| "false".to_string())
             .parse()?,
+        http_max_attempts: dotenv::var("ORNAGUIDERS_HTTP_MAX_ATTEMPTS")
+            .unwrap_or_else(|_| "3".to_string())
+            .parse()?,
+        http_retry_delay: dotenv::var("ORNAGUIDERS_HTTP_RETRY_DELAY")
+            .unwrap_or_else(|_| "500".to_string())
+            .parse()?,
+        validate_forms: dotenv::var("ORNAGUIDERS_VALIDATE_FORMS")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()?,
     };
 
     Ok(config)
@@ -53,3 +81,18 @@ where
 pub fn debug_urls() -> Result<bool, Error> {
     with_config(|config| Ok(config.debug_urls))
 }
+
+/// Return the `http_max_attempts` config value.
+pub fn http_max_attempts() -> Result<u32, Error> {
+    with_config(|config| Ok(config.http_max_attempts))
+}
+
+/// Return the `http_retry_delay` config value.
+pub fn http_retry_delay() -> Result<u64, Error> {
+    with_config(|config| Ok(config.http_retry_delay))
+}
+
+/// Return the `validate_forms` config value.
+pub fn validate_forms() -> Result<bool, Error> {
+    with_config(|config| Ok(config.validate_forms))
+}
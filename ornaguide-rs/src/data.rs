@@ -3,15 +3,31 @@ use std::{
     io::{BufReader, Write},
 };
 
-use crate::{error::Error, guide::Static, monsters::admin::AdminMonster};
+use crate::{
+    codex::{CodexItem, CodexSkill},
+    error::Error,
+    guide::Static,
+    items::admin::AdminItem,
+    monsters::admin::AdminMonster,
+    skills::admin::AdminSkill,
+};
 
 mod codex_data;
+mod codex_entity;
 mod codex_generic_monster;
+mod delta;
+mod diff;
 mod guide_data;
+mod match_ref;
 
+use codex_data::codex_json_path;
 pub use codex_data::CodexData;
+pub use codex_entity::CodexEntity;
 pub use codex_generic_monster::CodexGenericMonster;
-pub use guide_data::GuideData;
+pub use delta::{EntityDelta, OrnaDelta};
+pub use diff::{ChangedEntity, DataDiff, EntityDiff, EntityRef, FieldChange};
+pub use guide_data::{GuideData, MatchKind};
+pub use match_ref::MatchRef;
 
 /// Aggregate for both the codex and the guide data.
 #[derive(Clone, Default, PartialEq)]
@@ -25,33 +41,23 @@ pub struct OrnaData {
 impl OrnaData {
     /// Load data from a set of json files located in the given directory.
     pub fn load_from(directory: &str) -> Result<Self, Error> {
+        Self::load_with(directory, CodexData::load_from)
+    }
+
+    /// Load data from a set of json files located in the given directory. The codex data is
+    /// loaded leniently (see `CodexData::load_from_lenient`).
+    pub fn load_from_lenient(directory: &str) -> Result<Self, Error> {
+        Self::load_with(directory, CodexData::load_from_lenient)
+    }
+
+    /// Load data from a set of json files located in the given directory, loading the codex data
+    /// with `load_codex`.
+    fn load_with(
+        directory: &str,
+        load_codex: fn(&str) -> Result<CodexData, Error>,
+    ) -> Result<Self, Error> {
         Ok(OrnaData {
-            codex: CodexData {
-                items: serde_json::from_reader(BufReader::new(File::open(format!(
-                    "{}/codex_items.json",
-                    directory
-                ))?))?,
-                raids: serde_json::from_reader(BufReader::new(File::open(format!(
-                    "{}/codex_raids.json",
-                    directory
-                ))?))?,
-                monsters: serde_json::from_reader(BufReader::new(File::open(format!(
-                    "{}/codex_monsters.json",
-                    directory
-                ))?))?,
-                bosses: serde_json::from_reader(BufReader::new(File::open(format!(
-                    "{}/codex_bosses.json",
-                    directory
-                ))?))?,
-                skills: serde_json::from_reader(BufReader::new(File::open(format!(
-                    "{}/codex_skills.json",
-                    directory
-                ))?))?,
-                followers: serde_json::from_reader(BufReader::new(File::open(format!(
-                    "{}/codex_followers.json",
-                    directory
-                ))?))?,
-            },
+            codex: load_codex(directory)?,
             guide: GuideData {
                 items: serde_json::from_reader(BufReader::new(File::open(format!(
                     "{}/guide_items.json",
@@ -110,22 +116,22 @@ impl OrnaData {
         Writer: FnMut(&str, &dyn Fn(&mut dyn Write) -> Result<(), Error>) -> Result<(), Error>,
     {
         // Codex jsons
-        writer(&format!("{}/codex_items.json", directory), &|out| {
+        writer(&codex_json_path(directory, "items"), &|out| {
             serde_json::to_writer_pretty(out, &self.codex.items).map_err(Error::from)
         })?;
-        writer(&format!("{}/codex_raids.json", directory), &|out| {
+        writer(&codex_json_path(directory, "raids"), &|out| {
             serde_json::to_writer_pretty(out, &self.codex.raids).map_err(Error::from)
         })?;
-        writer(&format!("{}/codex_monsters.json", directory), &|out| {
+        writer(&codex_json_path(directory, "monsters"), &|out| {
             serde_json::to_writer_pretty(out, &self.codex.monsters).map_err(Error::from)
         })?;
-        writer(&format!("{}/codex_bosses.json", directory), &|out| {
+        writer(&codex_json_path(directory, "bosses"), &|out| {
             serde_json::to_writer_pretty(out, &self.codex.bosses).map_err(Error::from)
         })?;
-        writer(&format!("{}/codex_skills.json", directory), &|out| {
+        writer(&codex_json_path(directory, "skills"), &|out| {
             serde_json::to_writer_pretty(out, &self.codex.skills).map_err(Error::from)
         })?;
-        writer(&format!("{}/codex_followers.json", directory), &|out| {
+        writer(&codex_json_path(directory, "followers"), &|out| {
             serde_json::to_writer_pretty(out, &self.codex.followers).map_err(Error::from)
         })?;
 
@@ -190,6 +196,68 @@ impl OrnaData {
         })
     }
 
+    /// Find the guide item with the given name. See `GuideData::find_item_by_name`.
+    pub fn find_item_by_name(&self, name: &str) -> Option<&AdminItem> {
+        self.guide.find_item_by_name(name)
+    }
+
+    /// Find the guide item with the given name, case-insensitively or by slug. See
+    /// `GuideData::find_item_by_name_ci`.
+    pub fn find_item_by_name_ci(&self, name: &str) -> Option<&AdminItem> {
+        self.guide.find_item_by_name_ci(name)
+    }
+
+    /// Find every guide and codex entity whose name matches `name` (case-insensitively), across
+    /// all categories. Guide entities come first.
+    pub fn find_by_name(&self, name: &str) -> Vec<MatchRef<'_>> {
+        let name = name.to_lowercase();
+        let guide = &self.guide;
+        guide
+            .items
+            .items
+            .iter()
+            .map(MatchRef::GuideItem)
+            .chain(guide.monsters.monsters.iter().map(MatchRef::GuideMonster))
+            .chain(guide.skills.skills.iter().map(MatchRef::GuideSkill))
+            .chain(guide.pets.pets.iter().map(MatchRef::GuidePet))
+            .chain(self.codex.iter_all().map(MatchRef::Codex))
+            .filter(|entity| entity.name().to_lowercase() == name)
+            .collect()
+    }
+
+    /// Iterate over codex items along with their matching guide item.
+    /// Codex items that have no counterpart on the guide are skipped.
+    pub fn item_pairs(&self) -> impl Iterator<Item = (&CodexItem, &AdminItem)> {
+        self.codex.items.items.iter().filter_map(|codex_item| {
+            self.guide
+                .items
+                .find_by_slug(&codex_item.slug)
+                .map(|guide_item| (codex_item, guide_item))
+        })
+    }
+
+    /// Iterate over codex skills along with their matching guide skill.
+    /// Codex skills that have no counterpart on the guide are skipped.
+    pub fn skill_pairs(&self) -> impl Iterator<Item = (&CodexSkill, &AdminSkill)> {
+        self.codex.skills.skills.iter().filter_map(|codex_skill| {
+            self.guide
+                .skills
+                .find_by_slug(&codex_skill.slug)
+                .map(|guide_skill| (codex_skill, guide_skill))
+        })
+    }
+
+    /// Iterate over codex monsters, bosses and raids along with their matching guide monster.
+    /// Codex monsters that have no counterpart on the guide are skipped.
+    pub fn monster_pairs(&self) -> impl Iterator<Item = (CodexGenericMonster<'_>, &AdminMonster)> {
+        self.codex.iter_all_monsters().filter_map(|codex_monster| {
+            self.guide
+                .monsters
+                .find_by_uri(&codex_monster.uri())
+                .map(|guide_monster| (codex_monster, guide_monster))
+        })
+    }
+
     /// Find which monster/boss/raid in the codex corresponds to the given admin monster.
     pub fn find_generic_codex_monster_from_admin_monster<'a>(
         &'a self,
@@ -250,3 +318,93 @@ impl OrnaData {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        codex::{CodexItem, CodexSkill},
+        data::{MatchRef, OrnaData},
+        items::admin::AdminItem,
+        skills::admin::AdminSkill,
+    };
+
+    fn codex_item(slug: &str, name: &str) -> CodexItem {
+        CodexItem {
+            slug: slug.to_string(),
+            name: name.to_string(),
+            tier: 5,
+            ..CodexItem::default()
+        }
+    }
+
+    #[test]
+    fn test_pairs() {
+        let mut data = OrnaData::default();
+        data.guide.items.items = vec![
+            AdminItem {
+                id: 1,
+                codex_uri: "/codex/items/sword/".to_string(),
+                ..AdminItem::default()
+            },
+            AdminItem {
+                id: 2,
+                codex_uri: "/codex/items/not-on-codex/".to_string(),
+                ..AdminItem::default()
+            },
+        ];
+        data.codex.items.items = vec![
+            codex_item("sword", "Sword"),
+            codex_item("not-on-guide", "Not on guide"),
+        ];
+        data.guide.skills.skills = vec![AdminSkill {
+            id: 3,
+            codex_uri: "/codex/spells/fireball/".to_string(),
+            ..AdminSkill::default()
+        }];
+        data.codex.skills.skills = vec![CodexSkill {
+            slug: "fireball".to_string(),
+            ..CodexSkill::default()
+        }];
+
+        let item_pairs = data
+            .item_pairs()
+            .map(|(codex, guide)| (codex.slug.as_str(), guide.id))
+            .collect::<Vec<_>>();
+        assert_eq!(item_pairs, vec![("sword", 1)]);
+        let skill_pairs = data
+            .skill_pairs()
+            .map(|(codex, guide)| (codex.slug.as_str(), guide.id))
+            .collect::<Vec<_>>();
+        assert_eq!(skill_pairs, vec![("fireball", 3)]);
+        assert_eq!(data.monster_pairs().count(), 0);
+    }
+
+    #[test]
+    fn test_find_by_name() {
+        let mut data = OrnaData::default();
+        data.guide.items.items = vec![
+            AdminItem {
+                id: 1,
+                name: "Fire Sword".to_string(),
+                ..AdminItem::default()
+            },
+            AdminItem {
+                id: 2,
+                name: "Water Sword".to_string(),
+                ..AdminItem::default()
+            },
+        ];
+        data.codex.items.items = vec![codex_item("fire-sword", "Fire Sword")];
+
+        let matches = data.find_by_name("fire sword");
+        assert_eq!(matches.len(), 2);
+        assert!(matches!(matches[0], MatchRef::GuideItem(item) if item.id == 1));
+        assert!(matches[0].is_guide());
+        assert!(!matches[1].is_guide());
+        assert_eq!(
+            matches[1].to_string(),
+            "[Codex item] Fire Sword (/codex/items/fire-sword/)"
+        );
+        assert!(data.find_by_name("Sword").is_empty());
+    }
+}
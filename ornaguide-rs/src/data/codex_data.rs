@@ -1,6 +1,12 @@
+use std::{fs::File, io::BufReader};
+
 use crate::{
-    codex::{CodexBosses, CodexFollowers, CodexItems, CodexMonsters, CodexRaids, CodexSkills},
-    data::CodexGenericMonster,
+    codex::{
+        lenient::load_entities_lenient, parse_generic_monster_uri, CodexBosses, CodexFollowers,
+        CodexItems, CodexMonsters, CodexRaids, CodexSkills,
+    },
+    data::{CodexEntity, CodexGenericMonster},
+    error::Error,
 };
 
 /// Aggregate for codex data.
@@ -20,47 +26,89 @@ pub struct CodexData {
     pub followers: CodexFollowers,
 }
 
+/// Path to the `codex_{kind}.json` file of the given directory.
+pub(crate) fn codex_json_path(directory: &str, kind: &str) -> String {
+    format!("{}/codex_{}.json", directory, kind)
+}
+
 impl<'a> CodexData {
+    /// Load the codex data from the `codex_*.json` files of the given directory.
+    pub fn load_from(directory: &str) -> Result<Self, Error> {
+        let open = |kind: &str| -> Result<_, Error> {
+            Ok(BufReader::new(File::open(codex_json_path(
+                directory, kind,
+            ))?))
+        };
+        Ok(CodexData {
+            items: serde_json::from_reader(open("items")?)?,
+            raids: serde_json::from_reader(open("raids")?)?,
+            monsters: serde_json::from_reader(open("monsters")?)?,
+            bosses: serde_json::from_reader(open("bosses")?)?,
+            skills: serde_json::from_reader(open("skills")?)?,
+            followers: serde_json::from_reader(open("followers")?)?,
+        })
+    }
+
+    /// Load the codex data from the `codex_*.json` files of the given directory, leniently.
+    /// Fields of the entities we do not know of are printed rather than ignored, even if the
+    /// `strict-codex` feature is enabled.
+    pub fn load_from_lenient(directory: &str) -> Result<Self, Error> {
+        let path = |kind: &str| codex_json_path(directory, kind);
+        Ok(CodexData {
+            items: CodexItems {
+                items: load_entities_lenient(&path("items"), "items")?,
+            },
+            raids: CodexRaids {
+                raids: load_entities_lenient(&path("raids"), "raids")?,
+            },
+            monsters: CodexMonsters {
+                monsters: load_entities_lenient(&path("monsters"), "monsters")?,
+            },
+            bosses: CodexBosses {
+                bosses: load_entities_lenient(&path("bosses"), "bosses")?,
+            },
+            skills: CodexSkills {
+                skills: load_entities_lenient(&path("skills"), "skills")?,
+            },
+            followers: CodexFollowers {
+                followers: load_entities_lenient(&path("followers"), "followers")?,
+            },
+        })
+    }
+
     /// Find which monster/boss/raid corresponds to the given URI.
     /// The URI must be of the form `/codex/{kind}/{slug}/` or empty.
-    pub fn find_generic_monster_from_uri(&'a self, uri: &str) -> Option<CodexGenericMonster<'a>> {
+    /// Returns `Ok(None)` if the URI is empty or no entity matches it, and an error if the URI is
+    /// malformed.
+    pub fn find_generic_monster_from_uri(
+        &'a self,
+        uri: &str,
+    ) -> Result<Option<CodexGenericMonster<'a>>, Error> {
         if uri.is_empty() {
-            return None;
+            return Ok(None);
         }
 
-        // Strip `/codex/` and trailing slash from the uri.
-        let uri = uri[7..].trim_end_matches('/');
-        if let Some(pos) = uri.find('/') {
-            let kind = &uri[0..pos];
-            let slug = &uri[pos + 1..];
-            match kind {
-                "monsters" => self
-                    .monsters
-                    .monsters
-                    .iter()
-                    .find(|monster| monster.slug == slug)
-                    .map(CodexGenericMonster::Monster),
-                "bosses" => self
-                    .bosses
-                    .bosses
-                    .iter()
-                    .find(|boss| boss.slug == slug)
-                    .map(CodexGenericMonster::Boss),
-                "raids" => self
-                    .raids
-                    .raids
-                    .iter()
-                    .find(|raid| raid.slug == slug)
-                    .map(CodexGenericMonster::Raid),
-                _ => {
-                    println!("Unknown kind for generic monster {}", uri);
-                    None
-                }
-            }
-        } else {
-            println!("Failed to find generic monster for {}", uri);
-            None
-        }
+        let (kind, slug) = parse_generic_monster_uri(uri)?;
+        Ok(match kind {
+            "monsters" => self
+                .monsters
+                .monsters
+                .iter()
+                .find(|monster| monster.slug == slug)
+                .map(CodexGenericMonster::Monster),
+            "bosses" => self
+                .bosses
+                .bosses
+                .iter()
+                .find(|boss| boss.slug == slug)
+                .map(CodexGenericMonster::Boss),
+            _ => self
+                .raids
+                .raids
+                .iter()
+                .find(|raid| raid.slug == slug)
+                .map(CodexGenericMonster::Raid),
+        })
     }
 
     /// Return an iterator over all monsters / bosses / raids, wrapped in the
@@ -76,4 +124,128 @@ impl<'a> CodexData {
             // List raids, wrap them in the same generic type and chain the iterators.
             .chain(self.raids.raids.iter().map(CodexGenericMonster::Raid))
     }
+
+    /// Return an iterator over all entities of the codex (items, skills, followers, monsters,
+    /// bosses and raids), wrapped in the `CodexEntity` enum.
+    pub fn iter_all(&'a self) -> impl Iterator<Item = CodexEntity<'a>> {
+        self.items
+            .items
+            .iter()
+            .map(CodexEntity::Item)
+            .chain(self.skills.skills.iter().map(CodexEntity::Skill))
+            .chain(self.followers.followers.iter().map(CodexEntity::Follower))
+            .chain(self.iter_all_monsters().map(CodexEntity::Monster))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        codex::{CodexFollower, CodexItem, CodexMonster, CodexRaid, CodexSkill},
+        data::CodexData,
+    };
+
+    fn item(slug: &str) -> CodexItem {
+        CodexItem {
+            slug: slug.to_string(),
+            name: slug.to_string(),
+            tier: 1,
+            ..CodexItem::default()
+        }
+    }
+
+    fn follower(slug: &str) -> CodexFollower {
+        CodexFollower {
+            name: slug.to_string(),
+            slug: slug.to_string(),
+            icon: String::new(),
+            description: String::new(),
+            events: vec![],
+            rarity: String::new(),
+            tier: 1,
+            abilities: vec![],
+        }
+    }
+
+    fn monster(slug: &str) -> CodexMonster {
+        CodexMonster {
+            slug: slug.to_string(),
+            name: slug.to_string(),
+            icon: String::new(),
+            events: vec![],
+            family: String::new(),
+            rarity: String::new(),
+            tier: 1,
+            abilities: vec![],
+            drops: vec![],
+            spawns: vec![],
+        }
+    }
+
+    fn raid(slug: &str) -> CodexRaid {
+        CodexRaid {
+            slug: slug.to_string(),
+            name: slug.to_string(),
+            description: String::new(),
+            icon: String::new(),
+            events: vec![],
+            tier: 7,
+            tags: vec![],
+            abilities: vec![],
+            drops: vec![],
+        }
+    }
+
+    #[test]
+    fn test_find_generic_monster_from_uri() {
+        let mut data = CodexData::default();
+        data.monsters.monsters = vec![monster("slime")];
+        data.raids.raids = vec![raid("balor")];
+
+        let find = |uri| {
+            data.find_generic_monster_from_uri(uri)
+                .map(|found| found.map(|monster| monster.uri()))
+        };
+        assert_eq!(
+            find("/codex/monsters/slime/").unwrap().as_deref(),
+            Some("/codex/monsters/slime/")
+        );
+        assert_eq!(
+            find("/codex/raids/balor/").unwrap().as_deref(),
+            Some("/codex/raids/balor/")
+        );
+        assert_eq!(find("/codex/bosses/balor/").unwrap(), None);
+        assert_eq!(find("").unwrap(), None);
+        assert!(find("/codex/items/sword/").is_err());
+        assert!(find("/codex/monsters").is_err());
+    }
+
+    #[test]
+    fn test_iter_all() {
+        let mut data = CodexData::default();
+        data.items.items = vec![item("sword"), item("dagger")];
+        data.skills.skills = vec![CodexSkill {
+            name: "Fireball".to_string(),
+            slug: "fireball".to_string(),
+            ..CodexSkill::default()
+        }];
+        data.followers.followers = vec![follower("bat")];
+        data.monsters.monsters = vec![monster("slime")];
+        data.raids.raids = vec![raid("balor")];
+
+        assert_eq!(data.iter_all().count(), 6);
+        assert_eq!(
+            data.iter_all()
+                .map(|entity| entity.uri())
+                .collect::<Vec<_>>(),
+            vec![
+                "/codex/items/sword/",
+                "/codex/items/dagger/",
+                "/codex/spells/fireball/",
+                "/codex/followers/bat/",
+                "/codex/monsters/slime/",
+                "/codex/raids/balor/",
+            ]
+        );
+    }
 }
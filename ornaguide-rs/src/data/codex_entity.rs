@@ -0,0 +1,60 @@
+use crate::{
+    codex::{CodexFollower, CodexItem, CodexSkill},
+    data::CodexGenericMonster,
+};
+
+/// Any entity from the codex, be it an item, a skill, a follower or a monster.
+#[derive(Debug, Clone, Copy)]
+pub enum CodexEntity<'a> {
+    /// An item.
+    Item(&'a CodexItem),
+    /// A skill.
+    Skill(&'a CodexSkill),
+    /// A follower.
+    Follower(&'a CodexFollower),
+    /// A monster, boss or raid.
+    Monster(CodexGenericMonster<'a>),
+}
+
+impl<'a> CodexEntity<'a> {
+    /// Return the URI of the entity.
+    /// URI matches `/codex/{kind}/{slug}/`.
+    pub fn uri(&self) -> String {
+        match self {
+            CodexEntity::Item(x) => format!("/codex/items/{}/", x.slug),
+            CodexEntity::Skill(x) => format!("/codex/spells/{}/", x.slug),
+            CodexEntity::Follower(x) => format!("/codex/followers/{}/", x.slug),
+            CodexEntity::Monster(x) => x.uri(),
+        }
+    }
+
+    /// Return the slug of the entity.
+    pub fn slug(&self) -> &'a str {
+        match self {
+            CodexEntity::Item(x) => &x.slug,
+            CodexEntity::Skill(x) => &x.slug,
+            CodexEntity::Follower(x) => &x.slug,
+            CodexEntity::Monster(x) => x.slug(),
+        }
+    }
+
+    /// Return the name of the entity.
+    pub fn name(&self) -> &'a String {
+        match self {
+            CodexEntity::Item(x) => &x.name,
+            CodexEntity::Skill(x) => &x.name,
+            CodexEntity::Follower(x) => &x.name,
+            CodexEntity::Monster(x) => x.name(),
+        }
+    }
+
+    /// Return the icon of the entity.
+    pub fn icon(&self) -> &'a String {
+        match self {
+            CodexEntity::Item(x) => &x.icon,
+            CodexEntity::Skill(x) => &x.icon,
+            CodexEntity::Follower(x) => &x.icon,
+            CodexEntity::Monster(x) => x.icon(),
+        }
+    }
+}
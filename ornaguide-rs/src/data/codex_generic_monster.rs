@@ -32,7 +32,7 @@ impl<'a> CodexGenericMonster<'a> {
     }
 
     // Return the slug of the monster.
-    pub fn slug(&self) -> &str {
+    pub fn slug(&self) -> &'a str {
         match self {
             CodexGenericMonster::Monster(x) => &x.slug,
             CodexGenericMonster::Boss(x) => &x.slug,
@@ -104,8 +104,21 @@ impl<'a> CodexGenericMonster<'a> {
         }
     }
 
-    /// Return the tags attached to the monster as guide spawns
-    pub fn tags_as_guide_spawns(&self) -> Vec<&'static str> {
+    /// Return the locations in which the monster spawns.
+    /// Raids have no such locations; see `tags_as_guide_spawns`.
+    pub fn spawns(&self) -> &'a Vec<String> {
+        static EMPTY_VEC: Vec<String> = Vec::new();
+        match self {
+            CodexGenericMonster::Monster(x) => &x.spawns,
+            CodexGenericMonster::Boss(x) => &x.spawns,
+            CodexGenericMonster::Raid(_) => &EMPTY_VEC,
+        }
+    }
+
+    /// Return the tags attached to the monster as guide spawns, along with the locations in which
+    /// the monster spawns.
+    /// Tags that do not correspond to a spawn on the guide are ignored.
+    pub fn tags_as_guide_spawns(&self) -> Vec<&'a str> {
         static WRB_STR: &str = "World Raid";
         static KRB_STR: &str = "Kingdom Raid";
         self.tags()
@@ -114,10 +127,11 @@ impl<'a> CodexGenericMonster<'a> {
                 Tag::WorldRaid => Some(WRB_STR),
                 Tag::KingdomRaid => Some(KRB_STR),
                 // TODO(ethiraric, 27/07/2022): Include Other Realm Raid as a spawn?
-                Tag::OtherRealmsRaid => None,
-                _ => panic!("Unknown tag {:?} for monster", tag),
+                _ => None,
             })
+            .chain(self.spawns().iter().map(String::as_str))
             .sorted()
+            .dedup()
             .collect()
     }
 
@@ -0,0 +1,231 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    codex::{CodexBoss, CodexFollower, CodexItem, CodexMonster, CodexRaid, CodexSkill},
+    data::OrnaData,
+    items::admin::AdminItem,
+    monsters::admin::AdminMonster,
+    pets::admin::AdminPet,
+    skills::admin::AdminSkill,
+};
+
+/// The changes to a collection of entities between 2 versions of the data.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct EntityDelta<T, K> {
+    /// Entities that are only in the newer data.
+    pub added: Vec<T>,
+    /// Entities whose contents differ. Their newer version is stored.
+    pub changed: Vec<T>,
+    /// Keys of the entities that are only in the older data.
+    /// Guide entities are identified by their id, codex entities by their slug.
+    pub removed: Vec<K>,
+}
+
+impl<T, K> Default for EntityDelta<T, K> {
+    fn default() -> Self {
+        Self {
+            added: Vec::new(),
+            changed: Vec::new(),
+            removed: Vec::new(),
+        }
+    }
+}
+
+impl<T, K> EntityDelta<T, K> {
+    /// Return whether nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl<T, K> EntityDelta<T, K>
+where
+    T: Clone + PartialEq,
+    K: Clone + Eq + Hash,
+{
+    /// Compute the delta between `old` and `new`, identifying entities with `key`.
+    /// Entities are reported in the order in which they appear in `new` (`old` for removed ones).
+    fn compute<F>(old: &[T], new: &[T], key: F) -> Self
+    where
+        F: Fn(&T) -> K,
+    {
+        let old_by_key = old
+            .iter()
+            .map(|entity| (key(entity), entity))
+            .collect::<HashMap<_, _>>();
+        let mut new_keys = HashSet::new();
+
+        let mut delta = Self::default();
+        for entity in new.iter() {
+            let entity_key = key(entity);
+            match old_by_key.get(&entity_key) {
+                None => delta.added.push(entity.clone()),
+                Some(old_entity) if *old_entity != entity => delta.changed.push(entity.clone()),
+                Some(_) => {}
+            }
+            new_keys.insert(entity_key);
+        }
+        delta.removed = old
+            .iter()
+            .map(&key)
+            .filter(|old_key| !new_keys.contains(old_key))
+            .collect();
+        delta
+    }
+}
+
+/// The changes between 2 versions of `OrnaData`, per category of entities.
+/// Serializes to JSON so that consumers can sync only what changed.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct OrnaDelta {
+    /// Changes to the guide items.
+    pub guide_items: EntityDelta<AdminItem, u32>,
+    /// Changes to the guide monsters.
+    pub guide_monsters: EntityDelta<AdminMonster, u32>,
+    /// Changes to the guide skills.
+    pub guide_skills: EntityDelta<AdminSkill, u32>,
+    /// Changes to the guide pets.
+    pub guide_pets: EntityDelta<AdminPet, u32>,
+    /// Changes to the codex items.
+    pub codex_items: EntityDelta<CodexItem, String>,
+    /// Changes to the codex monsters.
+    pub codex_monsters: EntityDelta<CodexMonster, String>,
+    /// Changes to the codex bosses.
+    pub codex_bosses: EntityDelta<CodexBoss, String>,
+    /// Changes to the codex raids.
+    pub codex_raids: EntityDelta<CodexRaid, String>,
+    /// Changes to the codex skills.
+    pub codex_skills: EntityDelta<CodexSkill, String>,
+    /// Changes to the codex followers.
+    pub codex_followers: EntityDelta<CodexFollower, String>,
+}
+
+impl OrnaDelta {
+    /// Return whether nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.guide_items.is_empty()
+            && self.guide_monsters.is_empty()
+            && self.guide_skills.is_empty()
+            && self.guide_pets.is_empty()
+            && self.codex_items.is_empty()
+            && self.codex_monsters.is_empty()
+            && self.codex_bosses.is_empty()
+            && self.codex_raids.is_empty()
+            && self.codex_skills.is_empty()
+            && self.codex_followers.is_empty()
+    }
+}
+
+impl OrnaData {
+    /// Compute the entities that were added, changed or removed since `old`.
+    /// Static data from the guide is not included.
+    pub fn delta_since(&self, old: &OrnaData) -> OrnaDelta {
+        OrnaDelta {
+            guide_items: EntityDelta::compute(
+                &old.guide.items.items,
+                &self.guide.items.items,
+                |item| item.id,
+            ),
+            guide_monsters: EntityDelta::compute(
+                &old.guide.monsters.monsters,
+                &self.guide.monsters.monsters,
+                |monster| monster.id,
+            ),
+            guide_skills: EntityDelta::compute(
+                &old.guide.skills.skills,
+                &self.guide.skills.skills,
+                |skill| skill.id,
+            ),
+            guide_pets: EntityDelta::compute(&old.guide.pets.pets, &self.guide.pets.pets, |pet| {
+                pet.id
+            }),
+            codex_items: EntityDelta::compute(
+                &old.codex.items.items,
+                &self.codex.items.items,
+                |item| item.slug.clone(),
+            ),
+            codex_monsters: EntityDelta::compute(
+                &old.codex.monsters.monsters,
+                &self.codex.monsters.monsters,
+                |monster| monster.slug.clone(),
+            ),
+            codex_bosses: EntityDelta::compute(
+                &old.codex.bosses.bosses,
+                &self.codex.bosses.bosses,
+                |boss| boss.slug.clone(),
+            ),
+            codex_raids: EntityDelta::compute(
+                &old.codex.raids.raids,
+                &self.codex.raids.raids,
+                |raid| raid.slug.clone(),
+            ),
+            codex_skills: EntityDelta::compute(
+                &old.codex.skills.skills,
+                &self.codex.skills.skills,
+                |skill| skill.slug.clone(),
+            ),
+            codex_followers: EntityDelta::compute(
+                &old.codex.followers.followers,
+                &self.codex.followers.followers,
+                |follower| follower.slug.clone(),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{data::OrnaData, items::admin::AdminItem, monsters::admin::AdminMonster};
+
+    #[test]
+    fn test_delta_since() {
+        let mut old = OrnaData::default();
+        old.guide.items.items = vec![
+            AdminItem {
+                id: 1,
+                name: "Sword".to_string(),
+                tier: 1,
+                ..AdminItem::default()
+            },
+            AdminItem {
+                id: 2,
+                name: "Shield".to_string(),
+                ..AdminItem::default()
+            },
+        ];
+        assert!(old.delta_since(&old).is_empty());
+
+        let mut new = old.clone();
+        new.guide.items.items[0].tier = 2;
+        new.guide.monsters.monsters.push(AdminMonster {
+            id: 3,
+            name: "Slime".to_string(),
+            ..AdminMonster::default()
+        });
+
+        let delta = new.delta_since(&old);
+        assert!(delta.guide_items.added.is_empty());
+        assert_eq!(
+            delta.guide_items.changed,
+            vec![new.guide.items.items[0].clone()]
+        );
+        assert!(delta.guide_items.removed.is_empty());
+        assert_eq!(
+            delta.guide_monsters.added,
+            vec![new.guide.monsters.monsters[0].clone()]
+        );
+        assert!(delta.guide_monsters.changed.is_empty());
+        assert!(delta.codex_items.is_empty());
+
+        // Going backwards, the monster is removed.
+        assert_eq!(old.delta_since(&new).guide_monsters.removed, vec![3]);
+
+        let json = serde_json::to_string(&delta).unwrap();
+        assert!(json.contains("\"Slime\""));
+    }
+}
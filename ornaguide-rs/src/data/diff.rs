@@ -0,0 +1,284 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    data::OrnaData, items::admin::AdminItem, monsters::admin::AdminMonster, pets::admin::AdminPet,
+    skills::admin::AdminSkill,
+};
+
+/// A reference to a guide entity, by id and name.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EntityRef {
+    /// Id of the entity on the guide.
+    pub id: u32,
+    /// Name of the entity on the guide.
+    pub name: String,
+}
+
+/// A field whose value differs between 2 versions of an entity.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct FieldChange {
+    /// The name of the field, as serialized.
+    pub field: String,
+    /// The value in the older data. `null` if the field is absent.
+    pub old: Value,
+    /// The value in the newer data. `null` if the field is absent.
+    pub new: Value,
+}
+
+/// A guide entity whose contents differ between 2 versions of the data.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ChangedEntity {
+    /// Id of the entity on the guide.
+    pub id: u32,
+    /// Name of the entity in the newer data.
+    pub name: String,
+    /// The fields that differ, in the order in which they are serialized.
+    pub changes: Vec<FieldChange>,
+}
+
+/// The differences in a collection of guide entities between 2 versions of the data.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct EntityDiff {
+    /// Entities that are only in the newer data.
+    pub added: Vec<EntityRef>,
+    /// Entities that are only in the older data.
+    pub removed: Vec<EntityRef>,
+    /// Entities that are in both, with the fields that differ.
+    pub changed: Vec<ChangedEntity>,
+}
+
+impl EntityDiff {
+    /// Return whether nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Compute the differences between `old` and `new`.
+    /// `find_old` and `find_new` look an entity up by id in `old` and `new` respectively. `eq`
+    /// tells whether 2 versions of an entity are to be considered the same.
+    fn compute<'a, T, FO, FN>(
+        old: &'a [T],
+        new: &'a [T],
+        find_old: FO,
+        find_new: FN,
+        id_name: fn(&T) -> (u32, &str),
+        eq: fn(&T, &T) -> bool,
+    ) -> Self
+    where
+        T: Serialize + 'a,
+        FO: Fn(u32) -> Option<&'a T>,
+        FN: Fn(u32) -> Option<&'a T>,
+    {
+        let entity_ref = |entity: &T| {
+            let (id, name) = id_name(entity);
+            EntityRef {
+                id,
+                name: name.to_string(),
+            }
+        };
+
+        let mut diff = Self::default();
+        for entity in new.iter() {
+            let (id, name) = id_name(entity);
+            match find_old(id) {
+                None => diff.added.push(entity_ref(entity)),
+                Some(old_entity) if !eq(old_entity, entity) => {
+                    let changes = field_changes(old_entity, entity);
+                    if !changes.is_empty() {
+                        diff.changed.push(ChangedEntity {
+                            id,
+                            name: name.to_string(),
+                            changes,
+                        });
+                    }
+                }
+                Some(_) => {}
+            }
+        }
+        diff.removed = old
+            .iter()
+            .filter(|entity| find_new(id_name(entity).0).is_none())
+            .map(entity_ref)
+            .collect();
+        diff
+    }
+}
+
+/// List the serialized fields whose values differ between `old` and `new`.
+fn field_changes<T: Serialize>(old: &T, new: &T) -> Vec<FieldChange> {
+    let to_object = |entity: &T| match serde_json::to_value(entity) {
+        Ok(Value::Object(object)) => object,
+        _ => serde_json::Map::new(),
+    };
+    let old = to_object(old);
+    let new = to_object(new);
+
+    let mut changes = new
+        .iter()
+        .filter(|(field, value)| old.get(*field).unwrap_or(&Value::Null) != *value)
+        .map(|(field, value)| FieldChange {
+            field: field.clone(),
+            old: old.get(field).cloned().unwrap_or(Value::Null),
+            new: value.clone(),
+        })
+        .collect::<Vec<_>>();
+    changes.extend(
+        old.iter()
+            .filter(|(field, value)| !new.contains_key(*field) && !value.is_null())
+            .map(|(field, value)| FieldChange {
+                field: field.clone(),
+                old: value.clone(),
+                new: Value::Null,
+            }),
+    );
+    changes
+}
+
+/// The differences in guide entities between 2 versions of `OrnaData`.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct DataDiff {
+    /// Differences in the guide items.
+    pub items: EntityDiff,
+    /// Differences in the guide monsters.
+    pub monsters: EntityDiff,
+    /// Differences in the guide skills.
+    pub skills: EntityDiff,
+    /// Differences in the guide pets.
+    pub pets: EntityDiff,
+}
+
+impl DataDiff {
+    /// Return whether nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+            && self.monsters.is_empty()
+            && self.skills.is_empty()
+            && self.pets.is_empty()
+    }
+}
+
+impl OrnaData {
+    /// Compute the differences in guide entities from `self` to `other`.
+    /// `self` is considered the older data: entities only in `other` are reported as added.
+    /// Unlike `delta_since`, changed entities are listed with the fields that differ rather than
+    /// with their newer version.
+    pub fn diff(&self, other: &OrnaData) -> DataDiff {
+        let (old, new) = (&self.guide, &other.guide);
+        DataDiff {
+            items: EntityDiff::compute(
+                &old.items.items,
+                &new.items.items,
+                |id| old.items.find_by_id(id),
+                |id| new.items.find_by_id(id),
+                |item: &AdminItem| (item.id, item.name.as_str()),
+                AdminItem::semantically_eq,
+            ),
+            monsters: EntityDiff::compute(
+                &old.monsters.monsters,
+                &new.monsters.monsters,
+                |id| old.monsters.find_by_id(id),
+                |id| new.monsters.find_by_id(id),
+                |monster: &AdminMonster| (monster.id, monster.name.as_str()),
+                AdminMonster::eq,
+            ),
+            skills: EntityDiff::compute(
+                &old.skills.skills,
+                &new.skills.skills,
+                |id| old.skills.find_by_id(id),
+                |id| new.skills.find_by_id(id),
+                |skill: &AdminSkill| (skill.id, skill.name.as_str()),
+                AdminSkill::eq,
+            ),
+            pets: EntityDiff::compute(
+                &old.pets.pets,
+                &new.pets.pets,
+                |id| old.pets.find_by_id(id),
+                |id| new.pets.find_by_id(id),
+                |pet: &AdminPet| (pet.id, pet.name.as_str()),
+                AdminPet::eq,
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use crate::{
+        data::{
+            diff::{EntityRef, FieldChange},
+            OrnaData,
+        },
+        items::admin::AdminItem,
+        monsters::admin::AdminMonster,
+        skills::admin::AdminSkill,
+    };
+
+    #[test]
+    fn test_diff() {
+        let mut old = OrnaData::default();
+        old.guide.monsters.monsters.push(AdminMonster {
+            id: 1,
+            name: "Slime".to_string(),
+            ..AdminMonster::default()
+        });
+        old.guide.skills.skills.push(AdminSkill {
+            id: 2,
+            name: "Fireball".to_string(),
+            tier: 1,
+            ..AdminSkill::default()
+        });
+        old.guide.items.items.push(AdminItem {
+            id: 4,
+            name: "Bow".to_string(),
+            causes: vec![1, 2],
+            ..AdminItem::default()
+        });
+        assert!(old.diff(&old).is_empty());
+
+        let mut new = old.clone();
+        new.guide.monsters.monsters.clear();
+        new.guide.skills.skills[0].tier = 2;
+        new.guide.items.items.push(AdminItem {
+            id: 3,
+            name: "Sword".to_string(),
+            ..AdminItem::default()
+        });
+        // Only the order of the list fields differs, the item is not reported as changed.
+        new.guide.items.items[0].causes.reverse();
+
+        let diff = old.diff(&new);
+        assert_eq!(
+            diff.items.added,
+            vec![EntityRef {
+                id: 3,
+                name: "Sword".to_string()
+            }]
+        );
+        assert!(diff.items.removed.is_empty());
+        assert!(diff.items.changed.is_empty());
+        assert_eq!(
+            diff.monsters.removed,
+            vec![EntityRef {
+                id: 1,
+                name: "Slime".to_string()
+            }]
+        );
+        assert_eq!(diff.skills.changed.len(), 1);
+        assert_eq!(diff.skills.changed[0].id, 2);
+        assert_eq!(
+            diff.skills.changed[0].changes,
+            vec![FieldChange {
+                field: "tier".to_string(),
+                old: json!(1),
+                new: json!(2),
+            }]
+        );
+        assert!(diff.pets.is_empty());
+
+        let json = serde_json::to_value(&diff).unwrap();
+        assert_eq!(json["skills"]["changed"][0]["changes"][0]["field"], "tier");
+    }
+}
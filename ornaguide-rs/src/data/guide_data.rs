@@ -1,14 +1,24 @@
 use crate::{
-    codex::{CodexBoss, CodexMonster, CodexRaid},
+    codex::{CodexBoss, CodexFollower, CodexItem, CodexMonster, CodexRaid},
     data::CodexGenericMonster,
     error::Error,
     guide::Static,
-    items::admin::AdminItems,
+    items::admin::{AdminItem, AdminItems},
     monsters::admin::{AdminMonster, AdminMonsters},
-    pets::admin::AdminPets,
+    pets::admin::{AdminPet, AdminPets},
     skills::admin::AdminSkills,
 };
 
+/// How a codex entity was matched to a guide entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchKind {
+    /// The guide entity has the codex URI of the codex entity.
+    Slug,
+    /// The guide entity has no codex URI and was matched through heuristics (name, tier, icon).
+    /// The match may be wrong.
+    Heuristic,
+}
+
 /// Aggregate for guide data.
 #[derive(Clone, Default, PartialEq)]
 pub struct GuideData {
@@ -25,6 +35,62 @@ pub struct GuideData {
 }
 
 impl GuideData {
+    /// Find the admin item with the given name.
+    /// The name must match exactly. If multiple items share the name, the first one is returned.
+    pub fn find_item_by_name(&self, name: &str) -> Option<&AdminItem> {
+        self.items.items.iter().find(|item| item.name == name)
+    }
+
+    /// Find the admin item with the given name, being lenient on the name.
+    /// Lookups are made, in order:
+    ///   - by exact name
+    ///   - by case-insensitive name
+    ///   - by slug
+    ///
+    /// If multiple items match at the same step, the first one is returned.
+    pub fn find_item_by_name_ci(&self, name: &str) -> Option<&AdminItem> {
+        self.find_item_by_name(name)
+            .or_else(|| {
+                let name = name.to_lowercase();
+                self.items
+                    .items
+                    .iter()
+                    .find(|item| item.name.to_lowercase() == name)
+            })
+            .or_else(|| self.items.find_by_slug(name))
+    }
+
+    /// Return the ids of the items that use the item with the given id as an upgrade material.
+    /// See `AdminItems::upgraded_with`.
+    pub fn items_upgraded_with(&mut self, material: u32) -> &[u32] {
+        self.items.upgraded_with(material)
+    }
+
+    /// Find the admin item associated with the given codex item.
+    /// Items are matched by their codex URI. If no item has the URI of `needle`, items that have
+    /// no codex URI are matched on their tier and either their image or their name. Such a match
+    /// is returned with `MatchKind::Heuristic`, so that callers can warn about it before using it.
+    /// If there is no match, return an `Err`.
+    pub fn find_match_for_codex_item<'a>(
+        &'a self,
+        needle: &CodexItem,
+    ) -> Result<(&'a AdminItem, MatchKind), Error> {
+        if let Some(item) = self.items.find_by_slug(&needle.slug) {
+            return Ok((item, MatchKind::Slug));
+        }
+
+        self.items
+            .items
+            .iter()
+            .find(|admin| {
+                admin.codex_uri.is_empty()
+                    && admin.tier == needle.tier
+                    && (admin.image_name == needle.icon || admin.name == needle.name)
+            })
+            .map(|item| (item, MatchKind::Heuristic))
+            .ok_or_else(|| Error::Misc(format!("No match for codex item '{}'", needle.slug)))
+    }
+
     /// Find the admin monster associated with the given codex monster.
     /// If there is no match, return an `Err`.
     pub fn find_match_for_codex_generic_monster<'a>(
@@ -97,4 +163,116 @@ impl GuideData {
             })
             .ok_or_else(|| Error::Misc(format!("No match for codex raid '{}'", needle.slug)))
     }
+
+    /// Find the admin pet associated with the given codex follower.
+    /// Pets are matched by their codex URI. Pets that have no codex URI are matched on their tier
+    /// and either their image or their name.
+    /// If there is no match, return an `Err`.
+    pub fn find_match_for_codex_follower<'a>(
+        &'a self,
+        needle: &CodexFollower,
+    ) -> Result<&'a AdminPet, Error> {
+        self.pets
+            .find_by_slug(&needle.slug)
+            .or_else(|| {
+                self.pets.pets.iter().find(|admin| {
+                    admin.codex_uri.is_empty()
+                        && admin.tier == needle.tier
+                        && (admin.image_name == needle.icon || admin.name == needle.name)
+                })
+            })
+            .ok_or_else(|| Error::Misc(format!("No match for codex follower '{}'", needle.slug)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        codex::CodexItem,
+        data::{GuideData, MatchKind},
+        error::Error,
+        items::admin::AdminItem,
+    };
+
+    fn admin_item(id: u32, name: &str, codex_uri: &str) -> AdminItem {
+        AdminItem {
+            id,
+            name: name.to_string(),
+            codex_uri: codex_uri.to_string(),
+            ..AdminItem::default()
+        }
+    }
+
+    #[test]
+    fn test_find_item_by_name() {
+        let mut data = GuideData::default();
+        data.items.items = vec![
+            admin_item(1, "Mage's Ring", "/codex/items/mages-ring/"),
+            admin_item(2, "Sword", "/codex/items/sword/"),
+            admin_item(3, "sword", "/codex/items/sword-2/"),
+            admin_item(4, "Sword", ""),
+        ];
+        let id = |item: Option<&AdminItem>| item.map(|item| item.id);
+
+        // Exact match. Ambiguous names return the first match.
+        assert_eq!(id(data.find_item_by_name("Sword")), Some(2));
+        assert_eq!(id(data.find_item_by_name("sword")), Some(3));
+        assert_eq!(id(data.find_item_by_name("mage's ring")), None);
+        assert_eq!(id(data.find_item_by_name_ci("sword")), Some(3));
+
+        // Case-insensitive match.
+        assert_eq!(id(data.find_item_by_name_ci("mage's ring")), Some(1));
+        assert_eq!(id(data.find_item_by_name_ci("SWORD")), Some(2));
+
+        // Slug match.
+        assert_eq!(id(data.find_item_by_name_ci("mages-ring")), Some(1));
+        assert_eq!(id(data.find_item_by_name_ci("sword-2")), Some(3));
+        assert_eq!(id(data.find_item_by_name_ci("axe")), None);
+    }
+
+    fn codex_item(slug: &str, name: &str, tier: u8, icon: &str) -> CodexItem {
+        CodexItem {
+            slug: slug.to_string(),
+            name: name.to_string(),
+            icon: icon.to_string(),
+            tier,
+            ..CodexItem::default()
+        }
+    }
+
+    #[test]
+    fn test_find_match_for_codex_item() {
+        let mut data = GuideData::default();
+        let mut unlinked = admin_item(3, "Old Bow", "");
+        unlinked.tier = 2;
+        unlinked.image_name = "items/bow.png".to_string();
+        let mut linked = admin_item(1, "Sword", "/codex/items/sword/");
+        linked.tier = 2;
+        linked.image_name = "items/bow.png".to_string();
+        data.items.items = vec![linked, admin_item(2, "Bow", ""), unlinked];
+
+        let find = |item: &CodexItem| {
+            data.find_match_for_codex_item(item)
+                .map(|(item, kind)| (item.id, kind))
+                .ok()
+        };
+
+        // The slug matches, even though the item was renamed.
+        assert_eq!(
+            find(&codex_item("sword", "Great Sword", 2, "items/bow.png")),
+            Some((1, MatchKind::Slug))
+        );
+
+        // Only the tier and the icon match.
+        assert_eq!(
+            find(&codex_item("bow", "Bow", 2, "items/bow.png")),
+            Some((3, MatchKind::Heuristic))
+        );
+
+        // Nothing matches.
+        assert!(matches!(
+            data.find_match_for_codex_item(&codex_item("axe", "Axe", 3, "items/axe.png")),
+            Err(Error::Misc(_))
+        ));
+    }
 }
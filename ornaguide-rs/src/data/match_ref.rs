@@ -0,0 +1,64 @@
+use std::fmt::Display;
+
+use crate::{
+    data::{CodexEntity, CodexGenericMonster},
+    items::admin::AdminItem,
+    monsters::admin::AdminMonster,
+    pets::admin::AdminPet,
+    skills::admin::AdminSkill,
+};
+
+/// A reference to an entity from either the guide or the codex.
+#[derive(Debug, Clone, Copy)]
+pub enum MatchRef<'a> {
+    /// An item from the guide.
+    GuideItem(&'a AdminItem),
+    /// A monster from the guide.
+    GuideMonster(&'a AdminMonster),
+    /// A skill from the guide.
+    GuideSkill(&'a AdminSkill),
+    /// A pet from the guide.
+    GuidePet(&'a AdminPet),
+    /// An entity from the codex.
+    Codex(CodexEntity<'a>),
+}
+
+impl<'a> MatchRef<'a> {
+    /// Return the name of the entity.
+    pub fn name(&self) -> &'a str {
+        match self {
+            MatchRef::GuideItem(x) => &x.name,
+            MatchRef::GuideMonster(x) => &x.name,
+            MatchRef::GuideSkill(x) => &x.name,
+            MatchRef::GuidePet(x) => &x.name,
+            MatchRef::Codex(x) => x.name(),
+        }
+    }
+
+    /// Return whether the entity comes from the guide.
+    pub fn is_guide(&self) -> bool {
+        !matches!(self, MatchRef::Codex(_))
+    }
+}
+
+impl Display for MatchRef<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MatchRef::GuideItem(x) => write!(f, "[Guide item] {} (#{})", x.name, x.id),
+            MatchRef::GuideMonster(x) => write!(f, "[Guide monster] {} (#{})", x.name, x.id),
+            MatchRef::GuideSkill(x) => write!(f, "[Guide skill] {} (#{})", x.name, x.id),
+            MatchRef::GuidePet(x) => write!(f, "[Guide pet] {} (#{})", x.name, x.id),
+            MatchRef::Codex(x) => {
+                let kind = match x {
+                    CodexEntity::Item(_) => "item",
+                    CodexEntity::Skill(_) => "skill",
+                    CodexEntity::Follower(_) => "follower",
+                    CodexEntity::Monster(CodexGenericMonster::Monster(_)) => "monster",
+                    CodexEntity::Monster(CodexGenericMonster::Boss(_)) => "boss",
+                    CodexEntity::Monster(CodexGenericMonster::Raid(_)) => "raid",
+                };
+                write!(f, "[Codex {}] {} ({})", kind, x.name(), x.uri())
+            }
+        }
+    }
+}
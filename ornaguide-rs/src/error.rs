@@ -62,6 +62,13 @@ pub enum Error {
     ),
     /// There was an error in parsing HTML.
     HTMLParsingError(String),
+    /// Logging in to the guide failed.
+    LoginError(
+        /// The username we tried to log in with.
+        String,
+        /// The error message.
+        String,
+    ),
     /// A conversion from multiple codex status effects to guide ids did not fully succeed.
     PartialCodexStatusEffectsConversion(
         /// The status effects that were successfully converted.
@@ -104,6 +111,13 @@ pub enum Error {
         /// The skill codex URIs that were not found on the guide.
         Vec<String>,
     ),
+    /// A conversion from multiple codex skill buffed_bys to guide ids did not fully succeed.
+    PartialCodexSkillBuffedBysConversion(
+        /// The buffed_bys that were successfully converted.
+        Vec<u32>,
+        /// The monster codex URIs that were not found on the guide.
+        Vec<String>,
+    ),
     /// A conversion from multiple codex events to guide ids did not fully succeed.
     PartialCodexEventsConversion(
         /// The events that were successfully converted.
@@ -115,10 +129,64 @@ pub enum Error {
     BufferConversionError(String),
     /// An UTF-8 error occured.
     InvalidUTF8Conversion(String),
+    /// A checked save was aborted because the entity was edited on the guide since it was read.
+    Conflict(
+        /// The entity that was being saved.
+        String,
+        /// The fields that were changed on the guide.
+        Vec<String>,
+    ),
+    /// An admin form does not have the fields we expect.
+    FormFieldsMismatch(
+        /// The root of the form that was validated.
+        String,
+        /// The expected fields that are missing from the form.
+        Vec<String>,
+        /// The fields of the form that we do not know of.
+        Vec<String>,
+    ),
     /// Miscellaneous error.
     Misc(String),
 }
 
+impl Error {
+    /// Return whether the operation that failed with this error may succeed if attempted again.
+    ///
+    /// Errors are classified as follows:
+    ///   - Network errors (timeouts, failures to connect or to send the request, reset
+    ///     connections) are retryable.
+    ///   - HTTP responses with a 5xx status or `429 Too Many Requests` are retryable.
+    ///   - Other HTTP responses (4xx) are permanent.
+    ///   - Parsing, conversion, login and miscellaneous errors are permanent: retrying would yield
+    ///     the same result.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::Reqwest(err) => {
+                err.is_timeout()
+                    || err.is_connect()
+                    || err.is_request()
+                    || err.status().map(is_retryable_status).unwrap_or(false)
+            }
+            Error::ResponseError(_, _, status, _) => is_retryable_status(*status),
+            Error::Io(err) => matches!(
+                err.kind(),
+                std::io::ErrorKind::TimedOut
+                    | std::io::ErrorKind::ConnectionRefused
+                    | std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+                    | std::io::ErrorKind::Interrupted
+            ),
+            _ => false,
+        }
+    }
+}
+
+/// Return whether a request that got a response with the given HTTP status may be retried.
+fn is_retryable_status<S: Into<u16>>(status: S) -> bool {
+    let status = status.into();
+    status == 429 || (500..600).contains(&status)
+}
+
 impl Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -160,6 +228,9 @@ impl Display for Error {
                 write!(f, "HTTP POST {}: {}: {:?}", url, generic, errors)
             }
             Error::HTMLParsingError(err) => write!(f, "{}", err),
+            Error::LoginError(username, err) => {
+                write!(f, "Failed to log in as {}: {}", username, err)
+            }
             Error::PartialCodexStatusEffectsConversion(found, not_found) => write!(
                 f,
                 "Partial codex status effects conversion: OK {:?}, KO {:?}",
@@ -190,6 +261,11 @@ impl Display for Error {
                 "Partial codex monster abilities conversion: OK {:?}, KO {:?}",
                 found, not_found
             ),
+            Error::PartialCodexSkillBuffedBysConversion(found, not_found) => write!(
+                f,
+                "Partial codex skill buffed_bys conversion: OK {:?}, KO {:?}",
+                found, not_found
+            ),
             Error::PartialCodexEventsConversion(found, not_found) => write!(
                 f,
                 "Partial codex events conversion: OK {:?}, KO {:?}",
@@ -197,6 +273,17 @@ impl Display for Error {
             ),
             Error::InvalidUTF8Conversion(err) => write!(f, "{}", err),
             Error::BufferConversionError(err) => write!(f, "{}", err),
+            Error::Conflict(entity, fields) => write!(
+                f,
+                "{} was edited on the guide since it was read: {}",
+                entity,
+                fields.join(", ")
+            ),
+            Error::FormFieldsMismatch(form, missing, unexpected) => write!(
+                f,
+                "Form {} has changed: missing fields {:?}, unexpected fields {:?}",
+                form, missing, unexpected
+            ),
             Error::Misc(err) => write!(f, "{}", err),
         }
     }
@@ -255,3 +342,30 @@ impl From<FromUtf8Error> for Error {
         Self::InvalidUTF8Conversion(err.to_string())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::error::Error;
+
+    fn response_error(status: u16) -> Error {
+        Error::ResponseError(
+            "GET".to_string(),
+            "https://orna.guide/".to_string(),
+            status,
+            String::new(),
+        )
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        let timeout = Error::Io(std::io::Error::new(
+            std::io::ErrorKind::TimedOut,
+            "timed out",
+        ));
+        assert!(timeout.is_retryable());
+        assert!(!response_error(404).is_retryable());
+        assert!(response_error(503).is_retryable());
+        assert!(response_error(429).is_retryable());
+        assert!(!Error::Misc("oops".to_string()).is_retryable());
+    }
+}
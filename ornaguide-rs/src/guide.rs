@@ -1,18 +1,24 @@
+use std::time::Duration;
+
 use crate::{
-    error::Error, items::admin::AdminItem, monsters::admin::AdminMonster, pets::admin::AdminPet,
-    skills::admin::AdminSkill,
+    error::Error, guide::html_form_parser::ParsedForm, items::admin::AdminItem,
+    monsters::admin::AdminMonster, pets::admin::AdminPet, skills::admin::AdminSkill,
 };
 
+mod bulk;
 pub(crate) mod html_form_parser;
 pub(crate) mod html_list_parser;
 pub(crate) mod html_utils;
 mod http;
+mod login_parser;
 mod ornaguide;
 mod post_error_parser;
 mod r#static;
 
 pub mod fetch;
 
+pub use bulk::{bulk_update, BulkEditable, BulkReport};
+
 pub use r#static::{
     Element, EquippedBy, ItemCategory, ItemType, MonsterFamily, SkillType, Spawn, Static,
     StatusEffect, VecElements,
@@ -58,14 +64,51 @@ pub struct PetRow {
 pub trait AdminGuide {
     /// Retrieve the item with the given id from the guide.
     fn admin_retrieve_item_by_id(&self, id: u32) -> Result<AdminItem, Error>;
+    /// Retrieve the items with the given ids from the guide.
+    /// At most `concurrency` items are fetched at once. The results are in the order of `ids`.
+    /// The default implementation fetches items sequentially.
+    fn admin_retrieve_items_batch(
+        &self,
+        ids: &[u32],
+        concurrency: usize,
+    ) -> Vec<(u32, Result<AdminItem, Error>)> {
+        let _ = concurrency;
+        ids.iter()
+            .map(|id| (*id, self.admin_retrieve_item_by_id(*id)))
+            .collect()
+    }
     /// Save the given item to the guide.
     fn admin_save_item(&self, item: AdminItem) -> Result<(), Error>;
+    /// Save the changes made to the given item to the guide, unless it was edited since
+    /// `baseline` was read.
+    /// The item is retrieved again right before saving. If any field that `item` changes from
+    /// `baseline` has a different value on the guide than in `baseline`, nothing is saved and an
+    /// `Error::Conflict` is returned. Otherwise, only the fields `item` changes from `baseline`
+    /// are saved: other fields keep their value on the guide.
+    fn admin_save_item_checked(&self, item: AdminItem, baseline: &AdminItem) -> Result<(), Error> {
+        let id = item.id;
+        let entity = format!("AdminItem #{} '{}'", id, item.name);
+        let current = ParsedForm::from(self.admin_retrieve_item_by_id(id)?);
+        let baseline = ParsedForm::from(baseline.clone());
+        let form = ParsedForm::from(item);
+        let conflicts = form.conflicting_fields_vs(&baseline, &current);
+        if !conflicts.is_empty() {
+            return Err(Error::Conflict(entity, conflicts));
+        }
+
+        let mut merged = AdminItem::try_from(form.changes_applied_to(&baseline, current))?;
+        merged.id = id;
+        self.admin_save_item(merged)
+    }
     /// Retrieve the list of items from the admin view.
     fn admin_retrieve_items_list(&self) -> Result<Vec<ItemRow>, Error>;
+    /// Search items by name from the admin view.
+    /// This only retrieves the rows matching the query rather than the whole list.
+    fn admin_search_items(&self, query: &str) -> Result<Vec<ItemRow>, Error>;
     /// Add a new item to the guide.
     /// The csrfmiddlewaretoken and id fields of the provided item will be ignored.
-    /// In order to retrieve the id of the new item, all items have to be queried again.
-    fn admin_add_item(&self, item: AdminItem) -> Result<(), Error>;
+    /// Returns the id of the new item.
+    fn admin_add_item(&self, item: AdminItem) -> Result<u32, Error>;
 
     /// Retrieve the monster with the given id from the guide.
     fn admin_retrieve_monster_by_id(&self, id: u32) -> Result<AdminMonster, Error>;
@@ -73,10 +116,13 @@ pub trait AdminGuide {
     fn admin_save_monster(&self, monster: AdminMonster) -> Result<(), Error>;
     /// Retrieve the list of monsters from the admin view.
     fn admin_retrieve_monsters_list(&self) -> Result<Vec<MonsterRow>, Error>;
+    /// Search monsters by name from the admin view.
+    /// This only retrieves the rows matching the query rather than the whole list.
+    fn admin_search_monsters(&self, query: &str) -> Result<Vec<MonsterRow>, Error>;
     /// Add a new monster to the guide.
     /// The csrfmiddlewaretoken and id fields of the provided monster will be ignored.
-    /// In order to retrieve the id of the new monster, all monsters have to be queried again.
-    fn admin_add_monster(&self, monster: AdminMonster) -> Result<(), Error>;
+    /// Returns the id of the new monster.
+    fn admin_add_monster(&self, monster: AdminMonster) -> Result<u32, Error>;
 
     /// Retrieve the skill with the given id from the guide.
     fn admin_retrieve_skill_by_id(&self, id: u32) -> Result<AdminSkill, Error>;
@@ -84,10 +130,13 @@ pub trait AdminGuide {
     fn admin_save_skill(&self, skill: AdminSkill) -> Result<(), Error>;
     /// Retrieve the list of skills from the admin view.
     fn admin_retrieve_skills_list(&self) -> Result<Vec<SkillRow>, Error>;
+    /// Search skills by name from the admin view.
+    /// This only retrieves the rows matching the query rather than the whole list.
+    fn admin_search_skills(&self, query: &str) -> Result<Vec<SkillRow>, Error>;
     /// Add a new skill to the guide.
     /// The csrfmiddlewaretoken and id fields of the provided skill will be ignored.
-    /// In order to retrieve the id of the new skill, all skills have to be queried again.
-    fn admin_add_skill(&self, skill: AdminSkill) -> Result<(), Error>;
+    /// Returns the id of the new skill.
+    fn admin_add_skill(&self, skill: AdminSkill) -> Result<u32, Error>;
 
     /// Retrieve the pet with the given id from the guide.
     fn admin_retrieve_pet_by_id(&self, id: u32) -> Result<AdminPet, Error>;
@@ -95,10 +144,13 @@ pub trait AdminGuide {
     fn admin_save_pet(&self, pet: AdminPet) -> Result<(), Error>;
     /// Retrieve the list of pets from the admin view.
     fn admin_retrieve_pets_list(&self) -> Result<Vec<PetRow>, Error>;
+    /// Search pets by name from the admin view.
+    /// This only retrieves the rows matching the query rather than the whole list.
+    fn admin_search_pets(&self, query: &str) -> Result<Vec<PetRow>, Error>;
     /// Add a new pet to the guide.
     /// The csrfmiddlewaretoken and id fields of the provided pet will be ignored.
-    /// In order to retrieve the id of the new pet, all pets have to be queried again.
-    fn admin_add_pet(&self, pet: AdminPet) -> Result<(), Error>;
+    /// Returns the id of the new pet.
+    fn admin_add_pet(&self, pet: AdminPet) -> Result<u32, Error>;
 
     /// Retrieve the list of spawns from the admin view.
     fn admin_retrieve_spawns_list(&self) -> Result<Vec<Spawn>, Error>;
@@ -130,6 +182,32 @@ pub trait AdminGuide {
         })
     }
 
+    /// Apply `mutation` to each of `entities` matching `predicate` and save them to the guide.
+    /// Entities are selected from cached data (e.g.: `OrnaData`) rather than queried again.
+    /// Entities left untouched by the mutation are not saved. A save failing with a retryable
+    /// error is attempted once more, and `throttle` is waited between 2 saves.
+    fn admin_bulk_update<'a, T, P, M>(
+        &self,
+        entities: impl IntoIterator<Item = &'a T>,
+        predicate: P,
+        mutation: M,
+        throttle: Duration,
+    ) -> BulkReport
+    where
+        Self: Sized,
+        T: BulkEditable + PartialEq + 'a,
+        P: Fn(&T) -> bool,
+        M: Fn(&mut T),
+    {
+        bulk_update(
+            entities,
+            predicate,
+            mutation,
+            |entity| entity.save_to(self),
+            throttle,
+        )
+    }
+
     /// Add a new spawn to the guide.
     /// In order to retrieve the id of the new spawn, all spawns have to be queried again.
     fn admin_add_spawn(&self, spawn_name: &str) -> Result<(), Error>;
@@ -139,3 +217,178 @@ pub trait AdminGuide {
 }
 
 pub use ornaguide::{OrnaAdminGuide, OrnaGuide};
+
+#[cfg(test)]
+mod test {
+    use std::cell::RefCell;
+
+    use crate::{
+        error::Error,
+        guide::{
+            AdminGuide, Element, EquippedBy, ItemCategory, ItemRow, ItemType, MonsterFamily,
+            MonsterRow, PetRow, SkillRow, SkillType, Spawn, StatusEffect,
+        },
+        items::admin::AdminItem,
+        monsters::admin::AdminMonster,
+        pets::admin::AdminPet,
+        skills::admin::AdminSkill,
+    };
+
+    /// A guide holding a single item in memory, which records the items saved to it.
+    struct OfflineGuide {
+        item: AdminItem,
+        saved: RefCell<Vec<AdminItem>>,
+    }
+
+    impl OfflineGuide {
+        fn new(item: AdminItem) -> Self {
+            Self {
+                item,
+                saved: RefCell::new(Vec::new()),
+            }
+        }
+    }
+
+    impl AdminGuide for OfflineGuide {
+        fn admin_retrieve_item_by_id(&self, id: u32) -> Result<AdminItem, Error> {
+            assert_eq!(id, self.item.id);
+            Ok(self.item.clone())
+        }
+        fn admin_save_item(&self, item: AdminItem) -> Result<(), Error> {
+            self.saved.borrow_mut().push(item);
+            Ok(())
+        }
+        fn admin_retrieve_items_list(&self) -> Result<Vec<ItemRow>, Error> {
+            unimplemented!()
+        }
+        fn admin_search_items(&self, _: &str) -> Result<Vec<ItemRow>, Error> {
+            unimplemented!()
+        }
+        fn admin_add_item(&self, _: AdminItem) -> Result<u32, Error> {
+            unimplemented!()
+        }
+        fn admin_retrieve_monster_by_id(&self, _: u32) -> Result<AdminMonster, Error> {
+            unimplemented!()
+        }
+        fn admin_save_monster(&self, _: AdminMonster) -> Result<(), Error> {
+            unimplemented!()
+        }
+        fn admin_retrieve_monsters_list(&self) -> Result<Vec<MonsterRow>, Error> {
+            unimplemented!()
+        }
+        fn admin_search_monsters(&self, _: &str) -> Result<Vec<MonsterRow>, Error> {
+            unimplemented!()
+        }
+        fn admin_add_monster(&self, _: AdminMonster) -> Result<u32, Error> {
+            unimplemented!()
+        }
+        fn admin_retrieve_skill_by_id(&self, _: u32) -> Result<AdminSkill, Error> {
+            unimplemented!()
+        }
+        fn admin_save_skill(&self, _: AdminSkill) -> Result<(), Error> {
+            unimplemented!()
+        }
+        fn admin_retrieve_skills_list(&self) -> Result<Vec<SkillRow>, Error> {
+            unimplemented!()
+        }
+        fn admin_search_skills(&self, _: &str) -> Result<Vec<SkillRow>, Error> {
+            unimplemented!()
+        }
+        fn admin_add_skill(&self, _: AdminSkill) -> Result<u32, Error> {
+            unimplemented!()
+        }
+        fn admin_retrieve_pet_by_id(&self, _: u32) -> Result<AdminPet, Error> {
+            unimplemented!()
+        }
+        fn admin_save_pet(&self, _: AdminPet) -> Result<(), Error> {
+            unimplemented!()
+        }
+        fn admin_retrieve_pets_list(&self) -> Result<Vec<PetRow>, Error> {
+            unimplemented!()
+        }
+        fn admin_search_pets(&self, _: &str) -> Result<Vec<PetRow>, Error> {
+            unimplemented!()
+        }
+        fn admin_add_pet(&self, _: AdminPet) -> Result<u32, Error> {
+            unimplemented!()
+        }
+        fn admin_retrieve_spawns_list(&self) -> Result<Vec<Spawn>, Error> {
+            unimplemented!()
+        }
+        fn admin_retrieve_item_categories_list(&self) -> Result<Vec<ItemCategory>, Error> {
+            unimplemented!()
+        }
+        fn admin_retrieve_item_types_list(&self) -> Result<Vec<ItemType>, Error> {
+            unimplemented!()
+        }
+        fn admin_retrieve_monster_families_list(&self) -> Result<Vec<MonsterFamily>, Error> {
+            unimplemented!()
+        }
+        fn admin_retrieve_status_effects_list(&self) -> Result<Vec<StatusEffect>, Error> {
+            unimplemented!()
+        }
+        fn admin_retrieve_elements_list(&self) -> Vec<Element> {
+            unimplemented!()
+        }
+        fn admin_retrieve_equipped_bys_list(&self) -> Vec<EquippedBy> {
+            unimplemented!()
+        }
+        fn admin_retrieve_skill_types_list(&self) -> Result<Vec<SkillType>, Error> {
+            unimplemented!()
+        }
+        fn admin_add_spawn(&self, _: &str) -> Result<(), Error> {
+            unimplemented!()
+        }
+        fn admin_add_status_effect(&self, _: &str) -> Result<(), Error> {
+            unimplemented!()
+        }
+    }
+
+    #[test]
+    fn test_admin_save_item_checked() {
+        let baseline = AdminItem {
+            id: 1,
+            name: "Sword".to_string(),
+            tier: 1,
+            attack: 10,
+            ..AdminItem::default()
+        };
+        let edited = AdminItem {
+            attack: 12,
+            ..baseline.clone()
+        };
+
+        // The item is unchanged on the guide.
+        let guide = OfflineGuide::new(baseline.clone());
+        guide
+            .admin_save_item_checked(edited.clone(), &baseline)
+            .unwrap();
+        assert_eq!(guide.saved.borrow().len(), 1);
+        assert_eq!(guide.saved.borrow()[0].attack, 12);
+
+        // Fields we do not change may have been edited on the guide.
+        let guide = OfflineGuide::new(AdminItem {
+            tier: 2,
+            ..baseline.clone()
+        });
+        guide
+            .admin_save_item_checked(edited.clone(), &baseline)
+            .unwrap();
+        assert_eq!(guide.saved.borrow().len(), 1);
+        // The edit made on the guide is kept.
+        assert_eq!(guide.saved.borrow()[0].id, 1);
+        assert_eq!(guide.saved.borrow()[0].tier, 2);
+        assert_eq!(guide.saved.borrow()[0].attack, 12);
+
+        // The field we change was edited on the guide.
+        let guide = OfflineGuide::new(AdminItem {
+            attack: 11,
+            ..baseline.clone()
+        });
+        match guide.admin_save_item_checked(edited, &baseline) {
+            Err(Error::Conflict(_, fields)) => assert_eq!(fields, vec!["attack".to_string()]),
+            x => panic!("Expected a conflict, got {:?}", x),
+        }
+        assert!(guide.saved.borrow().is_empty());
+    }
+}
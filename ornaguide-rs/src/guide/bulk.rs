@@ -0,0 +1,157 @@
+use std::time::Duration;
+
+use crate::{
+    error::Error, guide::AdminGuide, items::admin::AdminItem, monsters::admin::AdminMonster,
+    pets::admin::AdminPet, skills::admin::AdminSkill,
+};
+
+/// An admin entity that can be edited in bulk.
+pub trait BulkEditable: Clone {
+    /// The id of the entity on the guide.
+    fn id(&self) -> u32;
+    /// Save the entity to the guide.
+    fn save_to<G: AdminGuide + ?Sized>(self, guide: &G) -> Result<(), Error>;
+}
+
+impl BulkEditable for AdminItem {
+    fn id(&self) -> u32 {
+        self.id
+    }
+
+    fn save_to<G: AdminGuide + ?Sized>(self, guide: &G) -> Result<(), Error> {
+        guide.admin_save_item(self)
+    }
+}
+
+impl BulkEditable for AdminMonster {
+    fn id(&self) -> u32 {
+        self.id
+    }
+
+    fn save_to<G: AdminGuide + ?Sized>(self, guide: &G) -> Result<(), Error> {
+        guide.admin_save_monster(self)
+    }
+}
+
+impl BulkEditable for AdminSkill {
+    fn id(&self) -> u32 {
+        self.id
+    }
+
+    fn save_to<G: AdminGuide + ?Sized>(self, guide: &G) -> Result<(), Error> {
+        guide.admin_save_skill(self)
+    }
+}
+
+impl BulkEditable for AdminPet {
+    fn id(&self) -> u32 {
+        self.id
+    }
+
+    fn save_to<G: AdminGuide + ?Sized>(self, guide: &G) -> Result<(), Error> {
+        guide.admin_save_pet(self)
+    }
+}
+
+/// The outcome of a bulk update.
+#[derive(Debug, Default)]
+pub struct BulkReport {
+    /// Ids of the entities that were saved.
+    pub updated: Vec<u32>,
+    /// Ids of the entities that matched but were left untouched by the mutation.
+    pub unchanged: Vec<u32>,
+    /// Ids of the entities that failed to save, along with the error.
+    pub failed: Vec<(u32, Error)>,
+}
+
+/// Apply `mutation` to each of `entities` matching `predicate` and save them with `save`.
+/// Entities the mutation does not modify are not saved. A save failing with a retryable error is
+/// attempted once more. `throttle` is waited between 2 saves.
+pub fn bulk_update<'a, T, P, M, S>(
+    entities: impl IntoIterator<Item = &'a T>,
+    predicate: P,
+    mutation: M,
+    mut save: S,
+    throttle: Duration,
+) -> BulkReport
+where
+    T: BulkEditable + PartialEq + 'a,
+    P: Fn(&T) -> bool,
+    M: Fn(&mut T),
+    S: FnMut(T) -> Result<(), Error>,
+{
+    let mut report = BulkReport::default();
+    let mut first = true;
+    for entity in entities.into_iter().filter(|entity| predicate(entity)) {
+        let mut edited = entity.clone();
+        mutation(&mut edited);
+        if edited == *entity {
+            report.unchanged.push(entity.id());
+            continue;
+        }
+
+        if !first && !throttle.is_zero() {
+            std::thread::sleep(throttle);
+        }
+        first = false;
+
+        let id = edited.id();
+        let result = match save(edited.clone()) {
+            Err(err) if err.is_retryable() => save(edited),
+            result => result,
+        };
+        match result {
+            Ok(()) => report.updated.push(id),
+            Err(err) => report.failed.push((id, err)),
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::{error::Error, guide::bulk::bulk_update, skills::admin::AdminSkill};
+
+    #[test]
+    fn test_bulk_update() {
+        let skill = |id: u32, type_: u32, is_magic: bool| AdminSkill {
+            id,
+            name: format!("Skill {}", id),
+            type_,
+            is_magic,
+            ..AdminSkill::default()
+        };
+        let buff = 2;
+        let skills = vec![
+            skill(1, buff, false),
+            skill(2, 1, false),
+            skill(3, buff, true),
+            skill(4, buff, false),
+        ];
+
+        let mut saved = Vec::new();
+        let report = bulk_update(
+            &skills,
+            |skill| skill.type_ == buff,
+            |skill| skill.is_magic = true,
+            |skill: AdminSkill| {
+                if skill.id == 4 {
+                    Err(Error::Misc("Save failed".to_string()))
+                } else {
+                    saved.push(skill);
+                    Ok(())
+                }
+            },
+            Duration::ZERO,
+        );
+
+        assert_eq!(report.updated, vec![1]);
+        assert_eq!(report.unchanged, vec![3]);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].0, 4);
+        assert_eq!(saved.len(), 1);
+        assert!(saved[0].is_magic);
+    }
+}
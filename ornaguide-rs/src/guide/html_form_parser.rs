@@ -14,6 +14,98 @@ pub struct ParsedForm {
     pub csrfmiddlewaretoken: String,
 }
 
+impl ParsedForm {
+    /// Return the value of the first field with the given name, if any.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Return the names of the fields of `self` that are not in `expected`, sorted and without
+    /// duplicates.
+    /// Used along `parse_all_fields_html` to detect fields the guide added to its forms.
+    pub fn unknown_fields_vs(&self, expected: &[&str]) -> Vec<&str> {
+        let mut unknown = self
+            .fields
+            .iter()
+            .map(|(field, _)| field.as_str())
+            .filter(|field| !expected.contains(field))
+            .collect::<Vec<_>>();
+        unknown.sort_unstable();
+        unknown.dedup();
+        unknown
+    }
+
+    /// Return the names of `expected` that are not fields of `self`, in the order of `expected`.
+    pub fn missing_fields_vs<'b>(&self, expected: &[&'b str]) -> Vec<&'b str> {
+        expected
+            .iter()
+            .filter(|name| !self.fields.iter().any(|(field, _)| field == *name))
+            .copied()
+            .collect()
+    }
+
+    /// Return all values of the fields with the given name.
+    /// Fields backed by a multiple select appear once per selected value.
+    fn values(&self, key: &str) -> Vec<&str> {
+        self.fields
+            .iter()
+            .filter(|(field, _)| field == key)
+            .map(|(_, value)| value.as_str())
+            .collect()
+    }
+
+    /// Return the names of the fields whose values differ between `self` and `baseline`.
+    fn changed_fields_vs<'a>(&'a self, baseline: &'a ParsedForm) -> Vec<&'a str> {
+        let mut changed = self
+            .fields
+            .iter()
+            .chain(baseline.fields.iter())
+            .map(|(field, _)| field.as_str())
+            .filter(|field| self.values(field) != baseline.values(field))
+            .collect::<Vec<_>>();
+        changed.sort_unstable();
+        changed.dedup();
+        changed
+    }
+
+    /// Apply the fields `self` changes from `baseline` onto `current`.
+    /// The other fields of `current` are left untouched, so that edits made on the guide since
+    /// `baseline` was read are kept.
+    pub fn changes_applied_to(&self, baseline: &ParsedForm, mut current: ParsedForm) -> ParsedForm {
+        let changed = self.changed_fields_vs(baseline);
+        current
+            .fields
+            .retain(|(field, _)| !changed.contains(&field.as_str()));
+        current.fields.extend(
+            self.fields
+                .iter()
+                .filter(|(field, _)| changed.contains(&field.as_str()))
+                .cloned(),
+        );
+        current
+    }
+
+    /// Return the names of the fields `self` changes from `baseline` and whose value in `current`
+    /// is no longer that of `baseline`, sorted and without duplicates.
+    /// `baseline` is the form as it was read before being edited into `self`, while `current` is
+    /// the form as it is now on the guide. A non-empty return means saving `self` would overwrite
+    /// a concurrent edit.
+    pub fn conflicting_fields_vs(
+        &self,
+        baseline: &ParsedForm,
+        current: &ParsedForm,
+    ) -> Vec<String> {
+        self.changed_fields_vs(baseline)
+            .into_iter()
+            .filter(|field| current.values(field) != baseline.values(field))
+            .map(str::to_string)
+            .collect()
+    }
+}
+
 /// Find the csrfmiddlewaretoken in the form.
 fn find_csrfmiddlewaretoken(form: &NodeRef) -> String {
     let node = descend_to(form, "[name=\"csrfmiddlewaretoken\"]", "form").unwrap();
@@ -155,6 +247,63 @@ fn parse_html_form(
     })
 }
 
+/// Extract all named fields from an HTML form, whether we know about them or not.
+/// Values are taken as-is from the `value` attribute (or the text of a `textarea`), without
+/// interpretation. Submit buttons and the csrf token are skipped.
+pub fn parse_all_fields_html(contents: &str, form_root_name: &str) -> Result<ParsedForm, Error> {
+    let html = parse_html().one(contents);
+
+    let form = descend_to(&html, form_root_name, "html")?;
+    let form = form.as_node();
+
+    let mut fields = Vec::new();
+    for node in form
+        .select("input, select, textarea")
+        .map_err(|()| Error::HTMLParsingError("Failed to select form fields".to_string()))?
+    {
+        let attributes = node.attributes.borrow();
+        let name = match attributes.get("name") {
+            Some(name) if name != "csrfmiddlewaretoken" => name,
+            _ => continue,
+        };
+        if attributes.get("type") == Some("submit") {
+            continue;
+        }
+        let value = if &node.name.local == "textarea" {
+            node.text_contents()
+        } else {
+            attributes.get("value").unwrap_or_default().to_string()
+        };
+        fields.push((name.to_string(), value));
+    }
+
+    Ok(ParsedForm {
+        fields,
+        csrfmiddlewaretoken: String::new(),
+    })
+}
+
+/// Check that the form in the HTML page has exactly the `expected` fields.
+/// If fields are missing or unknown, return an `Error::FormFieldsMismatch` listing them.
+pub fn validate_form_fields(
+    contents: &str,
+    form_root_name: &str,
+    expected: &[&str],
+) -> Result<(), Error> {
+    let form = parse_all_fields_html(contents, form_root_name)?;
+    let missing = form.missing_fields_vs(expected);
+    let unexpected = form.unknown_fields_vs(expected);
+    if missing.is_empty() && unexpected.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::FormFieldsMismatch(
+            form_root_name.to_string(),
+            missing.into_iter().map(str::to_string).collect(),
+            unexpected.into_iter().map(str::to_string).collect(),
+        ))
+    }
+}
+
 /// Extract given fields from an admin item change HTML page.
 pub fn parse_item_html(contents: &str, field_names: &[&str]) -> Result<ParsedForm, Error> {
     parse_html_form(contents, "#item_form", field_names)
@@ -308,3 +457,107 @@ pub(crate) const PET_FORM_FIELD_NAMES: &[&str] = &[
     "limited_details",
     "skills",
 ];
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        error::Error,
+        guide::html_form_parser::{
+            parse_all_fields_html, validate_form_fields, ParsedForm, SKILL_FORM_FIELD_NAMES,
+        },
+    };
+
+    fn form(fields: &[(&str, &str)]) -> ParsedForm {
+        ParsedForm {
+            fields: fields
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            csrfmiddlewaretoken: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_conflicting_fields() {
+        let baseline = form(&[("name", "Sword"), ("tier", "3"), ("causes", "1")]);
+        let edited = form(&[("name", "Sword"), ("tier", "4"), ("causes", "1")]);
+
+        // Nobody else touched the item.
+        assert!(edited
+            .conflicting_fields_vs(&baseline, &baseline)
+            .is_empty());
+        // Someone else changed a field we do not touch.
+        let current = form(&[("name", "Blade"), ("tier", "3"), ("causes", "1")]);
+        assert!(edited.conflicting_fields_vs(&baseline, &current).is_empty());
+        // Someone else changed the field we edit, or added a value to a list we edit.
+        let current = form(&[("name", "Sword"), ("tier", "5"), ("causes", "1")]);
+        assert_eq!(
+            edited.conflicting_fields_vs(&baseline, &current),
+            vec!["tier"]
+        );
+        let edited = form(&[("name", "Sword"), ("tier", "3")]);
+        let current = form(&[
+            ("name", "Sword"),
+            ("tier", "3"),
+            ("causes", "1"),
+            ("causes", "2"),
+        ]);
+        assert_eq!(
+            edited.conflicting_fields_vs(&baseline, &current),
+            vec!["causes"]
+        );
+    }
+
+    #[test]
+    fn test_unknown_fields_detected() {
+        let inputs = SKILL_FORM_FIELD_NAMES
+            .iter()
+            .map(|name| format!(r#"<input type="text" name="{}" id="id_{}">"#, name, name))
+            .collect::<String>();
+        let html = format!(
+            r#"<html><body><form id="skill_form">
+<input type="hidden" name="csrfmiddlewaretoken" value="token">
+{}
+<select name="target" id="id_target"><option value="1" selected>Foe</option></select>
+<textarea name="lore" id="id_lore">Some lore</textarea>
+<input type="submit" name="_save" value="Save">
+</form></body></html>"#,
+            inputs
+        );
+
+        let form = parse_all_fields_html(&html, "#skill_form").unwrap();
+        assert_eq!(
+            form.unknown_fields_vs(SKILL_FORM_FIELD_NAMES),
+            vec!["lore", "target"]
+        );
+    }
+
+    #[test]
+    fn test_validate_form_fields() {
+        let html = |skip: &str| {
+            let inputs = SKILL_FORM_FIELD_NAMES
+                .iter()
+                .filter(|name| **name != skip)
+                .map(|name| format!(r#"<input type="text" name="{}" id="id_{}">"#, name, name))
+                .collect::<String>();
+            format!(
+                r#"<html><body><form id="skill_form">
+<input type="hidden" name="csrfmiddlewaretoken" value="token">
+{}
+<input type="submit" name="_save" value="Save">
+</form></body></html>"#,
+                inputs
+            )
+        };
+
+        assert!(validate_form_fields(&html(""), "#skill_form", SKILL_FORM_FIELD_NAMES).is_ok());
+        match validate_form_fields(&html("tier"), "#skill_form", SKILL_FORM_FIELD_NAMES) {
+            Err(Error::FormFieldsMismatch(form, missing, unexpected)) => {
+                assert_eq!(form, "#skill_form");
+                assert_eq!(missing, vec!["tier"]);
+                assert!(unexpected.is_empty());
+            }
+            x => panic!("Unexpected result: {:?}", x),
+        }
+    }
+}
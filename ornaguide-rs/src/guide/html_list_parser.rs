@@ -95,3 +95,36 @@ pub fn parse_list_html(contents: &str) -> Result<ParsedTable, Error> {
         number_entries,
     })
 }
+
+#[cfg(test)]
+mod test {
+    use crate::guide::html_list_parser::parse_list_html;
+
+    static SEARCH_RESULTS_HTML: &str = r#"
+<html><body>
+<form id="changelist-search"><input type="text" name="q" value="slime"></form>
+<table id="result_list">
+  <thead><tr><th>Name</th></tr></thead>
+  <tbody>
+    <tr><th><a href="/admin/monsters/monster/12/change/?_changelist_filters=q%3Dslime">Slime</a></th></tr>
+    <tr><th><a href="/admin/monsters/monster/345/change/?_changelist_filters=q%3Dslime">Arisen Slime</a></th></tr>
+  </tbody>
+</table>
+<p class="paginator">2 results (1523 total) <a href="?">Show all</a></p>
+</body></html>
+"#;
+
+    #[test]
+    fn test_parse_search_results() {
+        let table = parse_list_html(SEARCH_RESULTS_HTML).unwrap();
+        assert_eq!(table.number_entries, 2);
+        assert_eq!(
+            table
+                .entries
+                .iter()
+                .map(|entry| (entry.id, entry.value.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(12, "Slime"), (345, "Arisen Slime")]
+        );
+    }
+}
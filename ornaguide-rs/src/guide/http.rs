@@ -1,11 +1,15 @@
 use std::{
     fs::File,
     io::{BufWriter, Write},
+    net::IpAddr,
+    path::{Path, PathBuf},
+    time::Duration,
 };
 
 use reqwest::{
     header::{HeaderMap, HeaderValue},
-    Client, Response, StatusCode, Url,
+    redirect::Policy,
+    Client, ClientBuilder, Response, StatusCode, Url,
 };
 
 use crate::{
@@ -21,34 +25,62 @@ use crate::{
         html_skill_parser::{parse_html_codex_skill, parse_html_codex_skill_translation},
         CodexBoss, CodexFollower, CodexItem, CodexMonster, CodexRaid, CodexSkill,
     },
-    config::debug_urls,
+    config::{debug_urls, http_max_attempts, http_retry_delay, validate_forms},
     error::Error,
     guide::{
         html_form_parser::{
-            parse_item_html, parse_monster_html, parse_pet_html, parse_skill_html,
-            parse_spawn_html, parse_status_effect_html, ParsedForm, ITEM_FORM_FIELD_NAMES,
-            MONSTER_FORM_FIELD_NAMES, PET_FORM_FIELD_NAMES, SKILL_FORM_FIELD_NAMES,
+            parse_all_fields_html, parse_item_html, parse_monster_html, parse_pet_html,
+            parse_skill_html, parse_spawn_html, parse_status_effect_html, validate_form_fields,
+            ParsedForm, ITEM_FORM_FIELD_NAMES, MONSTER_FORM_FIELD_NAMES, PET_FORM_FIELD_NAMES,
+            SKILL_FORM_FIELD_NAMES,
         },
         html_list_parser::{parse_list_html, Entry, ParsedTable},
-        post_error_parser::parse_post_error_html,
+        login_parser::{find_set_cookie, parse_login_csrf_token, parse_login_error},
+        post_error_parser::{parse_added_id, parse_post_error_html},
     },
     utils::block_on_this_thread,
 };
 
+/// Create a builder for the HTTP clients used to reach the guide and the codex.
+/// Compressed responses (gzip, deflate and brotli) are requested and transparently decoded.
+fn client_builder() -> ClientBuilder {
+    Client::builder().gzip(true).deflate(true).brotli(true)
+}
+
+/// If enabled in the config, check that the form in `contents` has exactly the `expected` fields.
+fn validate_form_if_enabled(
+    contents: &str,
+    form_root_name: &str,
+    expected: &[&str],
+) -> Result<(), Error> {
+    if validate_forms()? {
+        validate_form_fields(contents, form_root_name, expected)
+    } else {
+        Ok(())
+    }
+}
+
 pub(crate) struct Http {
     http: Client,
     orna_guide_host: String,
     playorna_host: String,
+    /// Path of the admin panel on the guide host, without trailing slash (e.g.: `/admin`).
+    admin_base: String,
+    /// Path of the codex on the codex host, without trailing slash (e.g.: `/codex`).
+    codex_base: String,
+    /// Directory in which fetched HTML pages are saved, if any.
+    html_cache_dir: Option<PathBuf>,
 }
 
 /// Perform a POST request on the URL, serializing the form as an urlencoded body and setting the
 /// referer to the URL.
+/// Returns the URL we ended up on after following redirects, along with the body of the response.
 async fn async_post_forms_to(
     http: &Client,
     url: &str,
     form: ParsedForm,
     form_root_name: &str,
-) -> Result<(), Error> {
+) -> Result<(String, String), Error> {
     if debug_urls()? {
         eprintln!("--- POST {}", url);
     }
@@ -70,11 +102,12 @@ async fn async_post_forms_to(
         .await?;
 
     let status = response.status();
+    let final_url = response.url().to_string();
     let text = response.text().await?;
     parse_post_error_html(url, &text, form_root_name)?;
 
     if status.is_success() {
-        Ok(())
+        Ok((final_url, text))
     } else {
         Err(Error::ResponseError(
             "POST".to_string(),
@@ -93,60 +126,143 @@ fn post_forms_to(
     form: ParsedForm,
     form_root_name: &str,
 ) -> Result<(), Error> {
-    block_on_this_thread(async_post_forms_to(http, url, form, form_root_name))
+    block_on_this_thread(async_post_forms_to(http, url, form, form_root_name)).map(|_| ())
+}
+
+/// Perform a POST request on the add URL of an entity and return the id of the new entity.
+/// `model_path` is the path to the admin list of the entity (e.g.: `/admin/items/item/`).
+fn post_add_form_to(
+    http: &Client,
+    url: &str,
+    form: ParsedForm,
+    form_root_name: &str,
+    model_path: &str,
+) -> Result<u32, Error> {
+    let (final_url, body) =
+        block_on_this_thread(async_post_forms_to(http, url, form, form_root_name))?;
+    parse_added_id(&final_url, &body, model_path)
 }
 
 /// Send an HTTP GET request and expect that the response will be a 200 OK.
 /// If the response isn't, return an error.
+/// Requests failing with a 5xx status or a timeout are retried, as configured in the `Config`.
 async fn get_expect_200(http: &Client, url: &str) -> Result<Response, Error> {
-    let response = http.get(url).send().await?;
-    if response.status() == StatusCode::OK {
-        Ok(response)
-    } else {
-        Err(Error::ResponseError(
-            "GET".to_string(),
-            url.to_string(),
-            response.status().as_u16(),
-            response.text().await?,
-        ))
+    get_expect_200_with_retries(
+        http,
+        url,
+        http_max_attempts()?,
+        Duration::from_millis(http_retry_delay()?),
+    )
+    .await
+}
+
+/// Send an HTTP GET request and expect that the response will be a 200 OK.
+/// Requests failing with a retryable error (see `Error::is_retryable`) are attempted up to
+/// `max_attempts` times. The delay between 2 attempts starts at `base_delay` and doubles after
+/// each attempt. Other errors are returned immediately.
+async fn get_expect_200_with_retries(
+    http: &Client,
+    url: &str,
+    max_attempts: u32,
+    base_delay: Duration,
+) -> Result<Response, Error> {
+    let mut delay = base_delay;
+    let mut attempt = 1;
+    loop {
+        let err = match http.get(url).send().await {
+            Ok(response) if response.status() == StatusCode::OK => return Ok(response),
+            Ok(response) => Error::ResponseError(
+                "GET".to_string(),
+                url.to_string(),
+                response.status().as_u16(),
+                response.text().await?,
+            ),
+            Err(err) => err.into(),
+        };
+
+        if !err.is_retryable() || attempt >= max_attempts {
+            return Err(err);
+        }
+        if debug_urls().unwrap_or(false) {
+            eprintln!("--- Retrying GET {} in {:?}: {}", url, delay, err);
+        }
+        tokio::time::sleep(delay).await;
+        delay *= 2;
+        attempt += 1;
     }
 }
 
-/// Execute a GET HTTP request and save the output.
-async fn async_get_and_save(http: &Client, url: &str) -> Result<String, Error> {
+/// Execute a GET HTTP request and save the output in `cache_dir`, if any.
+async fn async_get_and_save(
+    http: &Client,
+    url: &str,
+    cache_dir: Option<&Path>,
+) -> Result<String, Error> {
     if debug_urls()? {
         eprintln!("--- GET {}", url);
     }
     let response = get_expect_200(http, url).await?;
     let body = response.text().await?;
+    if let Some(cache_dir) = cache_dir {
+        save_html(cache_dir, url, &body)?;
+    }
+    Ok(body)
+}
+
+/// Whether the given URL host refers to the local machine (`localhost` or a loopback address).
+fn is_local_host(host: &str) -> bool {
+    host == "localhost"
+        || host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+/// Save the HTML page fetched from the given URL to the cache directory.
+/// Pages fetched from the local machine are not saved.
+fn save_html(cache_dir: &Path, url: &str, body: &str) -> Result<(), Error> {
     let url = Url::parse(url).unwrap();
-    if url.host_str().unwrap() != "localhost" {
+    if !is_local_host(url.host_str().unwrap()) {
         let path = url.path().replace('/', "_");
         let param = if let Some(x) = url.query() {
             format!("?{}", x)
         } else {
             String::new()
         };
-        let filename = format!("data/htmls/{}{}{}.html", url.host_str().unwrap(), path, param);
+        let filename = cache_dir.join(format!("{}{}{}.html", url.host_str().unwrap(), path, param));
         let mut writer = BufWriter::new(File::create(filename)?);
         write!(writer, "{}", body)?;
     }
-    Ok(body)
+    Ok(())
 }
 
-/// Execute a GET HTTP request and save the output.
-/// We need to have both the `send` and the `text` calls run on the same runtime. We cannot use two
-/// calls to `block_on` in `async_get_and_save`.
-fn get_and_save(http: &Client, url: &str) -> Result<String, Error> {
-    block_on_this_thread(async_get_and_save(http, url))
+/// Execute a GET HTTP request and return the raw bytes of the body.
+/// Unlike `get_and_save`, the response is not saved to disk.
+fn get_bytes(http: &Client, url: &str) -> Result<Vec<u8>, Error> {
+    block_on_this_thread(async {
+        if debug_urls()? {
+            eprintln!("--- GET {}", url);
+        }
+        Ok(get_expect_200(http, url).await?.bytes().await?.to_vec())
+    })
+}
+
+/// Append the search query to the admin list URL.
+/// The Django admin filters the list on the `q` parameter.
+fn admin_search_url(base_url: &str, query: &str) -> String {
+    Url::parse_with_params(base_url, &[("q", query)])
+        .map(String::from)
+        .unwrap_or_else(|_| base_url.to_string())
 }
 
 /// Cycles through the different pages of the route and reads each table.
-fn query_all_pages(base_url: &str, http: &Client) -> Result<Vec<Entry>, Error> {
+/// The base URL may already have query parameters (e.g.: a search query).
+fn query_all_pages(base_url: &str, http: &Http) -> Result<Vec<Entry>, Error> {
     let ParsedTable {
         entries,
         number_entries,
-    } = parse_list_html(&get_and_save(http, base_url)?)?;
+    } = parse_list_html(&http.get_and_save(base_url)?)?;
 
     if entries.len() >= number_entries {
         Ok(entries)
@@ -157,11 +273,16 @@ fn query_all_pages(base_url: &str, http: &Client) -> Result<Vec<Entry>, Error> {
             let ParsedTable {
                 mut entries,
                 number_entries: _,
-            } = parse_list_html(&get_and_save(
-                http,
-                &format!("{}/?p={}", base_url, page_no),
-            )?)?;
+            } = parse_list_html(&http.get_and_save(&if base_url.contains('?') {
+                format!("{}&p={}", base_url, page_no)
+            } else {
+                format!("{}/?p={}", base_url, page_no)
+            })?)?;
             page_no += 1;
+            // A search may yield an empty page if entries were removed meanwhile.
+            if entries.is_empty() {
+                break;
+            }
             ret.append(&mut entries);
         }
         Ok(ret)
@@ -169,11 +290,11 @@ fn query_all_pages(base_url: &str, http: &Client) -> Result<Vec<Entry>, Error> {
 }
 
 /// Cycles through the different pages of the route and reads each table.
-fn query_all_codex_pages(base_url: &str, http: &Client) -> Result<Vec<CodexListEntry>, Error> {
+fn query_all_codex_pages(base_url: &str, http: &Http) -> Result<Vec<CodexListEntry>, Error> {
     let ParsedList {
         entries,
         mut has_next_page,
-    } = parse_html_codex_list(&get_and_save(http, base_url)?)?;
+    } = parse_html_codex_list(&http.get_and_save(base_url)?)?;
 
     if !has_next_page {
         Ok(entries)
@@ -184,10 +305,9 @@ fn query_all_codex_pages(base_url: &str, http: &Client) -> Result<Vec<CodexListE
             let ParsedList {
                 mut entries,
                 has_next_page: not_done,
-            } = parse_html_codex_list(&get_and_save(
-                http,
-                &format!("{}/?p={}", base_url, page_no),
-            )?)?;
+            } = parse_html_codex_list(
+                &http.get_and_save(&format!("{}/?p={}", base_url, page_no))?,
+            )?;
             page_no += 1;
             ret.append(&mut entries);
             has_next_page = not_done;
@@ -200,17 +320,54 @@ impl Http {
     // --- Misc ---
     pub(crate) fn new() -> Self {
         Self {
-            http: Client::new(),
+            http: client_builder().build().unwrap(),
             orna_guide_host: "https://orna.guide".to_string(),
             playorna_host: "https://playorna.com".to_string(),
+            admin_base: "/admin".to_string(),
+            codex_base: "/codex".to_string(),
+            html_cache_dir: Some(PathBuf::from("data/htmls")),
         }
     }
 
+    /// Set the directory in which fetched HTML pages are saved, or disable saving them if `None`.
+    pub(crate) fn set_html_cache_dir(&mut self, html_cache_dir: Option<PathBuf>) {
+        self.html_cache_dir = html_cache_dir;
+    }
+
+    /// Execute a GET HTTP request and save the output to the HTML cache directory.
+    async fn async_get_and_save(&self, url: &str) -> Result<String, Error> {
+        async_get_and_save(&self.http, url, self.html_cache_dir.as_deref()).await
+    }
+
+    /// Execute a GET HTTP request and save the output to the HTML cache directory.
+    /// We need to have both the `send` and the `text` calls run on the same runtime. We cannot use
+    /// two calls to `block_on` in `async_get_and_save`.
+    fn get_and_save(&self, url: &str) -> Result<String, Error> {
+        block_on_this_thread(self.async_get_and_save(url))
+    }
+
+    /// Override the paths of the admin panel and of the codex on their respective hosts.
+    /// Trailing slashes are ignored.
+    pub(crate) fn set_base_paths(&mut self, admin_base: &str, codex_base: &str) {
+        self.admin_base = admin_base.trim_end_matches('/').to_string();
+        self.codex_base = codex_base.trim_end_matches('/').to_string();
+    }
+
+    /// URL to the root of the admin panel, without trailing slash.
+    fn admin_root(&self) -> String {
+        format!("{}{}", self.orna_guide_host, self.admin_base)
+    }
+
+    /// URL to the root of the codex, without trailing slash.
+    fn codex_root(&self) -> String {
+        format!("{}{}", self.playorna_host, self.codex_base)
+    }
+
     pub(crate) fn new_with_cookie(cookie: &str) -> Result<Self, Error> {
         let mut headers = HeaderMap::new();
         headers.insert("Cookie", HeaderValue::from_str(cookie).unwrap());
         Ok(Self {
-            http: Client::builder().default_headers(headers).build()?,
+            http: client_builder().default_headers(headers).build()?,
             ..Self::new()
         })
     }
@@ -229,18 +386,142 @@ impl Http {
         })
     }
 
+    /// Log in to the guide admin with the given credentials.
+    /// On success, the session cookie replaces whatever cookie the session had.
+    pub(crate) async fn async_login(
+        &mut self,
+        username: &str,
+        password: &str,
+    ) -> Result<(), Error> {
+        let url = format!("{}/login/", self.admin_root());
+        // Do not follow redirects, so we can read the cookies of the response to the POST.
+        let http = client_builder().redirect(Policy::none()).build()?;
+
+        if debug_urls()? {
+            eprintln!("--- GET {}", url);
+        }
+        let response = get_expect_200(&http, &url).await?;
+        let csrf_cookie = find_set_cookie(response.headers(), "csrftoken").ok_or_else(|| {
+            Error::LoginError(
+                username.to_string(),
+                "No csrftoken cookie on login page".to_string(),
+            )
+        })?;
+        let csrfmiddlewaretoken = parse_login_csrf_token(&response.text().await?)?;
+
+        if debug_urls()? {
+            eprintln!("--- POST {}", url);
+        }
+        let mut tmpurl = reqwest::Url::parse("http://x").unwrap();
+        tmpurl
+            .query_pairs_mut()
+            .append_pair("csrfmiddlewaretoken", &csrfmiddlewaretoken)
+            .append_pair("username", username)
+            .append_pair("password", password)
+            .append_pair("next", &format!("{}/", self.admin_base));
+        let body = tmpurl.query().unwrap().to_string();
+        let response = http
+            .post(&url)
+            .header("Referer", &url)
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .header("Cookie", &csrf_cookie)
+            .body(body)
+            .send()
+            .await?;
+
+        let status = response.status();
+        let session_cookie = find_set_cookie(response.headers(), "sessionid");
+        match (status.is_redirection(), session_cookie) {
+            (true, Some(session_cookie)) => {
+                let csrf_cookie =
+                    find_set_cookie(response.headers(), "csrftoken").unwrap_or(csrf_cookie);
+                let mut headers = HeaderMap::new();
+                headers.insert(
+                    "Cookie",
+                    HeaderValue::from_str(&format!("{}; {}", csrf_cookie, session_cookie))
+                        .map_err(|err| Error::LoginError(username.to_string(), err.to_string()))?,
+                );
+                self.http = client_builder().default_headers(headers).build()?;
+                Ok(())
+            }
+            _ => {
+                let text = response.text().await?;
+                let message = parse_login_error(&text)?
+                    .unwrap_or_else(|| format!("Unexpected response (HTTP {})", status.as_u16()));
+                Err(Error::LoginError(username.to_string(), message))
+            }
+        }
+    }
+
+    /// Log in to the guide admin with the given credentials.
+    pub(crate) fn login(&mut self, username: &str, password: &str) -> Result<(), Error> {
+        block_on_this_thread(self.async_login(username, password))
+    }
+
+    /// Download an image hosted on the guide.
+    /// The path is the `image_name` of the entity (e.g.: `items/sword.png`).
+    pub(crate) fn fetch_guide_image(&self, path: &str) -> Result<Vec<u8>, Error> {
+        get_bytes(
+            &self.http,
+            &format!("{}/static/orna/img/{}", self.orna_guide_host, path),
+        )
+    }
+
+    /// Download an image hosted on the codex.
+    /// The path is the `icon` of the entity (e.g.: `items/sword.png`).
+    pub(crate) fn fetch_codex_image(&self, path: &str) -> Result<Vec<u8>, Error> {
+        get_bytes(
+            &self.http,
+            &format!("{}/static/img/{}", self.playorna_host, path),
+        )
+    }
+
     // --- Guide Admin ---
 
+    /// Fetch the admin add pages of items, monsters, skills and pets and list the fields they
+    /// contain that we do not know of.
+    /// Returns, for each kind of entity with unknown fields, the kind and the unknown field names.
+    pub(crate) fn admin_unknown_form_fields(&self) -> Result<Vec<(String, Vec<String>)>, Error> {
+        let forms: [(&str, &str, &str, &[&str]); 4] = [
+            ("item", "items/item", "#item_form", ITEM_FORM_FIELD_NAMES),
+            (
+                "monster",
+                "monsters/monster",
+                "#monster_form",
+                MONSTER_FORM_FIELD_NAMES,
+            ),
+            (
+                "skill",
+                "skills/skill",
+                "#skill_form",
+                SKILL_FORM_FIELD_NAMES,
+            ),
+            ("pet", "pets/pet", "#pet_form", PET_FORM_FIELD_NAMES),
+        ];
+        let mut ret = Vec::new();
+        for (kind, model_path, form_root_name, expected) in forms {
+            let url = format!("{}/{}/add/", self.admin_root(), model_path);
+            let form = parse_all_fields_html(&self.get_and_save(&url)?, form_root_name)?;
+            let unknown = form.unknown_fields_vs(expected);
+            if !unknown.is_empty() {
+                ret.push((
+                    kind.to_string(),
+                    unknown.into_iter().map(str::to_string).collect(),
+                ));
+            }
+        }
+        Ok(ret)
+    }
+
     // Guide Admin Items
     pub(crate) async fn async_admin_retrieve_item_by_id(
         &self,
         id: u32,
     ) -> Result<ParsedForm, Error> {
-        let url = format!("{}/admin/items/item/{}/change/", self.orna_guide_host, id);
-        parse_item_html(
-            &async_get_and_save(&self.http, &url).await?,
-            ITEM_FORM_FIELD_NAMES,
-        )
+        let url = format!("{}/items/item/{}/change/", self.admin_root(), id);
+        let contents = self.async_get_and_save(&url).await?;
+        validate_form_if_enabled(&contents, "#item_form", ITEM_FORM_FIELD_NAMES)?;
+        parse_item_html(&contents, ITEM_FORM_FIELD_NAMES)
     }
 
     #[allow(dead_code)]
@@ -251,22 +532,33 @@ impl Http {
     pub(crate) fn admin_save_item(&self, id: u32, form: ParsedForm) -> Result<(), Error> {
         post_forms_to(
             &self.http,
-            &format!("{}/admin/items/item/{}/change/", self.orna_guide_host, id),
+            &format!("{}/items/item/{}/change/", self.admin_root(), id),
             form,
             "#item_form",
         )
     }
 
     pub(crate) fn admin_retrieve_items_list(&self) -> Result<Vec<Entry>, Error> {
-        let url = format!("{}/admin/items/item/", self.orna_guide_host);
-        query_all_pages(&url, &self.http)
+        let url = format!("{}/items/item/", self.admin_root());
+        query_all_pages(&url, self)
+    }
+
+    pub(crate) fn admin_search_items(&self, query: &str) -> Result<Vec<Entry>, Error> {
+        let url = format!("{}/items/item/", self.admin_root());
+        query_all_pages(&admin_search_url(&url, query), self)
     }
 
-    pub(crate) fn admin_add_item(&self, form: ParsedForm) -> Result<(), Error> {
-        let url = format!("{}/admin/items/item/add/", self.orna_guide_host);
-        let mut post_form = parse_item_html(&get_and_save(&self.http, &url)?, &[])?;
+    pub(crate) fn admin_add_item(&self, form: ParsedForm) -> Result<u32, Error> {
+        let url = format!("{}/items/item/add/", self.admin_root());
+        let mut post_form = parse_item_html(&self.get_and_save(&url)?, &[])?;
         post_form.fields = form.fields;
-        post_forms_to(&self.http, &url, post_form, "#item_form")
+        post_add_form_to(
+            &self.http,
+            &url,
+            post_form,
+            "#item_form",
+            &format!("{}/items/item/", self.admin_base),
+        )
     }
 
     // Guide Admin Monsters
@@ -274,14 +566,10 @@ impl Http {
         &self,
         id: u32,
     ) -> Result<ParsedForm, Error> {
-        let url = format!(
-            "{}/admin/monsters/monster/{}/change/",
-            self.orna_guide_host, id
-        );
-        parse_monster_html(
-            &async_get_and_save(&self.http, &url).await?,
-            MONSTER_FORM_FIELD_NAMES,
-        )
+        let url = format!("{}/monsters/monster/{}/change/", self.admin_root(), id);
+        let contents = self.async_get_and_save(&url).await?;
+        validate_form_if_enabled(&contents, "#monster_form", MONSTER_FORM_FIELD_NAMES)?;
+        parse_monster_html(&contents, MONSTER_FORM_FIELD_NAMES)
     }
 
     #[allow(dead_code)]
@@ -292,25 +580,33 @@ impl Http {
     pub(crate) fn admin_save_monster(&self, id: u32, form: ParsedForm) -> Result<(), Error> {
         post_forms_to(
             &self.http,
-            &format!(
-                "{}/admin/monsters/monster/{}/change/",
-                self.orna_guide_host, id
-            ),
+            &format!("{}/monsters/monster/{}/change/", self.admin_root(), id),
             form,
             "#monster_form",
         )
     }
 
     pub(crate) fn admin_retrieve_monsters_list(&self) -> Result<Vec<Entry>, Error> {
-        let url = format!("{}/admin/monsters/monster/", self.orna_guide_host);
-        query_all_pages(&url, &self.http)
+        let url = format!("{}/monsters/monster/", self.admin_root());
+        query_all_pages(&url, self)
+    }
+
+    pub(crate) fn admin_search_monsters(&self, query: &str) -> Result<Vec<Entry>, Error> {
+        let url = format!("{}/monsters/monster/", self.admin_root());
+        query_all_pages(&admin_search_url(&url, query), self)
     }
 
-    pub(crate) fn admin_add_monster(&self, form: ParsedForm) -> Result<(), Error> {
-        let url = format!("{}/admin/monsters/monster/add/", self.orna_guide_host);
-        let mut post_form = parse_monster_html(&get_and_save(&self.http, &url)?, &[])?;
+    pub(crate) fn admin_add_monster(&self, form: ParsedForm) -> Result<u32, Error> {
+        let url = format!("{}/monsters/monster/add/", self.admin_root());
+        let mut post_form = parse_monster_html(&self.get_and_save(&url)?, &[])?;
         post_form.fields = form.fields;
-        post_forms_to(&self.http, &url, post_form, "#monster_form")
+        post_add_form_to(
+            &self.http,
+            &url,
+            post_form,
+            "#monster_form",
+            &format!("{}/monsters/monster/", self.admin_base),
+        )
     }
 
     // Guide Admin Skills
@@ -318,11 +614,10 @@ impl Http {
         &self,
         id: u32,
     ) -> Result<ParsedForm, Error> {
-        let url = format!("{}/admin/skills/skill/{}/change/", self.orna_guide_host, id);
-        parse_skill_html(
-            &async_get_and_save(&self.http, &url).await?,
-            SKILL_FORM_FIELD_NAMES,
-        )
+        let url = format!("{}/skills/skill/{}/change/", self.admin_root(), id);
+        let contents = self.async_get_and_save(&url).await?;
+        validate_form_if_enabled(&contents, "#skill_form", SKILL_FORM_FIELD_NAMES)?;
+        parse_skill_html(&contents, SKILL_FORM_FIELD_NAMES)
     }
 
     #[allow(dead_code)]
@@ -333,22 +628,33 @@ impl Http {
     pub(crate) fn admin_save_skill(&self, id: u32, form: ParsedForm) -> Result<(), Error> {
         post_forms_to(
             &self.http,
-            &format!("{}/admin/skills/skill/{}/change/", self.orna_guide_host, id),
+            &format!("{}/skills/skill/{}/change/", self.admin_root(), id),
             form,
             "#skill_form",
         )
     }
 
     pub(crate) fn admin_retrieve_skills_list(&self) -> Result<Vec<Entry>, Error> {
-        let url = format!("{}/admin/skills/skill/", self.orna_guide_host);
-        query_all_pages(&url, &self.http)
+        let url = format!("{}/skills/skill/", self.admin_root());
+        query_all_pages(&url, self)
+    }
+
+    pub(crate) fn admin_search_skills(&self, query: &str) -> Result<Vec<Entry>, Error> {
+        let url = format!("{}/skills/skill/", self.admin_root());
+        query_all_pages(&admin_search_url(&url, query), self)
     }
 
-    pub(crate) fn admin_add_skill(&self, form: ParsedForm) -> Result<(), Error> {
-        let url = format!("{}/admin/skills/skill/add/", self.orna_guide_host);
-        let mut post_form = parse_skill_html(&get_and_save(&self.http, &url)?, &[])?;
+    pub(crate) fn admin_add_skill(&self, form: ParsedForm) -> Result<u32, Error> {
+        let url = format!("{}/skills/skill/add/", self.admin_root());
+        let mut post_form = parse_skill_html(&self.get_and_save(&url)?, &[])?;
         post_form.fields = form.fields;
-        post_forms_to(&self.http, &url, post_form, "#skill_form")
+        post_add_form_to(
+            &self.http,
+            &url,
+            post_form,
+            "#skill_form",
+            &format!("{}/skills/skill/", self.admin_base),
+        )
     }
 
     // Guide Admin Pets
@@ -356,11 +662,10 @@ impl Http {
         &self,
         id: u32,
     ) -> Result<ParsedForm, Error> {
-        let url = format!("{}/admin/pets/pet/{}/change/", self.orna_guide_host, id);
-        parse_pet_html(
-            &async_get_and_save(&self.http, &url).await?,
-            PET_FORM_FIELD_NAMES,
-        )
+        let url = format!("{}/pets/pet/{}/change/", self.admin_root(), id);
+        let contents = self.async_get_and_save(&url).await?;
+        validate_form_if_enabled(&contents, "#pet_form", PET_FORM_FIELD_NAMES)?;
+        parse_pet_html(&contents, PET_FORM_FIELD_NAMES)
     }
 
     #[allow(dead_code)]
@@ -371,66 +676,77 @@ impl Http {
     pub(crate) fn admin_save_pet(&self, id: u32, form: ParsedForm) -> Result<(), Error> {
         post_forms_to(
             &self.http,
-            &format!("{}/admin/pets/pet/{}/change/", self.orna_guide_host, id),
+            &format!("{}/pets/pet/{}/change/", self.admin_root(), id),
             form,
             "#pet_form",
         )
     }
 
     pub(crate) fn admin_retrieve_pets_list(&self) -> Result<Vec<Entry>, Error> {
-        let url = format!("{}/admin/pets/pet/", self.orna_guide_host);
-        query_all_pages(&url, &self.http)
+        let url = format!("{}/pets/pet/", self.admin_root());
+        query_all_pages(&url, self)
     }
 
-    pub(crate) fn admin_add_pet(&self, form: ParsedForm) -> Result<(), Error> {
-        let url = format!("{}/admin/pets/pet/add/", self.orna_guide_host);
-        let mut post_form = parse_pet_html(&get_and_save(&self.http, &url)?, &[])?;
+    pub(crate) fn admin_search_pets(&self, query: &str) -> Result<Vec<Entry>, Error> {
+        let url = format!("{}/pets/pet/", self.admin_root());
+        query_all_pages(&admin_search_url(&url, query), self)
+    }
+
+    pub(crate) fn admin_add_pet(&self, form: ParsedForm) -> Result<u32, Error> {
+        let url = format!("{}/pets/pet/add/", self.admin_root());
+        let mut post_form = parse_pet_html(&self.get_and_save(&url)?, &[])?;
         post_form.fields = form.fields;
-        post_forms_to(&self.http, &url, post_form, "#pet_form")
+        post_add_form_to(
+            &self.http,
+            &url,
+            post_form,
+            "#pet_form",
+            &format!("{}/pets/pet/", self.admin_base),
+        )
     }
 
     // Guide Static data
     pub(crate) fn admin_retrieve_spawns_list(&self) -> Result<Vec<Entry>, Error> {
-        let url = format!("{}/admin/orna/spawn/", self.orna_guide_host);
-        query_all_pages(&url, &self.http)
+        let url = format!("{}/orna/spawn/", self.admin_root());
+        query_all_pages(&url, self)
     }
 
     pub(crate) fn admin_retrieve_item_categories_list(&self) -> Result<Vec<Entry>, Error> {
-        let url = format!("{}/admin/items/category/", self.orna_guide_host);
-        query_all_pages(&url, &self.http)
+        let url = format!("{}/items/category/", self.admin_root());
+        query_all_pages(&url, self)
     }
 
     pub(crate) fn admin_retrieve_item_types_list(&self) -> Result<Vec<Entry>, Error> {
-        let url = format!("{}/admin/items/type/", self.orna_guide_host);
-        query_all_pages(&url, &self.http)
+        let url = format!("{}/items/type/", self.admin_root());
+        query_all_pages(&url, self)
     }
 
     pub(crate) fn admin_retrieve_monster_families_list(&self) -> Result<Vec<Entry>, Error> {
-        let url = format!("{}/admin/monsters/family/", self.orna_guide_host);
-        query_all_pages(&url, &self.http)
+        let url = format!("{}/monsters/family/", self.admin_root());
+        query_all_pages(&url, self)
     }
 
     pub(crate) fn admin_retrieve_status_effects_list(&self) -> Result<Vec<Entry>, Error> {
-        let url = format!("{}/admin/orna/statuseffect/", self.orna_guide_host);
-        query_all_pages(&url, &self.http)
+        let url = format!("{}/orna/statuseffect/", self.admin_root());
+        query_all_pages(&url, self)
     }
 
     pub(crate) fn admin_retrieve_skill_types_list(&self) -> Result<Vec<Entry>, Error> {
-        let url = format!("{}/admin/skills/skilltype/", self.orna_guide_host);
-        query_all_pages(&url, &self.http)
+        let url = format!("{}/skills/skilltype/", self.admin_root());
+        query_all_pages(&url, self)
     }
 
     pub(crate) fn admin_add_spawn(&self, spawn_name: &str) -> Result<(), Error> {
-        let url = format!("{}/admin/orna/spawn/add/", self.orna_guide_host);
-        let mut form = parse_spawn_html(&get_and_save(&self.http, &url)?)?;
+        let url = format!("{}/orna/spawn/add/", self.admin_root());
+        let mut form = parse_spawn_html(&self.get_and_save(&url)?)?;
         form.fields
             .push(("description".to_string(), spawn_name.to_string()));
         post_forms_to(&self.http, &url, form, "#spawn_form")
     }
 
     pub(crate) fn admin_add_status_effect(&self, status_effect_name: &str) -> Result<(), Error> {
-        let url = format!("{}/admin/orna/statuseffect/add/", self.orna_guide_host);
-        let mut form = parse_status_effect_html(&get_and_save(&self.http, &url)?)?;
+        let url = format!("{}/orna/statuseffect/add/", self.admin_root());
+        let mut form = parse_status_effect_html(&self.get_and_save(&url)?)?;
         form.fields
             .push(("name".to_string(), status_effect_name.to_string()));
         post_forms_to(&self.http, &url, form, "#statuseffect_form")
@@ -440,71 +756,71 @@ impl Http {
 
     // Codex Skills
     pub(crate) fn codex_retrieve_skills_list(&self) -> Result<Vec<CodexListEntry>, Error> {
-        let url = format!("{}/codex/spells", self.playorna_host);
-        query_all_codex_pages(&url, &self.http)
+        let url = format!("{}/spells", self.codex_root());
+        query_all_codex_pages(&url, self)
     }
 
     pub(crate) fn codex_retrieve_skill(&self, skill_name: &str) -> Result<CodexSkill, Error> {
-        let url = format!("{}/codex/spells/{}", self.playorna_host, skill_name);
-        parse_html_codex_skill(&get_and_save(&self.http, &url)?, skill_name.to_string())
+        let url = format!("{}/spells/{}", self.codex_root(), skill_name);
+        parse_html_codex_skill(&self.get_and_save(&url)?, skill_name.to_string())
     }
 
     // Codex Monsters
     pub(crate) fn codex_retrieve_monsters_list(&self) -> Result<Vec<CodexListEntry>, Error> {
-        let url = format!("{}/codex/monsters", self.playorna_host);
-        query_all_codex_pages(&url, &self.http)
+        let url = format!("{}/monsters", self.codex_root());
+        query_all_codex_pages(&url, self)
     }
 
     pub(crate) fn codex_retrieve_monster(&self, monster_name: &str) -> Result<CodexMonster, Error> {
-        let url = format!("{}/codex/monsters/{}", self.playorna_host, monster_name);
-        parse_html_codex_monster(&get_and_save(&self.http, &url)?, monster_name.to_string())
+        let url = format!("{}/monsters/{}", self.codex_root(), monster_name);
+        parse_html_codex_monster(&self.get_and_save(&url)?, monster_name.to_string())
     }
 
     // Codex Bosses
     pub(crate) fn codex_retrieve_bosses_list(&self) -> Result<Vec<CodexListEntry>, Error> {
-        let url = format!("{}/codex/bosses", self.playorna_host);
-        query_all_codex_pages(&url, &self.http)
+        let url = format!("{}/bosses", self.codex_root());
+        query_all_codex_pages(&url, self)
     }
 
     pub(crate) fn codex_retrieve_boss(&self, boss_name: &str) -> Result<CodexBoss, Error> {
-        let url = format!("{}/codex/bosses/{}", self.playorna_host, boss_name);
-        parse_html_codex_boss(&get_and_save(&self.http, &url)?, boss_name.to_string())
+        let url = format!("{}/bosses/{}", self.codex_root(), boss_name);
+        parse_html_codex_boss(&self.get_and_save(&url)?, boss_name.to_string())
     }
 
     // Codex Raids
     pub(crate) fn codex_retrieve_raids_list(&self) -> Result<Vec<CodexListEntry>, Error> {
-        let url = format!("{}/codex/raids", self.playorna_host);
-        query_all_codex_pages(&url, &self.http)
+        let url = format!("{}/raids", self.codex_root());
+        query_all_codex_pages(&url, self)
     }
 
     pub(crate) fn codex_retrieve_raid(&self, raid_name: &str) -> Result<CodexRaid, Error> {
-        let url = format!("{}/codex/raids/{}", self.playorna_host, raid_name);
-        parse_html_codex_raid(&get_and_save(&self.http, &url)?, raid_name.to_string())
+        let url = format!("{}/raids/{}", self.codex_root(), raid_name);
+        parse_html_codex_raid(&self.get_and_save(&url)?, raid_name.to_string())
     }
 
     // Codex Items
     pub(crate) fn codex_retrieve_items_list(&self) -> Result<Vec<CodexListEntry>, Error> {
-        let url = format!("{}/codex/items", self.playorna_host);
-        query_all_codex_pages(&url, &self.http)
+        let url = format!("{}/items", self.codex_root());
+        query_all_codex_pages(&url, self)
     }
 
     pub(crate) fn codex_retrieve_item(&self, item_name: &str) -> Result<CodexItem, Error> {
-        let url = format!("{}/codex/items/{}", self.playorna_host, item_name);
-        parse_html_codex_item(&get_and_save(&self.http, &url)?, item_name.to_string())
+        let url = format!("{}/items/{}", self.codex_root(), item_name);
+        parse_html_codex_item(&self.get_and_save(&url)?, item_name.to_string())
     }
 
     // Codex Followers
     pub(crate) fn codex_retrieve_followers_list(&self) -> Result<Vec<CodexListEntry>, Error> {
-        let url = format!("{}/codex/followers", self.playorna_host);
-        query_all_codex_pages(&url, &self.http)
+        let url = format!("{}/followers", self.codex_root());
+        query_all_codex_pages(&url, self)
     }
 
     pub(crate) fn codex_retrieve_follower(
         &self,
         follower_name: &str,
     ) -> Result<CodexFollower, Error> {
-        let url = format!("{}/codex/followers/{}", self.playorna_host, follower_name);
-        parse_html_codex_follower(&get_and_save(&self.http, &url)?, follower_name.to_string())
+        let url = format!("{}/followers/{}", self.codex_root(), follower_name);
+        parse_html_codex_follower(&self.get_and_save(&url)?, follower_name.to_string())
     }
 
     // --- Codex i18n ---
@@ -515,10 +831,12 @@ impl Http {
         locale: &str,
     ) -> Result<CodexSkill, Error> {
         let url = format!(
-            "{}/codex/spells/{}/?lang={}",
-            self.playorna_host, skill_name, locale
+            "{}/spells/{}/?lang={}",
+            self.codex_root(),
+            skill_name,
+            locale
         );
-        parse_html_codex_skill_translation(&get_and_save(&self.http, &url)?, skill_name.to_string())
+        parse_html_codex_skill_translation(&self.get_and_save(&url)?, skill_name.to_string())
     }
 
     pub(crate) fn codex_retrieve_monster_translation(
@@ -527,13 +845,12 @@ impl Http {
         locale: &str,
     ) -> Result<CodexMonster, Error> {
         let url = format!(
-            "{}/codex/monsters/{}/?lang={}",
-            self.playorna_host, monster_name, locale
+            "{}/monsters/{}/?lang={}",
+            self.codex_root(),
+            monster_name,
+            locale
         );
-        parse_html_codex_monster_translation(
-            &get_and_save(&self.http, &url)?,
-            monster_name.to_string(),
-        )
+        parse_html_codex_monster_translation(&self.get_and_save(&url)?, monster_name.to_string())
     }
 
     pub(crate) fn codex_retrieve_boss_translation(
@@ -542,10 +859,12 @@ impl Http {
         locale: &str,
     ) -> Result<CodexBoss, Error> {
         let url = format!(
-            "{}/codex/bosses/{}/?lang={}",
-            self.playorna_host, boss_name, locale
+            "{}/bosses/{}/?lang={}",
+            self.codex_root(),
+            boss_name,
+            locale
         );
-        parse_html_codex_boss_translation(&get_and_save(&self.http, &url)?, boss_name.to_string())
+        parse_html_codex_boss_translation(&self.get_and_save(&url)?, boss_name.to_string())
     }
 
     pub(crate) fn codex_retrieve_raid_translation(
@@ -553,11 +872,8 @@ impl Http {
         raid_name: &str,
         locale: &str,
     ) -> Result<CodexRaid, Error> {
-        let url = format!(
-            "{}/codex/raids/{}/?lang={}",
-            self.playorna_host, raid_name, locale
-        );
-        parse_html_codex_raid_translation(&get_and_save(&self.http, &url)?, raid_name.to_string())
+        let url = format!("{}/raids/{}/?lang={}", self.codex_root(), raid_name, locale);
+        parse_html_codex_raid_translation(&self.get_and_save(&url)?, raid_name.to_string())
     }
 
     pub(crate) fn codex_retrieve_item_translation(
@@ -565,11 +881,8 @@ impl Http {
         item_name: &str,
         locale: &str,
     ) -> Result<CodexItem, Error> {
-        let url = format!(
-            "{}/codex/items/{}/?lang={}",
-            self.playorna_host, item_name, locale
-        );
-        parse_html_codex_item_translation(&get_and_save(&self.http, &url)?, item_name.to_string())
+        let url = format!("{}/items/{}/?lang={}", self.codex_root(), item_name, locale);
+        parse_html_codex_item_translation(&self.get_and_save(&url)?, item_name.to_string())
     }
 
     pub(crate) fn codex_retrieve_follower_translation(
@@ -578,12 +891,228 @@ impl Http {
         locale: &str,
     ) -> Result<CodexFollower, Error> {
         let url = format!(
-            "{}/codex/followers/{}/?lang={}",
-            self.playorna_host, follower_name, locale
+            "{}/followers/{}/?lang={}",
+            self.codex_root(),
+            follower_name,
+            locale
         );
-        parse_html_codex_follower_translation(
-            &get_and_save(&self.http, &url)?,
-            follower_name.to_string(),
+        parse_html_codex_follower_translation(&self.get_and_save(&url)?, follower_name.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        io::{Read, Write},
+        net::TcpListener,
+        time::Duration,
+    };
+
+    use flate2::{write::GzEncoder, Compression};
+
+    use crate::{
+        error::Error,
+        guide::http::{
+            admin_search_url, client_builder, get_expect_200_with_retries, save_html, Http,
+        },
+        utils::block_on_this_thread,
+    };
+
+    #[test]
+    fn test_gzip_response_is_decoded() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"<html>codex</html>").unwrap();
+        let body = encoder.finish().unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 4096];
+            let len = stream.read(&mut request).unwrap();
+            let request = String::from_utf8_lossy(&request[..len]).to_lowercase();
+            stream
+                .write_all(
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Encoding: gzip\r\nContent-Length: {}\r\n\
+                         Connection: close\r\n\r\n",
+                        body.len()
+                    )
+                    .as_bytes(),
+                )
+                .unwrap();
+            stream.write_all(&body).unwrap();
+            request
+        });
+
+        let client = client_builder().build().unwrap();
+        let text = block_on_this_thread(async {
+            client
+                .get(format!("http://{}/", addr))
+                .send()
+                .await
+                .unwrap()
+                .text()
+                .await
+                .unwrap()
+        });
+        assert_eq!(text, "<html>codex</html>");
+        let request = server.join().unwrap();
+        assert!(request.contains("accept-encoding:"));
+        assert!(request.contains("gzip"));
+    }
+
+    /// Serve the given responses, one per connection, and return the number of requests received.
+    fn serve_statuses(statuses: Vec<&'static str>) -> (String, std::thread::JoinHandle<usize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || {
+            let mut count = 0;
+            for status in statuses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = [0; 4096];
+                let _ = stream.read(&mut request).unwrap();
+                stream
+                    .write_all(
+                        format!(
+                            "HTTP/1.1 {}\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok",
+                            status
+                        )
+                        .as_bytes(),
+                    )
+                    .unwrap();
+                count += 1;
+            }
+            count
+        });
+        (format!("http://{}/", addr), server)
+    }
+
+    #[test]
+    fn test_get_retries_server_errors() {
+        let (url, server) = serve_statuses(vec![
+            "503 Service Unavailable",
+            "503 Service Unavailable",
+            "200 OK",
+        ]);
+        let client = client_builder().build().unwrap();
+        let text = block_on_this_thread(async {
+            get_expect_200_with_retries(&client, &url, 3, Duration::from_millis(1))
+                .await
+                .unwrap()
+                .text()
+                .await
+                .unwrap()
+        });
+        assert_eq!(text, "ok");
+        assert_eq!(server.join().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_get_does_not_retry_client_errors() {
+        let (url, server) = serve_statuses(vec!["404 Not Found"]);
+        let client = client_builder().build().unwrap();
+        let result = block_on_this_thread(get_expect_200_with_retries(
+            &client,
+            &url,
+            3,
+            Duration::from_millis(1),
+        ));
+        assert!(matches!(result, Err(Error::ResponseError(_, _, 404, _))));
+        assert_eq!(server.join().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_html_cache_dir() {
+        let cache_dir =
+            std::env::temp_dir().join(format!("ornaguide-htmls-{}", std::process::id()));
+        std::fs::create_dir_all(&cache_dir).unwrap();
+        let cached_files = || std::fs::read_dir(&cache_dir).unwrap().count();
+        save_html(
+            &cache_dir,
+            "https://orna.guide/items?show=1",
+            "<html></html>",
+        )
+        .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(cache_dir.join("orna.guide_items?show=1.html")).unwrap(),
+            "<html></html>"
+        );
+        assert_eq!(cached_files(), 1);
+
+        // Pages from the local machine are not saved.
+        for url in [
+            "http://localhost:8000/items",
+            "http://127.0.0.1:8000/items",
+            "http://[::1]:8000/items",
+        ] {
+            save_html(&cache_dir, url, "<html></html>").unwrap();
+        }
+        assert_eq!(cached_files(), 1);
+
+        // Neither are pages fetched through `Http`, whether caching is enabled or not.
+        let (url, server) = serve_statuses(vec!["200 OK", "200 OK"]);
+        let mut http = Http::new();
+        http.set_html_cache_dir(Some(cache_dir.clone()));
+        assert_eq!(http.get_and_save(&url).unwrap(), "ok");
+        http.set_html_cache_dir(None);
+        assert_eq!(http.get_and_save(&url).unwrap(), "ok");
+        assert_eq!(server.join().unwrap(), 2);
+        assert_eq!(cached_files(), 1);
+
+        std::fs::remove_dir_all(&cache_dir).unwrap();
+    }
+
+    /// Answer a single request and return the path that was requested.
+    fn serve_path() -> (String, std::thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 4096];
+            let len = stream.read(&mut request).unwrap();
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok")
+                .unwrap();
+            String::from_utf8_lossy(&request[..len])
+                .split(' ')
+                .nth(1)
+                .unwrap_or_default()
+                .to_string()
+        });
+        (format!("http://{}", addr), server)
+    }
+
+    #[test]
+    fn test_base_paths() {
+        let mut http = Http::new_with_cookie_and_hosts(
+            "",
+            "http://localhost:8000".to_string(),
+            "http://localhost:8001".to_string(),
         )
+        .unwrap();
+        assert_eq!(http.admin_root(), "http://localhost:8000/admin");
+        assert_eq!(http.codex_root(), "http://localhost:8001/codex");
+
+        http.set_base_paths("/mirror/admin", "/mirror/codex/");
+        assert_eq!(http.admin_root(), "http://localhost:8000/mirror/admin");
+        assert_eq!(http.codex_root(), "http://localhost:8001/mirror/codex");
+
+        // Requests are sent to the mirror.
+        let (host, server) = serve_path();
+        let mut http = Http::new_with_cookie_and_hosts("", host.clone(), host).unwrap();
+        http.set_html_cache_dir(None);
+        http.set_base_paths("/mirror/admin", "/mirror/codex/");
+        // The response is not a codex page, only the request matters.
+        let _ = http.codex_retrieve_item("sword");
+        assert_eq!(server.join().unwrap(), "/mirror/codex/items/sword");
+    }
+
+    #[test]
+    fn test_admin_search_url() {
+        assert_eq!(
+            admin_search_url("https://orna.guide/admin/items/item/", "Fire sword & co"),
+            "https://orna.guide/admin/items/item/?q=Fire+sword+%26+co"
+        );
     }
 }
@@ -0,0 +1,141 @@
+use kuchiki::{parse_html, traits::TendrilSink};
+use reqwest::header::{HeaderMap, SET_COOKIE};
+
+use crate::{
+    error::Error,
+    utils::html::{descend_to, get_attribute_from_node, node_to_text, try_descend_to},
+};
+
+/// Parse the admin login page and return the csrf token of the login form.
+pub fn parse_login_csrf_token(contents: &str) -> Result<String, Error> {
+    let html = parse_html().one(contents);
+    let form = descend_to(&html, "#login-form", "html")?;
+    let input = descend_to(
+        form.as_node(),
+        "[name=\"csrfmiddlewaretoken\"]",
+        "login form",
+    )?;
+    get_attribute_from_node(input.as_node(), "value", "csrfmiddlewaretoken")
+}
+
+/// When receiving the response to a login POST request, look for a login error.
+/// The guide answers with the login form again if the login failed. Returns `None` if the login
+/// form isn't in the response.
+pub fn parse_login_error(contents: &str) -> Result<Option<String>, Error> {
+    let html = parse_html().one(contents);
+    let form = match try_descend_to(&html, "#login-form", "html")? {
+        Some(x) => x,
+        None => return Ok(None),
+    };
+    Ok(Some(
+        match try_descend_to(&html, ".errornote", "login page")? {
+            Some(node) => node_to_text(node.as_node()),
+            None => try_descend_to(form.as_node(), ".errorlist", "login form")?
+                .map(|node| node_to_text(node.as_node()))
+                .unwrap_or_else(|| "Login form was sent back".to_string()),
+        },
+    ))
+}
+
+/// Find the cookie with the given name in the `Set-Cookie` headers of a response.
+/// Returns the `name=value` pair, stripped of its attributes.
+pub fn find_set_cookie(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get_all(SET_COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .filter_map(|value| value.split(';').next())
+        .map(str::trim)
+        .find(|cookie| {
+            cookie
+                .split_once('=')
+                .map(|(key, _)| key == name)
+                .unwrap_or(false)
+        })
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod test {
+    use reqwest::header::{HeaderMap, HeaderValue, SET_COOKIE};
+
+    use crate::guide::login_parser::{find_set_cookie, parse_login_csrf_token, parse_login_error};
+
+    static LOGIN_PAGE: &str = r#"<html><body><div id="content" class="colM">
+<div id="content-main">
+<form action="/admin/login/?next=/admin/" method="post" id="login-form"><input type="hidden" name="csrfmiddlewaretoken" value="Ab12Cd34">
+  <div class="form-row">
+    <label class="required" for="id_username">Username:</label> <input type="text" name="username" autofocus autocapitalize="none" autocomplete="username" maxlength="150" required id="id_username">
+  </div>
+  <div class="form-row">
+    <label class="required" for="id_password">Password:</label> <input type="password" name="password" autocomplete="current-password" required id="id_password">
+    <input type="hidden" name="next" value="/admin/">
+  </div>
+  <div class="submit-row">
+    <input type="submit" value="Log in">
+  </div>
+</form>
+</div>
+</div></body></html>"#;
+
+    static LOGIN_FAILED_PAGE: &str = r#"<html><body><div id="content" class="colM">
+<p class="errornote">
+Please enter the correct username and password for a staff account. Note that both fields may be case-sensitive.
+</p>
+<div id="content-main">
+<form action="/admin/login/?next=/admin/" method="post" id="login-form"><input type="hidden" name="csrfmiddlewaretoken" value="Ef56Gh78">
+  <div class="form-row">
+    <label class="required" for="id_username">Username:</label> <input type="text" name="username" value="ethi" required id="id_username">
+  </div>
+</form>
+</div>
+</div></body></html>"#;
+
+    static ADMIN_INDEX_PAGE: &str = r#"<html><body><div id="content" class="colMS">
+<h1>Site administration</h1>
+</div></body></html>"#;
+
+    #[test]
+    fn test_parse_login_page() {
+        assert_eq!(parse_login_csrf_token(LOGIN_PAGE).unwrap(), "Ab12Cd34");
+        assert!(parse_login_csrf_token(ADMIN_INDEX_PAGE).is_err());
+    }
+
+    #[test]
+    fn test_parse_login_success() {
+        assert_eq!(parse_login_error(ADMIN_INDEX_PAGE).unwrap(), None);
+
+        let mut headers = HeaderMap::new();
+        headers.append(
+            SET_COOKIE,
+            HeaderValue::from_static(
+                "csrftoken=Zz99; expires=Thu, 01 Jan 2099 00:00:00 GMT; Path=/",
+            ),
+        );
+        headers.append(
+            SET_COOKIE,
+            HeaderValue::from_static("sessionid=s3ss10n; HttpOnly; Path=/"),
+        );
+        assert_eq!(
+            find_set_cookie(&headers, "sessionid"),
+            Some("sessionid=s3ss10n".to_string())
+        );
+        assert_eq!(
+            find_set_cookie(&headers, "csrftoken"),
+            Some("csrftoken=Zz99".to_string())
+        );
+        assert_eq!(find_set_cookie(&headers, "session"), None);
+    }
+
+    #[test]
+    fn test_parse_login_failure() {
+        assert_eq!(
+            parse_login_error(LOGIN_FAILED_PAGE).unwrap(),
+            Some(
+                "Please enter the correct username and password for a staff account. Note that \
+                 both fields may be case-sensitive."
+                    .to_string()
+            )
+        );
+    }
+}
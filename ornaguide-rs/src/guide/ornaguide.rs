@@ -1,3 +1,7 @@
+use std::path::PathBuf;
+
+use futures::StreamExt;
+
 use crate::{
     codex::{
         BossEntry as CodexBossEntry, Codex, CodexMonster, CodexSkill,
@@ -39,6 +43,11 @@ impl OrnaGuide {
     fn http(&self) -> &Http {
         &self.http
     }
+
+    /// Get the http session from the guide, mutably.
+    fn http_mut(&mut self) -> &mut Http {
+        &mut self.http
+    }
 }
 
 impl Default for OrnaGuide {
@@ -72,6 +81,43 @@ impl OrnaAdminGuide {
         })
     }
 
+    /// Override the paths of the admin panel (`/admin` by default) and of the codex (`/codex` by
+    /// default), for mirrors that serve them elsewhere.
+    pub fn with_base_paths(mut self, admin_base: &str, codex_base: &str) -> Self {
+        self.guide.http_mut().set_base_paths(admin_base, codex_base);
+        self
+    }
+
+    /// Set the directory in which the HTML pages fetched from the guide and the codex are saved
+    /// (`data/htmls` by default). Pass `None` to not save them at all.
+    pub fn with_html_cache_dir(mut self, html_cache_dir: Option<PathBuf>) -> Self {
+        self.guide.http_mut().set_html_cache_dir(html_cache_dir);
+        self
+    }
+
+    /// Log in to the guide with the given credentials, replacing the session cookie.
+    /// Returns `Error::LoginError` if the guide rejected the credentials.
+    pub fn login(&mut self, username: &str, password: &str) -> Result<(), Error> {
+        self.guide.http_mut().login(username, password)
+    }
+
+    /// Download the image with the given `image_name` from the guide.
+    pub fn fetch_guide_image(&self, image_name: &str) -> Result<Vec<u8>, Error> {
+        self.guide.http().fetch_guide_image(image_name)
+    }
+
+    /// Download the icon with the given path from the codex.
+    pub fn fetch_codex_image(&self, icon: &str) -> Result<Vec<u8>, Error> {
+        self.guide.http().fetch_codex_image(icon)
+    }
+
+    /// List the fields of the admin forms that the parsers do not know of.
+    /// Returns, for each kind of entity (`item`, `monster`, ...) with unknown fields, the kind and
+    /// the names of the unknown fields. These fields would be dropped when saving an entity.
+    pub fn admin_unknown_form_fields(&self) -> Result<Vec<(String, Vec<String>)>, Error> {
+        self.guide.http().admin_unknown_form_fields()
+    }
+
     /// Retrieve the item with the given id from the guide (asynchronous).
     pub async fn async_admin_retrieve_item_by_id(&self, id: u32) -> Result<AdminItem, Error> {
         Ok(AdminItem {
@@ -122,6 +168,22 @@ impl AdminGuide for OrnaAdminGuide {
         block_on_this_thread(self.async_admin_retrieve_item_by_id(id))
     }
 
+    fn admin_retrieve_items_batch(
+        &self,
+        ids: &[u32],
+        concurrency: usize,
+    ) -> Vec<(u32, Result<AdminItem, Error>)> {
+        block_on_this_thread(
+            futures::stream::iter(
+                ids.iter().map(|id| async move {
+                    (*id, self.async_admin_retrieve_item_by_id(*id).await)
+                }),
+            )
+            .buffered(concurrency.max(1))
+            .collect(),
+        )
+    }
+
     fn admin_save_item(&self, item: AdminItem) -> Result<(), Error> {
         self.guide
             .http()
@@ -141,7 +203,20 @@ impl AdminGuide for OrnaAdminGuide {
             .collect())
     }
 
-    fn admin_add_item(&self, item: AdminItem) -> Result<(), Error> {
+    fn admin_search_items(&self, query: &str) -> Result<Vec<ItemRow>, Error> {
+        Ok(self
+            .guide
+            .http()
+            .admin_search_items(query)?
+            .into_iter()
+            .map(|entry| ItemRow {
+                id: entry.id,
+                name: entry.value,
+            })
+            .collect())
+    }
+
+    fn admin_add_item(&self, item: AdminItem) -> Result<u32, Error> {
         self.guide.http().admin_add_item(ParsedForm::from(item))
     }
 
@@ -171,7 +246,20 @@ impl AdminGuide for OrnaAdminGuide {
             .collect())
     }
 
-    fn admin_add_monster(&self, monster: AdminMonster) -> Result<(), Error> {
+    fn admin_search_monsters(&self, query: &str) -> Result<Vec<MonsterRow>, Error> {
+        Ok(self
+            .guide
+            .http()
+            .admin_search_monsters(query)?
+            .into_iter()
+            .map(|entry| MonsterRow {
+                id: entry.id,
+                name: entry.value,
+            })
+            .collect())
+    }
+
+    fn admin_add_monster(&self, monster: AdminMonster) -> Result<u32, Error> {
         self.guide
             .http()
             .admin_add_monster(ParsedForm::from(monster))
@@ -200,7 +288,20 @@ impl AdminGuide for OrnaAdminGuide {
             .collect())
     }
 
-    fn admin_add_skill(&self, skill: AdminSkill) -> Result<(), Error> {
+    fn admin_search_skills(&self, query: &str) -> Result<Vec<SkillRow>, Error> {
+        Ok(self
+            .guide
+            .http()
+            .admin_search_skills(query)?
+            .into_iter()
+            .map(|entry| SkillRow {
+                id: entry.id,
+                name: entry.value,
+            })
+            .collect())
+    }
+
+    fn admin_add_skill(&self, skill: AdminSkill) -> Result<u32, Error> {
         self.guide.http().admin_add_skill(ParsedForm::from(skill))
     }
 
@@ -227,7 +328,20 @@ impl AdminGuide for OrnaAdminGuide {
             .collect())
     }
 
-    fn admin_add_pet(&self, pet: AdminPet) -> Result<(), Error> {
+    fn admin_search_pets(&self, query: &str) -> Result<Vec<PetRow>, Error> {
+        Ok(self
+            .guide
+            .http()
+            .admin_search_pets(query)?
+            .into_iter()
+            .map(|entry| PetRow {
+                id: entry.id,
+                name: entry.value,
+            })
+            .collect())
+    }
+
+    fn admin_add_pet(&self, pet: AdminPet) -> Result<u32, Error> {
         self.guide.http().admin_add_pet(ParsedForm::from(pet))
     }
 
@@ -3,7 +3,9 @@ use kuchiki::{parse_html, traits::TendrilSink};
 
 use crate::{
     error::Error,
-    utils::html::{descend_iter, descend_to, list_attributes_form_node, node_to_text},
+    utils::html::{
+        descend_iter, descend_to, get_attribute_from_node, list_attributes_form_node, node_to_text,
+    },
 };
 
 /// When receiving the response to a form POST request, parse the response and look for errors.
@@ -49,3 +51,78 @@ pub fn parse_post_error_html(url: &str, contents: &str, form_root_name: &str) ->
         specific_errors,
     ))
 }
+
+/// Extract the id from the path to the change page of an entity.
+/// `model_path` is the path to the admin list of the entity (e.g.: `/admin/items/item/`). The
+/// change page is then `/admin/items/item/{id}/change/`. Host and query string are ignored.
+fn id_from_change_path(path: &str, model_path: &str) -> Option<u32> {
+    let path = path.split('?').next().unwrap_or_default();
+    let start = path.find(model_path)? + model_path.len();
+    path[start..].strip_suffix("/change/")?.parse().ok()
+}
+
+/// After adding an entity to the guide, find the id of the newly created entity.
+/// The guide either redirects to the change page of the new entity or to the list of entities with
+/// a success message linking to the change page of the new entity.
+/// `final_url` is the URL we ended on after following redirects, and `contents` the HTML page.
+pub fn parse_added_id(final_url: &str, contents: &str, model_path: &str) -> Result<u32, Error> {
+    if let Some(id) = id_from_change_path(final_url, model_path) {
+        return Ok(id);
+    }
+
+    let html = parse_html().one(contents);
+    descend_iter(&html, ".messagelist .success a", "add response")?
+        .filter_map(|node| get_attribute_from_node(node.as_node(), "href", "success link").ok())
+        .find_map(|href| id_from_change_path(&href, model_path))
+        .ok_or_else(|| {
+            Error::HTMLParsingError(format!(
+                "Failed to find the id of the added entity in {}",
+                final_url
+            ))
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::guide::post_error_parser::parse_added_id;
+
+    static ADD_RESPONSE: &str = r#"<html><body><div class="main" id="main">
+<div class="content">
+<ul class="messagelist">
+  <li class="success">The item “<a href="/admin/items/item/4321/change/">Fire Sword</a>” was added successfully.</li>
+</ul>
+<div id="content" class="flex"><h1>Select item to change</h1></div>
+</div></div></body></html>"#;
+
+    #[test]
+    fn test_parse_added_id_from_message() {
+        assert_eq!(
+            parse_added_id(
+                "https://orna.guide/admin/items/item/",
+                ADD_RESPONSE,
+                "/admin/items/item/"
+            )
+            .unwrap(),
+            4321
+        );
+        assert!(parse_added_id(
+            "https://orna.guide/admin/monsters/monster/",
+            ADD_RESPONSE,
+            "/admin/monsters/monster/"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_added_id_from_redirect() {
+        assert_eq!(
+            parse_added_id(
+                "https://orna.guide/admin/pets/pet/87/change/?_changelist_filters=q%3D",
+                "",
+                "/admin/pets/pet/"
+            )
+            .unwrap(),
+            87
+        );
+    }
+}
@@ -1,6 +1,12 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
-use crate::{error::Error, guide::html_form_parser::ParsedForm};
+use crate::{
+    error::Error,
+    guide::{html_form_parser::ParsedForm, Static},
+    utils::{lazy_index::LazyIndex, lazy_reverse_index::LazyReverseIndex},
+};
 
 /// An item fetched from the admin panel.
 #[derive(Clone, Debug, Serialize, Deserialize, Derivative)]
@@ -87,6 +93,7 @@ pub struct AdminItem {
     ///   * 2 at ornate quality
     ///   * 3 when Masterforged
     ///   * 4 when Godforged
+    ///
     /// Meaning the maximum adorns that an item can have is this + 4.
     pub base_adornment_slots: u8,
     /// Rarity of the item (based on the background of the item at common quality).
@@ -98,8 +105,10 @@ pub struct AdminItem {
     ///   - `LG`: Legendary (purple)
     pub rarity: String,
     /// Id of the element of the item, if equippable.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub element: Option<u32>,
     /// Ids of class categories who can equip the item, if equippable.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub equipped_by: Vec<u32>,
     /// Whether the item is two handed, if a weapon.
     pub two_handed: bool,
@@ -118,24 +127,54 @@ pub struct AdminItem {
     /// Whether this item is in the arena pool.
     pub arena: bool,
     /// Id of the category of the item, if a weapon (Staffs, Daggers, Polearms, ...).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub category: Option<u32>,
     /// Ids of statuses the item can inflict, if equippable.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub causes: Vec<u32>,
     /// Ids of statuses the item cures.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub cures: Vec<u32>,
     /// Ids of statuses the item gives.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub gives: Vec<u32>,
     /// Ids of statuses the item grants immunity to, if equippable.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub prevents: Vec<u32>,
     /// Ids of materials the item needs to be upgraded, if upgradable.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub materials: Vec<u32>,
     /// Price of the item, if it can be bought from shops.
     pub price: u32,
     /// Off-hand ability, if a weapon.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub ability: Option<u32>,
 }
 
 impl AdminItem {
+    /// Whether `self` and `other` have the same contents.
+    /// The order of list fields (`equipped_by`, `causes`, `cures`, `gives`, `prevents` and
+    /// `materials`) is ignored, as is the CSRF token.
+    pub fn semantically_eq(&self, other: &AdminItem) -> bool {
+        self == other || self.with_sorted_lists() == other.with_sorted_lists()
+    }
+
+    /// Return a copy of the item with all its list fields sorted.
+    fn with_sorted_lists(&self) -> Self {
+        let mut item = self.clone();
+        for list in [
+            &mut item.equipped_by,
+            &mut item.causes,
+            &mut item.cures,
+            &mut item.gives,
+            &mut item.prevents,
+            &mut item.materials,
+        ] {
+            list.sort_unstable();
+        }
+        item
+    }
+
     /// Return the slug of the item.
     /// If the item has no `codex_uri`, return an empty string.
     pub fn slug(&self) -> &str {
@@ -214,99 +253,106 @@ impl TryFrom<ParsedForm> for AdminItem {
     type Error = Error;
 
     fn try_from(form: ParsedForm) -> Result<Self, Self::Error> {
+        // Name the entity in errors, so a failing conversion can be traced back to it.
+        let entity = format!("AdminItem '{}'", form.get("name").unwrap_or_default());
         let mut item = AdminItem {
             csrfmiddlewaretoken: form.csrfmiddlewaretoken,
             ..Default::default()
         };
 
         for (key, value) in form.fields.into_iter() {
-            match key.as_str() {
-                "codex" => item.codex_uri = value,
-                "name" => item.name = value,
-                "tier" => item.tier = value.parse()?,
-                "type" => item.type_ = value.parse()?,
-                "image_name" => item.image_name = value,
-                "description" => item.description = value,
-                "notes" => item.notes = value,
-                "hp" => item.hp = value.parse()?,
-                "hp_affected_by_quality" => item.hp_affected_by_quality = value == "on",
-                "mana" => item.mana = value.parse()?,
-                "mana_affected_by_quality" => item.mana_affected_by_quality = value == "on",
-                "attack" => item.attack = value.parse()?,
-                "attack_affected_by_quality" => item.attack_affected_by_quality = value == "on",
-                "magic" => item.magic = value.parse()?,
-                "magic_affected_by_quality" => item.magic_affected_by_quality = value == "on",
-                "defense" => item.defense = value.parse()?,
-                "defense_affected_by_quality" => item.defense_affected_by_quality = value == "on",
-                "resistance" => item.resistance = value.parse()?,
-                "resistance_affected_by_quality" => {
-                    item.resistance_affected_by_quality = value == "on"
-                }
-                "dexterity" => item.dexterity = value.parse()?,
-                "dexterity_affected_by_quality" => {
-                    item.dexterity_affected_by_quality = value == "on"
-                }
-                "ward" => item.ward = value.parse()?,
-                "ward_affected_by_quality" => item.ward_affected_by_quality = value == "on",
-                "crit" => item.crit = value.parse()?,
-                "crit_affected_by_quality" => item.crit_affected_by_quality = value == "on",
-                "foresight" => item.foresight = value.parse()?,
-                "view_distance" => item.view_distance = value.parse()?,
-                "follower_stats" => item.follower_stats = value.parse()?,
-                "follower_act" => item.follower_act = value.parse()?,
-                "status_infliction" => item.status_infliction = value.parse()?,
-                "status_protection" => item.status_protection = value.parse()?,
-                "mana_saver" => item.mana_saver = value.parse()?,
-                "potion_effectiveness" => item.potion_effectiveness = value.parse()?,
-                "has_slots" => item.has_slots = value == "on",
-                "base_adornment_slots" => item.base_adornment_slots = value.parse()?,
-                "rarity" => item.rarity = value,
-                "element" => {
-                    item.element = if value.is_empty() {
-                        None
-                    } else {
-                        Some(value.parse()?)
-                    }
-                }
-                "equipped_by" => item.equipped_by.push(value.parse()?),
-                "two_handed" => item.two_handed = value == "on",
-                "orn_bonus" => item.orn_bonus = value.parse()?,
-                "gold_bonus" => item.gold_bonus = value.parse()?,
-                "drop_bonus" => item.drop_bonus = value.parse()?,
-                "spawn_bonus" => item.spawn_bonus = value.parse()?,
-                "exp_bonus" => item.exp_bonus = value.parse()?,
-                "boss" => item.boss = value == "on",
-                "arena" => item.arena = value == "on",
-                "category" => {
-                    item.category = if value.is_empty() {
-                        None
-                    } else {
-                        Some(value.parse()?)
-                    }
-                }
-                "causes" => item.causes.push(value.parse()?),
-                "cures" => item.cures.push(value.parse()?),
-                "gives" => item.gives.push(value.parse()?),
-                "prevents" => item.prevents.push(value.parse()?),
-                "materials" => item.materials.push(value.parse()?),
-                "price" => item.price = value.parse()?,
-                "ability" => {
-                    item.ability = if value.is_empty() {
-                        None
-                    } else {
-                        Some(value.parse()?)
-                    }
-                }
-                key => {
-                    return Err(Error::ExtraField(key.to_string(), value));
-                }
-            }
+            set_field(&mut item, &key, value).map_err(|err| match err {
+                Error::ExtraField(..) => Error::ExtraField(entity.clone(), key),
+                _ => Error::InvalidField(entity.clone(), key, None),
+            })?;
         }
 
         Ok(item)
     }
 }
 
+/// Set the field `key` of the item from its form value.
+fn set_field(item: &mut AdminItem, key: &str, value: String) -> Result<(), Error> {
+    match key {
+        "codex" => item.codex_uri = value,
+        "name" => item.name = value,
+        "tier" => item.tier = value.parse()?,
+        "type" => item.type_ = value.parse()?,
+        "image_name" => item.image_name = value,
+        "description" => item.description = value,
+        "notes" => item.notes = value,
+        "hp" => item.hp = value.parse()?,
+        "hp_affected_by_quality" => item.hp_affected_by_quality = value == "on",
+        "mana" => item.mana = value.parse()?,
+        "mana_affected_by_quality" => item.mana_affected_by_quality = value == "on",
+        "attack" => item.attack = value.parse()?,
+        "attack_affected_by_quality" => item.attack_affected_by_quality = value == "on",
+        "magic" => item.magic = value.parse()?,
+        "magic_affected_by_quality" => item.magic_affected_by_quality = value == "on",
+        "defense" => item.defense = value.parse()?,
+        "defense_affected_by_quality" => item.defense_affected_by_quality = value == "on",
+        "resistance" => item.resistance = value.parse()?,
+        "resistance_affected_by_quality" => item.resistance_affected_by_quality = value == "on",
+        "dexterity" => item.dexterity = value.parse()?,
+        "dexterity_affected_by_quality" => item.dexterity_affected_by_quality = value == "on",
+        "ward" => item.ward = value.parse()?,
+        "ward_affected_by_quality" => item.ward_affected_by_quality = value == "on",
+        "crit" => item.crit = value.parse()?,
+        "crit_affected_by_quality" => item.crit_affected_by_quality = value == "on",
+        "foresight" => item.foresight = value.parse()?,
+        "view_distance" => item.view_distance = value.parse()?,
+        "follower_stats" => item.follower_stats = value.parse()?,
+        "follower_act" => item.follower_act = value.parse()?,
+        "status_infliction" => item.status_infliction = value.parse()?,
+        "status_protection" => item.status_protection = value.parse()?,
+        "mana_saver" => item.mana_saver = value.parse()?,
+        "potion_effectiveness" => item.potion_effectiveness = value.parse()?,
+        "has_slots" => item.has_slots = value == "on",
+        "base_adornment_slots" => item.base_adornment_slots = value.parse()?,
+        "rarity" => item.rarity = value,
+        "element" => {
+            item.element = if value.is_empty() {
+                None
+            } else {
+                Some(value.parse()?)
+            }
+        }
+        "equipped_by" => item.equipped_by.push(value.parse()?),
+        "two_handed" => item.two_handed = value == "on",
+        "orn_bonus" => item.orn_bonus = value.parse()?,
+        "gold_bonus" => item.gold_bonus = value.parse()?,
+        "drop_bonus" => item.drop_bonus = value.parse()?,
+        "spawn_bonus" => item.spawn_bonus = value.parse()?,
+        "exp_bonus" => item.exp_bonus = value.parse()?,
+        "boss" => item.boss = value == "on",
+        "arena" => item.arena = value == "on",
+        "category" => {
+            item.category = if value.is_empty() {
+                None
+            } else {
+                Some(value.parse()?)
+            }
+        }
+        "causes" => item.causes.push(value.parse()?),
+        "cures" => item.cures.push(value.parse()?),
+        "gives" => item.gives.push(value.parse()?),
+        "prevents" => item.prevents.push(value.parse()?),
+        "materials" => item.materials.push(value.parse()?),
+        "price" => item.price = value.parse()?,
+        "ability" => {
+            item.ability = if value.is_empty() {
+                None
+            } else {
+                Some(value.parse()?)
+            }
+        }
+        key => {
+            return Err(Error::ExtraField(key.to_string(), value));
+        }
+    }
+    Ok(())
+}
+
 impl From<AdminItem> for ParsedForm {
     fn from(item: AdminItem) -> Self {
         let mut form = ParsedForm {
@@ -444,12 +490,30 @@ impl From<AdminItem> for ParsedForm {
 pub struct AdminItems {
     /// Items from the guide's admin view.
     pub items: Vec<AdminItem>,
+    /// Index of `items` by id.
+    #[serde(skip)]
+    ids: LazyIndex<u32>,
+    /// Index of `items` by codex slug.
+    #[serde(skip)]
+    slugs: LazyIndex<str>,
+    /// Ids of the items using a material to upgrade, by material id.
+    #[serde(skip)]
+    upgraded_with: LazyReverseIndex,
+}
+
+impl From<Vec<AdminItem>> for AdminItems {
+    fn from(items: Vec<AdminItem>) -> Self {
+        Self {
+            items,
+            ..Default::default()
+        }
+    }
 }
 
 impl<'a> AdminItems {
     /// Find the admin item associated with the given id.
     pub fn find_by_id(&'a self, needle: u32) -> Option<&'a AdminItem> {
-        self.items.iter().find(|item| item.id == needle)
+        self.ids.find(&self.items, &needle, |item| Some(&item.id))
     }
 
     /// Find the admin item associated with the given id.
@@ -474,9 +538,10 @@ impl<'a> AdminItems {
 
     /// Find the admin item associated with the given slug.
     pub fn find_by_slug(&'a self, needle: &str) -> Option<&'a AdminItem> {
-        self.items.iter().find(|item| {
-            !item.codex_uri.is_empty()
-                && item.codex_uri["/codex/items/".len()..].trim_end_matches('/') == needle
+        self.slugs.find(&self.items, needle, |item| {
+            Some(item.codex_uri.as_str())
+                .filter(|uri| !uri.is_empty())
+                .map(|uri| uri["/codex/items/".len()..].trim_end_matches('/'))
         })
     }
 
@@ -490,4 +555,199 @@ impl<'a> AdminItems {
             ))
         })
     }
+
+    /// Return the ids of the items that use the item with the given id as an upgrade material.
+    /// The reverse index is built on the first call and reused until `items` changes (see
+    /// `LazyReverseIndex`).
+    pub fn upgraded_with(&mut self, material: u32) -> &[u32] {
+        self.upgraded_with.get(
+            &self.items,
+            material,
+            |item| item.id,
+            |item| &item.materials,
+        )
+    }
+
+    /// Count the items of each type.
+    /// Keys are the names of the types, as found in `static_`. Types that are not in `static_`
+    /// are named after their id (`#12`).
+    pub fn counts_by_type(&self, static_: &Static) -> HashMap<String, usize> {
+        let mut counts = HashMap::new();
+        for item in self.items.iter() {
+            let name = static_
+                .item_types
+                .iter()
+                .find(|type_| type_.id == item.type_)
+                .map(|type_| type_.name.clone())
+                .unwrap_or_else(|| format!("#{}", item.type_));
+            *counts.entry(name).or_insert(0) += 1;
+        }
+        counts
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use crate::{
+        guide::{html_form_parser::ParsedForm, ItemType, Static},
+        items::admin::{AdminItem, AdminItems},
+    };
+
+    #[test]
+    fn test_counts_by_type() {
+        let static_ = Static {
+            item_types: vec![
+                ItemType {
+                    id: 1,
+                    name: "Weapon".to_string(),
+                },
+                ItemType {
+                    id: 2,
+                    name: "Armor".to_string(),
+                },
+            ],
+            ..Static::default()
+        };
+        let items = AdminItems::from(
+            [1, 2, 1, 7]
+                .into_iter()
+                .map(|type_| AdminItem {
+                    type_,
+                    ..AdminItem::default()
+                })
+                .collect::<Vec<_>>(),
+        );
+
+        assert_eq!(
+            items.counts_by_type(&static_),
+            HashMap::from([
+                ("Weapon".to_string(), 2),
+                ("Armor".to_string(), 1),
+                ("#7".to_string(), 1),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_tier_form_round_trip() {
+        let item = AdminItem {
+            name: "Stick".to_string(),
+            tier: 12,
+            ..AdminItem::default()
+        };
+
+        let parsed = AdminItem::try_from(ParsedForm::from(item)).unwrap();
+        assert_eq!(parsed.tier, 12);
+    }
+
+    #[test]
+    fn test_serialize_omits_empty_fields() {
+        let item = AdminItem {
+            id: 12,
+            name: "Stick".to_string(),
+            causes: vec![3],
+            ..AdminItem::default()
+        };
+
+        let json = serde_json::to_value(&item).unwrap();
+        let object = json.as_object().unwrap();
+        assert!(!object.contains_key("materials"));
+        assert!(!object.contains_key("equipped_by"));
+        assert!(!object.contains_key("element"));
+        assert_eq!(object.get("causes"), Some(&serde_json::json!([3])));
+
+        let deserialized: AdminItem = serde_json::from_value(json).unwrap();
+        assert!(deserialized.materials.is_empty());
+        assert_eq!(deserialized.element, None);
+        assert_eq!(deserialized, item);
+    }
+
+    #[test]
+    fn test_semantically_eq() {
+        let item = AdminItem {
+            id: 1,
+            name: "Sword".to_string(),
+            causes: vec![1, 2, 3],
+            materials: vec![4, 5],
+            ..AdminItem::default()
+        };
+        let reordered = AdminItem {
+            causes: vec![3, 1, 2],
+            materials: vec![5, 4],
+            ..item.clone()
+        };
+        assert_ne!(item, reordered);
+        assert!(item.semantically_eq(&reordered));
+        assert!(reordered.semantically_eq(&item));
+
+        let changed = AdminItem {
+            causes: vec![3, 1, 4],
+            ..reordered.clone()
+        };
+        assert!(!item.semantically_eq(&changed));
+        let changed = AdminItem {
+            tier: 2,
+            ..reordered
+        };
+        assert!(!item.semantically_eq(&changed));
+    }
+
+    #[test]
+    fn test_upgraded_with() {
+        let item = |id, materials| AdminItem {
+            id,
+            materials,
+            ..AdminItem::default()
+        };
+        let mut items = AdminItems::from(vec![
+            item(1, vec![]),
+            item(2, vec![]),
+            item(3, vec![1]),
+            item(4, vec![1, 2]),
+            item(5, vec![2]),
+        ]);
+
+        assert_eq!(items.upgraded_with(1), &[3, 4]);
+        assert_eq!(items.upgraded_with(2), &[4, 5]);
+        assert!(items.upgraded_with(3).is_empty());
+        assert!(items.upgraded_with(12).is_empty());
+
+        // The index is reused by later calls.
+        let cached = items.upgraded_with(1).as_ptr();
+        assert_eq!(items.upgraded_with(2), &[4, 5]);
+        assert_eq!(items.upgraded_with(1).as_ptr(), cached);
+
+        // Modifications to the items are taken into account.
+        items.items[0].materials.push(2);
+        items.items.push(item(6, vec![3]));
+        assert_eq!(items.upgraded_with(2), &[1, 4, 5]);
+        assert_eq!(items.upgraded_with(3), &[6]);
+        items.items[4].materials.clear();
+        assert_eq!(items.upgraded_with(2), &[1, 4]);
+    }
+
+    #[test]
+    fn test_find_after_push() {
+        let item = |id, slug: &str| AdminItem {
+            id,
+            codex_uri: format!("/codex/items/{}/", slug),
+            ..AdminItem::default()
+        };
+        let mut items = AdminItems::from(vec![item(1, "sword"), item(2, "bow")]);
+        assert_eq!(items.find_by_id(2).map(|item| item.id), Some(2));
+        assert_eq!(items.find_by_slug("sword").map(|item| item.id), Some(1));
+        assert!(items.find_by_id(3).is_none());
+
+        items.items.push(item(3, "axe"));
+        assert_eq!(items.find_by_id(3).map(|item| item.id), Some(3));
+        assert_eq!(items.find_by_slug("axe").map(|item| item.id), Some(3));
+        assert_eq!(items.find_by_slug("bow").map(|item| item.id), Some(2));
+
+        items.items.remove(0);
+        assert!(items.find_by_id(1).is_none());
+        assert!(items.find_by_slug("sword").is_none());
+        assert_eq!(items.find_by_id(2).map(|item| item.id), Some(2));
+    }
 }
@@ -83,6 +83,34 @@ macro_rules! retry_once {
     };
 }
 
+/// Build a structure from its `Default` value, with the given fields set.
+/// This is akin to struct update syntax (`Type { a, b: c, ..Type::default() }`), but also works
+/// for structures with private fields outside of their crate (e.g.: `AdminItem`).
+/// ```
+/// # use ornaguide_rs::{items::admin::AdminItem, with_defaults};
+/// let id = 1;
+/// let item = with_defaults!(AdminItem {
+///     id,
+///     name: "Sword".to_string(),
+/// });
+/// assert_eq!(item.id, 1);
+/// assert_eq!(item.name, "Sword");
+/// ```
+#[macro_export]
+macro_rules! with_defaults {
+    (@value $field:ident : $value:expr) => {
+        $value
+    };
+    (@value $field:ident) => {
+        $field
+    };
+    ($type:path { $($field:ident $(: $value:expr)?),* $(,)? }) => {{
+        let mut entity = <$type>::default();
+        $(entity.$field = $crate::with_defaults!(@value $field $(: $value)?);)*
+        entity
+    }};
+}
+
 /// A trait to extend `Result<Vec<u32>, Error>`, when it comes from an attempt to convert an array
 /// of elements to an array of guide ids.
 pub trait VecIdConversionResult {
@@ -5,6 +5,7 @@ use crate::{
     error::Error,
     guide::{html_form_parser::ParsedForm, Spawn},
     misc::sanitize_guide_name,
+    utils::lazy_index::LazyIndex,
 };
 
 /// An item fetched from the admin panel.
@@ -26,6 +27,7 @@ pub struct AdminMonster {
     /// The tier of the monster.
     pub tier: u8,
     /// The family to which the monster belongs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub family: Option<u32>,
     /// Path to the image of the monster.
     pub image_name: String,
@@ -40,21 +42,29 @@ pub struct AdminMonster {
     /// Handwritten notes from the guide team on the monster.
     pub notes: String,
     /// Ids of where the monster spawns.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub spawns: Vec<u32>,
     /// Ids of elements to which the monster is weak.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub weak_to: Vec<u32>,
     /// Ids of elements to which the monster is resistant.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub resistant_to: Vec<u32>,
     /// Ids of elements to which the monster is immune.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub immune_to: Vec<u32>,
     /// Ids of statuses to which the monster is immune.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub immune_to_status: Vec<u32>,
     /// Ids of statuses to which the monster is vulnerable.
     /// This field is likely to disappear.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub vulnerable_to_status: Vec<u32>,
     /// Ids of items the monster drops.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub drops: Vec<u32>,
     /// Ids of skills the monster uses.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub skills: Vec<u32>,
 }
 
@@ -138,7 +148,7 @@ impl From<AdminMonster> for ParsedForm {
             "family",
             item.family
                 .map(|family| family.to_string())
-                .unwrap_or_else(String::new),
+                .unwrap_or_default(),
         );
         push("image_name", item.image_name);
         if item.boss {
@@ -280,6 +290,22 @@ impl AdminMonster {
             .collect()
     }
 
+    /// List the location spawns associated to the monster.
+    /// These are the spawns that are neither events nor raids.
+    pub fn get_location_spawns<'a>(&self, guide_spawns: &'a [Spawn]) -> Vec<&'a str> {
+        self.spawns
+            .iter()
+            .filter_map(|spawn_id| guide_spawns.iter().find(|spawn| spawn.id == *spawn_id))
+            .filter(|spawn| {
+                spawn.event_name().is_empty()
+                    && spawn.name != "Kingdom Raid"
+                    && spawn.name != "World Raid"
+            })
+            .map(|spawn| spawn.name.as_str())
+            .sorted()
+            .collect::<Vec<_>>()
+    }
+
     /// List the raid spawns associated to the monster.
     /// The spawns are either "Kingdom Raid" or "World Raid" (may be inclusive).
     pub fn get_raid_spawns<'a>(&self, guide_spawns: &'a [Spawn]) -> Vec<&'a str> {
@@ -303,12 +329,28 @@ impl AdminMonster {
 pub struct AdminMonsters {
     /// Monsters from the guide's admin view.
     pub monsters: Vec<AdminMonster>,
+    /// Index of `monsters` by id.
+    #[serde(skip)]
+    ids: LazyIndex<u32>,
+    /// Index of `monsters` by codex uri.
+    #[serde(skip)]
+    uris: LazyIndex<str>,
+}
+
+impl From<Vec<AdminMonster>> for AdminMonsters {
+    fn from(monsters: Vec<AdminMonster>) -> Self {
+        Self {
+            monsters,
+            ..Default::default()
+        }
+    }
 }
 
 impl<'a> AdminMonsters {
     /// Find the monster with the given id.
     pub fn find_by_id(&'a self, needle: u32) -> Option<&'a AdminMonster> {
-        self.monsters.iter().find(|monster| monster.id == needle)
+        self.ids
+            .find(&self.monsters, &needle, |monster| Some(&monster.id))
     }
 
     /// Find the monster with the given id
@@ -320,9 +362,9 @@ impl<'a> AdminMonsters {
 
     /// Find the monster with the given codex uri.
     pub fn find_by_uri(&'a self, needle: &str) -> Option<&'a AdminMonster> {
-        self.monsters
-            .iter()
-            .find(|monster| monster.codex_uri == needle)
+        self.uris.find(&self.monsters, needle, |monster| {
+            Some(monster.codex_uri.as_str())
+        })
     }
 
     /// Find the monster with the given codex uri.
@@ -336,3 +378,20 @@ impl<'a> AdminMonsters {
         })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{guide::html_form_parser::ParsedForm, monsters::admin::AdminMonster};
+
+    #[test]
+    fn test_tier_form_round_trip() {
+        let monster = AdminMonster {
+            name: "Slime".to_string(),
+            tier: 12,
+            ..AdminMonster::default()
+        };
+
+        let parsed = AdminMonster::try_from(ParsedForm::from(monster)).unwrap();
+        assert_eq!(parsed.tier, 12);
+    }
+}
@@ -222,3 +222,20 @@ impl<'a> AdminPets {
             .ok_or_else(|| Error::Misc(format!("No match for admin pet with id #{}", needle)))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{guide::html_form_parser::ParsedForm, pets::admin::AdminPet};
+
+    #[test]
+    fn test_tier_form_round_trip() {
+        let pet = AdminPet {
+            name: "Fox".to_string(),
+            tier: 12,
+            ..AdminPet::default()
+        };
+
+        let parsed = AdminPet::try_from(ParsedForm::from(pet)).unwrap();
+        assert_eq!(parsed.tier, 12);
+    }
+}
@@ -1,6 +1,13 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
-use crate::{error::Error, guide::html_form_parser::ParsedForm, misc::sanitize_guide_name};
+use crate::{
+    error::Error,
+    guide::{html_form_parser::ParsedForm, Static},
+    misc::sanitize_guide_name,
+    utils::lazy_index::LazyIndex,
+};
 
 /// A skill fetched from the admin panel.
 #[derive(Clone, Debug, Serialize, Deserialize, Derivative)]
@@ -28,6 +35,7 @@ pub struct AdminSkill {
     /// The in-game description of the skill.
     pub description: String,
     /// ID of the element of the skill.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub element: Option<u32>,
     /// Whether the skill is an off-hand skill.
     /// Off-hand skills have their own entry, that is distinct from the non-off-hand ones.
@@ -47,12 +55,16 @@ pub struct AdminSkill {
     /// Handwritten notes from the guide team on the item.
     pub extra: String,
     /// Ids of monsters who buff this skill (if a passive that requires kills).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub buffed_by: Vec<u32>,
     /// Ids of status effects the skill inflicts.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub causes: Vec<u32>,
     /// Ids of status effects the skill cures.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub cures: Vec<u32>,
     /// Ids of status effects the skill gives.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub gives: Vec<u32>,
 }
 
@@ -101,49 +113,60 @@ impl TryFrom<ParsedForm> for AdminSkill {
     type Error = Error;
 
     fn try_from(form: ParsedForm) -> Result<Self, Self::Error> {
+        // Name the entity in errors, so a failing conversion can be traced back to it.
+        let entity = format!("AdminSkill '{}'", form.get("name").unwrap_or_default());
         let mut item = AdminSkill {
             csrfmiddlewaretoken: form.csrfmiddlewaretoken,
             ..Default::default()
         };
 
         for (key, value) in form.fields.into_iter() {
-            match key.as_str() {
-                "codex" => item.codex_uri = value,
-                "name" => item.name = value,
-                "tier" => item.tier = value.parse()?,
-                "type" => item.type_ = value.parse()?,
-                "is_magic" => item.is_magic = value == "on",
-                "mana_cost" => item.mana_cost = value.parse()?,
-                "description" => item.description = value,
-                "element" => {
-                    item.element = if value.is_empty() {
-                        None
-                    } else {
-                        Some(value.parse()?)
-                    }
-                }
-                "offhand" => item.offhand = value == "on",
-                "cost" => item.cost = value.parse()?,
-                "bought" => item.bought = value == "on",
-                "skill_power" => item.skill_power = value.parse()?,
-                "strikes" => item.strikes = value.parse()?,
-                "modifier_min" => item.modifier_min = value.parse()?,
-                "modifier_max" => item.modifier_max = value.parse()?,
-                "extra" => item.extra = value,
-                "buffed_by" => item.buffed_by.push(value.parse()?),
-                "causes" => item.causes.push(value.parse()?),
-                "cures" => item.cures.push(value.parse()?),
-                "gives" => item.gives.push(value.parse()?),
-                key => {
-                    return Err(Error::ExtraField(key.to_string(), value));
-                }
-            }
+            set_field(&mut item, &key, value).map_err(|err| match err {
+                Error::ExtraField(..) => Error::ExtraField(entity.clone(), key),
+                _ => Error::InvalidField(entity.clone(), key, None),
+            })?;
         }
 
         Ok(item)
     }
 }
 
+/// Set the field `key` of the skill from its form value.
+fn set_field(item: &mut AdminSkill, key: &str, value: String) -> Result<(), Error> {
+    match key {
+        "codex" => item.codex_uri = value,
+        "name" => item.name = value,
+        "tier" => item.tier = value.parse()?,
+        "type" => item.type_ = value.parse()?,
+        "is_magic" => item.is_magic = value == "on",
+        "mana_cost" => item.mana_cost = value.parse()?,
+        "description" => item.description = value,
+        "element" => {
+            item.element = if value.is_empty() {
+                None
+            } else {
+                Some(value.parse()?)
+            }
+        }
+        "offhand" => item.offhand = value == "on",
+        "cost" => item.cost = value.parse()?,
+        "bought" => item.bought = value == "on",
+        "skill_power" => item.skill_power = value.parse()?,
+        "strikes" => item.strikes = value.parse()?,
+        "modifier_min" => item.modifier_min = value.parse()?,
+        "modifier_max" => item.modifier_max = value.parse()?,
+        "extra" => item.extra = value,
+        "buffed_by" => item.buffed_by.push(value.parse()?),
+        "causes" => item.causes.push(value.parse()?),
+        "cures" => item.cures.push(value.parse()?),
+        "gives" => item.gives.push(value.parse()?),
+        key => {
+            return Err(Error::ExtraField(key.to_string(), value));
+        }
+    }
+    Ok(())
+}
+
 impl From<AdminSkill> for ParsedForm {
     fn from(item: AdminSkill) -> Self {
         let mut form = ParsedForm {
@@ -179,6 +202,9 @@ impl From<AdminSkill> for ParsedForm {
         push("modifier_min", item.modifier_min.to_string());
         push("modifier_max", item.modifier_max.to_string());
         push("extra", item.extra);
+        for x in item.buffed_by.iter() {
+            push("buffed_by", x.to_string());
+        }
         for x in item.causes.iter() {
             push("causes", x.to_string());
         }
@@ -198,12 +224,28 @@ impl From<AdminSkill> for ParsedForm {
 pub struct AdminSkills {
     /// Skills from the guide's admin view.
     pub skills: Vec<AdminSkill>,
+    /// Index of `skills` by id.
+    #[serde(skip)]
+    ids: LazyIndex<u32>,
+    /// Index of `skills` by codex slug.
+    #[serde(skip)]
+    slugs: LazyIndex<str>,
+}
+
+impl From<Vec<AdminSkill>> for AdminSkills {
+    fn from(skills: Vec<AdminSkill>) -> Self {
+        Self {
+            skills,
+            ..Default::default()
+        }
+    }
 }
 
 impl<'a> AdminSkills {
     /// Find the admin skill corresponding to the given id.
     pub fn find_by_id(&'a self, needle: u32) -> Option<&'a AdminSkill> {
-        self.skills.iter().find(|skill| skill.id == needle)
+        self.ids
+            .find(&self.skills, &needle, |skill| Some(&skill.id))
     }
 
     /// Find the admin skill corresponding to the given id.
@@ -231,9 +273,10 @@ impl<'a> AdminSkills {
 
     /// Find the admin skill associated with the given slug
     pub fn find_by_slug(&'a self, needle: &str) -> Option<&'a AdminSkill> {
-        self.skills.iter().find(|skill| {
-            !skill.codex_uri.is_empty()
-                && skill.codex_uri["/codex/spells/".len()..].trim_end_matches('/') == needle
+        self.slugs.find(&self.skills, needle, |skill| {
+            Some(skill.codex_uri.as_str())
+                .filter(|uri| !uri.is_empty())
+                .map(|uri| uri["/codex/spells/".len()..].trim_end_matches('/'))
         })
     }
 
@@ -265,4 +308,55 @@ impl<'a> AdminSkills {
             ))
         })
     }
+
+    /// Count the skills of each type.
+    /// Keys are the names of the types, as found in `static_`. Types that are not in `static_`
+    /// are named after their id (`#12`).
+    pub fn counts_by_type(&self, static_: &Static) -> HashMap<String, usize> {
+        let mut counts = HashMap::new();
+        for skill in self.skills.iter() {
+            let name = static_
+                .skill_types
+                .iter()
+                .find(|type_| type_.id == skill.type_)
+                .map(|type_| type_.name.clone())
+                .unwrap_or_else(|| format!("#{}", skill.type_));
+            *counts.entry(name).or_insert(0) += 1;
+        }
+        counts
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{guide::html_form_parser::ParsedForm, skills::admin::AdminSkill};
+
+    #[test]
+    fn test_tier_form_round_trip() {
+        let skill = AdminSkill {
+            name: "Fireball".to_string(),
+            tier: 12,
+            ..AdminSkill::default()
+        };
+
+        let parsed = AdminSkill::try_from(ParsedForm::from(skill)).unwrap();
+        assert_eq!(parsed.tier, 12);
+    }
+
+    #[test]
+    fn test_invalid_field_names_entity() {
+        let form = ParsedForm {
+            fields: vec![
+                ("name".to_string(), "Fireball".to_string()),
+                ("type".to_string(), "Attack".to_string()),
+            ],
+            ..ParsedForm::default()
+        };
+
+        let err = AdminSkill::try_from(form).unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "Failed to convert AdminSkill 'Fireball': invalid field type"
+        );
+    }
 }
@@ -1,6 +1,8 @@
 use futures::Future;
 
 pub(crate) mod html;
+pub(crate) mod lazy_index;
+pub(crate) mod lazy_reverse_index;
 
 /// Build a tokio runtime for the current thread and await the future on it.
 pub fn block_on_this_thread<F: Future>(future: F) -> F::Output {
@@ -79,8 +79,8 @@ pub fn list_attributes_form_node(node: &NodeRef, node_name: &str) -> Result<Vec<
         let attributes = attributes.borrow();
         Ok(attributes
             .map
-            .iter()
-            .flat_map(|(_, value)| value.value.split(' ').map(str::to_string))
+            .values()
+            .flat_map(|value| value.value.split(' ').map(str::to_string))
             .collect())
     } else {
         Err(Error::HTMLParsingError(format!(
@@ -0,0 +1,145 @@
+use std::{collections::HashMap, hash::Hash, sync::RwLock};
+
+/// The length of the slice an index was built for, along with the index itself.
+type CachedIndex<K> = Option<(usize, HashMap<K, usize>)>;
+
+/// An index from keys to positions in a slice of entities, built on the first lookup.
+///
+/// The indexed slice is usually a public `Vec` that may be mutated at any time without the index
+/// knowing. Positions found in the index are thus checked against the slice before being
+/// returned. If the slice changed length, or if the index does not point to a matching entity,
+/// the slice is scanned and the index rebuilt.
+///
+/// Cloning the index returns an empty index and indices always compare equal, so that the index
+/// does not get in the way of the structure holding it.
+pub(crate) struct LazyIndex<Q>
+where
+    Q: ?Sized + ToOwned,
+{
+    /// The index, if it was built.
+    index: RwLock<CachedIndex<Q::Owned>>,
+}
+
+impl<Q> LazyIndex<Q>
+where
+    Q: ?Sized + ToOwned + Hash + Eq,
+    Q::Owned: Hash + Eq,
+{
+    /// Find the entity whose key is `needle` in `entities`.
+    /// `key_of` returns the key of an entity, if it has one. If multiple entities share the same
+    /// key, the first one at the time the index was built is returned.
+    pub(crate) fn find<'a, T>(
+        &self,
+        entities: &'a [T],
+        needle: &Q,
+        key_of: fn(&T) -> Option<&Q>,
+    ) -> Option<&'a T> {
+        let indexed = self.index.read().ok().and_then(|index| {
+            index
+                .as_ref()
+                .filter(|(len, _)| *len == entities.len())
+                .and_then(|(_, index)| index.get(needle))
+                .map(|pos| &entities[*pos])
+                .filter(|entity| key_of(entity) == Some(needle))
+        });
+        if indexed.is_some() {
+            return indexed;
+        }
+
+        // The index is either stale, not built or there is no such entity.
+        let found = entities
+            .iter()
+            .find(|entity| key_of(entity) == Some(needle));
+        if found.is_some() || !self.is_built_for(entities) {
+            self.rebuild(entities, key_of);
+        }
+        found
+    }
+
+    /// Whether the index has been built for a slice of the same length as `entities`.
+    fn is_built_for<T>(&self, entities: &[T]) -> bool {
+        self.index
+            .read()
+            .map(|index| matches!(index.as_ref(), Some((len, _)) if *len == entities.len()))
+            .unwrap_or(false)
+    }
+
+    /// Build the index anew from `entities`.
+    fn rebuild<T>(&self, entities: &[T], key_of: fn(&T) -> Option<&Q>) {
+        let mut map = HashMap::with_capacity(entities.len());
+        for (pos, entity) in entities.iter().enumerate() {
+            if let Some(key) = key_of(entity) {
+                map.entry(key.to_owned()).or_insert(pos);
+            }
+        }
+        if let Ok(mut index) = self.index.write() {
+            *index = Some((entities.len(), map));
+        }
+    }
+}
+
+impl<Q> Default for LazyIndex<Q>
+where
+    Q: ?Sized + ToOwned,
+{
+    fn default() -> Self {
+        Self {
+            index: RwLock::new(None),
+        }
+    }
+}
+
+impl<Q> Clone for LazyIndex<Q>
+where
+    Q: ?Sized + ToOwned,
+{
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl<Q> PartialEq for LazyIndex<Q>
+where
+    Q: ?Sized + ToOwned,
+{
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::utils::lazy_index::LazyIndex;
+
+    #[test]
+    fn test_lazy_index() {
+        let index = LazyIndex::<str>::default();
+        let key_of: fn(&(u32, String)) -> Option<&str> =
+            |entity| Some(entity.1.as_str()).filter(|name| !name.is_empty());
+        let mut entities = vec![(1, "sword".to_string()), (2, "bow".to_string())];
+        let id = |entity: Option<&(u32, String)>| entity.map(|entity| entity.0);
+
+        assert_eq!(id(index.find(&entities, "bow", key_of)), Some(2));
+        assert_eq!(id(index.find(&entities, "axe", key_of)), None);
+        assert_eq!(id(index.find(&entities, "", key_of)), None);
+
+        // The index is rebuilt after a push.
+        entities.push((3, "axe".to_string()));
+        entities.push((4, "sword".to_string()));
+        assert_eq!(id(index.find(&entities, "axe", key_of)), Some(3));
+        assert_eq!(id(index.find(&entities, "sword", key_of)), Some(1));
+
+        // In-place modifications that keep the length are detected.
+        entities[0].1 = "dagger".to_string();
+        assert_eq!(id(index.find(&entities, "sword", key_of)), Some(4));
+        assert_eq!(id(index.find(&entities, "dagger", key_of)), Some(1));
+        entities.swap(0, 1);
+        assert_eq!(id(index.find(&entities, "bow", key_of)), Some(2));
+        assert_eq!(id(index.find(&entities, "dagger", key_of)), Some(1));
+
+        // Removals as well.
+        entities.remove(0);
+        assert_eq!(id(index.find(&entities, "bow", key_of)), None);
+        assert_eq!(id(index.find(&entities, "axe", key_of)), Some(3));
+    }
+}
@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+
+/// The entities referring to a key, as positions in the indexed slice and as ids.
+#[derive(Default)]
+struct Referrers {
+    /// Positions of the entities in the indexed slice.
+    positions: Vec<usize>,
+    /// Ids of the entities, in the same order as `positions`.
+    ids: Vec<u32>,
+}
+
+/// The length of the slice an index was built for, along with the index itself.
+type CachedIndex = Option<(usize, HashMap<u32, Referrers>)>;
+
+/// An index from keys to the ids of the entities of a slice that refer to them, built on the
+/// first lookup.
+///
+/// The indexed slice is usually a public `Vec` that may be mutated at any time without the index
+/// knowing. Before being returned, the referrers of a key are checked against the slice. If the
+/// slice changed length, or if a referrer no longer has the expected id or no longer refers to
+/// the key, the index is rebuilt. Entities that start referring to a key without the slice
+/// changing length are only detected once the index is rebuilt for another reason.
+///
+/// Cloning the index returns an empty index and indices always compare equal, so that the index
+/// does not get in the way of the structure holding it.
+#[derive(Default)]
+pub(crate) struct LazyReverseIndex {
+    /// The index, if it was built.
+    index: CachedIndex,
+}
+
+impl LazyReverseIndex {
+    /// Return the ids of the entities of `entities` that refer to `key`, in the order of
+    /// `entities`.
+    /// `id_of` returns the id of an entity and `keys_of` the keys it refers to.
+    pub(crate) fn get<T>(
+        &mut self,
+        entities: &[T],
+        key: u32,
+        id_of: fn(&T) -> u32,
+        keys_of: fn(&T) -> &[u32],
+    ) -> &[u32] {
+        if !self.is_valid_for(entities, key, id_of, keys_of) {
+            self.rebuild(entities, id_of, keys_of);
+        }
+        self.index
+            .as_ref()
+            .and_then(|(_, index)| index.get(&key))
+            .map(|referrers| referrers.ids.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Whether the index was built and the referrers of `key` match `entities`.
+    fn is_valid_for<T>(
+        &self,
+        entities: &[T],
+        key: u32,
+        id_of: fn(&T) -> u32,
+        keys_of: fn(&T) -> &[u32],
+    ) -> bool {
+        match self.index.as_ref() {
+            Some((len, index)) if *len == entities.len() => {
+                index.get(&key).is_none_or(|referrers| {
+                    referrers
+                        .positions
+                        .iter()
+                        .zip(referrers.ids.iter())
+                        .all(|(pos, id)| {
+                            let entity = &entities[*pos];
+                            id_of(entity) == *id && keys_of(entity).contains(&key)
+                        })
+                })
+            }
+            _ => false,
+        }
+    }
+
+    /// Build the index anew from `entities`.
+    fn rebuild<T>(&mut self, entities: &[T], id_of: fn(&T) -> u32, keys_of: fn(&T) -> &[u32]) {
+        let mut map = HashMap::<u32, Referrers>::new();
+        for (pos, entity) in entities.iter().enumerate() {
+            for key in keys_of(entity).iter() {
+                let referrers = map.entry(*key).or_default();
+                if referrers.positions.last() != Some(&pos) {
+                    referrers.positions.push(pos);
+                    referrers.ids.push(id_of(entity));
+                }
+            }
+        }
+        self.index = Some((entities.len(), map));
+    }
+}
+
+impl Clone for LazyReverseIndex {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl PartialEq for LazyReverseIndex {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}